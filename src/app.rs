@@ -1,40 +1,85 @@
 use crate::{
-    command::CommandRunner,
-    evt, fs, languages,
+    command::{CommandResult, CommandRunner},
+    error::{Category, Problem},
+    evt,
+    fs::{self, application::Tool},
+    languages,
+    log::LogBuffer,
+    models::{lesson, Repository},
+    status::ProgressScope,
     ui::tui::{
-        self,
-        screens::{self, Screen, Screens},
-        Evt,
+        self, coalesce, flow, glyphs,
+        palette::Palette,
+        screens::{self, workshops::Batch, Action, Screen, Screens},
+        widgets::{Dialog, FrameStats, Response, Toast},
+        AsDefault, ChangeLanguage, Evt, Then,
     },
-    Error, Status,
+    Error, Status, StatusHandle,
+};
+use crossterm::{
+    event::{
+        self, DisableFocusChange, EnableFocusChange, EventStream, KeyCode, KeyEvent, KeyModifiers,
+    },
+    execute,
 };
-use crossterm::event::{self, EventStream, KeyCode};
 use futures::{future::FutureExt, StreamExt};
 use futures_timer::Delay;
 use ratatui::{buffer::Buffer, layout::Rect, widgets::Widget};
 use std::{
-    collections::HashMap,
-    sync::{
-        atomic::{AtomicBool, AtomicU8, Ordering},
-        Arc, Mutex,
-    },
-    time::Duration,
+    collections::{HashMap, VecDeque},
+    path::PathBuf,
+    sync::atomic::{AtomicBool, AtomicU8, Ordering},
+    time::{Duration, Instant},
 };
 use tokio::{
     select,
     sync::mpsc::{Receiver, Sender},
 };
-use tokio_util::sync::CancellationToken;
-use tracing::{debug, error, info};
+use tokio_util::{sync::CancellationToken, task::TaskTracker};
+use tracing::{debug, error, info, trace_span, warn, Instrument};
+
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// How long to wait for the containers of a cancelled check to be taken down when quitting
+const CLEAN_UP_TIMEOUT: Duration = Duration::from_secs(30);
+const MAX_EVENTS_PER_FRAME: usize = 256;
+const TOAST_DURATION: Duration = Duration::from_secs(4);
+
+/// The most screens remembered to go back to
+const MAX_HISTORY: usize = 50;
+
+/// A screen visited, with the selections it was showing so going back to it shows it the same
+#[derive(Clone, Debug, Default, PartialEq)]
+struct Visit {
+    /// the screen, which keeps its own list selections and scroll positions
+    screen: Screens,
+    /// the spoken language selected at the time
+    spoken_language: Option<languages::spoken::Code>,
+    /// the programming language selected at the time
+    programming_language: Option<languages::programming::Code>,
+    /// the workshop selected at the time
+    workshop: Option<String>,
+    /// the lesson selected at the time
+    lesson: Option<String>,
+}
 
-const MAX_LOG_LINES: usize = 10000;
+impl Visit {
+    // the screens that are steps of a flow, like picking a language, are left out of the history
+    // since their answers have already been used
+    fn is_remembered(&self) -> bool {
+        !matches!(
+            self.screen,
+            Screens::Log | Screens::Spoken | Screens::Programming | Screens::Setup
+        )
+    }
+}
 
 /// Tui implementation of the UI
 pub struct App {
-    /// The receiver from the logger
-    from_logger: Receiver<String>,
+    /// The log lines from the logger and the commands
+    log_lines: LogBuffer,
     /// The status
-    status: Arc<Mutex<Status>>,
+    status: StatusHandle,
     /// The available screens - uses wrapper types with 'static lifetime
     screens: HashMap<Screens, Box<dyn Screen>>,
     /// If the log window is shown
@@ -43,53 +88,124 @@ pub struct App {
     screen: AtomicU8,
     /// the cancelation token
     token: CancellationToken,
+    /// the cancelation token for running commands, a child of the app token
+    command_token: CancellationToken,
+    /// tracks the background tasks so they can be waited on at shutdown
+    tasks: TaskTracker,
     /// the receiver for UI events
     receiver: Receiver<screens::Event>,
     /// the sender for UI events
     sender: Sender<screens::Event>,
     /// command runner for external processes
     command_runner: CommandRunner,
+    /// render with ASCII glyphs only
+    ascii: bool,
+    /// the colors to render with
+    palette: Palette,
+    /// the workshops loaded from the `.workshops` directory, shared with the screens
+    repository: Repository,
+    /// turn off the spinners and other animations
+    reduced_motion: bool,
+    /// the frame statistics shown in the debug overlay, if it is showing
+    stats: Option<FrameStats>,
+    /// the notification being shown, if any
+    toast: Option<Toast>,
+    /// the dialog showing over the current screen, if any
+    dialog: Option<Dialog>,
+    /// the number of notifications shown so far, used as the id of the next one
+    toasts: u64,
+    /// the screen showing now
+    visit: Visit,
+    /// the screens to go back to, the most recent last
+    back: Vec<Visit>,
+    /// the screens gone back from, to go forward to again, the most recent last
+    forward: Vec<Visit>,
+    /// start in the selected workshop and lesson instead of at the home page
+    resume: bool,
 }
 
 impl Drop for App {
     fn drop(&mut self) {
         // cancel the token to stop the run loop
         self.token.cancel();
+        let _ = execute!(std::io::stdout(), DisableFocusChange);
         ratatui::restore();
     }
 }
 
 impl App {
     /// Create a new UI
-    pub fn new(from_logger: Receiver<String>) -> Result<Self, Error> {
+    pub fn new(log_lines: LogBuffer) -> Result<Self, Error> {
         let (sender, receiver) = tokio::sync::mpsc::channel(1_000_000);
-        let command_runner = CommandRunner::new(sender.clone());
+        let status = Status::load()?;
+        // the environment of the lesson commands is read from the status when they run, so that
+        // a changed config reaches them
+        let command_runner = CommandRunner::new(sender.clone())
+            .timeout(status.command_timeout())
+            .log_buffer(log_lines.clone());
+        let ascii = status.ascii().unwrap_or_else(glyphs::detect_ascii);
+        let reduced_motion = status.reduced_motion();
+        let repository = status.repository();
+        let token = CancellationToken::new();
+        let command_token = token.child_token();
 
         Ok(Self {
-            from_logger,
-            status: Arc::new(Mutex::new(Status::load()?)),
-            screens: Self::create_screens(),
+            screens: Self::create_screens(log_lines.clone(), reduced_motion),
+            log_lines,
+            status: StatusHandle::new(status),
             log: AtomicBool::new(false),
-            screen: AtomicU8::new(Screens::Workshops as u8),
-            token: CancellationToken::new(),
+            screen: AtomicU8::new(Screens::Welcome as u8),
+            token,
+            command_token,
+            tasks: TaskTracker::new(),
             receiver,
             sender,
             command_runner,
+            ascii,
+            palette: Palette::default(),
+            repository,
+            reduced_motion,
+            stats: None,
+            toast: None,
+            dialog: None,
+            toasts: 0,
+            visit: Visit::default(),
+            back: Vec::new(),
+            forward: Vec::new(),
+            resume: false,
         })
     }
 
+    /// Start in the selected workshop and lesson instead of at the home page
+    pub fn resume(mut self, resume: bool) -> Self {
+        self.resume = resume;
+        self
+    }
+
     // create the screens
-    fn create_screens() -> HashMap<Screens, Box<dyn Screen>> {
-        let mut screens = HashMap::<Screens, Box<dyn Screen>>::with_capacity(8);
+    fn create_screens(
+        log_lines: LogBuffer,
+        reduced_motion: bool,
+    ) -> HashMap<Screens, Box<dyn Screen>> {
+        let mut screens = HashMap::<Screens, Box<dyn Screen>>::with_capacity(13);
 
         // Welcome Screen
-        screens.insert(Screens::Welcome, Box::new(screens::Welcome::default()));
+        screens.insert(
+            Screens::Welcome,
+            Box::new(screens::Welcome::default().reduced_motion(reduced_motion)),
+        );
 
         // Workshop Selection Screen
-        screens.insert(Screens::Workshops, Box::new(screens::Workshops::new()));
+        screens.insert(
+            Screens::Workshops,
+            Box::new(screens::Workshops::new().reduced_motion(reduced_motion)),
+        );
 
         // Log Screen
-        screens.insert(Screens::Log, Box::new(screens::Log::new(MAX_LOG_LINES)));
+        screens.insert(
+            Screens::Log,
+            Box::new(screens::Log::new(log_lines).reduced_motion(reduced_motion)),
+        );
 
         // License Screen
         screens.insert(Screens::License, Box::new(screens::License::default()));
@@ -103,18 +219,33 @@ impl App {
             Box::new(screens::Programming::default()),
         );
 
-        // Set Default Confirmation Screen
+        // Lessons Screen
         screens.insert(
-            Screens::SetDefault,
-            Box::new(screens::SetDefault::default()),
+            Screens::Lessons,
+            Box::new(screens::Lessons::new().reduced_motion(reduced_motion)),
         );
 
-        // Lessons Screen
-        screens.insert(Screens::Lessons, Box::new(screens::Lessons::new()));
-
         // Lesson Screen
         screens.insert(Screens::Lesson, Box::new(screens::Lesson::default()));
 
+        // Workshop Catalog Screen
+        screens.insert(Screens::Catalog, Box::new(screens::Catalog::default()));
+
+        // Disk Usage Screen
+        screens.insert(Screens::DiskUsage, Box::new(screens::DiskUsage::default()));
+
+        // Workshop Search Screen
+        screens.insert(Screens::Search, Box::new(screens::Search::default()));
+
+        // Setup Checklist Screen
+        screens.insert(Screens::Setup, Box::new(screens::Setup::default()));
+
+        // Workshop Completion Screen
+        screens.insert(
+            Screens::Completion,
+            Box::new(screens::Completion::default()),
+        );
+
         screens
     }
 
@@ -123,14 +254,36 @@ impl App {
         &self.command_runner
     }
 
+    /// Get a clone of the cancelation token. Cancelling it stops the run loop, cancels any
+    /// running commands and saves the status.
+    pub fn token(&self) -> CancellationToken {
+        self.token.clone()
+    }
+
+    /// Cancel all background tasks and wait for them to finish so that no child processes are
+    /// left running when the application exits, then take down the containers of a cancelled
+    /// check
+    async fn shutdown(&mut self) {
+        self.token.cancel();
+        self.tasks.close();
+        if tokio::time::timeout(SHUTDOWN_TIMEOUT, self.tasks.wait())
+            .await
+            .is_err()
+        {
+            error!("Timed out waiting for background tasks to finish");
+        }
+        match tokio::time::timeout(CLEAN_UP_TIMEOUT, self.command_runner.clean_up()).await {
+            Ok(Ok(())) => {}
+            Ok(Err(e)) => error!("Failed to clean up after the cancelled check: {e}"),
+            Err(_) => error!("Timed out cleaning up after the cancelled check"),
+        }
+    }
+
     /// Setup python
     async fn detect_python(&mut self) -> Result<(), Error> {
         // try to get the python executable and minimum version from the status
         let (py_exe, py_min_ver) = {
-            let status = self
-                .status
-                .lock()
-                .map_err(|e| Error::StatusLock(e.to_string()))?;
+            let status = self.status.read().await;
             (
                 status.python_executable().map(String::from),
                 status.python_minimum_version().to_string(),
@@ -142,10 +295,7 @@ impl App {
             let python_executable = fs::application::find_python_executable(py_min_ver).await?;
             debug!("Setting Python executable: {}", python_executable);
             {
-                let mut status = self
-                    .status
-                    .lock()
-                    .map_err(|e| Error::StatusLock(e.to_string()))?;
+                let mut status = self.status.write().await;
                 status.set_python_executable(&python_executable, true);
             }
         }
@@ -157,10 +307,7 @@ impl App {
     async fn detect_docker_compose(&mut self) -> Result<(), Error> {
         // try to get the docker executable from the status
         let (docker_compose_exe, docker_compose_min_ver) = {
-            let status = self
-                .status
-                .lock()
-                .map_err(|e| Error::StatusLock(e.to_string()))?;
+            let status = self.status.read().await;
             (
                 status.docker_compose_executable().map(String::from),
                 status.docker_compose_minimum_version().to_string(),
@@ -176,10 +323,7 @@ impl App {
                 docker_compose_executable
             );
             {
-                let mut status = self
-                    .status
-                    .lock()
-                    .map_err(|e| Error::StatusLock(e.to_string()))?;
+                let mut status = self.status.write().await;
                 status.set_docker_compose_executable(&docker_compose_executable, true);
             }
         }
@@ -191,10 +335,7 @@ impl App {
     async fn detect_git(&mut self) -> Result<(), Error> {
         // try to get the git executable and minimum version from the status
         let (git_exe, git_min_ver) = {
-            let status = self
-                .status
-                .lock()
-                .map_err(|e| Error::StatusLock(e.to_string()))?;
+            let status = self.status.read().await;
             (
                 status.git_executable().map(String::from),
                 status.git_minimum_version().to_string(),
@@ -206,10 +347,7 @@ impl App {
             let git_executable = fs::application::find_git_executable(git_min_ver).await?;
             debug!("Setting Git executable: {}", git_executable);
             {
-                let mut status = self
-                    .status
-                    .lock()
-                    .map_err(|e| Error::StatusLock(e.to_string()))?;
+                let mut status = self.status.write().await;
                 status.set_git_executable(&git_executable, true);
             }
         }
@@ -217,57 +355,253 @@ impl App {
         Ok(())
     }
 
+    /// Uninstall the workshop and forget the progress in it
+    async fn uninstall_workshop(&self, workshop: &str) -> Result<(), Error> {
+        info!("Uninstalling workshop: {workshop}");
+        {
+            let mut status = self.status.write().await;
+            if status.workshop() == Some(workshop) {
+                status.set_workshop(None);
+                status.set_lesson(None);
+            }
+            status.forget_workshop(workshop);
+        }
+        let uninstalled = fs::application::uninstall_workshop(workshop);
+        self.repository.invalidate(Some(workshop));
+        uninstalled
+    }
+
+    /// Run the batch operation on one workshop with the executable of the tool it needs
+    async fn batch_workshop(
+        command_runner: &CommandRunner,
+        batch: Batch,
+        workshop: &str,
+        executable: &str,
+        (trusted_signers, require_signatures): &(Vec<String>, bool),
+        token: &CancellationToken,
+    ) -> Result<(), String> {
+        let workshop_dir = fs::application::workshop_dir(workshop).map_err(|e| e.to_string())?;
+        match batch {
+            Batch::Update => {
+                if !workshop_dir.join(".git").exists() {
+                    return Err("not installed from git".to_string());
+                }
+                // pulling would move a pinned workshop off the commit it is pinned to
+                if let Some(pin) = fs::install::Pin::load(&workshop_dir) {
+                    return Err(format!(
+                        "pinned to {}, use `workshop upgrade {workshop} <rev>` instead",
+                        pin.rev
+                    ));
+                }
+                let result = command_runner
+                    .update_workshop(
+                        executable,
+                        &workshop_dir,
+                        trusted_signers,
+                        *require_signatures,
+                        token,
+                    )
+                    .await
+                    .map_err(|e| e.to_string())?;
+                if !result.success {
+                    return Err(result.last_line);
+                }
+                Ok(())
+            }
+            Batch::PullImages => {
+                for lesson_dir in fs::application::lesson_compose_dirs(&workshop_dir) {
+                    let result = command_runner
+                        .pull_images(executable, &workshop_dir, &lesson_dir, token)
+                        .await
+                        .map_err(|e| e.to_string())?;
+                    if !result.success {
+                        return Err(result.last_line);
+                    }
+                }
+                Ok(())
+            }
+            Batch::Uninstall => Err("uninstalling is not run in the background".to_string()),
+        }
+    }
+
+    /// Show the problem in a dialog, the log has the details
+    async fn show_problem(to_ui: &Sender<screens::Event>, problem: &Problem) -> Result<(), Error> {
+        let view_log = evt!(None, tui::Event::ShowLog(None));
+        let dialog = evt!(
+            None,
+            tui::Event::Dialog(Dialog::problem(problem, Some(view_log)))
+        );
+        to_ui.send(dialog.into()).await?;
+        Ok(())
+    }
+
+    /// Tell the user that the feature can't be used without the tool
+    async fn tool_missing(
+        to_ui: &Sender<screens::Event>,
+        feature: &str,
+        tool: Tool,
+        e: Error,
+    ) -> Result<(), Error> {
+        error!("{feature} needs {tool}: {e}");
+        let log = evt!(
+            Screens::Log,
+            tui::Event::Log(format!(
+                "! {e}, {feature} needs {tool}, install it from {}",
+                tool.download_url()
+            ))
+        );
+        to_ui.send(log.into()).await?;
+        let problem = Problem {
+            category: Category::MissingTool,
+            message: format!("{feature} needs {tool}: {e}"),
+            remediation: Some(format!("Install it from {}.", tool.download_url())),
+        };
+        Self::show_problem(to_ui, &problem).await
+    }
+
+    /// Setup node and npm, only the JavaScript and TypeScript workshops need them
+    async fn detect_node(&mut self) -> Result<(), Error> {
+        let (node_exe, node_min_ver, npm_exe, npm_min_ver) = {
+            let status = self.status.read().await;
+            (
+                status.node_executable().map(String::from),
+                status.node_minimum_version().to_string(),
+                status.npm_executable().map(String::from),
+                status.npm_minimum_version().to_string(),
+            )
+        };
+
+        // if we don't have the paths, try to find them
+        if node_exe.is_none() {
+            let node_executable = fs::application::find_node_executable(node_min_ver).await?;
+            debug!("Setting Node.js executable: {}", node_executable);
+            let mut status = self.status.write().await;
+            status.set_node_executable(&node_executable, true);
+        }
+        if npm_exe.is_none() {
+            let npm_executable = fs::application::find_npm_executable(npm_min_ver).await?;
+            debug!("Setting npm executable: {}", npm_executable);
+            let mut status = self.status.write().await;
+            status.set_npm_executable(&npm_executable, true);
+        }
+
+        Ok(())
+    }
+
+    async fn detect_go(&mut self) -> Result<(), Error> {
+        let (go_exe, go_min_ver) = {
+            let status = self.status.read().await;
+            (
+                status.go_executable().map(String::from),
+                status.go_minimum_version().to_string(),
+            )
+        };
+
+        // if we don't have the path, try to find it
+        if go_exe.is_none() {
+            let go_executable = fs::application::find_go_executable(go_min_ver).await?;
+            debug!("Setting Go executable: {}", go_executable);
+            let mut status = self.status.write().await;
+            status.set_go_executable(&go_executable, true);
+        }
+
+        Ok(())
+    }
+
+    /// Check the pinned workshops and chain a reset dialog in front of the given event for each
+    /// workshop whose git working tree deviates from the pinned commit
+    async fn verify_pinned_workshops(&self, event: Evt) -> Evt {
+        let git_exe = {
+            let status = self.status.read().await;
+            match status.git_executable() {
+                Some(git_exe) => git_exe.to_string(),
+                None => return event,
+            }
+        };
+        let Ok(entries) = fs::application::data_dir().and_then(|d| Ok(std::fs::read_dir(d)?))
+        else {
+            return event;
+        };
+
+        let mut event = event;
+        for entry in entries.flatten() {
+            let workshop_dir = entry.path();
+            let Some(pin) = fs::install::Pin::load(&workshop_dir) else {
+                continue;
+            };
+            let workshop = entry.file_name().to_string_lossy().to_string();
+            match pin.deviates(&git_exe, &workshop_dir).await {
+                Ok(false) => {}
+                Ok(true) => {
+                    info!("Workshop {workshop} deviates from pinned {}", pin.rev);
+                    let reset = evt!(
+                        None,
+                        tui::Event::ResetWorkshop(workshop.clone(), Some(event.clone()))
+                    );
+                    event = evt!(
+                        None,
+                        tui::Event::Dialog(Dialog::yes_no(
+                            format!("Reset {workshop} to {}?", pin.rev),
+                            Some(reset),
+                            Some(event),
+                        )),
+                    );
+                }
+                Err(e) => error!("Failed to verify pinned workshop {workshop}: {e}"),
+            }
+        }
+        event
+    }
+
     /// Queue up the initial events for the application
-    async fn initial_events(&mut self, install: Option<String>) -> Result<(), Error> {
+    async fn initial_events(&mut self) -> Result<(), Error> {
         // initialize the state
         let (workshop, lesson) = {
-            let status = self
-                .status
-                .lock()
-                .map_err(|e| Error::StatusLock(e.to_string()))?;
+            let status = self.status.read().await;
             (
                 status.workshop().map(String::from),
                 status.lesson().map(String::from),
             )
         };
 
-        // send the correct initial message to restore the state
-        let event = match (workshop, lesson) {
-            (None, _) => {
-                let load_workshops = evt!(Screens::Workshops, tui::Event::LoadWorkshops);
-                evt!(None, tui::Event::HideLog(Some(load_workshops)))
-            }
-            (Some(workshop), lesson) => {
-                // re-runs the deps.py check and if it succeeds will drop you back into the lesson
-                let load = if lesson.is_none() {
-                    evt!(Screens::Lessons, tui::Event::LoadLessons)
-                } else {
-                    evt!(Screens::Lesson, tui::Event::LoadLesson)
-                };
-                let hide_log = evt!(None, tui::Event::HideLog(Some(load)));
-                evt!(
-                    None,
-                    tui::Event::CheckDeps(workshop.to_string(), Some(hide_log), None,),
-                )
+        // re-runs the deps.py check and if it succeeds will drop you back into the lesson
+        let resume = workshop.filter(|_| self.resume).and_then(|workshop| {
+            let load = if lesson.is_none() {
+                evt!(Screens::Lessons, tui::Event::LoadLessons)
+            } else {
+                evt!(Screens::Lesson, tui::Event::LoadLesson)
+            };
+            flow::open_workshop(workshop.to_string(), load).build()
+        });
+        // otherwise start at the home page, it offers to resume where the student left off
+        let event = match resume {
+            Some(resume) => resume,
+            None => {
+                let load_welcome = evt!(Screens::Welcome, tui::Event::LoadWelcome);
+                evt!(None, tui::Event::HideLog(Some(load_welcome)))
             }
         };
 
-        // if there's a workshop to install, do that first
-        if let Some(install) = install {
-            // if we are installing a workshop, send the install event
-            let install_event = evt!(None, tui::Event::InstallWorkshop(install, event.into()));
-            self.sender.send(install_event.into()).await?;
-        } else {
-            self.sender.send(event.into()).await?;
-        }
+        // offer to reset any pinned workshops that no longer match their pinned commit
+        let event = self.verify_pinned_workshops(event).await;
+
+        self.sender.send(event.into()).await?;
 
         Ok(())
     }
 
     /// async run loop
-    pub async fn run(&mut self, install: Option<String>) -> Result<(), Error> {
+    pub async fn run(&mut self) -> Result<(), Error> {
         // initialize the terminal
         let mut terminal = ratatui::init();
+        // report when the terminal gains or loses focus, terminals that can't just don't
+        let _ = execute!(std::io::stdout(), EnableFocusChange);
+
+        // pick the colors, this may ask the terminal for its background before input is read
+        self.palette = {
+            let status = self.status.read().await;
+            Palette::new(status.theme(), status.color_vision())
+        };
 
         // initialize the input event stream
         let mut reader = EventStream::new();
@@ -281,24 +615,20 @@ impl App {
             return Err(fs::Error::NoPythonExecutable.into());
         }
 
-        // try to get the docker compose executable and minimum version from the status
-        if self.detect_docker_compose().await.is_err() {
-            error!("Failed to detect Docker Compose executable or version");
-            return Err(fs::Error::NoDockerComposeExecutable.into());
-        }
-
-        // try to get the git executable and minimum version from the status
-        if self.detect_git().await.is_err() {
-            error!("Failed to detect Git executable or version");
-            return Err(fs::Error::NoGitExecutable.into());
-        }
+        // docker compose and git are only looked for when something needs them, so that the
+        // lessons can be read without them
 
         // queue up the initial events
-        if self.initial_events(install).await.is_err() {
+        if self.initial_events().await.is_err() {
             error!("Failed to queue initial events");
             return Err(Error::InitialEvents);
         }
 
+        // only redraw when something changed, starting with the first frame
+        let mut dirty = true;
+        let mut status_changed = self.status.subscribe();
+        let mut events_handled = 0;
+
         'run: loop {
             let input_event = reader.next().fuse();
 
@@ -316,20 +646,66 @@ impl App {
                         }
                         None => break 'run,
                     }
+
+                    // queue the input that is already waiting too so that it is handled in
+                    // the same frame
+                    while let Some(maybe_event) = reader.next().now_or_never() {
+                        match maybe_event {
+                            Some(Ok(evt)) => self.sender.send(evt.into()).await?,
+                            Some(Err(e)) => {
+                                error!("Error reading event: {}", e);
+                                break 'run;
+                            }
+                            None => break 'run,
+                        }
+                    }
                 }
 
-                // queue up a log message
-                Some(msg) = self.from_logger.recv() => {
-                    self.sender.send((Some(Screens::Log), tui::Event::Log(msg)).into()).await?;
+                // new log lines only need a redraw if the log is showing
+                _ = self.log_lines.changed() => {
+                    dirty = self.log.load(Ordering::SeqCst);
                 }
 
                 // get the next event in the queue
                 Some(evt) = self.receiver.recv() => {
-                    self.handle_event(evt, self.sender.clone(), self.status.clone()).await?;
+                    // handle everything that is already queued before drawing the next frame
+                    let mut events = VecDeque::from([evt]);
+                    while events.len() < MAX_EVENTS_PER_FRAME {
+                        match self.receiver.try_recv() {
+                            Ok(evt) => events.push_back(evt),
+                            Err(_) => break,
+                        }
+                    }
+                    while !events.is_empty() {
+                        for evt in self.coalesce(&mut events) {
+                            let handled = self
+                                .handle_event(evt, self.sender.clone(), self.status.clone())
+                                .instrument(trace_span!("handle_event"))
+                                .await;
+                            match handled {
+                                // only the errors inside the app end it, the others are shown
+                                // to the student with what to do about them
+                                Err(e) if e.category() != Category::Internal => {
+                                    error!("{e}");
+                                    Self::show_problem(&self.sender, &e.problem()).await?;
+                                }
+                                handled => handled?,
+                            }
+                            events_handled += 1;
+                        }
+                    }
+                    dirty = true;
                 }
 
-                // check the timeout
-                _ = &mut timeout => {}
+                // the status was changed, possibly by a background task
+                Ok(()) = status_changed.changed() => {
+                    dirty = true;
+                }
+
+                // the next animation frame is due
+                _ = &mut timeout => {
+                    dirty = self.is_animating();
+                }
 
                 // check if we should quit
                 _ = self.token.cancelled() => {
@@ -338,41 +714,210 @@ impl App {
                 }
             }
 
-            if self.log.load(Ordering::SeqCst) {
-                // if the log is visible, set a timer to redraw the UI @ 60 FPS
-                timeout = Delay::new(Duration::from_secs_f64(1.0 / 60.0));
+            if self.is_animating() {
+                // if a spinner is showing, set a timer to draw its next frame
+                timeout = Delay::new(Duration::from_millis(100));
             } else {
                 // otherwise set the timer to 10 minutes
                 timeout = Delay::new(Duration::from_secs(600));
             }
 
+            if !dirty {
+                continue;
+            }
+            dirty = false;
+
+            // the debug overlay shows the work behind the previous frame
+            if let Some(stats) = self.stats.as_mut() {
+                stats.events = events_handled;
+                stats.queued = self.receiver.len();
+                stats.workshops = self.repository.loaded();
+                stats.log_lines = self.log_lines.line_count();
+                stats.log_bytes = self.log_lines.interned_bytes();
+            }
+            events_handled = 0;
+
             // render the UI
-            if let Err(e) = terminal.draw(|f| f.render_widget(&mut *self, f.area())) {
+            let started = Instant::now();
+            let drawn = trace_span!("render")
+                .in_scope(|| terminal.draw(|f| f.render_widget(&mut *self, f.area())));
+            if let Err(e) = drawn {
                 error!("Error drawing UI: {e}");
             }
+            if let Some(stats) = self.stats.as_mut() {
+                stats.draw_time = started.elapsed();
+            }
         }
 
-        // clean up the terminal
+        // stop any background work
         info!("Quitting...");
+        self.shutdown().await;
+
+        // save the status and clean up the terminal
         {
-            let status = self
-                .status
-                .lock()
-                .map_err(|e| Error::StatusLock(e.to_string()))?;
+            let status = self.status.read().await;
             status.save()?;
         }
+        let _ = execute!(std::io::stdout(), DisableFocusChange);
         ratatui::restore();
 
         Ok(())
     }
 
+    // take the next events to handle from the front of the queued events, with runs of
+    // navigation keys coalesced unless the visible screen takes text input
+    fn coalesce(&mut self, events: &mut VecDeque<screens::Event>) -> Vec<screens::Event> {
+        if !self.text_input() {
+            let runs = coalesce::navigation(events);
+            if !runs.is_empty() {
+                let mut presses = Vec::new();
+                for (key, count) in runs {
+                    // the visible screen moves by the whole run at once if it can
+                    if count == 1 || !self.navigate(key, count) {
+                        presses.extend(std::iter::repeat_n(
+                            screens::Event::Input(event::Event::Key(key)),
+                            count,
+                        ));
+                    }
+                }
+                return presses;
+            }
+        }
+        events.pop_front().into_iter().collect()
+    }
+
+    // move the selection of the visible screen by the navigation key pressed count times
+    fn navigate(&mut self, key: KeyEvent, count: usize) -> bool {
+        if self.dialog.is_some() || self.log.load(Ordering::SeqCst) {
+            return false;
+        }
+        let current_screen = self.screen.load(Ordering::SeqCst).into();
+        self.screens
+            .get_mut(&current_screen)
+            .is_some_and(|screen| screen.navigate(key, count))
+    }
+
+    // does the visible screen take text input
+    fn text_input(&self) -> bool {
+        if let Some(dialog) = &self.dialog {
+            return dialog.takes_text_input();
+        }
+        !self.log.load(Ordering::SeqCst)
+            && self
+                .screens
+                .get(&Screens::from(self.screen.load(Ordering::SeqCst)))
+                .is_some_and(|screen| screen.takes_text_input())
+    }
+
+    // show the visited screen again as it was left, with the selections it was showing
+    async fn revisit(&mut self, visit: Visit, to_ui: &Sender<screens::Event>) -> Result<(), Error> {
+        debug!("Revisit screen: {}", visit.screen);
+        let changed = {
+            let mut status = self.status.write().await;
+            let changed = status.spoken_language() != visit.spoken_language
+                || status.programming_language() != visit.programming_language
+                || status.workshop() != visit.workshop.as_deref()
+                || status.lesson() != visit.lesson.as_deref();
+            if status.spoken_language() != visit.spoken_language {
+                status.set_spoken_language(visit.spoken_language, false);
+            }
+            if status.programming_language() != visit.programming_language {
+                status.set_programming_language(visit.programming_language, false);
+            }
+            if status.workshop() != visit.workshop.as_deref() {
+                status.set_workshop(visit.workshop);
+            }
+            if status.lesson() != visit.lesson.as_deref() {
+                status.set_lesson(visit.lesson);
+            }
+            changed
+        };
+        // the lessons screens only hold the lessons of the workshop last loaded
+        let reload = match visit.screen {
+            Screens::Lessons if changed => Some(tui::Event::LoadLessons),
+            Screens::Lesson if changed => Some(tui::Event::LoadLesson),
+            _ => None,
+        };
+        match reload {
+            Some(event) => to_ui.send((Some(visit.screen), event).into()).await?,
+            None => self.switch_screen(visit.screen, to_ui).await?,
+        }
+        Ok(())
+    }
+
+    // show the screen, letting the screen it replaces know that it is left and the screen know
+    // that it is entered
+    async fn switch_screen(
+        &mut self,
+        screen: Screens,
+        to_ui: &Sender<screens::Event>,
+    ) -> Result<(), Error> {
+        let previous = Screens::from(self.screen.swap(screen.clone() as u8, Ordering::SeqCst));
+        if previous == screen {
+            return Ok(());
+        }
+        if let Some(left) = self.screens.get_mut(&previous) {
+            left.on_leave(to_ui.clone(), self.status.clone()).await?;
+        }
+        if let Some(entered) = self.screens.get_mut(&screen) {
+            entered.on_enter(to_ui.clone(), self.status.clone()).await?;
+        }
+        Ok(())
+    }
+
+    // the command palette listing the actions of the visible screen, followed by the ones that
+    // work on every screen
+    fn command_palette(&self) -> Dialog {
+        let visible = if self.log.load(Ordering::SeqCst) {
+            Screens::Log
+        } else {
+            Screens::from(self.screen.load(Ordering::SeqCst))
+        };
+        let mut actions = self
+            .screens
+            .get(&visible)
+            .map(|screen| screen.actions())
+            .unwrap_or_default();
+        if !self.back.is_empty() {
+            actions.push(Action::alt("Go back", KeyCode::Left));
+        }
+        if !self.forward.is_empty() {
+            actions.push(Action::alt("Go forward", KeyCode::Right));
+        }
+        actions.push(Action::new("Show or hide the log", KeyCode::Char('`')));
+        actions.push(Action::ctrl(
+            "Reload the workshop from disk",
+            KeyCode::Char('r'),
+        ));
+        actions.push(Action::new("Quit", KeyCode::Char('q')));
+        let choices = actions
+            .into_iter()
+            .map(|action| (action.name, Some(evt!(None, tui::Event::Key(action.key)))))
+            .collect();
+        Dialog::palette("Command Palette", choices)
+    }
+
+    // is a visible screen animating and in need of redraws without any events
+    fn is_animating(&self) -> bool {
+        let screen = if self.log.load(Ordering::SeqCst) {
+            Screens::Log
+        } else {
+            Screens::from(self.screen.load(Ordering::SeqCst))
+        };
+        !self.reduced_motion
+            && self
+                .screens
+                .get(&screen)
+                .is_some_and(|screen| screen.is_animating())
+    }
+
     /// handle UI events
     pub async fn handle_ui_event(
         &mut self,
         screen: Option<Screens>,
         event: tui::Event,
         to_ui: Sender<screens::Event>,
-        status: Arc<Mutex<Status>>,
+        status: StatusHandle,
     ) -> Result<(), Error> {
         if let Some(dest_screen) = screen.clone() {
             // pass the event to the target screen
@@ -386,31 +931,533 @@ impl App {
                 tui::Event::Quit => {
                     self.token.cancel();
                 }
-                tui::Event::ToggleLog => {
-                    self.log.fetch_xor(true, Ordering::SeqCst);
+                tui::Event::ToggleLog => {
+                    self.log.fetch_xor(true, Ordering::SeqCst);
+                }
+                tui::Event::Reload => {
+                    // the loaded files are kept for good, so edits only show up once they are
+                    // forgotten and copied over the ones in the `.workshops` directory
+                    let workshop = status.read().await.workshop().map(String::from);
+                    info!(
+                        "Reloading from disk: {}",
+                        workshop.as_deref().unwrap_or("all")
+                    );
+                    if let Some(workshop) = workshop.as_deref() {
+                        match fs::workshops::refresh(workshop) {
+                            Ok(copied) => debug!("Copied {copied} changed files of {workshop}"),
+                            Err(e) => warn!("Failed to copy the changes of {workshop}: {e}"),
+                        }
+                    }
+                    self.repository.invalidate(workshop.as_deref());
+                    let screen = Screens::from(self.screen.load(Ordering::SeqCst));
+                    let reload = match screen {
+                        Screens::Workshops => Some(tui::Event::RefreshWorkshops),
+                        Screens::Lessons => Some(tui::Event::LoadLessons),
+                        Screens::Lesson => Some(tui::Event::LoadLesson),
+                        _ => None,
+                    };
+                    if let Some(event) = reload {
+                        to_ui.send((Some(screen), event).into()).await?;
+                    }
+                    let toast = evt!(None, tui::Event::Toast("Reloaded from disk".to_string()));
+                    to_ui.send(toast.into()).await?;
+                }
+                tui::Event::Toast(text) => {
+                    let id = self.toasts;
+                    self.toasts += 1;
+                    self.toast = Some(Toast { text, id });
+                    let hide = evt!(None, tui::Event::HideToast(id));
+                    to_ui
+                        .send((None, tui::Event::Delay(TOAST_DURATION, Some(hide))).into())
+                        .await?;
+                }
+                tui::Event::Dialog(dialog) => {
+                    self.dialog = Some(dialog);
+                }
+                tui::Event::Key(key) => {
+                    return self
+                        .handle_input_event(event::Event::Key(key), to_ui, status)
+                        .await;
+                }
+                tui::Event::HideToast(id) => {
+                    if self.toast.as_ref().is_some_and(|toast| toast.id == id) {
+                        self.toast = None;
+                    }
+                }
+                tui::Event::ShowLog(next) => {
+                    self.log.store(true, Ordering::SeqCst);
+                    if let Some(next) = next {
+                        to_ui.send(next.into()).await?;
+                    }
+                }
+                tui::Event::HideLog(next) => {
+                    self.log.store(false, Ordering::SeqCst);
+                    if let Some(next) = next {
+                        to_ui.send(next.into()).await?;
+                    }
+                }
+                tui::Event::Delay(duration, next) => {
+                    // wait in the background so that the event loop keeps running
+                    let token = self.token.clone();
+                    self.tasks.spawn(async move {
+                        select! {
+                            _ = tokio::time::sleep(duration) => {
+                                if let Some(next) = next {
+                                    let _ = to_ui.send(next.into()).await;
+                                }
+                            }
+                            _ = token.cancelled() => {}
+                        }
+                    });
+                }
+                tui::Event::UpdateWorkshop(workshop) => {
+                    if let Err(e) = self.detect_git().await {
+                        return Self::tool_missing(&to_ui, "Updating workshops", Tool::Git, e)
+                            .await;
+                    }
+                    let (git_executable, trusted_signers, require_signatures) = {
+                        let status = status.read().await;
+                        (
+                            status.git_executable().map(String::from),
+                            status.trusted_signers().to_vec(),
+                            status.require_signatures(),
+                        )
+                    };
+                    let git_exe = git_executable.ok_or(fs::Error::NoGitExecutable)?;
+
+                    let show_log = evt!(None, tui::Event::ShowLog(None));
+                    to_ui.send(show_log.into()).await?;
+
+                    let workshop_dir = fs::application::workshop_dir(&workshop)?;
+                    if !workshop_dir.join(".git").exists() {
+                        let not_git = evt!(
+                            Screens::Log,
+                            tui::Event::Log(format!(
+                                "! {workshop} was not installed from git and cannot be updated"
+                            ))
+                        );
+                        to_ui.send(not_git.into()).await?;
+                        return Ok(());
+                    }
+                    // pulling would move a pinned workshop off the commit it is pinned to
+                    if let Some(pin) = fs::install::Pin::load(&workshop_dir) {
+                        let pinned = evt!(
+                            Screens::Log,
+                            tui::Event::Log(format!(
+                                "! {workshop} is pinned to {} and is not updated, run `workshop upgrade {workshop} <rev>` to move it to another revision",
+                                pin.rev
+                            ))
+                        );
+                        to_ui.send(pinned.into()).await?;
+                        return Ok(());
+                    }
+
+                    let running = evt!(
+                        Screens::Log,
+                        tui::Event::Log(format!("r Updating workshop: {workshop}"))
+                    );
+                    to_ui.send(running.into()).await?;
+
+                    // Run the update in background and reload the workshops afterwards
+                    let command_runner = self.command_runner.clone();
+                    let token = self.command_token.clone();
+                    let sender = to_ui.clone();
+                    let refresh = evt!(Screens::Workshops, tui::Event::RefreshWorkshops);
+                    let next = Some(evt!(None, tui::Event::HideLog(Some(refresh))));
+
+                    self.tasks.spawn(async move {
+                        match command_runner
+                            .update_workshop(
+                                &git_exe,
+                                &workshop_dir,
+                                &trusted_signers,
+                                require_signatures,
+                                &token,
+                            )
+                            .await
+                        {
+                            Ok(result) => {
+                                let _ = sender
+                                    .send(
+                                        (
+                                            Some(Screens::Log),
+                                            tui::Event::CommandCompleted(
+                                                Box::new(result),
+                                                Then::always(next),
+                                            ),
+                                        )
+                                            .into(),
+                                    )
+                                    .await;
+                            }
+                            Err(e) => {
+                                let _ = sender
+                                    .send(
+                                        (
+                                            Some(Screens::Log),
+                                            tui::Event::Log(format!(
+                                                "! workshop update failed: {e}"
+                                            )),
+                                        )
+                                            .into(),
+                                    )
+                                    .await;
+                            }
+                        }
+                    });
+                }
+                tui::Event::BatchWorkshops(batch, workshops) => {
+                    // the tools are found up front so a missing one stops the batch before it starts
+                    let executable = match batch {
+                        Batch::Update => {
+                            if let Err(e) = self.detect_git().await {
+                                return Self::tool_missing(
+                                    &to_ui,
+                                    "Updating workshops",
+                                    Tool::Git,
+                                    e,
+                                )
+                                .await;
+                            }
+                            let status = status.read().await;
+                            status.git_executable().map(String::from)
+                        }
+                        Batch::PullImages => {
+                            if let Err(e) = self.detect_docker_compose().await {
+                                return Self::tool_missing(
+                                    &to_ui,
+                                    "Pre-pulling environments",
+                                    Tool::DockerCompose,
+                                    e,
+                                )
+                                .await;
+                            }
+                            let status = status.read().await;
+                            status.docker_compose_executable().map(String::from)
+                        }
+                        Batch::Uninstall => None,
+                    };
+
+                    let show_log = evt!(None, tui::Event::ShowLog(None));
+                    to_ui.send(show_log.into()).await?;
+                    let total = workshops.len();
+                    let running = evt!(
+                        Screens::Log,
+                        tui::Event::Log(format!("r {} {total} workshops", batch.doing()))
+                    );
+                    to_ui.send(running.into()).await?;
+
+                    let refresh = evt!(Screens::Workshops, tui::Event::RefreshWorkshops);
+                    let next = Some(evt!(None, tui::Event::HideLog(Some(refresh))));
+                    // each workshop is logged as it finishes, and the report sums them up
+                    let finish = move |failed: Vec<(String, String)>| {
+                        let result = CommandResult {
+                            success: failed.is_empty(),
+                            last_line: batch.report(total, &failed),
+                            ..Default::default()
+                        };
+                        evt!(
+                            Screens::Log,
+                            tui::Event::CommandCompleted(Box::new(result), Then::always(next))
+                        )
+                    };
+                    let logged =
+                        move |index: usize, workshop: &str, outcome: &Result<(), String>| {
+                            let line = match outcome {
+                                Ok(()) => {
+                                    format!("v [{}/{total}] {workshop} {}", index + 1, batch.done())
+                                }
+                                Err(e) => format!("x [{}/{total}] {workshop}: {e}", index + 1),
+                            };
+                            evt!(Screens::Log, tui::Event::Log(line))
+                        };
+
+                    match executable {
+                        None => {
+                            let mut failed = Vec::new();
+                            for (index, workshop) in workshops.iter().enumerate() {
+                                let outcome = self
+                                    .uninstall_workshop(workshop)
+                                    .await
+                                    .map_err(|e| e.to_string());
+                                to_ui.send(logged(index, workshop, &outcome).into()).await?;
+                                if let Err(e) = outcome {
+                                    failed.push((workshop.clone(), e));
+                                }
+                            }
+                            to_ui.send(finish(failed).into()).await?;
+                        }
+                        Some(executable) => {
+                            let (signers, command_env) = {
+                                let status = status.read().await;
+                                (
+                                    (
+                                        status.trusted_signers().to_vec(),
+                                        status.require_signatures(),
+                                    ),
+                                    status.command_env(),
+                                )
+                            };
+                            let command_runner = self.command_runner.clone().env(command_env);
+                            let token = self.command_token.clone();
+                            let sender = to_ui.clone();
+                            self.tasks.spawn(async move {
+                                let mut failed = Vec::new();
+                                for (index, workshop) in workshops.iter().enumerate() {
+                                    if token.is_cancelled() {
+                                        failed.push((workshop.clone(), "cancelled".to_string()));
+                                        continue;
+                                    }
+                                    let outcome = Self::batch_workshop(
+                                        &command_runner,
+                                        batch,
+                                        workshop,
+                                        &executable,
+                                        &signers,
+                                        &token,
+                                    )
+                                    .await;
+                                    let _ =
+                                        sender.send(logged(index, workshop, &outcome).into()).await;
+                                    if let Err(e) = outcome {
+                                        failed.push((workshop.clone(), e));
+                                    }
+                                }
+                                let _ = sender.send(finish(failed).into()).await;
+                            });
+                        }
+                    }
+                }
+                tui::Event::ResetWorkshop(workshop, next) => {
+                    if let Err(e) = self.detect_git().await {
+                        Self::tool_missing(&to_ui, "Resetting workshops", Tool::Git, e).await?;
+                        if let Some(next) = next {
+                            to_ui.send(next.into()).await?;
+                        }
+                        return Ok(());
+                    }
+                    let git_executable = {
+                        let status = status.read().await;
+                        status.git_executable().map(String::from)
+                    };
+                    let git_exe = git_executable.ok_or(fs::Error::NoGitExecutable)?;
+                    let workshop_dir = fs::application::workshop_dir(&workshop)?;
+                    if !workshop_dir.join(".git").exists() {
+                        error!("Workshop {workshop} was not installed from git");
+                        if let Some(next) = next {
+                            to_ui.send(next.into()).await?;
+                        }
+                        return Ok(());
+                    }
+                    // workshops that are not pinned are restored to their current commit
+                    let pin = fs::install::Pin::load(&workshop_dir).unwrap_or(fs::install::Pin {
+                        rev: "HEAD".to_string(),
+                        commit: "HEAD".to_string(),
+                    });
+
+                    let show_log = evt!(None, tui::Event::ShowLog(None));
+                    to_ui.send(show_log.into()).await?;
+
+                    let running = evt!(
+                        Screens::Log,
+                        tui::Event::Log(format!("r Resetting {workshop} to {}", pin.rev))
+                    );
+                    to_ui.send(running.into()).await?;
+
+                    let command_runner = self.command_runner.clone();
+                    let token = self.command_token.clone();
+                    let sender = to_ui.clone();
+
+                    self.tasks.spawn(async move {
+                        match command_runner
+                            .reset_workshop(&git_exe, &workshop_dir, &pin.commit, &token)
+                            .await
+                        {
+                            Ok(result) => {
+                                let _ = sender
+                                    .send(
+                                        (
+                                            Some(Screens::Log),
+                                            tui::Event::CommandCompleted(
+                                                Box::new(result),
+                                                Then::always(next),
+                                            ),
+                                        )
+                                            .into(),
+                                    )
+                                    .await;
+                            }
+                            Err(e) => {
+                                let _ = sender
+                                    .send(
+                                        (
+                                            Some(Screens::Log),
+                                            tui::Event::Log(format!(
+                                                "! workshop reset failed: {e}"
+                                            )),
+                                        )
+                                            .into(),
+                                    )
+                                    .await;
+                            }
+                        }
+                    });
+                }
+                tui::Event::CheckIntegrity(workshop) => {
+                    let workshop_dir = fs::application::workshop_dir(&workshop)?;
+                    let Some(manifest) = fs::install::Manifest::load(&workshop_dir) else {
+                        let missing = evt!(
+                            Screens::Log,
+                            tui::Event::Log(format!(
+                                "^ No manifest was recorded when {workshop} was installed"
+                            ))
+                        );
+                        to_ui.send(missing.into()).await?;
+                        to_ui.send((None, tui::Event::ShowLog(None)).into()).await?;
+                        return Ok(());
+                    };
+
+                    let changes = manifest.diff(&fs::install::Manifest::build(&workshop_dir)?);
+                    if changes.is_empty() {
+                        let intact = evt!(
+                            Screens::Log,
+                            tui::Event::Log(format!("v {workshop} matches its installed content"))
+                        );
+                        to_ui.send(intact.into()).await?;
+                        to_ui.send((None, tui::Event::ShowLog(None)).into()).await?;
+                        return Ok(());
+                    }
+
+                    for change in &changes {
+                        let changed = evt!(
+                            Screens::Log,
+                            tui::Event::Log(format!("^ {workshop} {change}"))
+                        );
+                        to_ui.send(changed.into()).await?;
+                    }
+                    if !workshop_dir.join(".git").exists() {
+                        let reinstall = evt!(
+                            Screens::Log,
+                            tui::Event::Log(format!(
+                                "! {workshop} was not installed from git, reinstall it to restore it"
+                            ))
+                        );
+                        to_ui.send(reinstall.into()).await?;
+                        to_ui.send((None, tui::Event::ShowLog(None)).into()).await?;
+                        return Ok(());
+                    }
+
+                    // offer to restore the installed content from git
+                    let refresh = evt!(Screens::Workshops, tui::Event::RefreshWorkshops);
+                    let hide_log = evt!(None, tui::Event::HideLog(Some(refresh)));
+                    let restore = evt!(
+                        None,
+                        tui::Event::ResetWorkshop(workshop.clone(), Some(hide_log))
+                    );
+                    let confirm = evt!(
+                        None,
+                        tui::Event::Dialog(Dialog::yes_no(
+                            format!("Restore {} changed files?", changes.len()),
+                            Some(restore),
+                            None,
+                        ))
+                    );
+                    to_ui.send(confirm.into()).await?;
                 }
-                tui::Event::ShowLog(next) => {
-                    self.log.store(true, Ordering::SeqCst);
-                    if let Some(next) = next {
-                        to_ui.send(next.into()).await?;
+                tui::Event::UninstallWorkshop(workshop, next) => {
+                    if let Err(e) = self.uninstall_workshop(&workshop).await {
+                        error!("Failed to uninstall workshop {workshop}: {e}");
                     }
+                    let next = next
+                        .unwrap_or_else(|| evt!(Screens::Workshops, tui::Event::RefreshWorkshops));
+                    to_ui.send(next.into()).await?;
                 }
-                tui::Event::HideLog(next) => {
-                    self.log.store(false, Ordering::SeqCst);
+                tui::Event::ResetProgress(scope, next) => {
+                    info!("Resetting progress: {scope}");
+                    let (spoken_language, programming_language) = {
+                        let mut status = status.write().await;
+                        status.reset_progress(&scope);
+                        (status.spoken_language(), status.programming_language())
+                    };
+                    if let Err(e) =
+                        fs::workshops::reset_progress(&scope, spoken_language, programming_language)
+                            .await
+                    {
+                        error!("Failed to reset progress for {scope}: {e}");
+                    }
+                    match &scope {
+                        ProgressScope::Lesson(workshop, _) | ProgressScope::Workshop(workshop) => {
+                            self.repository.invalidate(Some(workshop))
+                        }
+                        ProgressScope::All => self.repository.invalidate(None),
+                    }
                     if let Some(next) = next {
                         to_ui.send(next.into()).await?;
                     }
                 }
-                tui::Event::Delay(duration, next) => {
-                    // send a delay event to the UI
-                    tokio::time::sleep(duration).await;
-                    if let Some(next) = next {
-                        to_ui.send(next.into()).await?;
+                tui::Event::SyncProgress(message) => {
+                    let sync = {
+                        let status = status.read().await;
+                        status.save()?;
+                        status.progress_sync()
+                    };
+                    if let Some(sync) = sync {
+                        self.tasks.spawn(async move {
+                            if let Err(e) = sync.commit(&message).await {
+                                error!("Failed to commit progress: {e}");
+                            }
+                        });
                     }
                 }
+                tui::Event::CancelCommand => {
+                    info!("Cancelling running commands");
+                    self.command_token.cancel();
+                    self.command_token = self.token.child_token();
+                }
                 tui::Event::Show(screen) => {
                     debug!("Show screen: {}", screen);
-                    self.screen.store(screen.clone() as u8, Ordering::SeqCst);
+                    self.switch_screen(screen.clone(), &to_ui).await?;
+                    let visit = {
+                        let status = status.read().await;
+                        Visit {
+                            screen,
+                            spoken_language: status.spoken_language(),
+                            programming_language: status.programming_language(),
+                            workshop: status.workshop().map(String::from),
+                            lesson: status.lesson().map(String::from),
+                        }
+                    };
+                    // reloading the screen showing isn't a step to go back to
+                    if visit.screen != self.visit.screen {
+                        let previous = std::mem::replace(&mut self.visit, visit);
+                        if previous.is_remembered() {
+                            self.back.push(previous);
+                            if self.back.len() > MAX_HISTORY {
+                                self.back.remove(0);
+                            }
+                        }
+                        self.forward.clear();
+                    } else {
+                        self.visit = visit;
+                    }
+                }
+                tui::Event::Back => {
+                    if let Some(visit) = self.back.pop() {
+                        let current = std::mem::replace(&mut self.visit, visit.clone());
+                        if current.is_remembered() {
+                            self.forward.push(current);
+                        }
+                        self.revisit(visit, &to_ui).await?;
+                    }
+                }
+                tui::Event::Forward => {
+                    if let Some(visit) = self.forward.pop() {
+                        let current = std::mem::replace(&mut self.visit, visit.clone());
+                        if current.is_remembered() {
+                            self.back.push(current);
+                        }
+                        self.revisit(visit, &to_ui).await?;
+                    }
                 }
                 tui::Event::SetSpokenLanguage(spoken_language, default, next) => {
                     debug!(
@@ -419,7 +1466,8 @@ impl App {
                     );
 
                     let (default, next): (bool, Option<Evt>) = match default {
-                        Some(default) => {
+                        AsDefault::Yes | AsDefault::No => {
+                            let default = default == AsDefault::Yes;
                             debug!(
                                 "Setting spoken language as default: {}, {}",
                                 languages::spoken_name(spoken_language),
@@ -433,45 +1481,28 @@ impl App {
                                 languages::spoken_name(spoken_language)
                             );
 
-                            // this is the event to send if the user selects "yes" in the dialog
-                            let set_default_yes = evt!(
-                                None,
-                                tui::Event::SetSpokenLanguage(
-                                    spoken_language,
-                                    Some(true),
-                                    next.clone(),
-                                ),
-                            );
-
-                            // this is the event to send if the user selects "no" in the dialog
-                            let set_default_no = evt!(
-                                None,
-                                tui::Event::SetSpokenLanguage(
-                                    spoken_language,
-                                    Some(false),
-                                    next.clone(),
-                                ),
-                            );
-
-                            // this is the event to send to initialize the dialog
-                            let set_default = evt!(
-                                Screens::SetDefault,
-                                tui::Event::SetDefault(
-                                    "Set as Default?".to_string(),
-                                    Some(set_default_yes),
-                                    Some(set_default_no),
-                                ),
+                            // ask whether to make it the default, setting it again with the
+                            // answer and then going on
+                            let set_default = flow::ask_default(
+                                |default| {
+                                    evt!(
+                                        None,
+                                        tui::Event::SetSpokenLanguage(
+                                            spoken_language,
+                                            default,
+                                            None
+                                        )
+                                    )
+                                },
+                                next,
                             );
-                            (false, Some(set_default))
+                            (false, set_default.build())
                         }
                     };
 
                     // set the default spoken language
                     {
-                        let mut status = self
-                            .status
-                            .lock()
-                            .map_err(|e| Error::StatusLock(e.to_string()))?;
+                        let mut status = self.status.write().await;
                         status.set_spoken_language(spoken_language, default);
                     }
 
@@ -487,7 +1518,8 @@ impl App {
                     );
 
                     let (default, n): (bool, Option<Evt>) = match default {
-                        Some(default) => {
+                        AsDefault::Yes | AsDefault::No => {
+                            let default = default == AsDefault::Yes;
                             debug!(
                                 "Setting programming language as default: {}, {}, next: {:?}",
                                 languages::programming_name(programming_language),
@@ -503,45 +1535,28 @@ impl App {
                                 next
                             );
 
-                            // this is the event to send if the user selects "yes" in the dialog
-                            let set_default_yes = evt!(
-                                None,
-                                tui::Event::SetProgrammingLanguage(
-                                    programming_language,
-                                    Some(true),
-                                    next.clone(),
-                                ),
-                            );
-
-                            // this is the event to send if the user selects "no" in the dialog
-                            let set_default_no = evt!(
-                                None,
-                                tui::Event::SetProgrammingLanguage(
-                                    programming_language,
-                                    Some(false),
-                                    next.clone(),
-                                ),
-                            );
-
-                            // this is the event to send to initialize the dialog
-                            let set_default = evt!(
-                                Screens::SetDefault,
-                                tui::Event::SetDefault(
-                                    "Set as Default?".to_string(),
-                                    Some(set_default_yes),
-                                    Some(set_default_no),
-                                ),
+                            // ask whether to make it the default, setting it again with the
+                            // answer and then going on
+                            let set_default = flow::ask_default(
+                                |default| {
+                                    evt!(
+                                        None,
+                                        tui::Event::SetProgrammingLanguage(
+                                            programming_language,
+                                            default,
+                                            None
+                                        )
+                                    )
+                                },
+                                next,
                             );
-                            (false, Some(set_default))
+                            (false, set_default.build())
                         }
                     };
 
                     // set the default programming language
                     {
-                        let mut status = self
-                            .status
-                            .lock()
-                            .map_err(|e| Error::StatusLock(e.to_string()))?;
+                        let mut status = self.status.write().await;
                         status.set_programming_language(programming_language, default);
                     }
 
@@ -554,10 +1569,7 @@ impl App {
                     if let Some(workshop) = workshop {
                         debug!("Setting workshop: {:?}", workshop);
                         let (spoken_language, programming_language) = {
-                            let status = self
-                                .status
-                                .lock()
-                                .map_err(|e| Error::StatusLock(e.to_string()))?;
+                            let status = self.status.read().await;
                             (status.spoken_language(), status.programming_language())
                         };
 
@@ -583,14 +1595,17 @@ impl App {
                                     all_languages.clone()
                                 )
                             );
+                            // preselect the language of the system locale if the workshop has it
+                            let detected = languages::spoken::system_language()
+                                .filter(|spoken| all_languages.contains_key(spoken));
                             let change_spoken_language = (
                                 Some(Screens::Spoken),
-                                tui::Event::ChangeSpokenLanguage(
-                                    all_languages.clone(),
-                                    None,
-                                    false,
-                                    Some(set_workshop),
-                                ),
+                                tui::Event::ChangeSpokenLanguage(ChangeLanguage {
+                                    next: Some(set_workshop),
+                                    ..ChangeLanguage::new(all_languages.clone())
+                                        .selected(detected)
+                                        .allow_any(false)
+                                }),
                             );
 
                             to_ui.send(change_spoken_language.into()).await?;
@@ -608,12 +1623,10 @@ impl App {
                             );
                             let change_programming_language = (
                                 Some(Screens::Programming),
-                                tui::Event::ChangeProgrammingLanguage(
-                                    all_languages.clone(),
-                                    None,
-                                    false,
-                                    Some(set_workshop),
-                                ),
+                                tui::Event::ChangeProgrammingLanguage(ChangeLanguage {
+                                    next: Some(set_workshop),
+                                    ..ChangeLanguage::new(all_languages.clone()).allow_any(false)
+                                }),
                             );
 
                             to_ui.send(change_programming_language.into()).await?;
@@ -622,28 +1635,20 @@ impl App {
                             // workshop, initializing the local workshop data and loading the lessons
                             info!("Workshop selected: {}", workshop);
                             {
-                                let mut status = self
-                                    .status
-                                    .lock()
-                                    .map_err(|e| Error::StatusLock(e.to_string()))?;
+                                let mut status = self.status.write().await;
                                 status.set_workshop(Some(workshop.clone()));
                                 fs::workshops::init_data_dir(&workshop)?;
                             }
                             let load_lessons = evt!(Screens::Lessons, tui::Event::LoadLessons);
-                            let hide_log = evt!(None, tui::Event::HideLog(Some(load_lessons)));
-                            let check_deps = evt!(
-                                None,
-                                tui::Event::CheckDeps(workshop.clone(), Some(hide_log), None,),
-                            );
-                            to_ui.send(check_deps.into()).await?;
+                            if let Some(open) = flow::open_workshop(workshop, load_lessons).build()
+                            {
+                                to_ui.send(open.into()).await?;
+                            }
                         }
                     } else {
                         debug!("Clearing workshop");
                         {
-                            let mut status = self
-                                .status
-                                .lock()
-                                .map_err(|e| Error::StatusLock(e.to_string()))?;
+                            let mut status = self.status.write().await;
                             status.set_workshop(None);
                         }
                         to_ui
@@ -656,9 +1661,7 @@ impl App {
                     if let Some(lesson) = lesson {
                         debug!("Setting lesson: {:?}", lesson);
                         {
-                            let mut status = status
-                                .lock()
-                                .map_err(|e| Error::StatusLock(e.to_string()))?;
+                            let mut status = status.write().await;
                             status.set_lesson(Some(lesson.clone()));
                         }
                         to_ui
@@ -667,9 +1670,7 @@ impl App {
                     } else {
                         debug!("Clearing lesson");
                         {
-                            let mut status = status
-                                .lock()
-                                .map_err(|e| Error::StatusLock(e.to_string()))?;
+                            let mut status = status.write().await;
                             status.set_lesson(None);
                         }
                         to_ui
@@ -677,20 +1678,194 @@ impl App {
                             .await?;
                     }
                 }
-                tui::Event::CheckDeps(workshop, success, failed) => {
+                tui::Event::OpenLesson(workshop, spoken_language, programming_language, lesson) => {
+                    info!("Opening workshop: {workshop}, lesson: {lesson:?}");
+                    fs::workshops::init_data_dir(&workshop)?;
+                    let Some(workshop_data) = self.repository.workshop(&workshop) else {
+                        error!("Failed to load workshop: {workshop}");
+                        return Ok(());
+                    };
+                    // the description is the same for every programming language, so keep the
+                    // selected one if the workshop has it
+                    let programming_language = match programming_language {
+                        Some(programming_language) => programming_language,
+                        None => {
+                            let supported = workshop_data
+                                .get_programming_languages_for_spoken_language(spoken_language);
+                            self.status
+                                .get(|status| status.programming_language())
+                                .await
+                                .filter(|selected| supported.contains(selected))
+                                .unwrap_or(workshop_data.get_defaults().programming_language)
+                        }
+                    };
+                    {
+                        let mut status = self.status.write().await;
+                        status.set_spoken_language(Some(spoken_language), false);
+                        status.set_programming_language(Some(programming_language), false);
+                        status.set_workshop(Some(workshop.clone()));
+                        status.set_lesson(lesson.clone());
+                    }
+                    let load = if lesson.is_some() {
+                        evt!(Screens::Lesson, tui::Event::LoadLesson)
+                    } else {
+                        evt!(Screens::Lessons, tui::Event::LoadLessons)
+                    };
+                    if let Some(open) = flow::open_workshop(workshop, load).build() {
+                        to_ui.send(open.into()).await?;
+                    }
+                }
+                tui::Event::RunSetupScript(workshop, back) => {
+                    let (spoken, programming, python_executable, command_env) = {
+                        let status = self.status.read().await;
+                        (
+                            status.spoken_language(),
+                            status.programming_language(),
+                            status.python_executable().map(String::from),
+                            status.command_env(),
+                        )
+                    };
+                    let script = self
+                        .repository
+                        .workshop(&workshop)
+                        .and_then(|workshop_data| {
+                            workshop_data.get_setup_script_path(spoken, programming)
+                        });
+                    let Some(script) = script else {
+                        error!("No setup script for workshop: {workshop}");
+                        return Ok(());
+                    };
+                    let py_exe = python_executable.ok_or(fs::Error::NoPythonExecutable)?;
+                    // the script sets up the project directory the `.workshops` directory is in
+                    let project_dir = fs::workshops::data_dir()
+                        .and_then(|dir| dir.parent().map(PathBuf::from))
+                        .unwrap_or_else(|| PathBuf::from("."));
+
+                    let show_log = evt!(None, tui::Event::ShowLog(None));
+                    to_ui.send(show_log.into()).await?;
+                    let running = evt!(
+                        Screens::Log,
+                        tui::Event::Log(format!("r Running setup script: {}", script.display()))
+                    );
+                    to_ui.send(running.into()).await?;
+
+                    // go back to the checklist when it worked, and offer to run it again when it
+                    // didn't
+                    let success = evt!(None, tui::Event::HideLog(back.clone()));
+                    let again = evt!(None, tui::Event::RunSetupScript(workshop, back.clone()));
+                    let failed = evt!(
+                        None,
+                        tui::Event::Dialog(Dialog::choice(
+                            "The Setup Script Failed",
+                            vec![
+                                ("Run it again".to_string(), Some(again)),
+                                ("Back to the checklist".to_string(), Some(success.clone())),
+                            ],
+                            Some(success.clone()),
+                        ))
+                    );
+
+                    let command_runner = self.command_runner.clone().env(command_env);
+                    let token = self.command_token.clone();
+                    let sender = to_ui.clone();
+                    self.tasks.spawn(async move {
+                        let event = match command_runner
+                            .run_setup(&py_exe, &script, &project_dir, &token)
+                            .await
+                        {
+                            Ok(result) => tui::Event::CommandCompleted(
+                                Box::new(result),
+                                Then::on_success(success).or_else(failed),
+                            ),
+                            Err(e) => tui::Event::Log(format!("! setup script failed: {e}")),
+                        };
+                        let _ = sender.send((Some(Screens::Log), event).into()).await;
+                    });
+                }
+                tui::Event::CheckDeps(
+                    workshop,
+                    Then {
+                        success,
+                        failure: failed,
+                    },
+                ) => {
+                    // don't let a prefetch compete with the check for the disk
+                    self.repository.cancel_prefetch();
                     // Run dependency check using workshop data (with fallback to defaults)
-                    if let Some(workshop_data) = fs::workshops::load(&workshop) {
-                        let (programming_language, spoken_language, python_executable) = {
-                            let status = self
-                                .status
-                                .lock()
-                                .map_err(|e| Error::StatusLock(e.to_string()))?;
+                    if let Some(workshop_data) = self.repository.workshop(&workshop) {
+                        // node and go are only looked for when the workshop is taken in a
+                        // language that runs on them
+                        let language = self
+                            .status
+                            .get(|status| status.programming_language())
+                            .await
+                            .unwrap_or(workshop_data.get_defaults().programming_language);
+                        // most dependency check failures come from skipping the setup, so the
+                        // first check waits until the student says they finished it
+                        let setup_finished = self
+                            .status
+                            .get(|status| status.setup_progress(&workshop, language).finished)
+                            .await;
+                        if !setup_finished {
+                            let check_deps = evt!(
+                                None,
+                                tui::Event::CheckDeps(
+                                    workshop.clone(),
+                                    Then {
+                                        success,
+                                        failure: failed
+                                    }
+                                )
+                            );
+                            let setup = evt!(
+                                Screens::Setup,
+                                tui::Event::LoadSetup(workshop, Some(check_deps))
+                            );
+                            to_ui.send(setup.into()).await?;
+                            return Ok(());
+                        }
+                        let detected = if language.needs_node() {
+                            Some(("Node.js and npm", Tool::Node, self.detect_node().await))
+                        } else if language.needs_go() {
+                            Some(("Go", Tool::Go, self.detect_go().await))
+                        } else {
+                            None
+                        };
+                        if let Some((name, tool, Err(e))) = detected {
+                            let log = evt!(
+                                Screens::Log,
+                                tui::Event::Log(format!(
+                                    "! {e}, install {name} from {}",
+                                    tool.download_url()
+                                ))
+                            );
+                            to_ui.send(log.into()).await?;
+                            if let Some(failed) = failed {
+                                to_ui.send(failed.into()).await?;
+                            }
+                            return Ok(());
+                        }
+                        let (
+                            programming_language,
+                            spoken_language,
+                            python_executable,
+                            executables,
+                            command_env,
+                        ) = {
+                            let status = self.status.read().await;
                             (
                                 status.programming_language(),
                                 status.spoken_language(),
                                 status.python_executable().map(String::from),
+                                status.tool_executables(),
+                                status.command_env(),
                             )
                         };
+                        let requires = workshop_data
+                            .get_metadata(spoken_language)
+                            .await
+                            .map(|metadata| metadata.requires)
+                            .unwrap_or_default();
 
                         let py_exe = python_executable.ok_or(fs::Error::NoPythonExecutable)?;
 
@@ -719,14 +1894,21 @@ impl App {
                                 );
                                 debug!("Script exists: {}", deps_script.exists());
 
-                                // Run dependency check in background
-                                let command_runner = self.command_runner.clone();
-                                let token = self.token.clone();
+                                // Run dependency check in background, with the tools detected
+                                // above in the environment
+                                let command_runner = self.command_runner.clone().env(command_env);
+                                let token = self.command_token.clone();
                                 let sender = to_ui.clone();
 
-                                tokio::spawn(async move {
+                                self.tasks.spawn(async move {
                                     match command_runner
-                                        .check_dependencies(&py_exe, &deps_script, &token)
+                                        .check_dependencies(
+                                            &py_exe,
+                                            &requires,
+                                            &executables,
+                                            &deps_script,
+                                            &token,
+                                        )
                                         .await
                                     {
                                         Ok(result) => {
@@ -735,7 +1917,11 @@ impl App {
                                                     (
                                                         Some(Screens::Log),
                                                         tui::Event::CommandCompleted(
-                                                            result, success, failed,
+                                                            Box::new(result),
+                                                            Then {
+                                                                success,
+                                                                failure: failed,
+                                                            },
                                                         ),
                                                     )
                                                         .into(),
@@ -772,8 +1958,175 @@ impl App {
                         }
                     }
                 }
-                tui::Event::CheckSolution(success, failed) => {
+                tui::Event::RunLessonCommand(command) => {
+                    let (spoken, programming, workshop, lesson, command_env) = {
+                        let status = status.read().await;
+                        (
+                            status.spoken_language(),
+                            status.programming_language(),
+                            status.workshop().map(String::from),
+                            status.lesson().map(String::from),
+                            status.command_env(),
+                        )
+                    };
+                    let lesson_dir = workshop
+                        .as_deref()
+                        .and_then(|workshop| self.repository.workshop(workshop))
+                        .zip(lesson)
+                        .map(|(workshop_data, lesson)| {
+                            workshop_data.get_lesson_dir_path(&lesson, spoken, programming)
+                        });
+                    let Some(lesson_dir) = lesson_dir.transpose()? else {
+                        error!("Cannot run {}: missing workshop or lesson", command.name);
+                        return Ok(());
+                    };
+                    let Some((program, args)) = command.run.split_first() else {
+                        error!("The lesson command {} has nothing to run", command.name);
+                        return Ok(());
+                    };
+                    let (program, args) = (program.clone(), args.to_vec());
+
+                    let show_log = evt!(None, tui::Event::ShowLog(None));
+                    to_ui.send(show_log.into()).await?;
+                    let running = evt!(
+                        Screens::Log,
+                        tui::Event::Log(format!("r Running lesson command: {}", command.name))
+                    );
+                    to_ui.send(running.into()).await?;
+
+                    // x in the log stops a command that runs until it is cancelled
+                    let command_runner = self.command_runner.clone().env(command_env);
+                    let token = self.command_token.clone();
+                    let sender = to_ui.clone();
+                    self.tasks.spawn(async move {
+                        let args = args.iter().map(String::as_str).collect::<Vec<_>>();
+                        let event = match command_runner
+                            .run_command(&program, &args, Some(&lesson_dir), &token, true)
+                            .await
+                        {
+                            Ok(result) => {
+                                let hide_log = evt!(None, tui::Event::HideLog(None));
+                                tui::Event::CommandCompleted(
+                                    Box::new(result),
+                                    Then::always(Some(hide_log)),
+                                )
+                            }
+                            Err(e) => tui::Event::Log(format!("! {} failed: {e}", command.name)),
+                        };
+                        let _ = sender.send((Some(Screens::Log), event).into()).await;
+                    });
+                }
+                tui::Event::CheckLessonDeps(
+                    workshop,
+                    lesson,
+                    Then {
+                        success,
+                        failure: failed,
+                    },
+                ) => {
+                    let (spoken, programming, python_executable, executables, command_env) = {
+                        let status = status.read().await;
+                        (
+                            status.spoken_language(),
+                            status.programming_language(),
+                            status.python_executable().map(String::from),
+                            status.tool_executables(),
+                            status.command_env(),
+                        )
+                    };
+                    let Some(workshop_data) = self.repository.workshop(&workshop) else {
+                        error!("Failed to load workshop data for: {}", workshop);
+                        if let Some(failed) = failed {
+                            to_ui.send(failed.into()).await?;
+                        }
+                        return Ok(());
+                    };
+                    let language =
+                        programming.unwrap_or(workshop_data.get_defaults().programming_language);
+                    let deps_script =
+                        workshop_data.get_lesson_deps_script_path(&lesson, spoken, programming);
+                    let requires = match workshop_data
+                        .get_lessons_data(spoken, programming)
+                        .await?
+                        .get(&lesson)
+                    {
+                        Some(lesson_data) => lesson_data
+                            .get_metadata()
+                            .await
+                            .map(|metadata| metadata.requires)
+                            .unwrap_or_default(),
+                        None => Default::default(),
+                    };
+
+                    // most lessons need nothing beyond the workshop's own dependencies
+                    let checked = evt!(
+                        None,
+                        tui::Event::LessonDepsChecked(workshop, language, lesson.clone(), success)
+                    );
+                    if deps_script.is_none() && requires.is_empty() {
+                        to_ui.send(checked.into()).await?;
+                        return Ok(());
+                    }
+
+                    let py_exe = python_executable.ok_or(fs::Error::NoPythonExecutable)?;
+                    let show_log = evt!(None, tui::Event::ShowLog(None));
+                    to_ui.send(show_log.into()).await?;
+                    let running = evt!(
+                        Screens::Log,
+                        tui::Event::Log(format!("r Running lesson dependency check: {lesson}"))
+                    );
+                    to_ui.send(running.into()).await?;
+
+                    let command_runner = self.command_runner.clone().env(command_env);
+                    let token = self.command_token.clone();
+                    let sender = to_ui.clone();
+                    self.tasks.spawn(async move {
+                        let event = match command_runner
+                            .check_lesson_dependencies(
+                                &py_exe,
+                                &requires,
+                                &executables,
+                                deps_script.as_deref(),
+                                &token,
+                            )
+                            .await
+                        {
+                            Ok(result) => tui::Event::CommandCompleted(
+                                Box::new(result),
+                                Then {
+                                    success: Some(checked),
+                                    failure: failed,
+                                },
+                            ),
+                            Err(e) => tui::Event::Log(format!("! check lesson deps failed: {e}")),
+                        };
+                        let _ = sender.send((Some(Screens::Log), event).into()).await;
+                    });
+                }
+                tui::Event::LessonDepsChecked(workshop, language, lesson, next) => {
+                    status
+                        .update(|status| {
+                            status.set_lesson_deps_checked(&workshop, language, &lesson)
+                        })
+                        .await;
+                    if let Some(next) = next {
+                        to_ui.send(next.into()).await?;
+                    }
+                }
+                tui::Event::CheckSolution(Then {
+                    success,
+                    failure: failed,
+                }) => {
                     debug!("Check solution");
+                    self.repository.cancel_prefetch();
+                    if let Err(e) = self.detect_docker_compose().await {
+                        Self::tool_missing(&to_ui, "Checking solutions", Tool::DockerCompose, e)
+                            .await?;
+                        if let Some(failed) = failed {
+                            to_ui.send(failed.into()).await?;
+                        }
+                        return Ok(());
+                    }
                     // Get current status information
                     let (
                         spoken,
@@ -782,10 +2135,10 @@ impl App {
                         lesson,
                         python_executable,
                         docker_compose_executable,
+                        command_env,
+                        command_timeout,
                     ) = {
-                        let status = status
-                            .lock()
-                            .map_err(|e| Error::StatusLock(e.to_string()))?;
+                        let status = status.read().await;
                         (
                             status.spoken_language(),
                             status.programming_language(),
@@ -793,6 +2146,8 @@ impl App {
                             status.lesson().map(String::from),
                             status.python_executable().map(String::from),
                             status.docker_compose_executable().map(String::from),
+                            status.command_env(),
+                            status.command_timeout(),
                         )
                     };
 
@@ -802,7 +2157,38 @@ impl App {
 
                     // Check if we have required workshop and lesson
                     if let (Some(workshop), Some(lesson)) = (workshop, lesson) {
-                        if let Some(workshop_data) = fs::workshops::load(&workshop) {
+                        if let Some(workshop_data) = self.repository.workshop(&workshop) {
+                            // the tools only this lesson needs are checked before its first check
+                            let language = programming
+                                .unwrap_or(workshop_data.get_defaults().programming_language);
+                            let checked = status
+                                .get(|status| {
+                                    status.lesson_deps_checked(&workshop, language, &lesson)
+                                })
+                                .await;
+                            if !checked {
+                                let check_solution = evt!(
+                                    None,
+                                    tui::Event::CheckSolution(Then {
+                                        success,
+                                        failure: failed.clone(),
+                                    })
+                                );
+                                let check_lesson_deps = evt!(
+                                    None,
+                                    tui::Event::CheckLessonDeps(
+                                        workshop,
+                                        lesson,
+                                        Then {
+                                            success: Some(check_solution),
+                                            failure: failed,
+                                        }
+                                    )
+                                );
+                                to_ui.send(check_lesson_deps.into()).await?;
+                                return Ok(());
+                            }
+
                             let show_log = evt!(None, tui::Event::ShowLog(None));
                             to_ui.send(show_log.into()).await?;
 
@@ -820,23 +2206,60 @@ impl App {
                                         lesson_dir.display()
                                     );
 
+                                    // the lesson may use its own check script and timeout
+                                    let check = match workshop_data
+                                        .get_lessons_data(spoken, programming)
+                                        .await?
+                                        .get(&lesson)
+                                    {
+                                        Some(lesson_data) => {
+                                            lesson_data.get_metadata().await?.check
+                                        }
+                                        None => lesson::Check::default(),
+                                    };
+
                                     // Spawn async task to run solution check
-                                    let command_runner = self.command_runner.clone();
-                                    let token = self.token.clone();
+                                    let command_runner = self
+                                        .command_runner
+                                        .clone()
+                                        .env(command_env)
+                                        .timeout(check.timeout().or(command_timeout));
+                                    let token = self.command_token.clone();
                                     let sender = to_ui.clone();
 
-                                    tokio::spawn(async move {
+                                    self.tasks.spawn(async move {
                                         match command_runner
-                                            .check_solution(&dc_exe, &py_exe, &lesson_dir, &token)
+                                            .check_solution(
+                                                &dc_exe,
+                                                &py_exe,
+                                                &lesson_dir,
+                                                &check.script,
+                                                &token,
+                                            )
                                             .await
                                         {
                                             Ok(result) => {
+                                                let _ = sender
+                                                    .send(
+                                                        (
+                                                            Some(Screens::Lesson),
+                                                            tui::Event::CheckResult(Box::new(
+                                                                result.clone(),
+                                                            )),
+                                                        )
+                                                            .into(),
+                                                    )
+                                                    .await;
                                                 let _ = sender
                                                     .send(
                                                         (
                                                             Some(Screens::Log),
                                                             tui::Event::CommandCompleted(
-                                                                result, success, failed,
+                                                                Box::new(result),
+                                                                Then {
+                                                                    success,
+                                                                    failure: failed,
+                                                                },
                                                             ),
                                                         )
                                                             .into(),
@@ -880,15 +2303,19 @@ impl App {
                     }
                 }
                 tui::Event::InstallWorkshop(url, next) => {
-                    // Get current status information
-                    let git_executable = {
-                        let status = status
-                            .lock()
-                            .map_err(|e| Error::StatusLock(e.to_string()))?;
-                        status.git_executable().map(String::from)
-                    };
-                    let git_exe = git_executable.ok_or(fs::Error::NoGitExecutable)?;
-
+                    // only workshops cloned from a repository need git
+                    let source = fs::install::Source::parse(&url);
+                    if matches!(source, fs::install::Source::Git(..)) {
+                        if let Err(e) = self.detect_git().await {
+                            return Self::tool_missing(
+                                &to_ui,
+                                "Installing workshops from a repository",
+                                Tool::Git,
+                                e,
+                            )
+                            .await;
+                        }
+                    }
                     let show_log = evt!(None, tui::Event::ShowLog(None));
                     to_ui.send(show_log.into()).await?;
 
@@ -898,28 +2325,43 @@ impl App {
                     );
                     to_ui.send(running.into()).await?;
 
-                    debug!("Attempting to clone the workshop from: {url}");
+                    // Get current status information
+                    let (git_executable, trusted_signers, require_signatures) = {
+                        let status = status.read().await;
+                        (
+                            status.git_executable().map(String::from),
+                            status.trusted_signers().to_vec(),
+                            status.require_signatures(),
+                        )
+                    };
 
-                    // Run dependency check in background
+                    // Run the install in background
                     let command_runner = self.command_runner.clone();
-                    let token = self.token.clone();
+                    let token = self.command_token.clone();
                     let sender = to_ui.clone();
-                    let data_dir = fs::application::data_dir()?;
 
-                    tokio::spawn(async move {
-                        match command_runner
-                            .install_workshop(&git_exe, &url, &data_dir, &token)
+                    let install = async move {
+                        command_runner
+                            .install(
+                                &source,
+                                git_executable.as_deref(),
+                                &trusted_signers,
+                                require_signatures,
+                                &token,
+                            )
                             .await
-                        {
+                    };
+
+                    self.tasks.spawn(async move {
+                        match install.await {
                             Ok(result) => {
                                 let _ = sender
                                     .send(
                                         (
                                             Some(Screens::Log),
                                             tui::Event::CommandCompleted(
-                                                result,
-                                                next.clone(),
-                                                next.clone(),
+                                                Box::new(result),
+                                                Then::always(next.clone()),
                                             ),
                                         )
                                             .into(),
@@ -969,17 +2411,78 @@ impl App {
         &mut self,
         event: event::Event,
         to_ui: Sender<screens::Event>,
-        status: Arc<Mutex<Status>>,
+        status: StatusHandle,
     ) -> Result<(), Error> {
+        if let event::Event::Resize(width, height) = event {
+            for screen in self.screens.values_mut() {
+                screen.on_resize(width, height);
+            }
+            return Ok(());
+        }
+
+        // the lesson timer pauses while the terminal doesn't have focus
+        if matches!(event, event::Event::FocusGained | event::Event::FocusLost) {
+            if let Some(screen) = self.screens.get_mut(&Screens::Lesson) {
+                return screen.handle_event(event.into(), to_ui, status).await;
+            }
+        }
+
         if let event::Event::Key(key) = event {
+            // screens taking text input get every key
+            let text_input = self.text_input();
             match key.code {
                 // These key bindings work on every screen
-                KeyCode::Char('q') | KeyCode::Char('Q') => {
-                    self.token.cancel();
+                KeyCode::Char('q') | KeyCode::Char('Q') if !text_input => {
+                    // quitting kills the running commands, so ask first
+                    if self.command_runner.is_running() {
+                        self.dialog = Some(Dialog::yes_no(
+                            "Stop the running command and quit?",
+                            Some(evt!(None, tui::Event::Quit)),
+                            None,
+                        ));
+                    } else {
+                        self.token.cancel();
+                    }
+                }
+                KeyCode::Char('`') if !text_input => {
+                    to_ui.send((None, tui::Event::ToggleLog).into()).await?
+                }
+                KeyCode::Left if key.modifiers.contains(KeyModifiers::ALT) && !text_input => {
+                    to_ui.send((None, tui::Event::Back).into()).await?
+                }
+                KeyCode::Right if key.modifiers.contains(KeyModifiers::ALT) && !text_input => {
+                    to_ui.send((None, tui::Event::Forward).into()).await?
+                }
+                KeyCode::Char('p')
+                    if key.modifiers.contains(KeyModifiers::CONTROL)
+                        && !text_input
+                        && self.dialog.is_none() =>
+                {
+                    self.dialog = Some(self.command_palette());
+                }
+                KeyCode::Char('r')
+                    if key.modifiers.contains(KeyModifiers::CONTROL)
+                        && !text_input
+                        && self.dialog.is_none() =>
+                {
+                    to_ui.send((None, tui::Event::Reload).into()).await?
+                }
+                KeyCode::F(12) => {
+                    self.stats = match self.stats {
+                        Some(_) => None,
+                        None => Some(FrameStats::default()),
+                    };
                 }
-                KeyCode::Char('`') => to_ui.send((None, tui::Event::ToggleLog).into()).await?,
                 _ => {
-                    if self.log.load(Ordering::SeqCst) {
+                    if let Some(dialog) = self.dialog.as_mut() {
+                        // a dialog gets every key until it closes
+                        if let Response::Closed(next) = dialog.handle_key(key.code) {
+                            self.dialog = None;
+                            if let Some(next) = next {
+                                to_ui.send(next.into()).await?;
+                            }
+                        }
+                    } else if self.log.load(Ordering::SeqCst) {
                         // send key events to the log window if it is showing
                         if let Some(screen) = self.screens.get_mut(&Screens::Log) {
                             return screen.handle_event(event.into(), to_ui, status).await;
@@ -1010,7 +2513,7 @@ impl Screen for App {
         &mut self,
         event: screens::Event,
         to_ui: Sender<screens::Event>,
-        status: Arc<Mutex<Status>>,
+        status: StatusHandle,
     ) -> Result<(), Error> {
         match event {
             screens::Event::Input(input_event) => {
@@ -1048,5 +2551,18 @@ impl Screen for App {
 impl Widget for &mut App {
     fn render(self, area: Rect, buf: &mut Buffer) {
         let _ = self.render_screen(area, buf);
+        if let Some(dialog) = self.dialog.as_mut() {
+            dialog.render(area, buf);
+        }
+        if let Some(toast) = &self.toast {
+            toast.render(area, buf);
+        }
+        if let Some(stats) = &self.stats {
+            stats.render(area, buf);
+        }
+        self.palette.apply(area, buf);
+        if self.ascii {
+            glyphs::to_ascii(area, buf);
+        }
     }
 }
@@ -1,6 +1,13 @@
 use anyhow::Result;
-use clap::Parser;
-use workshop::{App, Log};
+use clap::{CommandFactory, Parser, Subcommand};
+use std::{io::BufRead, path::PathBuf};
+use workshop::{
+    cli::{self, Shell},
+    fs::{self, application},
+    log::{self, Log},
+    status::ProgressScope,
+    App, Status,
+};
 
 #[derive(Parser)]
 #[command(name = "workshop")]
@@ -8,13 +15,118 @@ use workshop::{App, Log};
 #[command(version = env!("CARGO_PKG_VERSION"))]
 #[command(disable_version_flag = true)]
 struct Args {
-    #[arg(long, help = "Install a workshop from a URL")]
-    install: Option<String>,
+    #[command(subcommand)]
+    command: Option<Commands>,
+
+    #[arg(
+        value_name = "URL",
+        help = "Open a workshop://<workshop>/<lesson> link"
+    )]
+    link: Option<String>,
+
+    #[arg(
+        long,
+        global = true,
+        help = "Work in this project directory instead of the current one"
+    )]
+    project: Option<PathBuf>,
+
+    #[arg(long, global = true, help = "Install workshops into this directory")]
+    data_dir: Option<PathBuf>,
+
+    #[arg(long, global = true, help = "Read the config file from this directory")]
+    config_dir: Option<PathBuf>,
+
+    #[arg(long, global = true, help = "Write the log file into this directory")]
+    state_dir: Option<PathBuf>,
+
+    #[arg(
+        long,
+        global = true,
+        help = "Keep downloads and other caches in this directory"
+    )]
+    cache_dir: Option<PathBuf>,
 
     #[arg(long, help = "Show version information")]
     version: bool,
 }
 
+#[derive(Subcommand)]
+enum Commands {
+    /// List the installed workshops
+    List,
+
+    /// Install a workshop from a git URL, a local directory, or a .tar.gz/.zip archive
+    Install { source: String },
+
+    /// Install another revision of a git workshop next to the installed one and carry the
+    /// progress over to it
+    Upgrade { workshop: String, rev: String },
+
+    /// Uninstall a workshop
+    Remove { workshop: String },
+
+    /// Start the workshop tool in the given workshop and lesson, or workshop:// link
+    Open {
+        workshop: String,
+        lesson: Option<String>,
+    },
+
+    /// Check the dependencies and the solution for a lesson, defaulting to the selected workshop
+    /// and lesson
+    Check {
+        #[arg(long)]
+        workshop: Option<String>,
+        #[arg(long)]
+        lesson: Option<String>,
+        #[arg(
+            long,
+            help = "Print the result as JSON instead of streaming the output"
+        )]
+        json: bool,
+    },
+
+    /// Validate the structure of a workshop directory
+    Validate { path: PathBuf },
+
+    /// Print the progress in the workshops in the current project
+    ExportProgress {
+        #[arg(long, help = "Print the progress as JSON instead of YAML")]
+        json: bool,
+    },
+
+    /// Reset progress for `all`, a `<workshop>`, or a `<workshop>/<lesson>`
+    ResetProgress {
+        #[arg(value_name = "SCOPE")]
+        scope: String,
+    },
+
+    /// Remove the docker containers, networks, and volumes left behind by lesson checks and the
+    /// workspaces of uninstalled workshops
+    Clean {
+        #[arg(long, help = "Only print what would be removed")]
+        dry_run: bool,
+        #[arg(long, short, help = "Remove without asking for confirmation")]
+        yes: bool,
+    },
+
+    /// Detect the tools again, check their versions, and check the application directories
+    Doctor {
+        #[arg(long, help = "Fix the stale tool paths without asking")]
+        fix: bool,
+    },
+
+    /// Register this executable as the handler for workshop:// links, started in the current
+    /// directory
+    RegisterLinks,
+
+    /// Speak JSON-RPC 2.0 over stdin and stdout, one message per line, for editor integrations
+    Rpc,
+
+    /// Print a shell completion script
+    Completions { shell: Shell },
+}
+
 #[tokio::main(flavor = "multi_thread")]
 async fn main() -> Result<()> {
     let args = Args::parse();
@@ -26,14 +138,73 @@ async fn main() -> Result<()> {
         return Ok(());
     }
 
+    // the progress is kept in the project, so switch to it before anything looks for it
+    if let Some(project) = &args.project {
+        std::env::set_current_dir(project)?;
+    }
+
+    // the directories given on the command line override the environment and the defaults
+    application::set_dir_overrides(application::DirOverrides {
+        data_dir: args.data_dir,
+        config_dir: args.config_dir,
+        state_dir: args.state_dir,
+        cache_dir: args.cache_dir,
+    });
+
+    // move workshops installed by older versions into the new data directory before any
+    // subcommand looks for them
+    application::migrate_legacy_data_dir()?;
+
+    // a bare link is the same as opening it
+    let command = match (args.command, args.link) {
+        (None, Some(link)) if link.starts_with(cli::LINK_SCHEME) => Some(Commands::Open {
+            workshop: link,
+            lesson: None,
+        }),
+        (None, Some(arg)) => {
+            anyhow::bail!(
+                "Unknown command or link: {arg}, links start with {}",
+                cli::LINK_SCHEME
+            )
+        }
+        (command, _) => command,
+    };
+
+    // run the subcommands without starting the TUI, except for open
+    let resume = matches!(command, Some(Commands::Open { .. }));
+    match command {
+        None => {}
+        Some(Commands::Open { workshop, lesson }) => {
+            let (workshop, lesson) = if workshop.starts_with(cli::LINK_SCHEME) {
+                cli::parse_link(&workshop)
+                    .ok_or_else(|| anyhow::anyhow!("Invalid workshop link: {workshop}"))?
+            } else {
+                (workshop, lesson)
+            };
+            cli::open(&mut Status::load()?, &workshop, lesson).await?;
+        }
+        Some(command) => return run_command(command).await,
+    }
+
     // initialize the logger
-    let from_logger = Log::init(Some("log.txt"))?;
+    let log_lines = Log::init(
+        Some(application::state_dir()?.join("log.txt")),
+        log::MAX_LOG_LINES,
+    )?;
+
+    // pull the progress from the progress remote before it gets loaded
+    let sync = Status::load()?.progress_sync();
+    if let Some(sync) = &sync {
+        if let Err(e) = sync.pull().await {
+            eprintln!("Failed to pull progress: {e}");
+        }
+    }
 
     // Initialize the app
-    let mut app = App::new(from_logger)?;
+    let mut app = App::new(log_lines)?.resume(resume);
 
     // run the app
-    let app_handle = tokio::spawn(async move { app.run(args.install).await });
+    let app_handle = tokio::spawn(async move { app.run().await });
 
     // Wait for the app to finish
     let app_result = app_handle.await?;
@@ -43,5 +214,185 @@ async fn main() -> Result<()> {
         eprintln!("App error: {e}");
     }
 
+    // push the progress made in this session
+    if let Some(sync) = sync {
+        if let Err(e) = sync.commit("Save progress").await {
+            eprintln!("Failed to commit progress: {e}");
+        } else if let Err(e) = sync.push().await {
+            eprintln!("Failed to push progress: {e}");
+        }
+    }
+
+    Ok(())
+}
+
+/// Run a subcommand that doesn't need the TUI
+async fn run_command(command: Commands) -> Result<()> {
+    match command {
+        Commands::List => cli::list(&Status::load()?).await?,
+        Commands::Install { source } => {
+            let result = cli::install(&Status::load()?, &source).await?;
+            if !result.success {
+                std::process::exit(1);
+            }
+        }
+        Commands::Upgrade { workshop, rev } => {
+            let (upgraded, migrated) = cli::upgrade(&Status::load()?, &workshop, &rev).await?;
+            println!("Carried the progress in {migrated} lessons over to: {upgraded}");
+        }
+        Commands::Remove { workshop } => {
+            cli::remove(&mut Status::load()?, &workshop)?;
+            println!("Removed workshop: {workshop}");
+        }
+        Commands::Check {
+            workshop,
+            lesson,
+            json,
+        } => {
+            let report = cli::check(&Status::load()?, workshop, lesson, json).await?;
+            if json {
+                println!("{}", serde_json::to_string_pretty(&report)?);
+            }
+            if !report.success {
+                std::process::exit(if report.exit_code > 0 {
+                    report.exit_code
+                } else {
+                    1
+                });
+            }
+        }
+        Commands::Validate { path } => {
+            let problems = cli::validate(&path).await?;
+            for problem in &problems {
+                println!("{problem}");
+            }
+            if !problems.is_empty() {
+                std::process::exit(1);
+            }
+            println!("{} is a valid workshop", path.display());
+        }
+        Commands::ExportProgress { json } => {
+            let progress = cli::export_progress(&Status::load()?).await?;
+            if json {
+                println!("{}", serde_json::to_string_pretty(&progress)?);
+            } else {
+                print!("{}", serde_yaml::to_string(&progress)?);
+            }
+        }
+        Commands::ResetProgress { scope } => reset_progress(ProgressScope::parse(scope)).await?,
+        Commands::Clean { dry_run, yes } => clean(dry_run, yes).await?,
+        Commands::Doctor { fix } => doctor(fix).await?,
+        Commands::RegisterLinks => {
+            let handler = cli::links::register(&std::env::current_dir()?).await?;
+            println!(
+                "Registered the workshop:// link handler: {}",
+                handler.display()
+            );
+        }
+        Commands::Rpc => cli::rpc::serve(Status::load()?).await?,
+        Commands::Completions { shell } => {
+            print!("{}", cli::completions(&Args::command(), shell));
+        }
+        Commands::Open { .. } => unreachable!("open starts the TUI"),
+    }
+    Ok(())
+}
+
+/// Reset the progress in the given scope, asking the user to type a confirmation for the
+/// destructive scopes
+async fn reset_progress(scope: ProgressScope) -> Result<()> {
+    if scope.is_destructive() {
+        println!("This permanently resets the progress for: {scope}");
+        println!("Type \"{scope}\" to confirm:");
+        let mut answer = String::new();
+        std::io::stdin().lock().read_line(&mut answer)?;
+        if answer.trim() != scope.to_string() {
+            println!("Progress not reset");
+            return Ok(());
+        }
+    }
+
+    let mut status = Status::load()?;
+    status.reset_progress(&scope);
+    fs::workshops::reset_progress(
+        &scope,
+        status.spoken_language(),
+        status.programming_language(),
+    )
+    .await?;
+    status.save()?;
+    println!("Progress reset for: {scope}");
+    Ok(())
+}
+
+/// Remove what past lesson checks and uninstalled workshops left behind, listing it first and
+/// asking the user to confirm unless told not to
+async fn clean(dry_run: bool, yes: bool) -> Result<()> {
+    let mut status = Status::load()?;
+    let leftovers = cli::clean::find(&status).await?;
+    if leftovers.is_empty() {
+        println!("Nothing to clean up");
+        return Ok(());
+    }
+    for leftover in &leftovers {
+        println!("{leftover}");
+    }
+    if dry_run {
+        return Ok(());
+    }
+    if !yes {
+        println!("Remove these {} items? [y/N]", leftovers.len());
+        let mut answer = String::new();
+        std::io::stdin().lock().read_line(&mut answer)?;
+        if !answer.trim().eq_ignore_ascii_case("y") {
+            println!("Nothing removed");
+            return Ok(());
+        }
+    }
+
+    let failed = cli::clean::remove(&mut status, &leftovers).await?;
+    for (leftover, reason) in &failed {
+        eprintln!("Failed to remove {leftover}: {reason}");
+    }
+    println!("Removed {} items", leftovers.len() - failed.len());
+    if !failed.is_empty() {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+/// Print the doctor's checks and offer to fix the problems it can fix
+async fn doctor(fix: bool) -> Result<()> {
+    let mut status = Status::load()?;
+    let checks = cli::doctor::diagnose(&status).await;
+    for check in &checks {
+        println!("{check}");
+    }
+
+    let fixes = checks
+        .iter()
+        .filter_map(|check| check.fix.clone())
+        .collect::<Vec<_>>();
+    if !fixes.is_empty() {
+        for fix in &fixes {
+            println!("Fix: {fix}");
+        }
+        if !fix {
+            println!("Apply these {} fixes? [y/N]", fixes.len());
+            let mut answer = String::new();
+            std::io::stdin().lock().read_line(&mut answer)?;
+            if !answer.trim().eq_ignore_ascii_case("y") {
+                println!("Nothing fixed");
+                std::process::exit(1);
+            }
+        }
+        cli::doctor::fix(&mut status, &fixes)?;
+        println!("Fixed {} problems", fixes.len());
+    }
+
+    // the fixed problems no longer count
+    if checks.iter().any(|check| !check.ok && check.fix.is_none()) {
+        std::process::exit(1);
+    }
     Ok(())
 }
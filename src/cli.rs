@@ -0,0 +1,667 @@
+use crate::{
+    command::{CommandResult, CommandRunner},
+    fs,
+    models::{glossary, lesson, workshop, Glossary},
+    ui::tui::{self, screens},
+    Error, Status,
+};
+use serde::Serialize;
+use std::{collections::BTreeMap, path::Path};
+use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
+
+pub mod clean;
+pub mod doctor;
+pub mod links;
+pub mod rpc;
+
+/// The shells we can generate completion scripts for
+#[derive(Clone, Copy, Debug, clap::ValueEnum)]
+pub enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+}
+
+/// The progress in all of the workshops in the current project
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct Progress {
+    pub peer_id: String,
+    pub workshops: BTreeMap<String, WorkshopProgress>,
+}
+
+/// The progress in a single workshop, keyed by `<spoken>/<programming>`
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct WorkshopProgress {
+    pub title: String,
+    pub status: workshop::Status,
+    pub lessons: BTreeMap<String, BTreeMap<String, lesson::Status>>,
+    /// the seconds of active time spent on the workshop
+    pub time_spent: u64,
+    /// the seconds of active time spent on each lesson
+    pub lesson_time: BTreeMap<String, u64>,
+    /// the student's markdown notes for each lesson
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub notes: BTreeMap<String, String>,
+}
+
+/// The result of a headless check of a lesson
+#[derive(Clone, Debug, Serialize)]
+pub struct CheckReport {
+    pub workshop: String,
+    pub lesson: String,
+    pub success: bool,
+    pub exit_code: i32,
+    /// the result of running the deps.py script
+    pub deps: StepReport,
+    /// the result of checking the tools the lesson needs, if it needs more than the workshop
+    pub lesson_deps: Option<StepReport>,
+    /// the result of running the check.py script, if the dependencies were met
+    pub check: Option<StepReport>,
+}
+
+/// The result of running a single script
+#[derive(Clone, Debug, Serialize)]
+pub struct StepReport {
+    pub success: bool,
+    pub exit_code: i32,
+    pub last_line: String,
+    pub output: Vec<String>,
+    /// the last command run and its arguments
+    pub argv: Vec<String>,
+    /// the last lines the last command wrote to stderr
+    pub stderr: Vec<String>,
+    /// how long the last command ran, in milliseconds
+    pub duration_ms: u128,
+}
+
+/// Where the output of a headless command goes as it arrives, besides the report
+#[derive(Clone, Debug)]
+pub enum Echo {
+    /// print it to stdout
+    Stdout,
+    /// send each line to the given channel
+    Channel(mpsc::UnboundedSender<String>),
+    /// only keep it for the report
+    Quiet,
+}
+
+impl Echo {
+    fn line(&self, line: &str) {
+        match self {
+            Echo::Stdout => println!("{line}"),
+            Echo::Channel(sender) => {
+                let _ = sender.send(line.to_string());
+            }
+            Echo::Quiet => {}
+        }
+    }
+}
+
+/// Run commands with a runner that collects their output instead of sending it to the Log
+/// screen, optionally echoing it as it arrives
+async fn run_headless<F, Fut>(status: &Status, echo: Echo, run: F) -> Result<StepReport, Error>
+where
+    F: FnOnce(CommandRunner) -> Fut,
+    Fut: std::future::Future<Output = Result<CommandResult, Error>>,
+{
+    let (sender, mut receiver) = mpsc::channel::<screens::Event>(100);
+    let echo_output = echo.clone();
+    let collector = tokio::spawn(async move {
+        let mut output = Vec::new();
+        while let Some(event) = receiver.recv().await {
+            let line = match event {
+                screens::Event::Ui(_, tui::Event::CommandStarted(_, cmd)) => format!("$ {cmd}"),
+                screens::Event::Ui(_, tui::Event::CommandOutput(line, _)) => line,
+                screens::Event::Ui(_, tui::Event::Log(msg)) => msg,
+                _ => continue,
+            };
+            echo_output.line(&line);
+            output.push(line);
+        }
+        output
+    });
+
+    // the runner is dropped when the future completes which closes the channel
+    let runner = CommandRunner::new(sender)
+        .timeout(status.command_timeout())
+        .env(status.command_env());
+    let result = run(runner).await?;
+    let output = collector.await?;
+    if !result.success {
+        for line in result.summary() {
+            echo.line(&line);
+        }
+    }
+    echo.line(&result.last_line);
+    Ok(StepReport {
+        success: result.success,
+        exit_code: result.exit_code,
+        last_line: result.last_line,
+        output,
+        argv: result.argv,
+        stderr: result.stderr,
+        duration_ms: result.duration.as_millis(),
+    })
+}
+
+/// Print the installed workshops
+pub async fn list(status: &Status) -> Result<(), Error> {
+    let workshops = fs::application::all_workshops()?;
+    let mut names = workshops.keys().collect::<Vec<_>>();
+    names.sort();
+    for name in names {
+        let metadata = workshops[name]
+            .get_metadata(status.spoken_language())
+            .await?;
+        println!(
+            "{name}\t{}\t{}\t{}",
+            metadata.title, metadata.difficulty, metadata.status
+        );
+    }
+    Ok(())
+}
+
+/// Install a workshop from a git URL, a local directory, or an archive
+pub async fn install(status: &Status, source: &str) -> Result<StepReport, Error> {
+    let git = match status.git_executable() {
+        Some(git) => Some(git.to_string()),
+        None => fs::application::find_git_executable(status.git_minimum_version())
+            .await
+            .ok(),
+    };
+    let source = fs::install::Source::parse(source);
+    run_headless(status, Echo::Stdout, |runner| async move {
+        runner
+            .install(
+                &source,
+                git.as_deref(),
+                status.trusted_signers(),
+                status.require_signatures(),
+                &CancellationToken::new(),
+            )
+            .await
+    })
+    .await
+}
+
+/// Upgrade to the workshop at the given revision, installing it from the repository the
+/// workshop was cloned from next to the installed version unless it is installed already, and
+/// carry the progress over for the lessons that are in both. The installed version is kept, so
+/// that a cohort can stay on it. Returns the name of the upgraded workshop and the number of
+/// lessons whose progress was carried over.
+pub async fn upgrade(status: &Status, workshop: &str, rev: &str) -> Result<(String, usize), Error> {
+    let from_dir = fs::application::workshop_dir(workshop)?;
+    let base = workshop.split_once('@').map_or(workshop, |(name, _)| name);
+    let installed = fs::install::pinned_name(base, rev);
+    let to = if fs::application::workshop_dir(&installed).is_ok() {
+        installed
+    } else {
+        let git = match status.git_executable() {
+            Some(git) => git.to_string(),
+            None => fs::application::find_git_executable(status.git_minimum_version()).await?,
+        };
+        let url = fs::install::git_output(&git, &from_dir, &["remote", "get-url", "origin"])
+            .await
+            .map_err(|_| fs::Error::InvalidInstallSource(from_dir.display().to_string()))?;
+        let source = format!("{url}@{rev}");
+        let report = install(status, &source).await?;
+        if !report.success {
+            return Err(Error::Command(report.last_line));
+        }
+        fs::install::Source::parse(&source)
+            .name()
+            .ok_or(fs::Error::InvalidInstallSource(source))?
+    };
+
+    // there is no progress to carry over outside of a project
+    if fs::workshops::data_dir().is_none() {
+        return Ok((to, 0));
+    }
+    let to_dir = fs::application::workshop_dir(&to)?;
+    let migrated = fs::progress::migrate(&from_dir, &to_dir)?;
+    Ok((to, migrated))
+}
+
+/// Uninstall a workshop, deselect it if it is the selected one, and forget the remembered
+/// languages and lesson
+pub fn remove(status: &mut Status, workshop: &str) -> Result<(), Error> {
+    fs::application::uninstall_workshop(workshop)?;
+    // the TUI must not start in the removed workshop
+    if status.workshop() == Some(workshop) {
+        status.set_workshop(None);
+        status.set_lesson(None);
+    }
+    status.forget_workshop(workshop);
+    status.save()
+}
+
+/// The URL scheme used to link to a workshop or lesson
+pub const LINK_SCHEME: &str = "workshop://";
+
+/// Parse a `workshop://<workshop>[/<lesson>]` link into the workshop and lesson names
+pub fn parse_link(link: &str) -> Option<(String, Option<String>)> {
+    let path = link.strip_prefix(LINK_SCHEME)?;
+    let path = path.split(['?', '#']).next().unwrap_or_default();
+    // any web page can open a link, so every part must be a plain name
+    let path = path.strip_suffix('/').unwrap_or(path);
+    let mut parts = path.split('/');
+    let workshop = fs::check_name(parts.next()?).ok()?.to_string();
+    let lesson = match parts.next() {
+        Some(lesson) => Some(fs::check_name(lesson).ok()?.to_string()),
+        None => None,
+    };
+    if parts.next().is_some() {
+        return None;
+    }
+    Some((workshop, lesson))
+}
+
+/// Select the workshop, and optionally the lesson, so that the TUI starts there
+pub async fn open(
+    status: &mut Status,
+    workshop: &str,
+    lesson: Option<String>,
+) -> Result<(), Error> {
+    let workshops_dir = fs::workshops::init_data_dir(workshop)?;
+    let workshop_data = workshop::Loader::new(workshop)
+        .path(&workshops_dir)
+        .try_load()?;
+
+    // use the remembered languages if the workshop supports them, otherwise its defaults
+    let languages = workshop_data.get_all_languages();
+    let (spoken, programming) = status
+        .workshop_context(workshop)
+        .and_then(|context| Some((context.spoken_language?, context.programming_language?)))
+        .or_else(|| Some((status.spoken_language()?, status.programming_language()?)))
+        .filter(|(spoken, programming)| {
+            languages
+                .get(spoken)
+                .is_some_and(|p| p.contains(programming))
+        })
+        .unwrap_or_else(|| {
+            let defaults = workshop_data.get_defaults();
+            (defaults.spoken_language, defaults.programming_language)
+        });
+
+    if let Some(lesson) = &lesson {
+        let lessons = workshop_data
+            .get_lessons_data(Some(spoken), Some(programming))
+            .await?;
+        if !lessons.contains_key(lesson) {
+            return Err(crate::models::Error::NoLessonData(lesson.clone()).into());
+        }
+    }
+
+    status.set_spoken_language(Some(spoken), false);
+    status.set_programming_language(Some(programming), false);
+    status.set_workshop(Some(workshop.to_string()));
+    status.set_lesson(lesson);
+    status.save()
+}
+
+/// Check the dependencies and then the solution for the given lesson, defaulting to the
+/// selected workshop and lesson, and mark the lesson as completed when the check passes. The
+/// output is echoed to stdout unless `quiet` is set.
+pub async fn check(
+    status: &Status,
+    workshop: Option<String>,
+    lesson: Option<String>,
+    quiet: bool,
+) -> Result<CheckReport, Error> {
+    let echo = if quiet { Echo::Quiet } else { Echo::Stdout };
+    check_with_echo(status, workshop, lesson, echo).await
+}
+
+/// Check the given lesson like [`check`] does, echoing the output as given
+pub async fn check_with_echo(
+    status: &Status,
+    workshop: Option<String>,
+    lesson: Option<String>,
+    echo: Echo,
+) -> Result<CheckReport, Error> {
+    let workshop = workshop
+        .or_else(|| status.workshop().map(String::from))
+        .ok_or(crate::models::Error::NoWorkshopSpecified)?;
+    let lesson = lesson
+        .or_else(|| status.lesson().map(String::from))
+        .ok_or(crate::models::Error::NoLessonSpecified)?;
+    // copy the workshop into the project first so that a fresh checkout can be graded
+    if fs::workshops::load(&workshop).is_none() {
+        fs::workshops::init_data_dir(&workshop)?;
+    }
+    let workshop_data = fs::workshops::load(&workshop)
+        .ok_or(crate::models::Error::WorkshopNotFound(workshop.clone()))?;
+    let (spoken, programming) = (status.spoken_language(), status.programming_language());
+    let lessons = workshop_data.get_lessons_data(spoken, programming).await?;
+    let lesson_data = lessons
+        .get(&lesson)
+        .ok_or(crate::models::Error::NoLessonData(lesson.clone()))?;
+
+    let python = match status.python_executable() {
+        Some(python) => python.to_string(),
+        None => fs::application::find_python_executable(status.python_minimum_version()).await?,
+    };
+
+    let python = python.as_str();
+    let requires = workshop_data.get_metadata(spoken).await?.requires;
+    let executables = status.tool_executables();
+    let deps_script = workshop_data.get_deps_script_path(spoken, programming)?;
+    let deps = run_headless(status, echo.clone(), |runner| async move {
+        runner
+            .check_dependencies(
+                python,
+                &requires,
+                &executables,
+                &deps_script,
+                &CancellationToken::new(),
+            )
+            .await
+    })
+    .await?;
+    let mut report = CheckReport {
+        workshop,
+        lesson,
+        success: false,
+        exit_code: deps.exit_code,
+        deps,
+        lesson_deps: None,
+        check: None,
+    };
+    if !report.deps.success {
+        return Ok(report);
+    }
+
+    let lesson_requires = lesson_data.get_metadata().await?.requires;
+    let lesson_deps_script =
+        workshop_data.get_lesson_deps_script_path(&report.lesson, spoken, programming);
+    if lesson_deps_script.is_some() || !lesson_requires.is_empty() {
+        let executables = status.tool_executables();
+        let lesson_deps = run_headless(status, echo.clone(), |runner| async move {
+            runner
+                .check_lesson_dependencies(
+                    python,
+                    &lesson_requires,
+                    &executables,
+                    lesson_deps_script.as_deref(),
+                    &CancellationToken::new(),
+                )
+                .await
+        })
+        .await?;
+        report.exit_code = lesson_deps.exit_code;
+        let met = lesson_deps.success;
+        report.lesson_deps = Some(lesson_deps);
+        if !met {
+            return Ok(report);
+        }
+    }
+
+    let docker_compose = match status.docker_compose_executable() {
+        Some(docker_compose) => docker_compose.to_string(),
+        None => {
+            fs::application::find_docker_compose_executable(status.docker_compose_minimum_version())
+                .await?
+        }
+    };
+    let check = lesson_data.get_metadata().await?.check;
+    let check = run_headless(status, echo, |runner| async move {
+        let timeout = check.timeout().or(status.command_timeout());
+        runner
+            .timeout(timeout)
+            .check_solution(
+                &docker_compose,
+                python,
+                lesson_data.get_path(),
+                &check.script,
+                &CancellationToken::new(),
+            )
+            .await
+    })
+    .await?;
+    report.success = check.success;
+    report.exit_code = check.exit_code;
+    report.check = Some(check);
+
+    if report.success {
+        lesson_data.update_status(lesson::Status::Completed).await?;
+        let mut all_completed = true;
+        for lesson_data in lessons.values() {
+            all_completed &= matches!(
+                lesson_data.get_metadata().await?.status,
+                lesson::Status::Completed
+            );
+        }
+        if all_completed {
+            workshop_data
+                .update_status(spoken, workshop::Status::Completed)
+                .await?;
+        }
+    }
+    Ok(report)
+}
+
+/// Validate the workshop in the given directory, returning the problems found
+pub async fn validate(path: &Path) -> Result<Vec<String>, Error> {
+    let (Some(name), Some(parent)) = (path.file_name(), path.parent()) else {
+        return Err(fs::Error::WorkshopDataDirNotFound.into());
+    };
+    let mut problems = Vec::new();
+    let workshop_data = match workshop::Loader::new(&name.to_string_lossy())
+        .path(parent)
+        .try_load()
+    {
+        Ok(workshop_data) => workshop_data,
+        Err(e) => {
+            problems.push(e.to_string());
+            return Ok(problems);
+        }
+    };
+
+    for (spoken, programmings) in workshop_data.get_all_languages() {
+        if let Err(e) = workshop_data.get_metadata(Some(*spoken)).await {
+            problems.push(format!("{spoken}: {e}"));
+        }
+        let glossary = Glossary::load(path, Some(*spoken));
+        for programming in programmings {
+            let prefix = format!("{spoken}/{programming}");
+            for file in ["setup.md", "deps.py"] {
+                if !path.join(&prefix).join(file).is_file() {
+                    problems.push(format!("{prefix}: missing {file}"));
+                }
+            }
+            let lessons = match workshop_data
+                .get_lessons_data(Some(*spoken), Some(*programming))
+                .await
+            {
+                Ok(lessons) => lessons,
+                Err(e) => {
+                    problems.push(format!("{prefix}: {e}"));
+                    continue;
+                }
+            };
+            for (name, lesson_data) in lessons {
+                let check_script = match lesson_data.get_metadata().await {
+                    Ok(lesson) => {
+                        for problem in lesson.validate() {
+                            problems.push(format!("{prefix}/{name}: {problem}"));
+                        }
+                        lesson.check.script
+                    }
+                    Err(e) => {
+                        problems.push(format!("{prefix}/{name}: {e}"));
+                        "check.py".to_string()
+                    }
+                };
+                if let Ok(text) = lesson_data.get_text().await {
+                    for problem in tui::widgets::quiz_problems(&text) {
+                        problems.push(format!("{prefix}/{name}: {problem}"));
+                    }
+                    for term in glossary::links(&text) {
+                        if glossary.define(term).is_none() {
+                            problems.push(format!("{prefix}/{name}: {term} isn't in the glossary"));
+                        }
+                    }
+                }
+                for file in ["lesson.md", &check_script, "docker-compose.yaml"] {
+                    if !lesson_data.get_path().join(file).is_file() {
+                        problems.push(format!("{prefix}/{name}: missing {file}"));
+                    }
+                }
+            }
+        }
+    }
+    problems.sort();
+    Ok(problems)
+}
+
+/// Gather the progress in every workshop in the current project
+pub async fn export_progress(status: &Status) -> Result<Progress, Error> {
+    let mut progress = Progress {
+        peer_id: status.peer_id().to_string(),
+        ..Default::default()
+    };
+    let Some(workshops_dir) = fs::workshops::data_dir() else {
+        return Ok(progress);
+    };
+
+    for (name, workshop_data) in fs::workshops::load_workshop_data(workshops_dir)? {
+        let metadata = workshop_data.get_metadata(status.spoken_language()).await?;
+        let mut workshop = WorkshopProgress {
+            title: metadata.title,
+            status: metadata.status,
+            time_spent: status.workshop_time(&name).as_secs(),
+            lesson_time: status
+                .workshop_context(&name)
+                .map(|context| context.time.clone())
+                .unwrap_or_default(),
+            notes: fs::notes::load_workshop(&name),
+            ..Default::default()
+        };
+        for (spoken, programmings) in workshop_data.get_all_languages() {
+            for programming in programmings {
+                let mut lessons = BTreeMap::new();
+                for (lesson, lesson_data) in workshop_data
+                    .get_lessons_data(Some(*spoken), Some(*programming))
+                    .await?
+                {
+                    lessons.insert(lesson, lesson_data.get_metadata().await?.status);
+                }
+                workshop
+                    .lessons
+                    .insert(format!("{spoken}/{programming}"), lessons);
+            }
+        }
+        progress.workshops.insert(name, workshop);
+    }
+    Ok(progress)
+}
+
+/// Generate a completion script for the given command
+pub fn completions(command: &clap::Command, shell: Shell) -> String {
+    let name = command.get_name();
+    let words = |command: &clap::Command| {
+        command
+            .get_subcommands()
+            .map(|s| s.get_name().to_string())
+            .chain(
+                command
+                    .get_arguments()
+                    .filter_map(|a| a.get_long().map(|l| format!("--{l}"))),
+            )
+            .collect::<Vec<_>>()
+    };
+
+    match shell {
+        Shell::Bash | Shell::Zsh => {
+            let mut script = String::new();
+            if matches!(shell, Shell::Zsh) {
+                script.push_str("autoload -U +X bashcompinit && bashcompinit\n");
+            }
+            script.push_str(&format!(
+                "_{name}() {{\n    local cur=\"${{COMP_WORDS[COMP_CWORD]}}\"\n    local words=\"{}\"\n    case \"${{COMP_WORDS[1]}}\" in\n",
+                words(command).join(" ")
+            ));
+            for sub in command.get_subcommands() {
+                script.push_str(&format!(
+                    "        {}) words=\"{}\" ;;\n",
+                    sub.get_name(),
+                    words(sub).join(" ")
+                ));
+            }
+            script.push_str(&format!(
+                "    esac\n    COMPREPLY=( $(compgen -W \"$words\" -- \"$cur\") )\n}}\ncomplete -o default -F _{name} {name}\n"
+            ));
+            script
+        }
+        Shell::Fish => {
+            let mut script = String::new();
+            for arg in command.get_arguments() {
+                if let Some(long) = arg.get_long() {
+                    script.push_str(&format!("complete -c {name} -l {long}\n"));
+                }
+            }
+            for sub in command.get_subcommands() {
+                let about = sub.get_about().map(|a| a.to_string()).unwrap_or_default();
+                script.push_str(&format!(
+                    "complete -c {name} -f -n __fish_use_subcommand -a {} -d '{}'\n",
+                    sub.get_name(),
+                    about.replace('\'', "\\'")
+                ));
+                for arg in sub.get_arguments() {
+                    if let Some(long) = arg.get_long() {
+                        script.push_str(&format!(
+                            "complete -c {name} -n '__fish_seen_subcommand_from {}' -l {long}\n",
+                            sub.get_name()
+                        ));
+                    }
+                }
+            }
+            script
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_link() {
+        assert_eq!(
+            parse_link("workshop://example-workshop/01-just-compile"),
+            Some((
+                "example-workshop".to_string(),
+                Some("01-just-compile".to_string())
+            ))
+        );
+        assert_eq!(
+            parse_link("workshop://example-workshop/?from=course"),
+            Some(("example-workshop".to_string(), None))
+        );
+        assert_eq!(parse_link("workshop://"), None);
+        assert_eq!(parse_link("workshop://a/b/c"), None);
+        assert_eq!(parse_link("workshop://../b"), None);
+        assert_eq!(parse_link("workshop://a/.."), None);
+        assert_eq!(parse_link("workshop://a//b"), None);
+        assert_eq!(parse_link("workshop:///b"), None);
+        assert_eq!(parse_link("https://example.com/a"), None);
+    }
+
+    #[test]
+    fn test_completions_list_subcommands() {
+        let command = clap::Command::new("workshop")
+            .arg(clap::Arg::new("data-dir").long("data-dir"))
+            .subcommand(clap::Command::new("list").about("List the workshops"))
+            .subcommand(clap::Command::new("check").arg(clap::Arg::new("lesson").long("lesson")));
+
+        let bash = completions(&command, Shell::Bash);
+        assert!(bash.contains("local words=\"list check --data-dir\""));
+        assert!(bash.contains("check) words=\"--lesson\" ;;"));
+        assert!(completions(&command, Shell::Zsh).starts_with("autoload"));
+
+        let fish = completions(&command, Shell::Fish);
+        assert!(fish.contains("-a list -d 'List the workshops'"));
+        assert!(fish.contains("'__fish_seen_subcommand_from check' -l lesson"));
+    }
+}
@@ -0,0 +1,195 @@
+use crate::{fs, Error, Status};
+use std::{fmt, path::PathBuf};
+use tokio::process::Command;
+use tracing::debug;
+
+/// The docker compose project name the lesson checks run under
+const COMPOSE_PROJECT: &str = "workshop";
+
+/// The network the lesson checks create
+const NETWORK: &str = "workshop-net";
+
+/// Something left behind by a past lesson check or an uninstalled workshop
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Leftover {
+    /// a docker container, by id and name
+    Container { id: String, name: String },
+    /// a docker network
+    Network(String),
+    /// a docker volume
+    Volume(String),
+    /// the copy of a workshop in the `.workshops` directory that is no longer installed
+    Workspace { workshop: String, path: PathBuf },
+    /// the notes for a workshop that is no longer installed
+    Notes { workshop: String, path: PathBuf },
+}
+
+impl fmt::Display for Leftover {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Leftover::Container { id, name } => write!(f, "container {name} ({id})"),
+            Leftover::Network(name) => write!(f, "network {name}"),
+            Leftover::Volume(name) => write!(f, "volume {name}"),
+            Leftover::Workspace { path, .. } => write!(f, "workspace {}", path.display()),
+            Leftover::Notes { path, .. } => write!(f, "notes {}", path.display()),
+        }
+    }
+}
+
+/// Find the docker containers, networks, and volumes created by past lesson checks and the
+/// workspaces in the current project for workshops that are no longer installed
+pub async fn find(status: &Status) -> Result<Vec<Leftover>, Error> {
+    let mut leftovers = Vec::new();
+    if let Some(docker) = docker_executable(status).await {
+        leftovers.extend(find_docker(&docker).await?);
+    }
+    leftovers.extend(find_workspaces()?);
+    Ok(leftovers)
+}
+
+/// Remove the leftovers, returning the ones that could not be removed with the reason
+pub async fn remove(
+    status: &mut Status,
+    leftovers: &[Leftover],
+) -> Result<Vec<(Leftover, String)>, Error> {
+    let docker = docker_executable(status).await;
+    let mut failed = Vec::new();
+    for leftover in leftovers {
+        let result = match (leftover, &docker) {
+            (Leftover::Container { id, .. }, Some(docker)) => {
+                run(docker, &["rm", "-f", id]).await.map(|_| ())
+            }
+            (Leftover::Network(name), Some(docker)) => {
+                run(docker, &["network", "rm", name]).await.map(|_| ())
+            }
+            (Leftover::Volume(name), Some(docker)) => {
+                run(docker, &["volume", "rm", name]).await.map(|_| ())
+            }
+            (Leftover::Container { .. } | Leftover::Network(_) | Leftover::Volume(_), None) => {
+                Err("docker was not found".to_string())
+            }
+            (Leftover::Workspace { workshop, path }, _) => {
+                status.forget_workshop(workshop);
+                std::fs::remove_dir_all(path).map_err(|e| e.to_string())
+            }
+            (Leftover::Notes { path, .. }, _) => {
+                std::fs::remove_dir_all(path).map_err(|e| e.to_string())
+            }
+        };
+        if let Err(reason) = result {
+            failed.push((leftover.clone(), reason));
+        }
+    }
+    status.save()?;
+    Ok(failed)
+}
+
+// the docker executable remembered from the last check, or the one found on the system
+async fn docker_executable(status: &Status) -> Option<String> {
+    match status.docker_compose_executable() {
+        Some(docker) => Some(docker.to_string()),
+        None => {
+            fs::application::find_docker_compose_executable(status.docker_compose_minimum_version())
+                .await
+                .ok()
+        }
+    }
+}
+
+// list the containers, networks, and volumes of the checks' compose project
+async fn find_docker(docker: &str) -> Result<Vec<Leftover>, Error> {
+    let label = format!("label=com.docker.compose.project={COMPOSE_PROJECT}");
+    let mut leftovers = Vec::new();
+
+    let containers = run(
+        docker,
+        &[
+            "ps",
+            "-a",
+            "--filter",
+            &label,
+            "--format",
+            "{{.ID}}\t{{.Names}}",
+        ],
+    )
+    .await
+    .map_err(Error::Command)?;
+    for line in containers {
+        if let Some((id, name)) = line.split_once('\t') {
+            leftovers.push(Leftover::Container {
+                id: id.to_string(),
+                name: name.to_string(),
+            });
+        }
+    }
+
+    // the checks create the network by hand so it may not carry the compose label
+    let mut networks = run(
+        docker,
+        &["network", "ls", "--filter", &label, "--format", "{{.Name}}"],
+    )
+    .await
+    .map_err(Error::Command)?;
+    let named = run(docker, &["network", "ls", "--format", "{{.Name}}"])
+        .await
+        .map_err(Error::Command)?;
+    networks.extend(named.into_iter().filter(|name| name == NETWORK));
+    networks.sort();
+    networks.dedup();
+    leftovers.extend(networks.into_iter().map(Leftover::Network));
+
+    let volumes = run(
+        docker,
+        &["volume", "ls", "--filter", &label, "--format", "{{.Name}}"],
+    )
+    .await
+    .map_err(Error::Command)?;
+    leftovers.extend(volumes.into_iter().map(Leftover::Volume));
+
+    Ok(leftovers)
+}
+
+// list the workspaces and notes in the `.workshops` directory for workshops that are no longer
+// installed
+fn find_workspaces() -> Result<Vec<Leftover>, Error> {
+    let Some(data_dir) = fs::workshops::data_dir() else {
+        return Ok(Vec::new());
+    };
+    let mut leftovers = Vec::new();
+    for workshop in fs::workshops::workshop_names(&data_dir)? {
+        if fs::application::workshop_dir(&workshop).is_ok() {
+            continue;
+        }
+        let notes = data_dir.join("notes").join(&workshop);
+        leftovers.push(Leftover::Workspace {
+            path: data_dir.join(&workshop),
+            workshop: workshop.clone(),
+        });
+        if notes.is_dir() {
+            leftovers.push(Leftover::Notes {
+                workshop,
+                path: notes,
+            });
+        }
+    }
+    Ok(leftovers)
+}
+
+// run a docker command, returning the non-empty lines of its output or the error it printed
+async fn run(docker: &str, args: &[&str]) -> Result<Vec<String>, String> {
+    debug!("Running: {docker} {}", args.join(" "));
+    let output = Command::new(docker)
+        .args(args)
+        .output()
+        .await
+        .map_err(|e| e.to_string())?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(str::to_string)
+        .collect())
+}
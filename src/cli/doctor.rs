@@ -0,0 +1,240 @@
+use crate::{
+    fs::{
+        self,
+        application::{self, Tool},
+    },
+    Error, Status,
+};
+use semver::Version;
+use std::{fmt, path::Path};
+
+/// The result of one of the doctor's checks
+#[derive(Clone, Debug)]
+pub struct Check {
+    /// what was checked
+    pub name: String,
+    /// did the check pass
+    pub ok: bool,
+    /// what was found
+    pub message: String,
+    /// how to fix the problem, if it can be fixed for the user
+    pub fix: Option<Fix>,
+}
+
+impl fmt::Display for Check {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mark = if self.ok { "ok" } else { "!!" };
+        write!(f, "[{mark}] {}: {}", self.name, self.message)
+    }
+}
+
+/// A problem the doctor can fix
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Fix {
+    /// remember the newly detected executable for the tool instead of the stale one
+    Executable(Tool, String),
+}
+
+impl fmt::Display for Fix {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Fix::Executable(tool, executable) => write!(f, "use {executable} for {tool}"),
+        }
+    }
+}
+
+/// Detect the tools again, check their versions against the minimums in the config and the ones
+/// the selected workshop requires, and check that the application directories can be written to
+pub async fn diagnose(status: &Status) -> Vec<Check> {
+    let tools = [
+        (
+            Tool::Python,
+            status.python_executable(),
+            status.python_minimum_version(),
+        ),
+        (
+            Tool::DockerCompose,
+            status.docker_compose_executable(),
+            status.docker_compose_minimum_version(),
+        ),
+        (
+            Tool::Git,
+            status.git_executable(),
+            status.git_minimum_version(),
+        ),
+    ];
+    // node, npm, and go are only detected for the workshops that need them
+    let language_tools = [
+        (
+            Tool::Node,
+            status.node_executable(),
+            status.node_minimum_version(),
+        ),
+        (
+            Tool::Npm,
+            status.npm_executable(),
+            status.npm_minimum_version(),
+        ),
+        (
+            Tool::Go,
+            status.go_executable(),
+            status.go_minimum_version(),
+        ),
+    ];
+    let language_tools = language_tools
+        .into_iter()
+        .filter(|(_, configured, _)| configured.is_some());
+    let mut checks = Vec::new();
+    for (tool, configured, minimum) in tools.into_iter().chain(language_tools) {
+        checks.push(check_tool(tool, configured, minimum).await);
+    }
+
+    if let Some(workshop) = status.workshop() {
+        let requires = match status.repository().workshop(workshop) {
+            Some(workshop_data) => workshop_data
+                .get_metadata(status.spoken_language())
+                .await
+                .map(|metadata| metadata.requires)
+                .unwrap_or_default(),
+            None => Default::default(),
+        };
+        let unmet = application::unmet_requirements(&requires, &status.tool_executables()).await;
+        checks.push(Check {
+            name: format!("Tools for {workshop}"),
+            ok: unmet.is_empty(),
+            message: if unmet.is_empty() {
+                "all of the required versions are installed".to_string()
+            } else {
+                unmet.join("; ")
+            },
+            fix: None,
+        });
+    }
+
+    let dirs = [
+        ("Data directory", application::data_dir()),
+        ("Config directory", application::config_dir()),
+        ("State directory", application::state_dir()),
+        ("Cache directory", application::cache_dir()),
+    ];
+    for (name, dir) in dirs {
+        checks.push(match dir {
+            Ok(dir) => check_dir(name, &dir),
+            Err(e) => Check {
+                name: name.to_string(),
+                ok: false,
+                message: e.to_string(),
+                fix: None,
+            },
+        });
+    }
+    if let Some(dir) = fs::workshops::data_dir() {
+        checks.push(check_dir("Project directory", &dir));
+    }
+    checks
+}
+
+/// Apply the fixes and save them
+pub fn fix(status: &mut Status, fixes: &[Fix]) -> Result<(), Error> {
+    for fix in fixes {
+        match fix {
+            Fix::Executable(Tool::Python, executable) => {
+                status.set_python_executable(executable, true)
+            }
+            Fix::Executable(Tool::DockerCompose, executable) => {
+                status.set_docker_compose_executable(executable, true)
+            }
+            Fix::Executable(Tool::Git, executable) => status.set_git_executable(executable, true),
+            Fix::Executable(Tool::Node, executable) => status.set_node_executable(executable, true),
+            Fix::Executable(Tool::Npm, executable) => status.set_npm_executable(executable, true),
+            Fix::Executable(Tool::Go, executable) => status.set_go_executable(executable, true),
+        }
+    }
+    status.save()
+}
+
+// check the remembered executable for the tool, and detect it again if it is missing, no longer
+// runs, or is too old
+async fn check_tool(tool: Tool, configured: Option<&str>, minimum: &str) -> Check {
+    let name = tool.to_string();
+    let Ok(minimum_version) = Version::parse(minimum) else {
+        return Check {
+            name,
+            ok: false,
+            message: format!("the minimum version {minimum} in the config is not valid"),
+            fix: None,
+        };
+    };
+
+    // the remembered executable is fine as long as it still runs and is new enough
+    let problem = match configured {
+        Some(executable) => match application::tool_version(tool, executable).await {
+            Some(version) if version >= minimum_version => {
+                return Check {
+                    name,
+                    ok: true,
+                    message: format!("{executable} (version {version})"),
+                    fix: None,
+                };
+            }
+            Some(version) => {
+                format!("{executable} is version {version}, below the minimum {minimum}")
+            }
+            None => format!("{executable} no longer runs"),
+        },
+        None => "not detected yet".to_string(),
+    };
+
+    let detected = match tool {
+        Tool::Python => application::find_python_executable(minimum).await.ok(),
+        Tool::DockerCompose => application::find_docker_compose_executable(minimum)
+            .await
+            .ok(),
+        Tool::Git => application::find_git_executable(minimum).await.ok(),
+        Tool::Node => application::find_node_executable(minimum).await.ok(),
+        Tool::Npm => application::find_npm_executable(minimum).await.ok(),
+        Tool::Go => application::find_go_executable(minimum).await.ok(),
+    };
+    match detected {
+        Some(executable) => {
+            let version = application::tool_version(tool, &executable)
+                .await
+                .map(|version| format!(" (version {version})"))
+                .unwrap_or_default();
+            Check {
+                name,
+                // a tool that was never detected is detected on the next start
+                ok: configured.is_none(),
+                message: format!("{problem}, found {executable}{version}"),
+                fix: configured
+                    .is_some()
+                    .then_some(Fix::Executable(tool, executable)),
+            }
+        }
+        None => Check {
+            name,
+            ok: false,
+            message: format!("{problem}, install {tool} {minimum} or newer"),
+            fix: None,
+        },
+    }
+}
+
+// check that files can be created in the directory
+fn check_dir(name: &str, dir: &Path) -> Check {
+    let (ok, message) = if !dir.exists() {
+        (true, format!("{} (not created yet)", dir.display()))
+    } else {
+        let probe = dir.join(".workshop-doctor");
+        match std::fs::write(&probe, "").and_then(|_| std::fs::remove_file(&probe)) {
+            Ok(()) => (true, dir.display().to_string()),
+            Err(e) => (false, format!("{} is not writable: {e}", dir.display())),
+        }
+    };
+    Check {
+        name: name.to_string(),
+        ok,
+        message,
+        fix: None,
+    }
+}
@@ -0,0 +1,197 @@
+use crate::{cli::LINK_SCHEME, Error};
+use std::path::{Path, PathBuf};
+use tokio::process::Command;
+use tracing::info;
+
+/// The name of the desktop entry handling `workshop://` links on Linux
+#[cfg(not(any(target_os = "macos", windows)))]
+const DESKTOP_FILE: &str = "workshop-url.desktop";
+
+/// Register the running `workshop` executable as the handler for `workshop://` links, started in
+/// the given project directory since that is where the progress is kept. Returns where the
+/// handler was registered.
+pub async fn register(project_dir: &Path) -> Result<PathBuf, Error> {
+    let executable = std::env::current_exe()?;
+    let scheme = LINK_SCHEME.trim_end_matches("://");
+    info!(
+        "Registering {} in {} for {scheme} links",
+        executable.display(),
+        project_dir.display()
+    );
+    register_handler(&executable, project_dir, scheme).await
+}
+
+// write a desktop entry for the scheme and make it the default handler
+#[cfg(not(any(target_os = "macos", windows)))]
+async fn register_handler(
+    executable: &Path,
+    project_dir: &Path,
+    scheme: &str,
+) -> Result<PathBuf, Error> {
+    let applications_dir = directories::BaseDirs::new()
+        .ok_or(crate::fs::Error::ApplicationDirsNotFound)?
+        .data_dir()
+        .join("applications");
+    let desktop_file = applications_dir.join(DESKTOP_FILE);
+    std::fs::create_dir_all(&applications_dir)?;
+    crate::fs::write_atomic(
+        &desktop_file,
+        desktop_entry(executable, project_dir, scheme),
+    )?;
+    let mime_type = format!("x-scheme-handler/{scheme}");
+    run("xdg-mime", &["default", DESKTOP_FILE, &mime_type]).await?;
+    // not every desktop has the database, xdg-mime is enough for the others
+    let _ = run(
+        "update-desktop-database",
+        &[applications_dir.to_string_lossy().as_ref()],
+    )
+    .await;
+    Ok(desktop_file)
+}
+
+// compile an AppleScript application that opens the links in a new Terminal window, since macOS
+// hands links to an application bundle in an event rather than as an argument
+#[cfg(target_os = "macos")]
+async fn register_handler(
+    executable: &Path,
+    project_dir: &Path,
+    scheme: &str,
+) -> Result<PathBuf, Error> {
+    let home = directories::BaseDirs::new()
+        .ok_or(crate::fs::Error::ApplicationDirsNotFound)?
+        .home_dir()
+        .to_path_buf();
+    let app = home.join("Applications").join("Workshop Links.app");
+    let script = crate::fs::application::cache_dir()?.join("workshop-links.applescript");
+    std::fs::create_dir_all(app.parent().unwrap_or(&home))?;
+    if let Some(parent) = script.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(&script, apple_script(executable, project_dir))?;
+    let app_path = app.to_string_lossy().to_string();
+    let compiled = run(
+        "osacompile",
+        &["-o", &app_path, script.to_string_lossy().as_ref()],
+    )
+    .await;
+    let _ = std::fs::remove_file(&script);
+    compiled?;
+
+    let plist = app.join("Contents").join("Info.plist");
+    let plist = plist.to_string_lossy();
+    let url_types = format!(
+        r#"[{{"CFBundleURLName":"io.libp2p.workshop","CFBundleURLSchemes":["{scheme}"]}}]"#
+    );
+    run(
+        "plutil",
+        &["-replace", "CFBundleURLTypes", "-json", &url_types, &plist],
+    )
+    .await?;
+    run(
+        "plutil",
+        &[
+            "-replace",
+            "CFBundleIdentifier",
+            "-string",
+            "io.libp2p.workshop.links",
+            &plist,
+        ],
+    )
+    .await?;
+    run(
+        "/System/Library/Frameworks/CoreServices.framework/Frameworks/LaunchServices.framework/Support/lsregister",
+        &["-f", &app_path],
+    )
+    .await?;
+    Ok(app)
+}
+
+// add the scheme to the classes of the current user in the registry
+#[cfg(windows)]
+async fn register_handler(
+    executable: &Path,
+    project_dir: &Path,
+    scheme: &str,
+) -> Result<PathBuf, Error> {
+    let key = format!(r"HKCU\Software\Classes\{scheme}");
+    // the link is handed to the executable directly, a shell would act on its `&` and `|`
+    let command = format!(
+        r#""{}" --project "{}" "%1""#,
+        executable.display(),
+        project_dir.display()
+    );
+    let command_key = format!(r"{key}\shell\open\command");
+    run("reg", &["add", &key, "/ve", "/d", "URL:Workshop", "/f"]).await?;
+    run("reg", &["add", &key, "/v", "URL Protocol", "/d", "", "/f"]).await?;
+    run("reg", &["add", &command_key, "/ve", "/d", &command, "/f"]).await?;
+    Ok(PathBuf::from(key))
+}
+
+// run the program and turn a failure into an error
+async fn run(program: &str, args: &[&str]) -> Result<(), Error> {
+    let output = Command::new(program)
+        .args(args)
+        .output()
+        .await
+        .map_err(|e| Error::Command(format!("{program} could not be run: {e}")))?;
+    if !output.status.success() {
+        return Err(Error::Command(format!(
+            "{program} failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(())
+}
+
+/// The desktop entry that opens the links of the scheme with the executable in a terminal
+pub fn desktop_entry(executable: &Path, project_dir: &Path, scheme: &str) -> String {
+    // the Exec key quotes arguments with double quotes and escapes these within them
+    let mut quoted = String::from("\"");
+    for c in executable.to_string_lossy().chars() {
+        if matches!(c, '"' | '`' | '$' | '\\') {
+            quoted.push('\\');
+        }
+        quoted.push(c);
+    }
+    quoted.push('"');
+    format!(
+        "[Desktop Entry]\nType=Application\nName=Workshop\nExec={quoted} %u\nPath={}\nTerminal=true\nNoDisplay=true\nMimeType=x-scheme-handler/{scheme};\n",
+        project_dir.display()
+    )
+}
+
+// the AppleScript that runs the executable on a link in a new Terminal window
+#[cfg(target_os = "macos")]
+fn apple_script(executable: &Path, project_dir: &Path) -> String {
+    // AppleScript strings escape quotes and backslashes
+    let quote = |path: &Path| {
+        format!(
+            "\"{}\"",
+            path.to_string_lossy()
+                .replace('\\', "\\\\")
+                .replace('"', "\\\"")
+        )
+    };
+    format!(
+        "on open location theURL\n\ttell application \"Terminal\"\n\t\tactivate\n\t\tdo script \"cd \" & quoted form of {} & \" && \" & quoted form of {} & \" \" & quoted form of theURL\n\tend tell\nend open location\n",
+        quote(project_dir),
+        quote(executable)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_desktop_entry() {
+        let entry = desktop_entry(
+            Path::new("/opt/my tools/workshop"),
+            Path::new("/home/student/project"),
+            "workshop",
+        );
+        assert!(entry.contains("Exec=\"/opt/my tools/workshop\" %u\n"));
+        assert!(entry.contains("Path=/home/student/project\n"));
+        assert!(entry.ends_with("MimeType=x-scheme-handler/workshop;\n"));
+    }
+}
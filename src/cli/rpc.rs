@@ -0,0 +1,265 @@
+use crate::{
+    cli::{self, Echo},
+    fs,
+    models::{lesson, template, Error as ModelError, WorkshopData},
+    Error, Status,
+};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::sync::Arc;
+use tokio::{
+    io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+    sync::mpsc,
+};
+use tokio_util::task::TaskTracker;
+
+// the JSON-RPC 2.0 error codes
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+const SERVER_ERROR: i64 = -32000;
+
+/// A JSON-RPC 2.0 request, or a notification when it has no id
+#[derive(Debug, Deserialize)]
+struct Request {
+    jsonrpc: String,
+    #[serde(default)]
+    id: Option<Value>,
+    method: String,
+    #[serde(default)]
+    params: Value,
+}
+
+/// The parameters naming a workshop and a lesson, defaulting to the selected ones
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct LessonParams {
+    workshop: Option<String>,
+    lesson: Option<String>,
+}
+
+type RpcError = (i64, String);
+
+impl From<Error> for (i64, String) {
+    fn from(e: Error) -> Self {
+        (SERVER_ERROR, e.to_string())
+    }
+}
+
+/// Serve JSON-RPC 2.0 requests read from stdin, one per line, writing the responses and
+/// notifications to stdout, one per line, until stdin is closed. This lets editor plugins run
+/// the workshop flow next to the student's code. The methods are:
+///
+/// - `workshops/list`: the installed workshops
+/// - `lessons/list` `{workshop}`: the lessons of a workshop and their status
+/// - `lesson/text` `{workshop, lesson}`: the title, status, and markdown text of a lesson
+/// - `lesson/check` `{workshop, lesson}`: check a lesson, sending a `check/output`
+///   notification `{id, line}` for every line of output before the result
+///
+/// Requests are handled concurrently so a running check doesn't hold up the others.
+pub async fn serve(status: Status) -> Result<(), Error> {
+    let status = Arc::new(status);
+    let (out, mut messages) = mpsc::unbounded_channel::<Value>();
+
+    // a single writer keeps the messages from interleaving
+    let writer = tokio::spawn(async move {
+        let mut stdout = tokio::io::stdout();
+        while let Some(message) = messages.recv().await {
+            let line = format!("{message}\n");
+            if stdout.write_all(line.as_bytes()).await.is_err() {
+                break;
+            }
+            let _ = stdout.flush().await;
+        }
+    });
+
+    let tasks = TaskTracker::new();
+    let mut lines = BufReader::new(tokio::io::stdin()).lines();
+    while let Some(line) = lines.next_line().await? {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let request = match parse_request(&line) {
+            Ok(request) => request,
+            Err(response) => {
+                let _ = out.send(response);
+                continue;
+            }
+        };
+        let status = status.clone();
+        let out = out.clone();
+        tasks.spawn(async move {
+            let result = handle(&status, &request, &out).await;
+            // notifications don't get a response
+            let Some(id) = request.id else {
+                return;
+            };
+            let response = match result {
+                Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+                Err((code, message)) => error_response(id, code, message),
+            };
+            let _ = out.send(response);
+        });
+    }
+
+    tasks.close();
+    tasks.wait().await;
+    drop(out);
+    writer.await?;
+    Ok(())
+}
+
+// parse a line into a request, or the error response to send for it
+fn parse_request(line: &str) -> Result<Request, Value> {
+    let value: Value = serde_json::from_str(line)
+        .map_err(|e| error_response(Value::Null, PARSE_ERROR, e.to_string()))?;
+    let id = value.get("id").cloned().unwrap_or(Value::Null);
+    let request: Request = serde_json::from_value(value)
+        .map_err(|e| error_response(id.clone(), INVALID_REQUEST, e.to_string()))?;
+    if request.jsonrpc != "2.0" {
+        return Err(error_response(
+            id,
+            INVALID_REQUEST,
+            "only JSON-RPC 2.0 is supported".to_string(),
+        ));
+    }
+    Ok(request)
+}
+
+fn error_response(id: Value, code: i64, message: String) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "error": { "code": code, "message": message } })
+}
+
+async fn handle(
+    status: &Status,
+    request: &Request,
+    out: &mpsc::UnboundedSender<Value>,
+) -> Result<Value, RpcError> {
+    match request.method.as_str() {
+        "workshops/list" => {
+            let workshops = fs::application::all_workshops()?;
+            let mut names = workshops.keys().collect::<Vec<_>>();
+            names.sort();
+            let mut list = Vec::new();
+            for name in names {
+                let metadata = workshops[name]
+                    .get_metadata(status.spoken_language())
+                    .await?;
+                list.push(json!({
+                    "name": name,
+                    "title": metadata.title,
+                    "difficulty": metadata.difficulty,
+                    "status": metadata.status,
+                }));
+            }
+            Ok(Value::Array(list))
+        }
+        "lessons/list" => {
+            let params = lesson_params(request)?;
+            let (name, workshop_data) = workshop(status, params.workshop)?;
+            let lessons = workshop_data
+                .get_lessons_data(status.spoken_language(), status.programming_language())
+                .await?;
+            let mut list = Vec::new();
+            for key in lesson::ordered_keys(&lessons).await? {
+                let metadata = lessons[&key].get_metadata().await?;
+                list.push(json!({
+                    "workshop": name,
+                    "name": key,
+                    "title": metadata.title,
+                    "status": metadata.status,
+                }));
+            }
+            Ok(Value::Array(list))
+        }
+        "lesson/text" => {
+            let params = lesson_params(request)?;
+            let lesson = params
+                .lesson
+                .or_else(|| status.lesson().map(String::from))
+                .ok_or(Error::from(ModelError::NoLessonSpecified))?;
+            let (name, workshop_data) = workshop(status, params.workshop)?;
+            let lessons = workshop_data
+                .get_lessons_data(status.spoken_language(), status.programming_language())
+                .await?;
+            let lesson_data = lessons
+                .get(&lesson)
+                .ok_or(Error::from(ModelError::NoLessonData(lesson.clone())))?;
+            let metadata = lesson_data.get_metadata().await?;
+            let text =
+                template::render(lesson_data.get_text().await?, &status.template_variables());
+            Ok(json!({
+                "workshop": name,
+                "name": lesson,
+                "title": metadata.title,
+                "status": metadata.status,
+                "text": text,
+            }))
+        }
+        "lesson/check" => {
+            let params = lesson_params(request)?;
+            // stream the output as notifications tied to the request
+            let (lines, mut output) = mpsc::unbounded_channel::<String>();
+            let id = request.id.clone().unwrap_or(Value::Null);
+            let notifications = out.clone();
+            let forwarder = tokio::spawn(async move {
+                while let Some(line) = output.recv().await {
+                    let _ = notifications.send(json!({
+                        "jsonrpc": "2.0",
+                        "method": "check/output",
+                        "params": { "id": id, "line": line },
+                    }));
+                }
+            });
+            let report =
+                cli::check_with_echo(status, params.workshop, params.lesson, Echo::Channel(lines))
+                    .await;
+            // the output goes out before the result
+            let _ = forwarder.await;
+            Ok(serde_json::to_value(report?).map_err(|e| (SERVER_ERROR, e.to_string()))?)
+        }
+        method => Err((METHOD_NOT_FOUND, format!("unknown method: {method}"))),
+    }
+}
+
+fn lesson_params(request: &Request) -> Result<LessonParams, RpcError> {
+    if request.params.is_null() {
+        return Ok(LessonParams::default());
+    }
+    serde_json::from_value(request.params.clone()).map_err(|e| (INVALID_PARAMS, e.to_string()))
+}
+
+// get the workshop from the current project, copying it into the project first like the check
+// does so that the lessons and their progress are the project's
+fn workshop(status: &Status, workshop: Option<String>) -> Result<(String, WorkshopData), Error> {
+    let name = workshop
+        .or_else(|| status.workshop().map(String::from))
+        .ok_or(ModelError::NoWorkshopSpecified)?;
+    if fs::workshops::load(&name).is_none() {
+        fs::workshops::init_data_dir(&name)?;
+    }
+    let workshop_data =
+        fs::workshops::load(&name).ok_or(ModelError::WorkshopNotFound(name.clone()))?;
+    Ok((name, workshop_data))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_request() {
+        let request =
+            parse_request(r#"{"jsonrpc":"2.0","id":1,"method":"workshops/list"}"#).unwrap();
+        assert_eq!(request.method, "workshops/list");
+        assert_eq!(request.id, Some(json!(1)));
+
+        let error = parse_request("not json").unwrap_err();
+        assert_eq!(error["error"]["code"], PARSE_ERROR);
+
+        let error = parse_request(r#"{"jsonrpc":"1.0","id":2,"method":"x"}"#).unwrap_err();
+        assert_eq!(error["error"]["code"], INVALID_REQUEST);
+        assert_eq!(error["id"], 2);
+    }
+}
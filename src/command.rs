@@ -1,34 +1,224 @@
 use crate::{
+    fs::{self, application::Tool},
+    log::LogBuffer,
     ui::tui::{self, screens, widgets::StatusMode},
     Error,
 };
-use std::path::Path;
+use std::{
+    collections::{BTreeMap, HashMap},
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    time::Duration,
+};
 use tokio::{
     io::{AsyncBufReadExt, BufReader},
     process::Command,
     sync::mpsc::Sender,
 };
 use tokio_util::sync::CancellationToken;
-use tracing::{debug, error};
+use tracing::{debug, error, info};
+
+/// The most lines of stdout and stderr kept in a command result, the earlier lines are dropped
+const MAX_CAPTURED_LINES: usize = 1000;
+
+/// The number of lines of stderr shown in the summary of a failed command
+const SUMMARY_STDERR_LINES: usize = 5;
 
 /// Result of command execution
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct CommandResult {
     pub success: bool,
     pub exit_code: i32,
     pub last_line: String,
+    /// the command and its arguments, empty when the result didn't come from running a command
+    pub argv: Vec<String>,
+    /// the last lines the command wrote to stdout
+    pub stdout: Vec<String>,
+    /// the last lines the command wrote to stderr
+    pub stderr: Vec<String>,
+    /// how long the command ran
+    pub duration: Duration,
+}
+
+impl CommandResult {
+    /// A summary of how the command ran, one line each for the command, the exit code and
+    /// duration, and the amount of output, followed by the end of stderr when it failed
+    pub fn summary(&self) -> Vec<String> {
+        if self.argv.is_empty() {
+            return Vec::new();
+        }
+        let mut summary = vec![
+            format!("$ {}", self.argv.join(" ")),
+            format!(
+                "exit code {} after {:.1}s",
+                self.exit_code,
+                self.duration.as_secs_f64()
+            ),
+            format!(
+                "{} lines on stdout, {} lines on stderr",
+                self.stdout.len(),
+                self.stderr.len()
+            ),
+        ];
+        if !self.success && !self.stderr.is_empty() {
+            summary.push("stderr:".to_string());
+            let skip = self.stderr.len().saturating_sub(SUMMARY_STDERR_LINES);
+            summary.extend(
+                self.stderr
+                    .iter()
+                    .skip(skip)
+                    .map(|line| format!("  {line}")),
+            );
+        }
+        summary
+    }
+}
+
+// keep the line, dropping the oldest one once there are too many
+fn capture(lines: &mut Vec<String>, line: &str) {
+    if lines.len() == MAX_CAPTURED_LINES {
+        lines.remove(0);
+    }
+    lines.push(line.to_string());
+}
+
+// get the installed workshop name, the last component of its directory
+fn path_name(workshop_dir: &Path) -> String {
+    workshop_dir
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default()
 }
 
 /// Generic command runner that sends output to the Log screen
 #[derive(Clone)]
 pub struct CommandRunner {
     event_sender: Sender<screens::Event>,
+    timeout: Option<Duration>,
+    log_buffer: Option<LogBuffer>,
+    env: Vec<(String, String)>,
+    /// the number of commands and checks running, shared by the clones
+    running: Arc<AtomicUsize>,
+    /// the docker compose project a check started and didn't see finish
+    compose: Arc<Mutex<Option<ComposeProject>>>,
+}
+
+/// A docker compose project started by a lesson check
+#[derive(Clone, Debug)]
+struct ComposeProject {
+    /// the docker executable
+    executable: String,
+    /// the lesson directory holding the compose file
+    lesson_dir: PathBuf,
+    /// the environment the compose file needs
+    env: Vec<(String, String)>,
+}
+
+// counts a command or check as running until it is dropped
+struct Running(Arc<AtomicUsize>);
+
+impl Running {
+    fn new(running: &Arc<AtomicUsize>) -> Self {
+        running.fetch_add(1, Ordering::SeqCst);
+        Self(running.clone())
+    }
+}
+
+impl Drop for Running {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
 }
 
 impl CommandRunner {
     /// Create a new CommandRunner
     pub fn new(event_sender: Sender<screens::Event>) -> Self {
-        Self { event_sender }
+        Self {
+            event_sender,
+            timeout: None,
+            log_buffer: None,
+            env: Vec::new(),
+            running: Arc::new(AtomicUsize::new(0)),
+            compose: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// Is a command or a check running
+    pub fn is_running(&self) -> bool {
+        self.running.load(Ordering::SeqCst) > 0
+    }
+
+    /// Take down the docker compose project of a check that was cancelled before it finished so
+    /// that no containers are left running
+    pub async fn clean_up(&self) -> Result<(), Error> {
+        let Some(project) = self.compose.lock().ok().and_then(|mut c| c.take()) else {
+            return Ok(());
+        };
+        info!(
+            "Stopping the containers started in {}",
+            project.lesson_dir.display()
+        );
+        let env = project
+            .env
+            .iter()
+            .map(|(key, value)| (key.as_str(), value.as_str()))
+            .collect::<Vec<_>>();
+        self.run_command_with_env(
+            &project.executable,
+            &[
+                "compose",
+                "--project-name",
+                "workshop",
+                "down",
+                "--remove-orphans",
+            ],
+            Some(&project.lesson_dir),
+            &env,
+            &CancellationToken::new(),
+            false,
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Set the maximum time a single command may run before it is killed
+    pub fn timeout(self, timeout: Option<Duration>) -> Self {
+        Self { timeout, ..self }
+    }
+
+    /// Add the command output to the given log buffer instead of sending an event for each line
+    pub fn log_buffer(self, log_buffer: LogBuffer) -> Self {
+        Self {
+            log_buffer: Some(log_buffer),
+            ..self
+        }
+    }
+
+    /// Set environment variables for every command, before the ones given to a single command
+    pub fn env(self, env: Vec<(String, String)>) -> Self {
+        Self { env, ..self }
+    }
+
+    // pass a line of command output on to the Log screen
+    async fn output(&self, line: String) -> Result<(), Error> {
+        match &self.log_buffer {
+            Some(log_buffer) => log_buffer.push_output(line),
+            None => {
+                self.event_sender
+                    .send(
+                        (
+                            Some(screens::Screens::Log),
+                            tui::Event::CommandOutput(line, None),
+                        )
+                            .into(),
+                    )
+                    .await?
+            }
+        }
+        Ok(())
     }
 
     /// Run a command and stream output to the Log screen
@@ -66,11 +256,17 @@ impl CommandRunner {
         token: &CancellationToken,
         trace: bool,
     ) -> Result<CommandResult, Error> {
+        let _running = Running::new(&self.running);
+
         // Build command
         let mut command = Command::new(cmd);
         command.args(args);
 
         // Set environment variables
+        for (key, value) in &self.env {
+            debug!("Setting environment variable: {key}={value}");
+            command.env(key, value);
+        }
         for (key, value) in env_vars {
             debug!("Setting environment variable: {key}={value}");
             command.env(key, value);
@@ -89,6 +285,9 @@ impl CommandRunner {
         // Send command info to log screen
         let cmd_info = format!("{cmd} {}", args.join(" "));
         debug!("Running command: {cmd_info}");
+        if let Some(log_buffer) = &self.log_buffer {
+            log_buffer.clear_output();
+        }
         self.event_sender
             .send(
                 (
@@ -125,11 +324,26 @@ impl CommandRunner {
         let mut stderr_lines = stderr_reader.lines();
 
         // Stream output until process completes or is cancelled
+        let started = std::time::Instant::now();
+        let mut stdout = Vec::new();
+        let mut stderr = Vec::new();
         let mut stdout_finished = false;
         let mut stderr_finished = false;
         let mut stdout_line: Option<String> = None;
         let mut stderr_line: Option<String> = None;
 
+        // Kill the command if it runs past the timeout
+        let deadline = self
+            .timeout
+            .map(|timeout| tokio::time::Instant::now() + timeout);
+        let timed_out = async {
+            match deadline {
+                Some(deadline) => tokio::time::sleep_until(deadline).await,
+                None => std::future::pending().await,
+            }
+        };
+        tokio::pin!(timed_out);
+
         let exit_status = loop {
             tokio::select! {
                 // Handle cancellation
@@ -138,18 +352,21 @@ impl CommandRunner {
                     return Err(Error::Command("Command cancelled".to_string()));
                 }
 
+                // Handle timeout
+                _ = &mut timed_out => {
+                    let _ = child.kill().await;
+                    error!("Command '{cmd_info}' timed out");
+                    return Err(Error::Command(format!("Command '{cmd_info}' timed out")));
+                }
+
                 // Read stdout line by line
                 line = stdout_lines.next_line(), if !stdout_finished => {
                     match line {
                         Ok(Some(line)) => {
+                            capture(&mut stdout, &line);
                             if let Some(prev_line) = stdout_line.take() {
                                 if trace {
-                                    self.event_sender
-                                        .send((
-                                            Some(screens::Screens::Log),
-                                            tui::Event::CommandOutput(prev_line, None)
-                                        ).into())
-                                        .await?;
+                                    self.output(prev_line).await?;
                                 }
                             }
                             stdout_line = Some(line);
@@ -169,14 +386,10 @@ impl CommandRunner {
                 line = stderr_lines.next_line(), if !stderr_finished => {
                     match line {
                         Ok(Some(line)) => {
+                            capture(&mut stderr, &line);
                             if let Some(prev_line) = stderr_line.take() {
                                 if trace {
-                                    self.event_sender
-                                        .send((
-                                            Some(screens::Screens::Log),
-                                            tui::Event::CommandOutput(prev_line, None)
-                                        ).into())
-                                        .await?;
+                                    self.output(prev_line).await?;
                                 }
                             }
                             stderr_line = Some(line);
@@ -207,20 +420,30 @@ impl CommandRunner {
             success,
             exit_code,
             last_line: last_line.clone(),
+            argv: std::iter::once(cmd)
+                .chain(args.iter().copied())
+                .map(String::from)
+                .collect(),
+            stdout,
+            stderr,
+            duration: started.elapsed(),
         };
 
         Ok(result)
     }
 
-    /// Run docker-compose up -d followed by python check.py
+    /// Run docker-compose up -d followed by the python check script of the lesson
     /// This is a convenience method for lesson solution checking
     pub async fn check_solution(
         &self,
         docker_compose_executable: &str,
         python_executable: &str,
         lesson_dir: &Path,
+        check_script: &str,
         token: &CancellationToken,
     ) -> Result<CommandResult, Error> {
+        let _running = Running::new(&self.running);
+
         // Calculate PROJECT_ROOT and LESSON_PATH for docker-compose environment
         let (project_root, lesson_path) = self.calculate_docker_env_paths(lesson_dir)?;
 
@@ -315,7 +538,18 @@ impl CommandRunner {
         )
         .await?;
 
-        // Run docker compose up --build
+        // Run docker compose up --build, remembering the project until it finishes so that it can
+        // be taken down if the check is cancelled
+        if let Ok(mut compose) = self.compose.lock() {
+            *compose = Some(ComposeProject {
+                executable: docker_compose_executable.to_string(),
+                lesson_dir: lesson_dir.to_path_buf(),
+                env: env_vars
+                    .iter()
+                    .map(|(key, value)| (key.to_string(), value.to_string()))
+                    .collect(),
+            });
+        }
         let docker_result = self
             .run_command_with_env(
                 docker_compose_executable.as_ref(),
@@ -334,6 +568,9 @@ impl CommandRunner {
                 false,
             )
             .await?;
+        if let Ok(mut compose) = self.compose.lock() {
+            *compose = None;
+        }
 
         if !docker_result.success {
             return Ok(docker_result);
@@ -372,10 +609,10 @@ impl CommandRunner {
         )
         .await?;
 
-        // Run python check.py
+        // Run the check script
         self.run_command(
             python_executable.as_ref(),
-            &["check.py"],
+            &[check_script],
             Some(lesson_dir),
             token,
             true,
@@ -383,13 +620,29 @@ impl CommandRunner {
         .await
     }
 
-    /// Run deps.py script for dependency checking
+    /// Check the tools against the minimum versions the workshop requires, then run the deps.py
+    /// script for dependency checking
     pub async fn check_dependencies(
         &self,
         python_executable: &str,
+        requires: &BTreeMap<Tool, String>,
+        executables: &HashMap<Tool, String>,
         deps_script: &Path,
         token: &CancellationToken,
     ) -> Result<CommandResult, Error> {
+        let unmet = fs::application::unmet_requirements(requires, executables).await;
+        if !unmet.is_empty() {
+            for message in unmet {
+                self.output(message).await?;
+            }
+            return Ok(CommandResult {
+                success: false,
+                exit_code: -1,
+                last_line: "Upgrade the tools listed above and check again".to_string(),
+                ..Default::default()
+            });
+        }
+
         let script_dir = deps_script
             .parent()
             .unwrap_or_else(|| std::path::Path::new("."));
@@ -404,31 +657,476 @@ impl CommandRunner {
         .await
     }
 
-    /// Run git to clone a repository to our application data directory
+    /// Check the tools a single lesson needs, first against the minimum versions the lesson
+    /// requires and then with the lesson's own deps.py script if it has one
+    pub async fn check_lesson_dependencies(
+        &self,
+        python_executable: &str,
+        requires: &BTreeMap<Tool, String>,
+        executables: &HashMap<Tool, String>,
+        deps_script: Option<&Path>,
+        token: &CancellationToken,
+    ) -> Result<CommandResult, Error> {
+        match deps_script {
+            Some(deps_script) => {
+                self.check_dependencies(
+                    python_executable,
+                    requires,
+                    executables,
+                    deps_script,
+                    token,
+                )
+                .await
+            }
+            None => {
+                let unmet = fs::application::unmet_requirements(requires, executables).await;
+                let success = unmet.is_empty();
+                for message in unmet {
+                    self.output(message).await?;
+                }
+                Ok(CommandResult {
+                    success,
+                    exit_code: if success { 0 } else { -1 },
+                    last_line: if success {
+                        "The tools for the lesson are installed".to_string()
+                    } else {
+                        "Upgrade the tools listed above and check again".to_string()
+                    },
+                    ..Default::default()
+                })
+            }
+        }
+    }
+
+    /// Run the workshop's setup script in the project directory, with Python for setup.py and
+    /// the shell for setup.sh
+    pub async fn run_setup(
+        &self,
+        python_executable: &str,
+        setup_script: &Path,
+        project_dir: &Path,
+        token: &CancellationToken,
+    ) -> Result<CommandResult, Error> {
+        let interpreter = match setup_script.extension().and_then(|ext| ext.to_str()) {
+            Some("py") => python_executable,
+            _ => "sh",
+        };
+        self.run_command(
+            interpreter,
+            &[setup_script.to_str().unwrap()],
+            Some(project_dir),
+            token,
+            true,
+        )
+        .await
+    }
+
+    /// Install a workshop from the given source into the application data directory. Directories
+    /// are copied, archives are unpacked, and git repositories are cloned and then checked
+    /// against the trusted signers. The content manifest is recorded for every install.
+    pub async fn install(
+        &self,
+        source: &fs::install::Source,
+        git_executable: Option<&str>,
+        trusted_signers: &[String],
+        require_signatures: bool,
+        token: &CancellationToken,
+    ) -> Result<CommandResult, Error> {
+        let data_dir = fs::application::data_dir()?;
+        match source {
+            fs::install::Source::Directory(_) | fs::install::Source::Archive(_)
+                if require_signatures =>
+            {
+                Ok(CommandResult {
+                    success: false,
+                    exit_code: -1,
+                    last_line:
+                        "Only signed git workshops can be installed when signatures are required"
+                            .to_string(),
+                    ..Default::default()
+                })
+            }
+            fs::install::Source::Directory(path) => {
+                debug!("Attempting to copy the workshop from: {}", path.display());
+                let installed =
+                    fs::install::install_directory(path, &data_dir).and_then(|target| {
+                        fs::install::Manifest::record(&target)?;
+                        // there is no signature to check on a copy
+                        fs::install::Trust::Unverified.save(path_name(&target))?;
+                        Ok(target)
+                    });
+                Ok(match installed {
+                    Ok(target) => CommandResult {
+                        success: true,
+                        exit_code: 0,
+                        last_line: format!("Installed workshop to {}", target.display()),
+                        ..Default::default()
+                    },
+                    Err(e) => CommandResult {
+                        success: false,
+                        exit_code: -1,
+                        last_line: format!("Workshop install failed: {e}"),
+                        ..Default::default()
+                    },
+                })
+            }
+            fs::install::Source::Git(url, rev) => {
+                debug!("Attempting to clone the workshop from: {url} at {rev:?}");
+                let git_exe = git_executable.ok_or(fs::Error::NoGitExecutable)?;
+                let name = source
+                    .name()
+                    .ok_or_else(|| fs::Error::InvalidInstallSource(url.clone()))?;
+                let result = self
+                    .install_workshop(git_exe, url, rev.as_deref(), &name, &data_dir, token)
+                    .await?;
+                if !result.success {
+                    return Ok(result);
+                }
+                let workshop_dir = data_dir.join(&name);
+
+                // check the signature on the installed commit
+                let trust =
+                    fs::install::Trust::verify(git_exe, &workshop_dir, trusted_signers).await?;
+                info!("Workshop {} trust: {trust:?}", workshop_dir.display());
+                if require_signatures && !matches!(trust, fs::install::Trust::Verified(_)) {
+                    std::fs::remove_dir_all(&workshop_dir)?;
+                    return Ok(CommandResult {
+                        success: false,
+                        exit_code: -1,
+                        last_line: "Workshop is not signed by a trusted signer".to_string(),
+                        ..Default::default()
+                    });
+                }
+                trust.save(&name)?;
+                fs::install::Manifest::record(&workshop_dir)?;
+
+                // record the commit the pinned revision resolved to
+                if let Some(rev) = rev {
+                    let commit =
+                        fs::install::git_output(git_exe, &workshop_dir, &["rev-parse", "HEAD"])
+                            .await?;
+                    info!("Pinned {} to {rev} ({commit})", workshop_dir.display());
+                    fs::install::Pin {
+                        rev: rev.clone(),
+                        commit,
+                    }
+                    .save(&workshop_dir)?;
+                }
+                Ok(result)
+            }
+            fs::install::Source::Archive(_) => {
+                debug!("Attempting to unpack the workshop from: {source:?}");
+                self.install_workshop_archive(source, &data_dir, token)
+                    .await
+            }
+        }
+    }
+
+    /// Run git to clone a repository into the named directory of our application data directory,
+    /// optionally checking out the given tag, branch, or commit
     pub async fn install_workshop(
         &self,
         git_executable: &str,
         repo_url: &str,
+        rev: Option<&str>,
+        name: &str,
         data_dir: &Path,
         token: &CancellationToken,
     ) -> Result<CommandResult, Error> {
         debug!(
-            "Running '{} clone {}' into '{}'",
+            "Running '{} clone {}' at {:?} into '{}'",
             git_executable,
             repo_url,
-            data_dir.display()
+            rev,
+            data_dir.join(name).display()
         );
 
+        // git would take a URL or revision starting with a dash for an option
+        if repo_url.starts_with('-') || rev.is_some_and(|rev| rev.starts_with('-')) {
+            return Err(fs::Error::InvalidInstallSource(repo_url.to_string()).into());
+        }
+        let mut args = vec!["clone", "--depth", "1"];
+        if let Some(rev) = rev {
+            args.extend(["--branch", rev]);
+        }
+        args.extend(["--", repo_url, name]);
+
+        let result = self
+            .run_command(git_executable.as_ref(), &args, Some(data_dir), token, true)
+            .await?;
+        match rev {
+            // --branch only takes tags and branches, so fetch a commit on its own
+            Some(rev) if !result.success && fs::install::is_commit(rev) => {
+                self.fetch_commit(git_executable, repo_url, rev, &data_dir.join(name), token)
+                    .await
+            }
+            _ => Ok(result),
+        }
+    }
+
+    // run git to fetch only the given commit of a repository into a new directory and check it
+    // out
+    async fn fetch_commit(
+        &self,
+        git_executable: &str,
+        repo_url: &str,
+        commit: &str,
+        workshop_dir: &Path,
+        token: &CancellationToken,
+    ) -> Result<CommandResult, Error> {
+        debug!("Fetching commit {commit} of {repo_url}");
+        if workshop_dir.exists() {
+            std::fs::remove_dir_all(workshop_dir)?;
+        }
+        std::fs::create_dir_all(workshop_dir)?;
+        let steps: [&[&str]; 4] = [
+            &["init", "--quiet"],
+            &["remote", "add", "--", "origin", repo_url],
+            &["fetch", "--depth", "1", "--", "origin", commit],
+            &["checkout", "--quiet", "FETCH_HEAD"],
+        ];
+        let mut result = CommandResult::default();
+        for args in steps {
+            result = self
+                .run_command(git_executable, args, Some(workshop_dir), token, true)
+                .await?;
+            if !result.success {
+                std::fs::remove_dir_all(workshop_dir)?;
+                break;
+            }
+        }
+        Ok(result)
+    }
+
+    /// Run git to reset an installed workshop back to the commit it is pinned to
+    pub async fn reset_workshop(
+        &self,
+        git_executable: &str,
+        workshop_dir: &Path,
+        commit: &str,
+        token: &CancellationToken,
+    ) -> Result<CommandResult, Error> {
         self.run_command(
             git_executable.as_ref(),
-            &["clone", "--depth", "1", repo_url],
-            Some(data_dir),
+            &["reset", "--hard", commit],
+            Some(workshop_dir),
             token,
             true,
         )
         .await
     }
 
+    /// Run git to pull the latest changes for an installed workshop, then check the signature on
+    /// the pulled commit and record its content manifest. An update that isn't signed by a trusted
+    /// signer is rolled back when signatures are required.
+    pub async fn update_workshop(
+        &self,
+        git_executable: &str,
+        workshop_dir: &Path,
+        trusted_signers: &[String],
+        require_signatures: bool,
+        token: &CancellationToken,
+    ) -> Result<CommandResult, Error> {
+        debug!(
+            "Running '{} pull --ff-only' in '{}'",
+            git_executable,
+            workshop_dir.display()
+        );
+
+        let before =
+            fs::install::git_output(git_executable, workshop_dir, &["rev-parse", "HEAD"]).await?;
+        let result = self
+            .run_command(
+                git_executable.as_ref(),
+                &["pull", "--ff-only"],
+                Some(workshop_dir),
+                token,
+                true,
+            )
+            .await?;
+        if !result.success {
+            return Ok(result);
+        }
+
+        // the pulled commit has to be as trusted as the installed one was
+        let trust =
+            fs::install::Trust::verify(git_executable, workshop_dir, trusted_signers).await?;
+        info!("Workshop {} trust: {trust:?}", workshop_dir.display());
+        if require_signatures && !matches!(trust, fs::install::Trust::Verified(_)) {
+            self.reset_workshop(git_executable, workshop_dir, &before, token)
+                .await?;
+            return Ok(CommandResult {
+                success: false,
+                exit_code: -1,
+                last_line: "Workshop update is not signed by a trusted signer".to_string(),
+                ..Default::default()
+            });
+        }
+        trust.save(path_name(workshop_dir))?;
+
+        // the pulled content is the new baseline for integrity checks
+        fs::install::Manifest::record(workshop_dir)?;
+        Ok(result)
+    }
+
+    /// Run docker compose to pull the images of the environment a lesson check runs in, ahead of
+    /// the first check. The images the lesson builds are left for the check to build.
+    pub async fn pull_images(
+        &self,
+        docker_compose_executable: &str,
+        workshop_dir: &Path,
+        lesson_dir: &Path,
+        token: &CancellationToken,
+    ) -> Result<CommandResult, Error> {
+        // the compose file only needs the paths to be set to be read, nothing is mounted
+        let lesson_path = lesson_dir
+            .strip_prefix(workshop_dir)
+            .unwrap_or(lesson_dir)
+            .to_string_lossy()
+            .to_string();
+        let project_root = workshop_dir.to_string_lossy().to_string();
+        let env_vars = [
+            ("PROJECT_ROOT", project_root.as_str()),
+            ("LESSON_PATH", lesson_path.as_str()),
+        ];
+
+        self.run_command_with_env(
+            docker_compose_executable,
+            &[
+                "compose",
+                "--project-name",
+                "workshop",
+                "pull",
+                "--ignore-buildable",
+            ],
+            Some(lesson_dir),
+            &env_vars,
+            token,
+            true,
+        )
+        .await
+    }
+
+    /// Download, if remote, and unpack a workshop archive into our application data directory,
+    /// recording the content manifest of the unpacked workshop
+    pub async fn install_workshop_archive(
+        &self,
+        source: &fs::install::Source,
+        data_dir: &Path,
+        token: &CancellationToken,
+    ) -> Result<CommandResult, Error> {
+        let fs::install::Source::Archive(location) = source else {
+            return Err(fs::Error::InvalidInstallSource(format!("{source:?}")).into());
+        };
+
+        if location.starts_with("http://") {
+            return Err(fs::Error::InvalidInstallSource(format!(
+                "{location} is not downloaded over https"
+            ))
+            .into());
+        }
+
+        // download the archive into the cache directory
+        let download = fs::application::cache_dir()?.join(format!(
+            ".{}.download",
+            source.name().unwrap_or_else(|| "workshop".to_string())
+        ));
+        let archive = if source.is_remote() {
+            let result = self
+                .run_command(
+                    "curl",
+                    &[
+                        "-L",
+                        "--fail",
+                        "--proto",
+                        "=https",
+                        "--proto-redir",
+                        "=https",
+                        "-o",
+                        download.to_string_lossy().as_ref(),
+                        location,
+                    ],
+                    Some(data_dir),
+                    token,
+                    true,
+                )
+                .await?;
+            if !result.success {
+                let _ = std::fs::remove_file(&download);
+                return Ok(result);
+            }
+            download.clone()
+        } else {
+            std::path::PathBuf::from(shellexpand::tilde(location).as_ref())
+        };
+
+        let kind = fs::install::ArchiveKind::of(location);
+        let unpacked = self.unpack_archive(kind, &archive, data_dir, token).await;
+        let _ = std::fs::remove_file(&download);
+        unpacked
+    }
+
+    // unpack the archive into a temporary directory next to the installed workshops and move
+    // its one top-level directory into place
+    async fn unpack_archive(
+        &self,
+        kind: fs::install::ArchiveKind,
+        archive: &Path,
+        data_dir: &Path,
+        token: &CancellationToken,
+    ) -> Result<CommandResult, Error> {
+        // make sure nothing gets unpacked outside of the data directory
+        let entries = fs::install::list_archive(kind, archive).await?;
+        fs::install::check_archive_entries(&entries)?;
+        let root = fs::install::archive_root(&entries)?;
+        let target = data_dir.join(&root);
+        if target.exists() {
+            return Err(fs::Error::WorkshopAlreadyInstalled(target.display().to_string()).into());
+        }
+
+        let unpack_dir = data_dir.join(format!(".{root}.unpack"));
+        if unpack_dir.exists() {
+            std::fs::remove_dir_all(&unpack_dir)?;
+        }
+        std::fs::create_dir_all(&unpack_dir)?;
+        debug!(
+            "Unpacking '{}' into '{}'",
+            archive.display(),
+            unpack_dir.display()
+        );
+        let archive = archive.to_string_lossy();
+        let unpack_to = unpack_dir.to_string_lossy();
+        let (program, args) = match kind {
+            fs::install::ArchiveKind::Tar => {
+                ("tar", ["-xf", archive.as_ref(), "-C", unpack_to.as_ref()])
+            }
+            fs::install::ArchiveKind::Zip => {
+                ("unzip", ["-q", archive.as_ref(), "-d", unpack_to.as_ref()])
+            }
+        };
+        let result = self
+            .run_command(program, &args, Some(data_dir), token, true)
+            .await;
+        let moved = match result {
+            Ok(result) if result.success => {
+                std::fs::rename(unpack_dir.join(&root), &target)
+                    .map_err(Error::from)
+                    .and_then(|_| {
+                        fs::install::Manifest::record(&target)?;
+                        // there is no signature to check on an archive
+                        fs::install::Trust::Unverified.save(&root)?;
+                        Ok(CommandResult {
+                            last_line: format!("Installed workshop to {}", target.display()),
+                            ..result
+                        })
+                    })
+            }
+            other => other,
+        };
+        let _ = std::fs::remove_dir_all(&unpack_dir);
+        moved
+    }
+
     /// Calculate PROJECT_ROOT and LESSON_PATH environment variables for docker-compose
     fn calculate_docker_env_paths(&self, lesson_dir: &Path) -> Result<(String, String), Error> {
         // Find the .workshops directory by going up from lesson_dir
@@ -466,3 +1164,32 @@ impl CommandRunner {
         ))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_summary() {
+        let mut result = CommandResult {
+            success: false,
+            exit_code: 2,
+            last_line: "boom".to_string(),
+            argv: vec!["python3".to_string(), "check.py".to_string()],
+            stdout: vec!["checking".to_string()],
+            stderr: (1..=7).map(|n| format!("error {n}")).collect(),
+            duration: Duration::from_millis(1500),
+        };
+        let summary = result.summary();
+        assert_eq!(summary[0], "$ python3 check.py");
+        assert_eq!(summary[1], "exit code 2 after 1.5s");
+        assert_eq!(summary[2], "1 lines on stdout, 7 lines on stderr");
+        assert_eq!(summary[3], "stderr:");
+        assert_eq!(summary[4], "  error 3");
+        assert_eq!(summary.len(), 9);
+
+        result.success = true;
+        assert_eq!(result.summary().len(), 3);
+        assert!(CommandResult::default().summary().is_empty());
+    }
+}
@@ -1,6 +1,7 @@
 use crate::{
     fs,
     languages::{programming, spoken},
+    ui::tui::palette::{ColorVision, Theme},
     Error,
 };
 use serde::{Deserialize, Serialize};
@@ -8,6 +9,7 @@ use tracing::info;
 
 /// Represents the application configuration
 #[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default)]
 pub struct Config {
     python_minumum_version: String,
     python_executable: Option<String>,
@@ -15,8 +17,26 @@ pub struct Config {
     docker_compose_executable: Option<String>,
     git_executable: Option<String>,
     git_minimum_version: String,
+    node_executable: Option<String>,
+    node_minimum_version: String,
+    npm_executable: Option<String>,
+    npm_minimum_version: String,
+    go_executable: Option<String>,
+    go_minimum_version: String,
     spoken_language: Option<spoken::Code>,
     programming_language: Option<programming::Code>,
+    relay_addr: String,
+    command_timeout: u64,
+    catalog_url: String,
+    trusted_signers: Vec<String>,
+    require_signatures: bool,
+    progress_remote: String,
+    ascii: Option<bool>,
+    theme: Theme,
+    color_vision: ColorVision,
+    reduced_motion: bool,
+    student_name: Option<String>,
+    badge_service: String,
 }
 
 impl Default for Config {
@@ -28,8 +48,27 @@ impl Default for Config {
             docker_compose_executable: None,
             git_executable: None,
             git_minimum_version: "2.39.0".to_string(),
+            node_executable: None,
+            node_minimum_version: "18.0.0".to_string(),
+            npm_executable: None,
+            npm_minimum_version: "9.0.0".to_string(),
+            go_executable: None,
+            go_minimum_version: "1.21.0".to_string(),
             spoken_language: None,
             programming_language: None,
+            relay_addr: "/ip4/127.0.0.1/tcp/4001".to_string(),
+            command_timeout: 1800,
+            catalog_url: "https://raw.githubusercontent.com/libp2p/workshop/main/catalog.json"
+                .to_string(),
+            trusted_signers: Vec::default(),
+            require_signatures: false,
+            progress_remote: String::default(),
+            ascii: None,
+            theme: Theme::Auto,
+            color_vision: ColorVision::Normal,
+            reduced_motion: false,
+            student_name: None,
+            badge_service: "https://img.shields.io/badge".to_string(),
         }
     }
 }
@@ -53,7 +92,7 @@ impl Config {
     /// Save the config to a file
     pub fn save(&self) -> Result<(), Error> {
         let config_path = fs::application::config_dir()?.join("config.yaml");
-        serde_yaml::to_writer(std::fs::File::create(&config_path).unwrap(), &self)?;
+        fs::write_atomic(&config_path, serde_yaml::to_string(&self)?)?;
         info!("Config saved to: {}", config_path.display());
         Ok(())
     }
@@ -88,6 +127,36 @@ impl Config {
         &self.git_minimum_version
     }
 
+    /// Get the preferred Node.js executable
+    pub fn node_executable(&self) -> Option<String> {
+        self.node_executable.clone()
+    }
+
+    /// Get the minimum required Node.js version
+    pub fn node_minimum_version(&self) -> &str {
+        &self.node_minimum_version
+    }
+
+    /// Get the preferred npm executable
+    pub fn npm_executable(&self) -> Option<String> {
+        self.npm_executable.clone()
+    }
+
+    /// Get the minimum required npm version
+    pub fn npm_minimum_version(&self) -> &str {
+        &self.npm_minimum_version
+    }
+
+    /// Get the preferred Go executable
+    pub fn go_executable(&self) -> Option<String> {
+        self.go_executable.clone()
+    }
+
+    /// Get the minimum required Go version
+    pub fn go_minimum_version(&self) -> &str {
+        &self.go_minimum_version
+    }
+
     /// Get the preferred spoken language
     pub fn spoken_language(&self) -> Option<spoken::Code> {
         self.spoken_language
@@ -98,6 +167,66 @@ impl Config {
         self.programming_language
     }
 
+    /// Get the relay multiaddr made available to lesson text
+    pub fn relay_addr(&self) -> &str {
+        &self.relay_addr
+    }
+
+    /// Get the maximum number of seconds a command may run, zero means no limit
+    pub fn command_timeout(&self) -> u64 {
+        self.command_timeout
+    }
+
+    /// Get the URL of the workshop catalog index
+    pub fn catalog_url(&self) -> &str {
+        &self.catalog_url
+    }
+
+    /// Get the trusted workshop signers in the ssh allowed signers format
+    pub fn trusted_signers(&self) -> &[String] {
+        &self.trusted_signers
+    }
+
+    /// Get whether workshops without a trusted signature are rejected at install time
+    pub fn require_signatures(&self) -> bool {
+        self.require_signatures
+    }
+
+    /// Get the git remote the progress is synced with, if syncing is enabled
+    pub fn progress_remote(&self) -> Option<&str> {
+        Some(self.progress_remote.as_str()).filter(|remote| !remote.is_empty())
+    }
+
+    /// Get whether to render with ASCII glyphs only, `None` means detect it from the terminal
+    pub fn ascii(&self) -> Option<bool> {
+        self.ascii
+    }
+
+    /// Get the color theme
+    pub fn theme(&self) -> Theme {
+        self.theme
+    }
+
+    /// Get the color vision the status colors are adjusted for
+    pub fn color_vision(&self) -> ColorVision {
+        self.color_vision
+    }
+
+    /// Should the spinners and other animations be turned off
+    pub fn reduced_motion(&self) -> bool {
+        self.reduced_motion
+    }
+
+    /// Get the name printed on completion certificates
+    pub fn student_name(&self) -> Option<&str> {
+        self.student_name.as_deref()
+    }
+
+    /// Get the base URL of the badge service, empty means no badges
+    pub fn badge_service(&self) -> &str {
+        &self.badge_service
+    }
+
     /// Set the preferred Python executable
     pub fn set_python_executable(&mut self, python_executable: &str) {
         self.python_executable = Some(python_executable.to_string());
@@ -113,6 +242,21 @@ impl Config {
         self.git_executable = Some(git_executable.to_string());
     }
 
+    /// Set the preferred Node.js executable
+    pub fn set_node_executable(&mut self, node_executable: &str) {
+        self.node_executable = Some(node_executable.to_string());
+    }
+
+    /// Set the preferred npm executable
+    pub fn set_npm_executable(&mut self, npm_executable: &str) {
+        self.npm_executable = Some(npm_executable.to_string());
+    }
+
+    /// Set the preferred Go executable
+    pub fn set_go_executable(&mut self, go_executable: &str) {
+        self.go_executable = Some(go_executable.to_string());
+    }
+
     /// Set the spoken language
     pub fn set_spoken_language(&mut self, spoken_language: Option<spoken::Code>) {
         self.spoken_language = spoken_language;
@@ -1,3 +1,6 @@
+use crate::{fs, models};
+use std::fmt;
+
 /// Errors generated from this crate
 #[derive(Debug, thiserror::Error)]
 #[non_exhaustive]
@@ -30,10 +33,6 @@ pub enum Error {
     #[error(transparent)]
     Fs(#[from] crate::fs::Error),
 
-    /// Status mutex lock error
-    #[error("Status mutex lock error: {0}")]
-    StatusLock(String),
-
     /// TUI error
     #[error("TUI error: {0}")]
     Tui(String),
@@ -50,3 +49,178 @@ pub enum Error {
     #[error("Initial events failed")]
     InitialEvents,
 }
+
+/// What an error is about, the title of the dialog showing it
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Category {
+    /// a tool the workshops need isn't installed
+    MissingTool,
+    /// docker isn't running or can't be reached
+    Docker,
+    /// something couldn't be downloaded
+    Network,
+    /// the workshop files are missing or not valid
+    Workshop,
+    /// a file couldn't be read or written
+    Files,
+    /// a command failed to run
+    Command,
+    /// something went wrong inside the app
+    Internal,
+}
+
+impl fmt::Display for Category {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let title = match self {
+            Category::MissingTool => "Missing Tool",
+            Category::Docker => "Docker Problem",
+            Category::Network => "Network Problem",
+            Category::Workshop => "Workshop Problem",
+            Category::Files => "File Problem",
+            Category::Command => "Command Failed",
+            Category::Internal => "Internal Error",
+        };
+        write!(f, "{title}")
+    }
+}
+
+/// An error as the student sees it: what it is about, what went wrong, and what to do about it
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Problem {
+    /// what the error is about
+    pub category: Category,
+    /// what went wrong
+    pub message: String,
+    /// what to do about it, if anything helps
+    pub remediation: Option<String>,
+}
+
+impl Error {
+    /// What the error is about
+    pub fn category(&self) -> Category {
+        match self {
+            Error::Fs(e) if e.tool().is_some() => Category::MissingTool,
+            Error::Fs(fs::Error::Load(..) | fs::Error::LoadTimeout(_)) | Error::Io(_) => {
+                Category::Files
+            }
+            Error::Fs(fs::Error::ApplicationDirsNotFound) | Error::ProjectDirs(_) => {
+                Category::Files
+            }
+            Error::Models(models::Error::CatalogFetchFailed(_)) => Category::Network,
+            Error::Fs(_) | Error::Models(_) | Error::Languages(_) | Error::YamlParsing(_) => {
+                Category::Workshop
+            }
+            Error::Command(message) if docker_not_running(message) => Category::Docker,
+            Error::Command(_) => Category::Command,
+            Error::TokioJoin(_) | Error::TokioChannel(_) | Error::Tui(_) | Error::InitialEvents => {
+                Category::Internal
+            }
+        }
+    }
+
+    /// What the student can do about the error, if anything helps
+    pub fn remediation(&self) -> Option<String> {
+        let remediation = match self {
+            Error::Fs(e) => match e {
+                _ if e.tool().is_some() => {
+                    let tool = e.tool()?;
+                    return Some(format!(
+                        "Install {tool} from {} and start workshop again.",
+                        tool.download_url()
+                    ));
+                }
+                fs::Error::Load(..) => {
+                    "Check that the file exists and can be read; reinstalling the workshop restores its files."
+                }
+                fs::Error::LoadTimeout(_) => {
+                    "The disk is busy or slow; wait a moment and try again."
+                }
+                fs::Error::InvalidInstallSource(_) => {
+                    "Install from a git URL, a workshop directory, or a .zip or .tar.gz archive."
+                }
+                fs::Error::WorkshopAlreadyInstalled(_) => {
+                    "Update the installed workshop instead, or uninstall it first."
+                }
+                fs::Error::UnsafeArchiveEntry(_) => {
+                    "The archive tries to write outside of the workshop; only install archives you trust."
+                }
+                _ => return None,
+            },
+            Error::Models(models::Error::CatalogFetchFailed(_)) => {
+                "Check the internet connection and try again."
+            }
+            Error::Models(models::Error::InvalidCatalog(_)) => {
+                "The catalog is being updated or is broken; try again later."
+            }
+            Error::Models(_) => {
+                "Update or reinstall the workshop to get the missing files, or tell its authors."
+            }
+            Error::YamlParsing(_) => {
+                "A workshop file isn't valid YAML; update the workshop or tell its authors."
+            }
+            Error::Io(e) if e.kind() == std::io::ErrorKind::PermissionDenied => {
+                "Check the permissions of the workshop data directory."
+            }
+            Error::Io(e) if e.kind() == std::io::ErrorKind::StorageFull => {
+                "The disk is full; free some space, for example with `workshop clean`."
+            }
+            Error::Command(message) if docker_not_running(message) => {
+                "Docker isn't running; start Docker Desktop, or the docker service on Linux, and try again."
+            }
+            Error::Command(message) if message.ends_with("timed out") => {
+                "The command took too long; a slow network or a busy machine can cause this, try again."
+            }
+            _ if self.category() == Category::Internal => {
+                "This is a bug; please report it with the log attached."
+            }
+            _ => return None,
+        };
+        Some(remediation.to_string())
+    }
+
+    /// The error as the student sees it
+    pub fn problem(&self) -> Problem {
+        Problem {
+            category: self.category(),
+            message: self.to_string(),
+            remediation: self.remediation(),
+        }
+    }
+}
+
+// does the output say that the docker daemon can't be reached
+fn docker_not_running(message: &str) -> bool {
+    let message = message.to_lowercase();
+    message.contains("cannot connect to the docker daemon")
+        || message.contains("is the docker daemon running")
+        || message.contains("docker desktop is not running")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_problem() {
+        let problem = Error::Fs(fs::Error::NoDockerComposeExecutable).problem();
+        assert_eq!(problem.category, Category::MissingTool);
+        assert!(problem
+            .remediation
+            .is_some_and(|remediation| remediation.contains("https://docs.docker.com")));
+
+        let daemon = Error::Command(
+            "Cannot connect to the Docker daemon at unix:///var/run/docker.sock".to_string(),
+        );
+        assert_eq!(daemon.category(), Category::Docker);
+        assert!(daemon
+            .remediation()
+            .is_some_and(|remediation| remediation.contains("start Docker Desktop")));
+
+        let fetch = Error::Models(models::Error::CatalogFetchFailed("offline".to_string()));
+        assert_eq!(fetch.category(), Category::Network);
+        assert_eq!(Error::InitialEvents.category(), Category::Internal);
+        assert!(Error::Command("exit code 1".to_string())
+            .remediation()
+            .is_none());
+    }
+}
@@ -1,8 +1,25 @@
+pub mod cache;
+
 pub mod error;
 pub use error::Error;
 
+pub mod install;
+
 pub mod lazy_loader;
-pub use lazy_loader::{LazyLoader, TryLoad};
+pub use lazy_loader::{read_to_string, LazyLoader, TryLoad};
+
+pub mod notes;
+
+pub mod progress;
+
+pub mod search;
+
+pub mod sync;
+pub use sync::ProgressSync;
+
+pub mod usage;
 
 pub mod utils;
-pub use utils::{application, workshops};
+pub use utils::{application, backup_path, check_name, workshops, write_atomic};
+
+pub mod vscode;
@@ -0,0 +1,99 @@
+use crate::fs::{application, install::checksum};
+use serde::{de::DeserializeOwned, Serialize};
+use std::{
+    path::{Path, PathBuf},
+    sync::Once,
+    time::{Duration, SystemTime},
+};
+use tracing::{debug, trace};
+
+/// How long a cache entry is kept after it was last used
+const MAX_UNUSED: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+
+/// Get the value parsed from the given source text from the on-disk cache, or parse it and cache
+/// the result. The cache entries are keyed by the checksum of the source, so editing the source
+/// file invalidates its entry, and the entries that haven't been used for a month are pruned. Any problem with the cache falls back to parsing the source.
+pub fn parse_cached<T, E, F>(kind: &str, source: &str, parse: F) -> Result<T, E>
+where
+    T: Serialize + DeserializeOwned,
+    F: FnOnce(&str) -> Result<T, E>,
+{
+    let Some(dir) = cache_dir() else {
+        return parse(source);
+    };
+    static PRUNE: Once = Once::new();
+    PRUNE.call_once(|| prune(&dir));
+    let path = dir
+        .join(kind)
+        .join(format!("{}.json", checksum(source.as_bytes())));
+
+    if let Some(value) = std::fs::read(&path)
+        .ok()
+        .and_then(|cached| serde_json::from_slice(&cached).ok())
+    {
+        trace!("(cache) hit: {}", path.display());
+        // the modification time records the last use for pruning
+        let _ = std::fs::File::options()
+            .append(true)
+            .open(&path)
+            .and_then(|file| file.set_modified(SystemTime::now()));
+        return Ok(value);
+    }
+
+    // a torn write is harmless since an entry that fails to deserialize is parsed again
+    let value = parse(source)?;
+    if let Ok(cached) = serde_json::to_vec(&value) {
+        let written = path
+            .parent()
+            .map_or(Ok(()), std::fs::create_dir_all)
+            .and_then(|_| std::fs::write(&path, cached));
+        if let Err(e) = written {
+            debug!("(cache) failed to write {}: {e}", path.display());
+        }
+    }
+    Ok(value)
+}
+
+// the cache is versioned so that entries written by other versions of the parsers are never read
+fn cache_dir() -> Option<PathBuf> {
+    Some(
+        application::cache_dir()
+            .ok()?
+            .join(format!("parsed-{}", env!("CARGO_PKG_VERSION"))),
+    )
+}
+
+// remove the caches of other versions and the entries that haven't been used for a while
+fn prune(dir: &Path) {
+    let Some(parent) = dir.parent() else {
+        return;
+    };
+    let Ok(caches) = std::fs::read_dir(parent) else {
+        return;
+    };
+    for cache in caches.flatten().map(|entry| entry.path()) {
+        let name = cache.file_name().unwrap_or_default().to_string_lossy();
+        if name.starts_with("parsed-") && cache != dir {
+            debug!("(cache) removing old cache: {}", cache.display());
+            let _ = std::fs::remove_dir_all(&cache);
+        }
+    }
+
+    let now = SystemTime::now();
+    let kinds = std::fs::read_dir(dir).into_iter().flatten().flatten();
+    for entry in kinds.flat_map(|kind| std::fs::read_dir(kind.path()).into_iter().flatten()) {
+        let Ok(entry) = entry else {
+            continue;
+        };
+        let unused = entry
+            .metadata()
+            .and_then(|metadata| metadata.modified())
+            .ok()
+            .and_then(|modified| now.duration_since(modified).ok())
+            .is_some_and(|unused| unused > MAX_UNUSED);
+        if unused {
+            trace!("(cache) removing unused: {}", entry.path().display());
+            let _ = std::fs::remove_file(entry.path());
+        }
+    }
+}
@@ -1,3 +1,5 @@
+use crate::fs::application::Tool;
+
 /// Errors generated from this module
 #[derive(Debug, thiserror::Error)]
 #[non_exhaustive]
@@ -21,4 +23,59 @@ pub enum Error {
     /// No Git executable found
     #[error("No Git executable found")]
     NoGitExecutable,
+
+    /// No Node.js executable found
+    #[error("No Node.js executable found")]
+    NoNodeExecutable,
+
+    /// No npm executable found
+    #[error("No npm executable found")]
+    NoNpmExecutable,
+
+    /// No Go executable found
+    #[error("No Go executable found")]
+    NoGoExecutable,
+
+    /// The install source is not a git URL, workshop directory, or archive
+    #[error("Invalid install source: {0}")]
+    InvalidInstallSource(String),
+
+    /// The workshop is already installed
+    #[error("Workshop already installed at: {0}")]
+    WorkshopAlreadyInstalled(String),
+
+    /// An archive entry would be unpacked outside of the data directory
+    #[error("Unsafe archive entry: {0}")]
+    UnsafeArchiveEntry(String),
+
+    /// An archive doesn't hold exactly one workshop directory
+    #[error("Archive must hold a single workshop directory, found: {0}")]
+    ArchiveLayout(String),
+
+    /// A name that would be joined onto a directory is empty or not a single path component
+    #[error("Invalid name: {0:?}")]
+    InvalidName(String),
+
+    /// A file could not be loaded
+    #[error("Failed to load {0}: {1}")]
+    Load(String, #[source] std::io::Error),
+
+    /// Loading a file took too long
+    #[error("Timed out loading {0}")]
+    LoadTimeout(String),
+}
+
+impl Error {
+    /// The tool that wasn't found, if the error is about a missing tool
+    pub fn tool(&self) -> Option<Tool> {
+        match self {
+            Error::NoPythonExecutable => Some(Tool::Python),
+            Error::NoDockerComposeExecutable => Some(Tool::DockerCompose),
+            Error::NoGitExecutable => Some(Tool::Git),
+            Error::NoNodeExecutable => Some(Tool::Node),
+            Error::NoNpmExecutable => Some(Tool::Npm),
+            Error::NoGoExecutable => Some(Tool::Go),
+            _ => None,
+        }
+    }
 }
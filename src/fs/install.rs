@@ -0,0 +1,681 @@
+use crate::{fs, Error};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    path::{Component, Path, PathBuf},
+};
+use tokio::process::Command;
+use tracing::debug;
+
+/// The archive file extensions that can be installed
+const ARCHIVE_EXTENSIONS: [&str; 4] = [".tar.gz", ".tgz", ".tar", ".zip"];
+
+/// The file in an installed workshop recording the revision it is pinned to
+pub const PIN_FILE: &str = ".workshop-pin.yaml";
+
+/// The file in an installed workshop recording the checksums of its content
+pub const MANIFEST_FILE: &str = ".workshop-manifest.yaml";
+
+/// Where a workshop is installed from
+#[derive(Clone, Debug, PartialEq)]
+pub enum Source {
+    /// a git repository URL that gets cloned, optionally pinned to a tag, branch, or commit
+    Git(String, Option<String>),
+    /// a local workshop directory that gets copied
+    Directory(PathBuf),
+    /// a local or remote .tar.gz, .tgz, .tar, or .zip archive that gets unpacked
+    Archive(String),
+}
+
+impl Source {
+    /// Figure out the kind of install source from the string given to `workshop install`
+    pub fn parse<S: AsRef<str>>(source: S) -> Self {
+        let source = source.as_ref();
+        let lower = source.to_lowercase();
+        if ARCHIVE_EXTENSIONS.iter().any(|ext| lower.ends_with(ext)) {
+            return Source::Archive(source.to_string());
+        }
+        let path = PathBuf::from(shellexpand::tilde(source).as_ref());
+        if path.is_dir() {
+            return Source::Directory(path);
+        }
+        // a trailing `@rev` pins the clone, the @ in `git@host:repo` style URLs and in the user
+        // of `https://user@host/repo` URLs is not a pin, while branches may have a `/`
+        match source.rsplit_once('@') {
+            Some((url, rev))
+                if !url.is_empty()
+                    && !rev.is_empty()
+                    && !rev.contains(':')
+                    && url
+                        .split_once("://")
+                        .is_none_or(|(_, rest)| rest.contains('/')) =>
+            {
+                Source::Git(url.to_string(), Some(rev.to_string()))
+            }
+            _ => Source::Git(source.to_string(), None),
+        }
+    }
+
+    /// Can the source be installed from the catalog, which is fetched from a remote server? Only
+    /// https and ssh git URLs and https archive URLs can, never local paths, and no part of them
+    /// may look like an option to git.
+    pub fn is_catalog_safe(&self) -> bool {
+        match self {
+            Source::Git(url, rev) => {
+                let scp_like = !url.contains("://")
+                    && url
+                        .split_once(':')
+                        .is_some_and(|(host, _)| host.contains('@') && !host.contains('/'));
+                !url.starts_with('-')
+                    && !rev.as_deref().is_some_and(|rev| rev.starts_with('-'))
+                    && (url.starts_with("https://") || url.starts_with("ssh://") || scp_like)
+            }
+            Source::Archive(url) => url.starts_with("https://"),
+            Source::Directory(_) => false,
+        }
+    }
+
+    /// Is the source an https URL that must be downloaded first
+    pub fn is_remote(&self) -> bool {
+        match self {
+            Source::Git(..) | Source::Directory(_) => false,
+            Source::Archive(url) => url.starts_with("https://"),
+        }
+    }
+
+    /// The file name of the source without any archive extension, followed by `@` and the
+    /// revision for a pinned git repository so that several versions can be installed side by side
+    pub fn name(&self) -> Option<String> {
+        let s = match self {
+            Source::Git(url, _) | Source::Archive(url) => url.trim_end_matches('/').to_string(),
+            Source::Directory(path) => return path_name(path),
+        };
+        let file_name = s.rsplit(['/', '\\']).next()?.to_string();
+        let lower = file_name.to_lowercase();
+        let stem = ARCHIVE_EXTENSIONS
+            .iter()
+            .chain([".git"].iter())
+            .find(|ext| lower.ends_with(*ext))
+            .map(|ext| file_name[..file_name.len() - ext.len()].to_string())
+            .unwrap_or(file_name);
+        // a URL ending in `..` must not name a directory outside of the data directory
+        fs::check_name(&stem).ok()?;
+        Some(match self {
+            Source::Git(_, Some(rev)) => pinned_name(&stem, rev),
+            _ => stem,
+        })
+    }
+}
+
+/// The name of the workshop pinned to the revision, `<name>@<rev>`, with the characters of the
+/// revision that can't be in a directory name, like the `/` of `release/1.0`, replaced by `-`
+pub fn pinned_name(name: &str, rev: &str) -> String {
+    let rev: String = rev
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || matches!(c, '.' | '-' | '_' | '+') {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    format!("{name}@{rev}")
+}
+
+/// Does the revision look like a commit hash rather than a tag or branch name?
+pub fn is_commit(rev: &str) -> bool {
+    (7..=40).contains(&rev.len()) && rev.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+// get the last component of the path as a string
+fn path_name(path: &Path) -> Option<String> {
+    path.canonicalize()
+        .ok()?
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+}
+
+/// Copy a local workshop directory into the data directory, returning the installed path
+pub fn install_directory<P: AsRef<Path>>(source: P, data_dir: P) -> Result<PathBuf, Error> {
+    let source = source.as_ref();
+    let name = path_name(source).ok_or(fs::Error::InvalidInstallSource(
+        source.display().to_string(),
+    ))?;
+    let target = data_dir.as_ref().join(name);
+    if target.exists() {
+        return Err(fs::Error::WorkshopAlreadyInstalled(target.display().to_string()).into());
+    }
+    debug!(
+        "Copying workshop from {} to {}",
+        source.display(),
+        target.display()
+    );
+    fs::workshops::copy_tree(source, &target)?;
+    Ok(target)
+}
+
+/// Check that every entry in an archive stays inside of the directory it is unpacked into
+pub fn check_archive_entries<S: AsRef<str>>(entries: &[S]) -> Result<(), Error> {
+    for entry in entries {
+        let entry = entry.as_ref();
+        let path = Path::new(entry);
+        let escapes = entry.starts_with('/')
+            || entry.starts_with('\\')
+            || path.components().any(|c| {
+                matches!(
+                    c,
+                    Component::ParentDir | Component::RootDir | Component::Prefix(_)
+                )
+            });
+        if escapes {
+            return Err(fs::Error::UnsafeArchiveEntry(entry.to_string()).into());
+        }
+    }
+    Ok(())
+}
+
+/// The formats of the archives that can be installed
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ArchiveKind {
+    /// a plain or gzipped tarball, handled by the system tar
+    Tar,
+    /// a zip file, handled by unzip
+    Zip,
+}
+
+impl ArchiveKind {
+    /// Figure out the archive format from its file name
+    pub fn of<S: AsRef<str>>(name: S) -> Self {
+        if name.as_ref().to_lowercase().ends_with(".zip") {
+            ArchiveKind::Zip
+        } else {
+            ArchiveKind::Tar
+        }
+    }
+}
+
+/// List the entries in an archive, refusing symbolic links, hard links, and special files so
+/// that unpacking it can only write regular files and directories
+pub async fn list_archive<P: AsRef<Path>>(
+    kind: ArchiveKind,
+    archive: P,
+) -> Result<Vec<String>, Error> {
+    let archive = archive.as_ref();
+    let (names, listing) = match kind {
+        ArchiveKind::Tar => (
+            archive_output("tar", &["-tf"], archive).await?,
+            archive_output("tar", &["-tvf"], archive).await?,
+        ),
+        ArchiveKind::Zip => {
+            // zipinfo has two header lines and a summary line around the entries
+            let listing = archive_output("unzip", &["-Z"], archive).await?;
+            let entries = listing.len().saturating_sub(3);
+            (
+                archive_output("unzip", &["-Z1"], archive).await?,
+                listing.into_iter().skip(2).take(entries).collect(),
+            )
+        }
+    };
+    check_archive_types(&listing)?;
+    Ok(names)
+}
+
+// run the archiver on the archive and return the lines of its output
+async fn archive_output(
+    program: &str,
+    args: &[&str],
+    archive: &Path,
+) -> Result<Vec<String>, Error> {
+    let output = Command::new(program)
+        .args(args)
+        .arg(archive)
+        .output()
+        .await?;
+    if !output.status.success() {
+        return Err(fs::Error::InvalidInstallSource(archive.display().to_string()).into());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(String::from)
+        .collect())
+}
+
+/// Check that every line of a verbose archive listing, which starts with the `ls -l` style mode
+/// of the entry, is a regular file or a directory
+pub fn check_archive_types<S: AsRef<str>>(listing: &[S]) -> Result<(), Error> {
+    for line in listing {
+        let line = line.as_ref();
+        if !line.starts_with(['-', 'd']) {
+            return Err(fs::Error::UnsafeArchiveEntry(line.to_string()).into());
+        }
+    }
+    Ok(())
+}
+
+/// Get the one top-level directory every entry in the archive is in, which becomes the
+/// installed workshop directory
+pub fn archive_root<S: AsRef<str>>(entries: &[S]) -> Result<String, Error> {
+    let mut root: Option<&str> = None;
+    for entry in entries {
+        let entry = entry.as_ref().trim_start_matches("./");
+        if entry.is_empty() {
+            continue;
+        }
+        let (first, _) = entry
+            .split_once(['/', '\\'])
+            .ok_or_else(|| fs::Error::ArchiveLayout(entry.to_string()))?;
+        match root {
+            Some(root) if root != first => {
+                return Err(fs::Error::ArchiveLayout(entry.to_string()).into())
+            }
+            _ => root = Some(first),
+        }
+    }
+    root.map(String::from)
+        .ok_or_else(|| fs::Error::ArchiveLayout(String::new()).into())
+}
+
+/// The revision an installed workshop is pinned to
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Pin {
+    /// the tag or branch given at install time
+    pub rev: String,
+    /// the commit the revision resolved to
+    pub commit: String,
+}
+
+impl Pin {
+    /// Load the pin for the installed workshop in the given directory, if it is pinned
+    pub fn load<P: AsRef<Path>>(workshop_dir: P) -> Option<Self> {
+        let content = std::fs::read_to_string(workshop_dir.as_ref().join(PIN_FILE)).ok()?;
+        serde_yaml::from_str(&content).ok()
+    }
+
+    /// Save the pin into the installed workshop directory
+    pub fn save<P: AsRef<Path>>(&self, workshop_dir: P) -> Result<(), Error> {
+        let content = serde_yaml::to_string(self)?;
+        std::fs::write(workshop_dir.as_ref().join(PIN_FILE), content)?;
+        Ok(())
+    }
+
+    /// Check if the git working tree in the workshop directory deviates from the pinned commit
+    pub async fn deviates<P: AsRef<Path>>(
+        &self,
+        git_executable: &str,
+        workshop_dir: P,
+    ) -> Result<bool, Error> {
+        let head = git_output(git_executable, &workshop_dir, &["rev-parse", "HEAD"]).await?;
+        let changes = git_output(
+            git_executable,
+            &workshop_dir,
+            &["status", "--porcelain", "--untracked-files=no"],
+        )
+        .await?;
+        Ok(head != self.commit || !changes.is_empty())
+    }
+}
+
+/// The content manifest of an installed workshop
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct Manifest {
+    /// the checksum of every file keyed by its path relative to the workshop directory
+    pub files: BTreeMap<String, String>,
+}
+
+impl Manifest {
+    /// Build the manifest from the current content of the workshop directory. The git metadata
+    /// and the files written by the installer are skipped.
+    pub fn build<P: AsRef<Path>>(workshop_dir: P) -> Result<Self, Error> {
+        fn walk(
+            root: &Path,
+            dir: &Path,
+            files: &mut BTreeMap<String, String>,
+        ) -> Result<(), Error> {
+            for entry in std::fs::read_dir(dir)? {
+                let path = entry?.path();
+                let name = path.file_name().unwrap_or_default().to_string_lossy();
+                if name == ".git" || name.starts_with(".workshop-") {
+                    continue;
+                }
+                if path.is_dir() {
+                    walk(root, &path, files)?;
+                } else {
+                    let relative = path
+                        .strip_prefix(root)
+                        .unwrap_or(&path)
+                        .components()
+                        .map(|c| c.as_os_str().to_string_lossy())
+                        .collect::<Vec<_>>()
+                        .join("/");
+                    files.insert(relative, checksum(&std::fs::read(&path)?));
+                }
+            }
+            Ok(())
+        }
+
+        let mut files = BTreeMap::new();
+        walk(workshop_dir.as_ref(), workshop_dir.as_ref(), &mut files)?;
+        Ok(Self { files })
+    }
+
+    /// Load the manifest recorded for the installed workshop in the given directory
+    pub fn load<P: AsRef<Path>>(workshop_dir: P) -> Option<Self> {
+        let content = std::fs::read_to_string(workshop_dir.as_ref().join(MANIFEST_FILE)).ok()?;
+        serde_yaml::from_str(&content).ok()
+    }
+
+    /// Save the manifest into the installed workshop directory
+    pub fn save<P: AsRef<Path>>(&self, workshop_dir: P) -> Result<(), Error> {
+        let content = serde_yaml::to_string(self)?;
+        std::fs::write(workshop_dir.as_ref().join(MANIFEST_FILE), content)?;
+        Ok(())
+    }
+
+    /// Build and save the manifest for the installed workshop in the given directory
+    pub fn record<P: AsRef<Path>>(workshop_dir: P) -> Result<(), Error> {
+        Self::build(&workshop_dir)?.save(&workshop_dir)
+    }
+
+    /// Compare the recorded manifest with the given one and return the changed, missing, and
+    /// added files
+    pub fn diff(&self, current: &Manifest) -> Vec<String> {
+        let mut changes = Vec::new();
+        for (path, sum) in &self.files {
+            match current.files.get(path) {
+                Some(current_sum) if current_sum == sum => {}
+                Some(_) => changes.push(format!("modified: {path}")),
+                None => changes.push(format!("missing: {path}")),
+            }
+        }
+        for path in current.files.keys() {
+            if !self.files.contains_key(path) {
+                changes.push(format!("added: {path}"));
+            }
+        }
+        changes
+    }
+}
+
+/// The checksum of a file's content
+pub(crate) fn checksum(content: &[u8]) -> String {
+    format!("{:08x}-{}", crc32fast::hash(content), content.len())
+}
+
+/// The provenance of an installed workshop
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub enum Trust {
+    /// the installed commit is signed by one of the trusted signers
+    Verified(String),
+    /// the installed commit is not signed by a trusted signer, or the workshop wasn't installed
+    /// from git so there is no signature to check
+    Unverified,
+    /// the workshop was installed without checking its signature
+    #[default]
+    Unknown,
+}
+
+impl Trust {
+    /// Load the trust for the installed workshop with the given name
+    pub fn load<S: AsRef<str>>(workshop: S) -> Self {
+        Self::path(workshop)
+            .and_then(|path| Ok(std::fs::read_to_string(path)?))
+            .ok()
+            .and_then(|content| serde_yaml::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    /// Save the trust for the installed workshop with the given name
+    pub fn save<S: AsRef<str>>(&self, workshop: S) -> Result<(), Error> {
+        let path = Self::path(workshop)?;
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        fs::write_atomic(&path, serde_yaml::to_string(self)?)?;
+        Ok(())
+    }
+
+    /// Forget the trust for the uninstalled workshop with the given name
+    pub fn remove<S: AsRef<str>>(workshop: S) -> Result<(), Error> {
+        match std::fs::remove_file(Self::path(workshop)?) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+
+    // the trust is kept in the state directory, out of reach of the workshop content
+    fn path<S: AsRef<str>>(workshop: S) -> Result<PathBuf, Error> {
+        let workshop = fs::check_name(workshop.as_ref())?;
+        Ok(fs::application::state_dir()?
+            .join("trust")
+            .join(format!("{workshop}.yaml")))
+    }
+
+    /// Verify the ssh signature on the HEAD commit of the git clone in the given directory
+    /// against the trusted signers, given as lines in the ssh allowed signers format
+    pub async fn verify<P: AsRef<Path>>(
+        git_executable: &str,
+        workshop_dir: P,
+        trusted_signers: &[String],
+    ) -> Result<Self, Error> {
+        if trusted_signers.is_empty() {
+            return Ok(Trust::Unknown);
+        }
+
+        // git needs the allowed signers in a file
+        let allowed_signers = workshop_dir.as_ref().with_extension("allowed_signers");
+        std::fs::write(&allowed_signers, trusted_signers.join("\n") + "\n")?;
+        let output = Command::new(git_executable)
+            .arg("-c")
+            .arg(format!(
+                "gpg.ssh.allowedSignersFile={}",
+                allowed_signers.display()
+            ))
+            .args(["verify-commit", "--raw", "HEAD"])
+            .current_dir(workshop_dir.as_ref())
+            .output()
+            .await;
+        let _ = std::fs::remove_file(&allowed_signers);
+        let output = output?;
+
+        if !output.status.success() {
+            debug!(
+                "Signature verification failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+            return Ok(Trust::Unverified);
+        }
+        Ok(Trust::Verified(
+            signer_principal(&String::from_utf8_lossy(&output.stderr))
+                .unwrap_or_else(|| "trusted signer".to_string()),
+        ))
+    }
+}
+
+// find the principal in the output of a successful ssh signature check, e.g.
+// `Good "git" signature for dwg@linuxprogrammer.org with ED25519 key SHA256:...`
+fn signer_principal(output: &str) -> Option<String> {
+    let line = output.lines().find(|l| l.starts_with("Good "))?;
+    let (_, rest) = line.split_once(" signature for ")?;
+    rest.split_whitespace().next().map(String::from)
+}
+
+/// Run git in the given directory and return its trimmed stdout
+pub async fn git_output<P: AsRef<Path>>(
+    git_executable: &str,
+    dir: P,
+    args: &[&str],
+) -> Result<String, Error> {
+    let output = Command::new(git_executable)
+        .args(args)
+        .current_dir(dir.as_ref())
+        .output()
+        .await?;
+    if !output.status.success() {
+        return Err(Error::Command(format!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_git_url() {
+        let source = Source::parse("https://github.com/libp2p/universal-connectivity-workshop.git");
+        assert_eq!(
+            source,
+            Source::Git(
+                "https://github.com/libp2p/universal-connectivity-workshop.git".to_string(),
+                None
+            )
+        );
+        assert_eq!(
+            source.name().as_deref(),
+            Some("universal-connectivity-workshop")
+        );
+        assert!(!source.is_remote());
+    }
+
+    #[test]
+    fn test_parse_pinned_git_url() {
+        let source = Source::parse("https://github.com/libp2p/workshop-demo.git@v1.2.0");
+        assert_eq!(
+            source,
+            Source::Git(
+                "https://github.com/libp2p/workshop-demo.git".to_string(),
+                Some("v1.2.0".to_string())
+            )
+        );
+        assert_eq!(source.name().as_deref(), Some("workshop-demo@v1.2.0"));
+        let source = Source::parse("https://github.com/libp2p/workshop-demo.git@release/1.0");
+        assert_eq!(source.name().as_deref(), Some("workshop-demo@release-1.0"));
+        assert_eq!(pinned_name("demo", "..\\x:y"), "demo@..-x-y");
+        assert!(source.is_catalog_safe());
+        assert!(Source::parse("git@github.com:libp2p/workshop-demo.git").is_catalog_safe());
+        assert!(!Source::parse("file:///tmp/workshop-demo.git").is_catalog_safe());
+        assert!(!Source::parse("--upload-pack=touch /tmp/x").is_catalog_safe());
+        assert!(!Source::parse("https://github.com/libp2p/demo.git@--depth=1").is_catalog_safe());
+        assert!(!Source::parse("/tmp/workshop-demo.tar.gz").is_catalog_safe());
+        let source = Source::parse("https://token@github.com/libp2p/workshop-demo.git");
+        assert_eq!(source.name().as_deref(), Some("workshop-demo"));
+        assert!(!is_commit("v1.2.0"));
+        assert!(is_commit("3f9c2a1"));
+
+        let source = Source::parse("git@github.com:libp2p/workshop-demo.git");
+        assert_eq!(
+            source,
+            Source::Git("git@github.com:libp2p/workshop-demo.git".to_string(), None)
+        );
+
+        let source = Source::parse("git@github.com:libp2p/workshop-demo.git@main");
+        assert_eq!(
+            source,
+            Source::Git(
+                "git@github.com:libp2p/workshop-demo.git".to_string(),
+                Some("main".to_string())
+            )
+        );
+    }
+
+    #[test]
+    fn test_parse_archive() {
+        let source = Source::parse("https://example.com/workshops/hello-world.tar.gz");
+        assert_eq!(
+            source,
+            Source::Archive("https://example.com/workshops/hello-world.tar.gz".to_string())
+        );
+        assert_eq!(source.name().as_deref(), Some("hello-world"));
+        assert!(source.is_remote());
+
+        let source = Source::parse("./hello-world.ZIP");
+        assert!(matches!(source, Source::Archive(_)));
+        assert!(!source.is_remote());
+        assert_eq!(ArchiveKind::of("./hello-world.ZIP"), ArchiveKind::Zip);
+        assert_eq!(ArchiveKind::of("hello-world.tgz"), ArchiveKind::Tar);
+
+        let source = Source::parse("http://example.com/workshops/hello-world.tar.gz");
+        assert!(!source.is_remote());
+    }
+
+    #[test]
+    fn test_parse_directory() {
+        let dir = std::env::temp_dir();
+        assert_eq!(
+            Source::parse(dir.to_string_lossy()),
+            Source::Directory(dir.clone())
+        );
+    }
+
+    #[test]
+    fn test_manifest_diff() {
+        let dir = std::env::temp_dir().join(format!("workshop-manifest-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("en")).unwrap();
+        std::fs::write(dir.join("en/workshop.md"), "# Workshop").unwrap();
+        std::fs::write(dir.join("LICENSE"), "MIT").unwrap();
+        Manifest::record(&dir).unwrap();
+
+        let recorded = Manifest::load(&dir).unwrap();
+        assert_eq!(recorded.files.len(), 2);
+        assert!(recorded.diff(&Manifest::build(&dir).unwrap()).is_empty());
+
+        std::fs::write(dir.join("en/workshop.md"), "# Edited").unwrap();
+        std::fs::remove_file(dir.join("LICENSE")).unwrap();
+        std::fs::write(dir.join("notes.txt"), "mine").unwrap();
+        assert_eq!(
+            recorded.diff(&Manifest::build(&dir).unwrap()),
+            vec![
+                "missing: LICENSE".to_string(),
+                "modified: en/workshop.md".to_string(),
+                "added: notes.txt".to_string(),
+            ]
+        );
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_signer_principal() {
+        let output =
+            "Good \"git\" signature for dwg@linuxprogrammer.org with ED25519 key SHA256:abc";
+        assert_eq!(
+            signer_principal(output).as_deref(),
+            Some("dwg@linuxprogrammer.org")
+        );
+        assert_eq!(signer_principal("Could not verify signature."), None);
+    }
+
+    #[test]
+    fn test_check_archive_entries() {
+        assert!(check_archive_entries(&["hello/", "hello/defaults.yaml", "hello/en/rs/"]).is_ok());
+        assert!(check_archive_entries(&["hello/../../etc/passwd"]).is_err());
+        assert!(check_archive_entries(&["/etc/passwd"]).is_err());
+        assert!(check_archive_entries(&["\\windows\\system32"]).is_err());
+
+        assert_eq!(
+            archive_root(&["./hello/", "./hello/defaults.yaml", "hello/en/"]).unwrap(),
+            "hello"
+        );
+        assert!(archive_root(&["hello/defaults.yaml", "other/defaults.yaml"]).is_err());
+        assert!(archive_root(&["defaults.yaml"]).is_err());
+        assert!(archive_root::<&str>(&[]).is_err());
+
+        assert!(check_archive_types(&[
+            "drwxr-xr-x root/root         0 2024-01-01 10:37 hello/",
+            "-rw-r--r-- root/root         3 2024-01-01 10:37 hello/defaults.yaml",
+        ])
+        .is_ok());
+        assert!(
+            check_archive_types(&["lrwxrwxrwx root/root 0 2024-01-01 10:37 hello/s -> /etc"])
+                .is_err()
+        );
+        assert!(check_archive_types(&[
+            "hrw-r--r-- root/root 0 2024-01-01 10:37 hello/a link to /etc/passwd"
+        ])
+        .is_err());
+    }
+}
@@ -1,6 +1,23 @@
-use std::path::{Path, PathBuf};
+use crate::fs::Error;
+use std::{
+    path::{Path, PathBuf},
+    time::Duration,
+};
 use tracing::trace;
 
+/// How long loading a single file may take before giving up on it
+pub const LOAD_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Read the file at the given path without blocking the runtime, giving up on it after
+/// [`LOAD_TIMEOUT`]. The errors name the file so they can be shown as they are.
+pub async fn read_to_string(path: &Path) -> Result<String, Error> {
+    match tokio::time::timeout(LOAD_TIMEOUT, tokio::fs::read_to_string(path)).await {
+        Ok(Ok(content)) => Ok(content),
+        Ok(Err(e)) => Err(Error::Load(path.display().to_string(), e)),
+        Err(_) => Err(Error::LoadTimeout(path.display().to_string())),
+    }
+}
+
 /// Trait that types must implement to be loadable
 #[async_trait::async_trait]
 pub trait TryLoad: Send + Sync {
@@ -0,0 +1,59 @@
+use crate::{
+    fs::{check_name, workshops, write_atomic},
+    Error,
+};
+use std::{collections::BTreeMap, path::PathBuf};
+
+// the notes for a lesson are in `notes/<workshop>/<lesson>.md` in the `.workshops` directory
+fn notes_dir() -> Option<PathBuf> {
+    workshops::data_dir().map(|dir| dir.join("notes"))
+}
+
+// the notes file of the lesson, none for names that aren't a plain file name
+fn notes_path(workshop: &str, lesson: &str) -> Option<PathBuf> {
+    let (workshop, lesson) = (check_name(workshop).ok()?, check_name(lesson).ok()?);
+    notes_dir().map(|dir| dir.join(workshop).join(format!("{lesson}.md")))
+}
+
+/// Load the student's markdown notes for a lesson, empty if there are none
+pub fn load(workshop: &str, lesson: &str) -> String {
+    notes_path(workshop, lesson)
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .unwrap_or_default()
+}
+
+/// Save the student's markdown notes for a lesson, removing the file when the notes are empty
+pub fn save(workshop: &str, lesson: &str, notes: &str) -> Result<(), Error> {
+    check_name(workshop)?;
+    check_name(lesson)?;
+    let Some(path) = notes_path(workshop, lesson) else {
+        return Err(crate::fs::Error::WorkshopDataDirNotFound.into());
+    };
+    if notes.trim().is_empty() {
+        if path.exists() {
+            std::fs::remove_file(&path)?;
+        }
+        return Ok(());
+    }
+    write_atomic(&path, notes)?;
+    Ok(())
+}
+
+/// Load all of the notes for a workshop, keyed by lesson
+pub fn load_workshop(workshop: &str) -> BTreeMap<String, String> {
+    let Some(entries) = check_name(workshop)
+        .ok()
+        .and_then(|workshop| std::fs::read_dir(notes_dir()?.join(workshop)).ok())
+    else {
+        return BTreeMap::new();
+    };
+    entries
+        .flatten()
+        .filter_map(|entry| {
+            let path = entry.path();
+            let lesson = path.file_stem()?.to_str()?.to_string();
+            let notes = std::fs::read_to_string(&path).ok()?;
+            (path.extension()? == "md").then_some((lesson, notes))
+        })
+        .collect()
+}
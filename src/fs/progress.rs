@@ -0,0 +1,403 @@
+use crate::{
+    fs::{
+        application, backup_path, check_name,
+        install::{pinned_name, Pin},
+        search, write_atomic,
+    },
+    languages::spoken,
+    models::{lesson, workshop},
+    Error,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::{Path, PathBuf},
+    sync::{Mutex, PoisonError},
+};
+use tracing::{info, warn};
+
+/// The progress of the student in one version of a workshop, kept apart from the workshop
+/// content so that updating the workshop never touches it
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct Progress {
+    /// the workshop status keyed by spoken language
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub workshop: BTreeMap<String, workshop::Status>,
+    /// the lesson status keyed by `<spoken>/<programming>/<lesson>`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub lessons: BTreeMap<String, lesson::Status>,
+    /// the answers to the quizzes in the lessons, keyed like the lessons and then by the index
+    /// of the quiz in the lesson
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub quizzes: BTreeMap<String, BTreeMap<usize, QuizAnswer>>,
+}
+
+/// The student's answer to a quiz in a lesson
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq, Serialize)]
+pub struct QuizAnswer {
+    /// the number of the picked choice
+    pub choice: usize,
+    /// is it the right one?
+    pub correct: bool,
+}
+
+impl Progress {
+    /// Get the progress carried over to another version of the workshop that has the given
+    /// lessons, keyed like the lessons of the progress. The lessons that are gone are left
+    /// behind, and a completed workshop is in progress again when the lessons it has now aren't
+    /// all completed in the languages the student took it in.
+    pub fn migrate(&self, lessons: &BTreeSet<String>) -> Progress {
+        let migrated = self
+            .lessons
+            .iter()
+            .filter(|(lesson, _)| lessons.contains(*lesson))
+            .map(|(lesson, status)| (lesson.clone(), status.clone()))
+            .collect::<BTreeMap<_, _>>();
+
+        // the `<spoken>/<programming>` prefixes of the lessons the student worked on
+        let taken = migrated
+            .keys()
+            .filter_map(|lesson| lesson.rsplit_once('/').map(|(languages, _)| languages))
+            .collect::<BTreeSet<_>>();
+        let mut workshop = self.workshop.clone();
+        for lesson in lessons {
+            let Some((languages, _)) = lesson.rsplit_once('/') else {
+                continue;
+            };
+            let completed = matches!(migrated.get(lesson), Some(lesson::Status::Completed));
+            if !taken.contains(languages) || completed {
+                continue;
+            }
+            let spoken = languages.split('/').next().unwrap_or_default();
+            if let Some(status @ workshop::Status::Completed) = workshop.get_mut(spoken) {
+                *status = workshop::Status::InProgress;
+            }
+        }
+
+        let quizzes = self
+            .quizzes
+            .iter()
+            .filter(|(lesson, _)| migrated.contains_key(*lesson))
+            .map(|(lesson, answers)| (lesson.clone(), answers.clone()))
+            .collect();
+
+        Progress {
+            workshop,
+            lessons: migrated,
+            quizzes,
+        }
+    }
+}
+
+// the progress of every workshop is in `progress/<key>.yaml` in the `.workshops` directory, or
+// in the state directory outside of a project
+fn progress_dir() -> Option<PathBuf> {
+    application::status_dir().map(|dir| dir.join("progress"))
+}
+
+// the progress file of the key, none for keys that aren't a plain file name
+fn progress_path(key: &str) -> Option<PathBuf> {
+    let key = check_name(key).ok()?;
+    progress_dir().map(|dir| dir.join(format!("{key}.yaml")))
+}
+
+/// Get the key the progress of the workshop in the given directory is stored under, its name
+/// followed by `@` and the revision it is pinned to, if it is pinned. The versions installed side
+/// by side already have the revision in their name.
+pub fn key<P: AsRef<Path>>(workshop_dir: P) -> Option<String> {
+    let workshop_dir = workshop_dir.as_ref();
+    let name = workshop_dir.file_name()?.to_string_lossy().to_string();
+    Some(match Pin::load(workshop_dir) {
+        Some(pin) if !name.contains('@') => pinned_name(&name, &pin.rev),
+        _ => name,
+    })
+}
+
+// the progress is changed by the TUI and by the spawned check tasks, so every change of a
+// progress file holds the lock from reading the file to writing it back
+static WRITES: Mutex<()> = Mutex::new(());
+
+// read the progress file, falling back to its backup if it is broken
+fn read(path: &Path) -> Option<Progress> {
+    let parse = |path: &Path| -> Result<Progress, Error> {
+        Ok(serde_yaml::from_str(&std::fs::read_to_string(path)?)?)
+    };
+    parse(path)
+        .or_else(|e| {
+            warn!("Failed to read progress file {}: {e}", path.display());
+            parse(&backup_path(path))
+        })
+        .ok()
+}
+
+/// Load the progress stored under the key, empty if there is none
+pub fn load(key: &str) -> Progress {
+    progress_path(key)
+        .filter(|path| path.is_file())
+        .and_then(|path| read(&path))
+        .unwrap_or_default()
+}
+
+/// Change the progress stored under the key. A broken progress file that can't be recovered
+/// from its backup is moved aside, so that the progress in it isn't overwritten.
+pub fn update<F: FnOnce(&mut Progress)>(key: &str, change: F) -> Result<(), Error> {
+    check_name(key)?;
+    let Some(path) = progress_path(key) else {
+        return Err(crate::fs::Error::WorkshopDataDirNotFound.into());
+    };
+    update_file(&path, change)
+}
+
+// change the progress in the file while holding the lock
+fn update_file<F: FnOnce(&mut Progress)>(path: &Path, change: F) -> Result<(), Error> {
+    let _lock = WRITES.lock().unwrap_or_else(PoisonError::into_inner);
+    let mut progress = if path.is_file() {
+        read(path).map_or_else(
+            || {
+                let corrupt = path.with_extension("yaml.corrupt");
+                warn!("Moving broken progress file aside: {}", corrupt.display());
+                std::fs::rename(path, &corrupt).map(|_| Progress::default())
+            },
+            Ok,
+        )?
+    } else {
+        Progress::default()
+    };
+    change(&mut progress);
+    write_atomic(path, serde_yaml::to_string(&progress)?)?;
+    Ok(())
+}
+
+/// Remove the progress stored under the key
+pub fn remove(key: &str) -> Result<(), Error> {
+    let Some(path) = progress_path(key) else {
+        return Ok(());
+    };
+    let _lock = WRITES.lock().unwrap_or_else(PoisonError::into_inner);
+    if path.is_file() {
+        info!("Removing workshop progress: {}", path.display());
+        std::fs::remove_file(&path)?;
+    }
+    Ok(())
+}
+
+/// Remove the progress in every workshop
+pub fn remove_all() -> Result<(), Error> {
+    if let Some(dir) = progress_dir().filter(|dir| dir.is_dir()) {
+        let _lock = WRITES.lock().unwrap_or_else(PoisonError::into_inner);
+        info!("Removing all workshop progress: {}", dir.display());
+        std::fs::remove_dir_all(dir)?;
+    }
+    Ok(())
+}
+
+/// Carry the progress in the workshop in one directory over to the version of it in another
+/// directory, for the lessons that are in both, keeping any progress already made in the other
+/// version. Returns the number of lessons carried over.
+pub fn migrate<P: AsRef<Path>>(from_dir: P, to_dir: P) -> Result<usize, Error> {
+    let (Some(from), Some(to)) = (key(&from_dir), key(&to_dir)) else {
+        return Err(crate::fs::Error::WorkshopDataDirNotFound.into());
+    };
+    let migrated = load(&from).migrate(&lessons(to_dir.as_ref()));
+    let count = migrated.lessons.len();
+    update(&to, |progress| {
+        for (spoken, status) in migrated.workshop {
+            progress.workshop.entry(spoken).or_insert(status);
+        }
+        for (lesson, status) in migrated.lessons {
+            progress.lessons.entry(lesson).or_insert(status);
+        }
+        for (lesson, answers) in migrated.quizzes {
+            progress.quizzes.entry(lesson).or_insert(answers);
+        }
+    })?;
+    Ok(count)
+}
+
+// get the `<spoken>/<programming>/<lesson>` keys of the lessons in the workshop directory
+fn lessons(workshop_dir: &Path) -> BTreeSet<String> {
+    let subdirs = |dir: &Path| search::subdirs(dir, |name| Some(name.to_string()));
+    let mut lessons = BTreeSet::new();
+    for (spoken, spoken_dir) in subdirs(workshop_dir) {
+        for (programming, programming_dir) in subdirs(&spoken_dir) {
+            for (lesson, lesson_dir) in subdirs(&programming_dir) {
+                if lesson_dir.join("lesson.yaml").is_file() {
+                    lessons.insert(format!("{spoken}/{programming}/{lesson}"));
+                }
+            }
+        }
+    }
+    lessons
+}
+
+// split the `<workshop>/<spoken>/<programming>/<lesson>` lesson directory into the key of the
+// workshop and the key of the lesson
+fn lesson_keys(lesson_dir: &Path) -> Option<(String, String)> {
+    let mut parts = lesson_dir.ancestors().map(Path::file_name);
+    let lesson = parts.next()??.to_string_lossy();
+    let programming = parts.next()??.to_string_lossy();
+    let spoken = parts.next()??.to_string_lossy();
+    let workshop_dir = lesson_dir.ancestors().nth(3)?;
+    Some((
+        key(workshop_dir)?,
+        format!("{spoken}/{programming}/{lesson}"),
+    ))
+}
+
+/// Get the stored status of the workshop in the given directory in the spoken language
+pub fn workshop_status<P: AsRef<Path>>(
+    workshop_dir: P,
+    spoken: spoken::Code,
+) -> Option<workshop::Status> {
+    let key = key(workshop_dir)?;
+    load(&key).workshop.remove(&spoken.to_string())
+}
+
+/// Store the status of the workshop in the given directory in the spoken language
+pub fn set_workshop_status<P: AsRef<Path>>(
+    workshop_dir: P,
+    spoken: spoken::Code,
+    status: workshop::Status,
+) -> Result<(), Error> {
+    let key = key(workshop_dir).ok_or(crate::fs::Error::WorkshopDataDirNotFound)?;
+    update(&key, |progress| {
+        progress.workshop.insert(spoken.to_string(), status);
+    })
+}
+
+/// Get the stored status of the lesson in the given directory
+pub fn lesson_status<P: AsRef<Path>>(lesson_dir: P) -> Option<lesson::Status> {
+    let (key, lesson) = lesson_keys(lesson_dir.as_ref())?;
+    load(&key).lessons.remove(&lesson)
+}
+
+/// Store the status of the lesson in the given directory
+pub fn set_lesson_status<P: AsRef<Path>>(
+    lesson_dir: P,
+    status: lesson::Status,
+) -> Result<(), Error> {
+    let (key, lesson) =
+        lesson_keys(lesson_dir.as_ref()).ok_or(crate::fs::Error::WorkshopDataDirNotFound)?;
+    update(&key, |progress| {
+        progress.lessons.insert(lesson, status);
+    })
+}
+
+/// Get the stored answers to the quizzes in the lesson in the given directory, keyed by the
+/// index of the quiz
+pub fn quiz_answers<P: AsRef<Path>>(lesson_dir: P) -> BTreeMap<usize, QuizAnswer> {
+    let Some((key, lesson)) = lesson_keys(lesson_dir.as_ref()) else {
+        return BTreeMap::new();
+    };
+    load(&key).quizzes.remove(&lesson).unwrap_or_default()
+}
+
+/// Store the answer to the quiz with the index in the lesson in the given directory
+pub fn set_quiz_answer<P: AsRef<Path>>(
+    lesson_dir: P,
+    quiz: usize,
+    answer: QuizAnswer,
+) -> Result<(), Error> {
+    let (key, lesson) =
+        lesson_keys(lesson_dir.as_ref()).ok_or(crate::fs::Error::WorkshopDataDirNotFound)?;
+    update(&key, |progress| {
+        progress
+            .quizzes
+            .entry(lesson)
+            .or_default()
+            .insert(quiz, answer);
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keys() {
+        let dir = std::env::temp_dir().join(format!("workshop-progress-{}", std::process::id()));
+        let workshop_dir = dir.join("ws");
+        let lesson_dir = workshop_dir.join("en").join("rs").join("01-intro");
+        std::fs::create_dir_all(&lesson_dir).unwrap();
+        assert_eq!(
+            lesson_keys(&lesson_dir),
+            Some(("ws".to_string(), "en/rs/01-intro".to_string()))
+        );
+
+        // a pinned workshop keeps the progress of each revision apart
+        Pin {
+            rev: "v1.0".to_string(),
+            commit: "abc".to_string(),
+        }
+        .save(&workshop_dir)
+        .unwrap();
+        assert_eq!(key(&workshop_dir), Some("ws@v1.0".to_string()));
+        assert_eq!(lessons(&workshop_dir), BTreeSet::new());
+        std::fs::write(lesson_dir.join("lesson.yaml"), "").unwrap();
+        assert_eq!(
+            lessons(&workshop_dir),
+            BTreeSet::from(["en/rs/01-intro".to_string()])
+        );
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_update() {
+        let dir = std::env::temp_dir().join(format!("workshop-update-{}", std::process::id()));
+        let path = dir.join("ws.yaml");
+        std::fs::create_dir_all(&dir).unwrap();
+
+        // a broken file is moved aside instead of being overwritten
+        std::fs::write(&path, "lessons: [").unwrap();
+        update_file(&path, |_| {}).unwrap();
+        assert!(path.with_extension("yaml.corrupt").is_file());
+
+        // changes made at the same time are all kept
+        let writers: Vec<_> = (0..8)
+            .map(|i| {
+                let path = path.clone();
+                std::thread::spawn(move || {
+                    update_file(&path, |progress| {
+                        let lesson = format!("en/rs/{i:02}-lesson");
+                        progress.lessons.insert(lesson, lesson::Status::Completed);
+                    })
+                })
+            })
+            .collect();
+        for writer in writers {
+            writer.join().unwrap().unwrap();
+        }
+        assert_eq!(read(&path).unwrap().lessons.len(), 8);
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_migrate() {
+        let progress: Progress = serde_yaml::from_str(
+            "workshop:\n  en: Completed\nlessons:\n  en/rs/01-intro: Completed\n  en/rs/02-gone: Completed\nquizzes:\n  en/rs/01-intro:\n    0: {choice: 2, correct: true}\n  en/rs/02-gone:\n    0: {choice: 1, correct: false}\n",
+        )
+        .unwrap();
+
+        // the lessons that are still there keep their progress
+        let lessons = BTreeSet::from(["en/rs/01-intro".to_string(), "en/go/01-intro".to_string()]);
+        let migrated = progress.migrate(&lessons);
+        assert_eq!(migrated.lessons.len(), 1);
+        assert_eq!(
+            migrated.quizzes.keys().collect::<Vec<_>>(),
+            vec!["en/rs/01-intro"]
+        );
+        assert!(matches!(
+            migrated.workshop.get("en"),
+            Some(workshop::Status::Completed)
+        ));
+
+        // a new lesson in the language the workshop was taken in isn't completed yet
+        let lessons = BTreeSet::from(["en/rs/01-intro".to_string(), "en/rs/03-new".to_string()]);
+        let migrated = progress.migrate(&lessons);
+        assert!(matches!(
+            migrated.workshop.get("en"),
+            Some(workshop::Status::InProgress)
+        ));
+    }
+}
@@ -0,0 +1,222 @@
+use crate::{
+    fs::{application, workshops},
+    languages::{programming, spoken},
+};
+use std::{
+    collections::BTreeMap,
+    fmt,
+    path::{Path, PathBuf},
+};
+use tracing::debug;
+
+/// The number of lines shown before and after a matching line
+const CONTEXT_LINES: usize = 2;
+
+/// The most matches returned, so that a short query doesn't bury the list
+const MAX_MATCHES: usize = 500;
+
+/// Where in a workshop the query was found
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Place {
+    /// the description of the workshop
+    Description,
+    /// the setup instructions for the programming language
+    Setup(programming::Code),
+    /// the text of a lesson in the programming language, by lesson key
+    Lesson(programming::Code, String),
+}
+
+/// A line of workshop content matching the query
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Match {
+    /// the workshop the match is in
+    pub workshop: String,
+    /// the spoken language of the matching text
+    pub spoken: spoken::Code,
+    /// where in the workshop the match is
+    pub place: Place,
+    /// the number of the matching line, starting at one
+    pub line: usize,
+    /// the matching line with the lines around it, by line number
+    pub context: Vec<(usize, String)>,
+}
+
+impl Match {
+    /// The programming language of the match, the description is the same for all of them
+    pub fn programming(&self) -> Option<programming::Code> {
+        match &self.place {
+            Place::Description => None,
+            Place::Setup(programming) | Place::Lesson(programming, _) => Some(*programming),
+        }
+    }
+
+    /// The lesson the match is in, if it is in one
+    pub fn lesson(&self) -> Option<&str> {
+        match &self.place {
+            Place::Lesson(_, lesson) => Some(lesson),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Match {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.place {
+            Place::Description => write!(f, "{} ({}) description", self.workshop, self.spoken),
+            Place::Setup(programming) => write!(
+                f,
+                "{} ({}, {}) setup",
+                self.workshop,
+                self.spoken,
+                programming.get_name()
+            ),
+            Place::Lesson(programming, lesson) => write!(
+                f,
+                "{} ({}, {}) {lesson}",
+                self.workshop,
+                self.spoken,
+                programming.get_name()
+            ),
+        }?;
+        write!(f, ":{}", self.line)
+    }
+}
+
+/// Search the descriptions, setup instructions, and lesson text of the installed workshops for
+/// the query, ignoring case
+pub async fn search(query: String) -> Vec<Match> {
+    tokio::task::spawn_blocking(move || search_workshops(&query))
+        .await
+        .unwrap_or_default()
+}
+
+// search every installed workshop, the copies in the `.workshops` directory shadow the installed
+// ones the same way they do when the workshops are loaded
+fn search_workshops(query: &str) -> Vec<Match> {
+    let query = query.trim().to_lowercase();
+    if query.is_empty() {
+        return Vec::new();
+    }
+
+    let mut dirs = application::search_dirs().unwrap_or_default();
+    dirs.extend(workshops::data_dir());
+    let mut workshop_dirs = BTreeMap::new();
+    for dir in dirs {
+        for workshop in workshops::workshop_names(&dir).unwrap_or_default() {
+            workshop_dirs.insert(workshop.clone(), dir.join(workshop));
+        }
+    }
+
+    let mut matches = Vec::new();
+    for (workshop, dir) in workshop_dirs {
+        debug!("Searching workshop: {}", dir.display());
+        for (spoken, spoken_dir) in subdirs(&dir, |name| spoken::Code::try_from(name).ok()) {
+            let mut search_file =
+                |path: PathBuf, place: Place| {
+                    matches.extend(find_in_file(&path, &query).into_iter().map(
+                        |(line, context)| Match {
+                            workshop: workshop.clone(),
+                            spoken,
+                            place: place.clone(),
+                            line,
+                            context,
+                        },
+                    ));
+                };
+            search_file(spoken_dir.join("description.md"), Place::Description);
+            for (programming, programming_dir) in
+                subdirs(&spoken_dir, |name| programming::Code::try_from(name).ok())
+            {
+                search_file(programming_dir.join("setup.md"), Place::Setup(programming));
+                for (lesson, lesson_dir) in subdirs(&programming_dir, |name| {
+                    Some(name.to_string()).filter(|_| !name.starts_with('.'))
+                }) {
+                    search_file(
+                        lesson_dir.join("lesson.md"),
+                        Place::Lesson(programming, lesson),
+                    );
+                }
+            }
+        }
+        if matches.len() >= MAX_MATCHES {
+            break;
+        }
+    }
+    matches.truncate(MAX_MATCHES);
+    matches
+}
+
+// the subdirectories of the directory whose names parse, sorted by name
+pub(crate) fn subdirs<T>(dir: &Path, parse: impl Fn(&str) -> Option<T>) -> Vec<(T, PathBuf)> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut paths = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_dir())
+        .collect::<Vec<_>>();
+    paths.sort();
+    paths
+        .into_iter()
+        .filter_map(|path| {
+            let name = path.file_name()?.to_string_lossy().to_string();
+            Some((parse(&name)?, path))
+        })
+        .collect()
+}
+
+// search the file, if there is one
+fn find_in_file(path: &Path, query: &str) -> Vec<(usize, Vec<(usize, String)>)> {
+    match std::fs::read_to_string(path) {
+        Ok(text) => find_in(&text, query),
+        Err(_) => Vec::new(),
+    }
+}
+
+// find the lines containing the lowercase query, returning the line numbers and the numbered
+// lines around them
+fn find_in(text: &str, query: &str) -> Vec<(usize, Vec<(usize, String)>)> {
+    let lines = text.lines().collect::<Vec<_>>();
+    lines
+        .iter()
+        .enumerate()
+        .filter(|(_, line)| line.to_lowercase().contains(query))
+        .map(|(index, _)| {
+            let start = index.saturating_sub(CONTEXT_LINES);
+            let end = (index + CONTEXT_LINES + 1).min(lines.len());
+            let context = (start..end)
+                .map(|number| (number + 1, lines[number].to_string()))
+                .collect();
+            (index + 1, context)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_in() {
+        let text = "# Hello\n\none\ntwo\nDial the PEER\nthree\nfour\nfive\npeer again";
+        let found = find_in(text, "peer");
+        assert_eq!(found.len(), 2);
+        assert_eq!(found[0].0, 5);
+        let lines = |found: &(usize, Vec<(usize, String)>)| {
+            found
+                .1
+                .iter()
+                .map(|(_, line)| line.clone())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            lines(&found[0]),
+            vec!["one", "two", "Dial the PEER", "three", "four"]
+        );
+        assert_eq!(found[0].1[0].0, 3);
+        assert_eq!(found[1].0, 9);
+        assert_eq!(lines(&found[1]), vec!["four", "five", "peer again"]);
+        assert!(find_in(text, "missing").is_empty());
+    }
+}
@@ -0,0 +1,143 @@
+use crate::{
+    fs::{
+        self, application,
+        install::{checksum, git_output},
+    },
+    Error,
+};
+use std::path::PathBuf;
+use tracing::{debug, info};
+
+/// Only the progress and the status in the `.workshops` directory are synced, not the copied
+/// workshops, the check output, or the files written while saving
+const EXCLUDE: &str = "/*\n!/progress/\n!/status.yaml\n*.tmp\n*.bak\n*.corrupt\n";
+
+/// Syncs the progress in the `.workshops` directory with a git remote
+#[derive(Clone, Debug)]
+pub struct ProgressSync {
+    /// the git executable
+    git: String,
+    /// the `.workshops` directory
+    dir: PathBuf,
+    /// the git directory of the progress repo, kept in the state directory so that no repo is
+    /// nested in the student's project
+    git_dir: PathBuf,
+    /// the remote URL
+    remote: String,
+    /// the identity used for commits when the user has not configured one
+    peer_id: String,
+}
+
+impl ProgressSync {
+    /// Create the progress sync if a remote is configured and there is a `.workshops` directory
+    pub fn new<S: AsRef<str>>(git: S, remote: Option<S>, peer_id: S) -> Option<Self> {
+        let dir = fs::workshops::data_dir()?;
+        let key = checksum(dir.to_string_lossy().as_bytes());
+        Some(Self {
+            git: git.as_ref().to_string(),
+            git_dir: application::state_dir()
+                .ok()?
+                .join("sync")
+                .join(format!("{key}.git")),
+            dir,
+            remote: remote?.as_ref().to_string(),
+            peer_id: peer_id.as_ref().to_string(),
+        })
+    }
+
+    // run git on the progress repo
+    async fn git(&self, args: &[&str]) -> Result<String, Error> {
+        let git_dir = format!("--git-dir={}", self.git_dir.display());
+        let work_tree = format!("--work-tree={}", self.dir.display());
+        let args: Vec<&str> = [git_dir.as_str(), work_tree.as_str()]
+            .into_iter()
+            .chain(args.iter().copied())
+            .collect();
+        git_output(&self.git, &self.dir, &args).await
+    }
+
+    /// Turn the progress directory into a git repo pointing at the remote, if it isn't one
+    pub async fn init(&self) -> Result<(), Error> {
+        if !self.git_dir.exists() {
+            info!("Initializing progress repo in: {}", self.git_dir.display());
+            if let Some(parent) = self.git_dir.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            // older versions kept the repo in the `.workshops` directory and synced all of it
+            let legacy = self.dir.join(".git");
+            if legacy.is_dir() {
+                // renaming fails across file systems so fall back to copying
+                if std::fs::rename(&legacy, &self.git_dir).is_err() {
+                    fs::workshops::copy_tree(&legacy, &self.git_dir)?;
+                    std::fs::remove_dir_all(&legacy)?;
+                }
+                let _ = std::fs::remove_file(self.dir.join(".gitignore"));
+                self.git(&["rm", "-r", "--cached", "--quiet", "--ignore-unmatch", "."])
+                    .await?;
+            } else {
+                self.git(&["init", "--quiet"]).await?;
+            }
+            let info = self.git_dir.join("info");
+            std::fs::create_dir_all(&info)?;
+            std::fs::write(info.join("exclude"), EXCLUDE)?;
+        }
+        if self.git(&["remote", "get-url", "origin"]).await.is_ok() {
+            self.git(&["remote", "set-url", "origin", &self.remote])
+                .await?;
+        } else {
+            self.git(&["remote", "add", "origin", &self.remote]).await?;
+        }
+        // commits need an identity so fall back to the student's peer id
+        if self.git(&["config", "user.email"]).await.is_err() {
+            let email = format!("{}@workshop.local", self.peer_id);
+            self.git(&["config", "user.name", &self.peer_id]).await?;
+            self.git(&["config", "user.email", &email]).await?;
+        }
+        Ok(())
+    }
+
+    /// Pull the progress from the remote
+    pub async fn pull(&self) -> Result<(), Error> {
+        self.init().await?;
+        // a new remote has nothing to pull yet
+        let branch = self.branch().await?;
+        if self
+            .git(&["ls-remote", "--exit-code", "--heads", "origin", &branch])
+            .await
+            .is_err()
+        {
+            debug!("Nothing to pull from the progress remote");
+            return Ok(());
+        }
+        self.commit("Save local progress").await?;
+        self.git(&["pull", "--quiet", "--rebase", "origin", &branch])
+            .await?;
+        Ok(())
+    }
+
+    /// Commit all of the progress with the given message, if anything changed
+    pub async fn commit<S: AsRef<str>>(&self, message: S) -> Result<(), Error> {
+        self.init().await?;
+        self.git(&["add", "--all"]).await?;
+        if self.git(&["status", "--porcelain"]).await?.is_empty() {
+            return Ok(());
+        }
+        info!("Committing progress: {}", message.as_ref());
+        self.git(&["commit", "--quiet", "-m", message.as_ref()])
+            .await?;
+        Ok(())
+    }
+
+    /// Push the committed progress to the remote
+    pub async fn push(&self) -> Result<(), Error> {
+        let branch = self.branch().await?;
+        self.git(&["push", "--quiet", "--set-upstream", "origin", &branch])
+            .await?;
+        Ok(())
+    }
+
+    // get the name of the current branch
+    async fn branch(&self) -> Result<String, Error> {
+        self.git(&["symbolic-ref", "--short", "HEAD"]).await
+    }
+}
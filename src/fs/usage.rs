@@ -0,0 +1,228 @@
+use crate::{
+    fs::{application, workshops},
+    Error,
+};
+use std::{
+    fmt,
+    path::{Path, PathBuf},
+};
+use tokio::process::Command;
+use tracing::debug;
+
+/// The label docker compose puts on everything the lesson checks build
+const COMPOSE_LABEL: &str = "label=com.docker.compose.project=workshop";
+
+/// The images the lesson checks build under their own names
+const IMAGE_REFERENCES: [&str; 2] = ["reference=workshop-lesson", "reference=ucw-checker-*"];
+
+/// What is taking up the disk space
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Kind {
+    /// an installed workshop in the application data directory
+    Installed(String),
+    /// the copy of a workshop, with the progress in it, in the project's `.workshops` directory
+    Workspace(String),
+    /// the docker images built by the lesson checks, by id
+    Images(Vec<String>),
+    /// the downloads and parsed files in the cache directory
+    Cache(Vec<PathBuf>),
+}
+
+impl fmt::Display for Kind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Kind::Installed(workshop) => write!(f, "{workshop} (installed)"),
+            Kind::Workspace(workshop) => write!(f, "{workshop} (workspace)"),
+            Kind::Images(ids) => write!(f, "Docker images ({})", ids.len()),
+            Kind::Cache(_) => write!(f, "Caches"),
+        }
+    }
+}
+
+/// The disk space taken up by one thing
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Usage {
+    /// what is taking up the space
+    pub kind: Kind,
+    /// the number of bytes
+    pub bytes: u64,
+}
+
+/// Measure the disk space taken up by the installed workshops, the workspaces in the current
+/// project, the docker images built by the lesson checks, and the caches, largest first
+pub async fn measure(docker: Option<&str>) -> Vec<Usage> {
+    let mut usage = Vec::new();
+
+    if let Ok(data_dir) = application::data_dir() {
+        for workshop in workshops::workshop_names(&data_dir).unwrap_or_default() {
+            usage.push(Usage {
+                bytes: dir_size(&data_dir.join(&workshop)),
+                kind: Kind::Installed(workshop),
+            });
+        }
+    }
+
+    if let Some(data_dir) = workshops::data_dir() {
+        for workshop in workshops::workshop_names(&data_dir).unwrap_or_default() {
+            usage.push(Usage {
+                bytes: dir_size(&data_dir.join(&workshop)),
+                kind: Kind::Workspace(workshop),
+            });
+        }
+    }
+
+    if let Some(docker) = docker {
+        match images(docker).await {
+            Ok((ids, bytes)) if !ids.is_empty() => usage.push(Usage {
+                kind: Kind::Images(ids),
+                bytes,
+            }),
+            Ok(_) => {}
+            Err(e) => debug!("Failed to list the docker images: {e}"),
+        }
+    }
+
+    let caches = cache_entries();
+    if !caches.is_empty() {
+        usage.push(Usage {
+            bytes: caches.iter().map(|path| dir_size(path)).sum(),
+            kind: Kind::Cache(caches),
+        });
+    }
+
+    usage.sort_by_key(|usage| std::cmp::Reverse(usage.bytes));
+    usage
+}
+
+/// Remove the docker images or the caches. Installed workshops and workspaces are removed by
+/// uninstalling the workshop and resetting its progress instead.
+pub async fn purge(kind: &Kind, docker: Option<&str>) -> Result<(), Error> {
+    match kind {
+        Kind::Images(ids) => {
+            let docker = docker.ok_or(crate::fs::Error::NoDockerComposeExecutable)?;
+            let mut args = vec!["image", "rm", "-f"];
+            args.extend(ids.iter().map(String::as_str));
+            run(docker, &args).await?;
+        }
+        Kind::Cache(paths) => {
+            for path in paths {
+                if path.is_dir() {
+                    std::fs::remove_dir_all(path)?;
+                } else {
+                    std::fs::remove_file(path)?;
+                }
+            }
+        }
+        Kind::Installed(_) | Kind::Workspace(_) => {}
+    }
+    Ok(())
+}
+
+/// Format a number of bytes like `512 B`, `1.5 MB`, or `2.3 GB`
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KB", "MB", "GB", "TB"];
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{size:.1} {}", UNITS[unit])
+}
+
+// the total size of the files in a directory, or of the file
+fn dir_size(path: &Path) -> u64 {
+    let Ok(metadata) = std::fs::symlink_metadata(path) else {
+        return 0;
+    };
+    if !metadata.is_dir() {
+        return metadata.len();
+    }
+    std::fs::read_dir(path)
+        .map(|entries| entries.flatten().map(|entry| dir_size(&entry.path())).sum())
+        .unwrap_or_default()
+}
+
+// the entries in the cache directory, leaving out the application data directory when it is
+// inside of it
+fn cache_entries() -> Vec<PathBuf> {
+    let Ok(cache_dir) = application::cache_dir() else {
+        return Vec::new();
+    };
+    let data_dir = application::data_dir().ok();
+    let Ok(entries) = std::fs::read_dir(cache_dir) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            data_dir
+                .as_ref()
+                .is_none_or(|data_dir| !data_dir.starts_with(path))
+        })
+        .collect()
+}
+
+// the ids and the total size of the images built by the lesson checks
+async fn images(docker: &str) -> Result<(Vec<String>, u64), Error> {
+    let mut ids = run(docker, &["image", "ls", "-q", "--filter", COMPOSE_LABEL]).await?;
+    let mut args = vec!["image", "ls", "-q"];
+    for reference in IMAGE_REFERENCES {
+        args.extend(["--filter", reference]);
+    }
+    ids.extend(run(docker, &args).await?);
+    ids.sort();
+    ids.dedup();
+    if ids.is_empty() {
+        return Ok((ids, 0));
+    }
+
+    let mut args = vec!["image", "inspect", "--format", "{{.Size}}"];
+    args.extend(ids.iter().map(String::as_str));
+    let bytes = run(docker, &args)
+        .await?
+        .iter()
+        .filter_map(|size| size.parse::<u64>().ok())
+        .sum();
+    Ok((ids, bytes))
+}
+
+// run a docker command, returning the non-empty lines of its output
+async fn run(docker: &str, args: &[&str]) -> Result<Vec<String>, Error> {
+    debug!("Running: {docker} {}", args.join(" "));
+    let output = Command::new(docker).args(args).output().await?;
+    if !output.status.success() {
+        return Err(Error::Command(
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(str::to_string)
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sizes() {
+        assert_eq!(format_size(512), "512 B");
+        assert_eq!(format_size(1536), "1.5 KB");
+        assert_eq!(format_size(5 * 1024 * 1024 * 1024), "5.0 GB");
+
+        let dir = std::env::temp_dir().join(format!("workshop-usage-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("nested")).unwrap();
+        std::fs::write(dir.join("a"), [0u8; 100]).unwrap();
+        std::fs::write(dir.join("nested").join("b"), [0u8; 28]).unwrap();
+        assert_eq!(dir_size(&dir), 128);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
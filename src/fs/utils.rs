@@ -5,18 +5,385 @@ use crate::{
     Error,
 };
 use semver::Version;
+use serde::{Deserialize, Serialize};
 use std::{
-    collections::HashMap,
+    collections::{BTreeMap, BTreeSet, HashMap},
     path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        OnceLock,
+    },
+    time::SystemTime,
 };
 use tokio::process::Command;
-use tracing::{debug, info};
+use tracing::{debug, info, warn};
 
 const APPLICATION_PARTS: [&str; 3] = ["io", "libp2p", "workshop"];
 
+/// The system-wide directories searched for pre-provisioned, read-only workshops when the
+/// `WORKSHOPS_PATH` environment variable is not set
+#[cfg(unix)]
+const SYSTEM_DIRS: &[&str] = &["/usr/share/workshop", "/usr/local/share/workshop"];
+#[cfg(not(unix))]
+const SYSTEM_DIRS: &[&str] = &[];
+
+/// The project-local directory, relative to the pwd, searched for workshops
+const PROJECT_DIR: &str = "workshops";
+
+/// Write the content to the file at the given path without ever leaving a truncated file behind.
+/// The content is written to a temporary file that is renamed over the target, and the previous
+/// version of the file is kept next to it with a `.bak` extension. Every write has a temporary
+/// file of its own, so concurrent writes of the same file don't mix, the last one wins.
+pub fn write_atomic<P: AsRef<Path>, C: AsRef<[u8]>>(path: P, content: C) -> Result<(), Error> {
+    use std::io::Write;
+
+    static WRITES: AtomicUsize = AtomicUsize::new(0);
+
+    let path = path.as_ref();
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let write = WRITES.fetch_add(1, Ordering::Relaxed);
+    let tmp_path = sibling(path, &format!("{}-{write}.tmp", std::process::id()));
+    let written = std::fs::File::create(&tmp_path).and_then(|mut file| {
+        file.write_all(content.as_ref())?;
+        file.sync_all()
+    });
+    if let Err(e) = written {
+        let _ = std::fs::remove_file(&tmp_path);
+        return Err(e.into());
+    }
+    if path.exists() {
+        // the backup is written the same way so that a concurrent write can't tear it
+        let backup_tmp = sibling(path, &format!("{}-{write}.bak.tmp", std::process::id()));
+        std::fs::copy(path, &backup_tmp)?;
+        std::fs::rename(&backup_tmp, backup_path(path))?;
+    }
+    std::fs::rename(&tmp_path, path)?;
+    // the rename is only durable once the directory holding the file is synced
+    #[cfg(unix)]
+    if let Some(parent) = path.parent() {
+        std::fs::File::open(parent)?.sync_all()?;
+    }
+    Ok(())
+}
+
+/// Get the path of the backup kept by [`write_atomic`] for the given file
+pub fn backup_path<P: AsRef<Path>>(path: P) -> PathBuf {
+    sibling(path.as_ref(), "bak")
+}
+
+// get the path next to the given one with the extra extension appended
+fn sibling(path: &Path, extension: &str) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".");
+    name.push(extension);
+    path.with_file_name(name)
+}
+
+/// Check that the name of a workshop, or of anything else kept in a directory of its own, is a
+/// single plain path component, so that joining it onto a directory can't reach outside of it
+pub fn check_name(name: &str) -> Result<&str, Error> {
+    let mut components = Path::new(name).components();
+    match (components.next(), components.next()) {
+        (Some(std::path::Component::Normal(component)), None) if component == name => Ok(name),
+        _ => Err(fs::Error::InvalidName(name.to_string()).into()),
+    }
+}
+
 pub mod application {
     use super::*;
 
+    /// The directory overrides given on the command line
+    static DIR_OVERRIDES: OnceLock<DirOverrides> = OnceLock::new();
+
+    /// The application directories given on the command line, these take precedence over the
+    /// environment variables and the platform defaults
+    #[derive(Clone, Debug, Default)]
+    pub struct DirOverrides {
+        /// where workshops are installed
+        pub data_dir: Option<PathBuf>,
+        /// where the config file is stored
+        pub config_dir: Option<PathBuf>,
+        /// where the log file is written
+        pub state_dir: Option<PathBuf>,
+        /// where downloads and other caches are kept
+        pub cache_dir: Option<PathBuf>,
+    }
+
+    /// The tools the workshops depend on
+    #[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+    #[serde(rename_all = "kebab-case")]
+    pub enum Tool {
+        Python,
+        #[serde(alias = "docker")]
+        DockerCompose,
+        Git,
+        Node,
+        Npm,
+        Go,
+    }
+
+    impl Tool {
+        /// The executable to try when no other one was detected
+        pub fn default_executable(&self) -> &'static str {
+            match self {
+                Tool::Python => "python3",
+                Tool::DockerCompose => "docker",
+                Tool::Git => "git",
+                Tool::Node => "node",
+                Tool::Npm => "npm",
+                Tool::Go => "go",
+            }
+        }
+
+        /// Where to get the tool
+        pub fn download_url(&self) -> &'static str {
+            match self {
+                Tool::Python => "https://www.python.org/downloads/",
+                Tool::DockerCompose => "https://docs.docker.com/compose/install/",
+                Tool::Git => "https://git-scm.com/downloads",
+                Tool::Node | Tool::Npm => "https://nodejs.org/en/download",
+                Tool::Go => "https://go.dev/dl/",
+            }
+        }
+
+        /// The environment variable holding the path to the tool for the deps and check scripts
+        pub fn env_var(&self) -> &'static str {
+            match self {
+                Tool::Python => "PYTHON_EXECUTABLE",
+                Tool::DockerCompose => "DOCKER_EXECUTABLE",
+                Tool::Git => "GIT_EXECUTABLE",
+                Tool::Node => "NODE_EXECUTABLE",
+                Tool::Npm => "NPM_EXECUTABLE",
+                Tool::Go => "GO_EXECUTABLE",
+            }
+        }
+    }
+
+    impl std::fmt::Display for Tool {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            match self {
+                Tool::Python => write!(f, "Python"),
+                Tool::DockerCompose => write!(f, "Docker Compose"),
+                Tool::Git => write!(f, "Git"),
+                Tool::Node => write!(f, "Node.js"),
+                Tool::Npm => write!(f, "npm"),
+                Tool::Go => write!(f, "Go"),
+            }
+        }
+    }
+
+    /// Run the given executable of the tool to get its version, `None` if it doesn't run or its
+    /// version can't be parsed
+    pub async fn tool_version(tool: Tool, executable: &str) -> Option<Version> {
+        let standalone = executable.contains("docker-compose");
+        let args: &[&str] = match tool {
+            Tool::DockerCompose if !standalone => &["compose", "version"],
+            Tool::Go => &["version"],
+            _ => &["--version"],
+        };
+        let output = Command::new(executable).args(args).output().await.ok()?;
+        if !output.status.success() {
+            return None;
+        }
+        parse_tool_version(&String::from_utf8_lossy(&output.stdout))
+    }
+
+    /// Check the tools against the minimum versions a workshop requires, using the given
+    /// executables or the default ones, and explain how to fix each tool that is missing or too old
+    pub async fn unmet_requirements(
+        requires: &BTreeMap<Tool, String>,
+        executables: &HashMap<Tool, String>,
+    ) -> Vec<String> {
+        let mut unmet = Vec::new();
+        for (tool, minimum) in requires {
+            let Some(minimum_version) = parse_tool_version(minimum) else {
+                warn!("Ignoring the invalid minimum {tool} version: {minimum}");
+                continue;
+            };
+            let executable = executables
+                .get(tool)
+                .map(String::as_str)
+                .unwrap_or(tool.default_executable());
+            match tool_version(*tool, executable).await {
+                Some(version) if version >= minimum_version => {}
+                Some(version) => unmet.push(format!(
+                    "This workshop needs {tool} {minimum} or newer but {executable} is {version}, \
+                     upgrade it from {}",
+                    tool.download_url()
+                )),
+                None => unmet.push(format!(
+                    "This workshop needs {tool} {minimum} or newer but {executable} was not \
+                     found, install it from {}",
+                    tool.download_url()
+                )),
+            }
+        }
+        unmet
+    }
+
+    // get the first version number in the output of a --version command, like "Python 3.12.1",
+    // "Docker Compose version v2.36.2", "go version go1.22.1 linux/amd64", or
+    // "git version 2.43.0.windows.1", a missing patch number counts as zero
+    pub(crate) fn parse_tool_version(output: &str) -> Option<Version> {
+        output.split_whitespace().find_map(|word| {
+            let numbers = word
+                .trim_start_matches(|c: char| c.is_ascii_alphabetic())
+                .trim_end_matches(',')
+                .split('.')
+                .map_while(|part| part.parse::<u64>().ok())
+                .collect::<Vec<_>>();
+            match numbers[..] {
+                [major, minor] => Some(Version::new(major, minor, 0)),
+                [major, minor, patch, ..] => Some(Version::new(major, minor, patch)),
+                _ => None,
+            }
+        })
+    }
+
+    /// Try to get the path to the node executable
+    pub async fn find_node_executable<S: AsRef<str>>(min_version: S) -> Result<String, Error> {
+        // Common node executable names
+        let mut candidates = vec!["node"];
+
+        // Platform-specific candidates
+        #[cfg(target_os = "windows")]
+        {
+            candidates.extend(vec![
+                "node.exe",
+                "C:\\Program Files\\nodejs\\node.exe",
+                "C:\\Program Files (x86)\\nodejs\\node.exe",
+            ]);
+        }
+
+        #[cfg(target_os = "macos")]
+        {
+            candidates.extend(vec![
+                "/usr/local/bin/node",
+                "/opt/homebrew/bin/node",
+                "~/.volta/bin/node",
+            ]);
+        }
+
+        #[cfg(target_os = "linux")]
+        {
+            candidates.extend(vec![
+                "/usr/bin/node",
+                "/usr/local/bin/node",
+                "/snap/bin/node",
+                "~/.volta/bin/node",
+            ]);
+        }
+
+        find_tool_executable(Tool::Node, &candidates, min_version.as_ref())
+            .await
+            .ok_or(fs::Error::NoNodeExecutable.into())
+    }
+
+    /// Try to get the path to the npm executable
+    pub async fn find_npm_executable<S: AsRef<str>>(min_version: S) -> Result<String, Error> {
+        // Common npm executable names
+        let mut candidates = vec!["npm"];
+
+        // Platform-specific candidates, npm is a batch script on Windows
+        #[cfg(target_os = "windows")]
+        {
+            candidates.extend(vec![
+                "npm.cmd",
+                "C:\\Program Files\\nodejs\\npm.cmd",
+                "C:\\Program Files (x86)\\nodejs\\npm.cmd",
+            ]);
+        }
+
+        #[cfg(target_os = "macos")]
+        {
+            candidates.extend(vec![
+                "/usr/local/bin/npm",
+                "/opt/homebrew/bin/npm",
+                "~/.volta/bin/npm",
+            ]);
+        }
+
+        #[cfg(target_os = "linux")]
+        {
+            candidates.extend(vec![
+                "/usr/bin/npm",
+                "/usr/local/bin/npm",
+                "/snap/bin/npm",
+                "~/.volta/bin/npm",
+            ]);
+        }
+
+        find_tool_executable(Tool::Npm, &candidates, min_version.as_ref())
+            .await
+            .ok_or(fs::Error::NoNpmExecutable.into())
+    }
+
+    /// Try to get the path to the go executable
+    pub async fn find_go_executable<S: AsRef<str>>(min_version: S) -> Result<String, Error> {
+        // Common go executable names
+        let mut candidates = vec!["go"];
+
+        // Platform-specific candidates
+        #[cfg(target_os = "windows")]
+        {
+            candidates.extend(vec![
+                "go.exe",
+                "C:\\Program Files\\Go\\bin\\go.exe",
+                "C:\\Go\\bin\\go.exe",
+            ]);
+        }
+
+        #[cfg(target_os = "macos")]
+        {
+            candidates.extend(vec![
+                "/usr/local/go/bin/go",
+                "/opt/homebrew/bin/go",
+                "/usr/local/bin/go",
+            ]);
+        }
+
+        #[cfg(target_os = "linux")]
+        {
+            candidates.extend(vec![
+                "/usr/local/go/bin/go",
+                "/usr/bin/go",
+                "/usr/lib/go/bin/go",
+                "/snap/bin/go",
+            ]);
+        }
+
+        find_tool_executable(Tool::Go, &candidates, min_version.as_ref())
+            .await
+            .ok_or(fs::Error::NoGoExecutable.into())
+    }
+
+    // get the first of the candidates that runs and is at least the minimum version
+    async fn find_tool_executable(
+        tool: Tool,
+        candidates: &[&str],
+        min_version: &str,
+    ) -> Option<String> {
+        let min_version = parse_tool_version(min_version)?;
+        for candidate in candidates {
+            // Expand tilde (~) for home directory on Unix-like systems
+            let candidate = shellexpand::tilde(candidate).to_string();
+            debug!("Checking {tool} candidate: {candidate}");
+            match tool_version(tool, &candidate).await {
+                Some(version) if version >= min_version => {
+                    info!("Found {tool} executable: {candidate} (version: {version})");
+                    return Some(candidate);
+                }
+                Some(version) => {
+                    debug!("{tool} version {version} is below minimum {min_version}")
+                }
+                None => debug!("Candidate '{candidate}' did not return a valid {tool} version"),
+            }
+        }
+        None
+    }
+
     /// Try to get the path to the python executable
     pub async fn find_python_executable<S: AsRef<str>>(min_version: S) -> Result<String, Error> {
         // parse the python version from the --version output
@@ -388,45 +755,240 @@ pub mod application {
         Err(fs::Error::NoDockerComposeExecutable.into())
     }
 
-    /// Get the application data directory. This works on Windows, macOS, and Linux.
-    pub fn data_dir() -> Result<PathBuf, Error> {
-        let data_dir = if let Ok(workshops_dir) = std::env::var("WORKSHOPS_DIR") {
-            PathBuf::from(workshops_dir)
-        } else {
-            directories::ProjectDirs::from(
-                APPLICATION_PARTS[0],
-                APPLICATION_PARTS[1],
-                APPLICATION_PARTS[2],
-            )
-            .map(|dirs| dirs.data_dir().to_path_buf())
-            .ok_or(fs::Error::ApplicationDirsNotFound)?
-        };
-
-        // create the data directory if it doesn't exist
-        std::fs::create_dir_all(&data_dir)?;
-
-        Ok(data_dir)
+    /// Set the application directories given on the command line. This must be called before
+    /// any of the directories are used and only the first call has any effect.
+    pub fn set_dir_overrides(overrides: DirOverrides) {
+        let _ = DIR_OVERRIDES.set(overrides);
     }
 
-    /// Get the application config directory. This works on Windows, macOS, and Linux.
-    pub fn config_dir() -> Result<PathBuf, Error> {
-        let config_dir = directories::ProjectDirs::from(
+    // get the platform specific project directories
+    fn project_dirs() -> Result<directories::ProjectDirs, Error> {
+        directories::ProjectDirs::from(
             APPLICATION_PARTS[0],
             APPLICATION_PARTS[1],
             APPLICATION_PARTS[2],
         )
-        .map(|dirs| dirs.config_dir().to_path_buf())
-        .ok_or(fs::Error::ApplicationDirsNotFound)?;
+        .ok_or(fs::Error::ApplicationDirsNotFound.into())
+    }
+
+    // resolve a directory from the command line override, then the first of the environment
+    // variables that is set, then the platform default, and create it if it doesn't exist
+    fn resolve_dir(
+        flag: impl Fn(&DirOverrides) -> Option<&PathBuf>,
+        vars: &[&str],
+        default: impl FnOnce(&directories::ProjectDirs) -> PathBuf,
+    ) -> Result<PathBuf, Error> {
+        let dir = match DIR_OVERRIDES.get().and_then(flag) {
+            Some(dir) => dir.clone(),
+            None => match vars.iter().find_map(std::env::var_os) {
+                Some(dir) => PathBuf::from(dir),
+                None => default(&project_dirs()?),
+            },
+        };
+        std::fs::create_dir_all(&dir)?;
+        Ok(dir)
+    }
+
+    /// Get the directory where workshops are installed. This is the `workshops` folder in the
+    /// cache directory unless overridden by `--data-dir`, `WORKSHOP_DATA_DIR`, or `WORKSHOPS_DIR`.
+    pub fn data_dir() -> Result<PathBuf, Error> {
+        resolve_dir(
+            |o| o.data_dir.as_ref(),
+            &["WORKSHOP_DATA_DIR", "WORKSHOPS_DIR"],
+            |dirs| dirs.cache_dir().join("workshops"),
+        )
+    }
+
+    /// Get the application config directory (`XDG_CONFIG_HOME` on Linux) unless overridden by
+    /// `--config-dir` or `WORKSHOP_CONFIG_DIR`.
+    pub fn config_dir() -> Result<PathBuf, Error> {
+        resolve_dir(
+            |o| o.config_dir.as_ref(),
+            &["WORKSHOP_CONFIG_DIR"],
+            |dirs| dirs.config_dir().to_path_buf(),
+        )
+    }
+
+    /// Get the application state directory (`XDG_STATE_HOME` on Linux, the local data directory
+    /// elsewhere) unless overridden by `--state-dir` or `WORKSHOP_STATE_DIR`.
+    pub fn state_dir() -> Result<PathBuf, Error> {
+        resolve_dir(
+            |o| o.state_dir.as_ref(),
+            &["WORKSHOP_STATE_DIR"],
+            |dirs| {
+                dirs.state_dir()
+                    .unwrap_or_else(|| dirs.data_local_dir())
+                    .to_path_buf()
+            },
+        )
+    }
+
+    /// Get the application cache directory (`XDG_CACHE_HOME` on Linux) unless overridden by
+    /// `--cache-dir` or `WORKSHOP_CACHE_DIR`.
+    pub fn cache_dir() -> Result<PathBuf, Error> {
+        resolve_dir(
+            |o| o.cache_dir.as_ref(),
+            &["WORKSHOP_CACHE_DIR"],
+            |dirs| dirs.cache_dir().to_path_buf(),
+        )
+    }
+
+    /// Move the workshops installed by older versions, which kept everything in the platform data
+    /// directory, into the current data directory. This only happens once, when the current data
+    /// directory is still empty, and only the directories holding a workshop are moved. Returns
+    /// true if any workshops were moved.
+    pub fn migrate_legacy_data_dir() -> Result<bool, Error> {
+        let legacy_dir = project_dirs()?.data_dir().to_path_buf();
+        let data_dir = data_dir()?;
+        if legacy_dir == data_dir
+            || !legacy_dir.is_dir()
+            || (data_dir.is_dir() && std::fs::read_dir(&data_dir)?.next().is_some())
+        {
+            return Ok(false);
+        }
+
+        let mut migrated = false;
+        for entry in std::fs::read_dir(&legacy_dir)? {
+            let source = entry?.path();
+            if !source.join("defaults.yaml").is_file() {
+                continue;
+            }
+            std::fs::create_dir_all(&data_dir)?;
+            let target = data_dir.join(source.file_name().unwrap_or_default());
+            info!(
+                "Migrating workshop from {} to {}",
+                source.display(),
+                target.display()
+            );
+            // renaming fails across file systems so fall back to copying
+            if std::fs::rename(&source, &target).is_err() {
+                workshops::copy_tree(&source, &target)?;
+                std::fs::remove_dir_all(&source)?;
+            }
+            migrated = true;
+        }
+        Ok(migrated)
+    }
+
+    /// Get the directory the status and progress are kept in: the `.workshops` directory of the
+    /// project once there is one, and the state directory before that
+    pub fn status_dir() -> Option<PathBuf> {
+        workshops::data_dir().or_else(|| state_dir().ok())
+    }
+
+    /// Get the directories searched for installed workshops, ordered from the lowest to the
+    /// highest precedence: the system-wide directories (or the directories listed in the
+    /// `WORKSHOPS_PATH` environment variable), the application data directory where new workshops
+    /// are installed, and the project-local `workshops` directory. A workshop in a directory with
+    /// a higher precedence shadows a workshop with the same name in a lower one.
+    pub fn search_dirs() -> Result<Vec<PathBuf>, Error> {
+        let system_dirs: Vec<PathBuf> = match std::env::var_os("WORKSHOPS_PATH") {
+            Some(paths) => std::env::split_paths(&paths).collect(),
+            None => SYSTEM_DIRS.iter().map(PathBuf::from).collect(),
+        };
+        let data_dir = data_dir()?;
+        let project_dir = std::env::current_dir().map(|d| d.join(PROJECT_DIR)).ok();
+
+        let mut dirs: Vec<PathBuf> = Vec::new();
+        for dir in system_dirs
+            .into_iter()
+            .chain(Some(data_dir.clone()))
+            .chain(project_dir)
+        {
+            if (dir == data_dir || dir.is_dir()) && !dirs.contains(&dir) {
+                dirs.push(dir);
+            }
+        }
+        Ok(dirs)
+    }
+
+    /// Find the directory of the given installed workshop in the search directories, honoring
+    /// their precedence
+    pub fn workshop_dir<S: AsRef<str>>(workshop: S) -> Result<PathBuf, Error> {
+        let workshop = check_name(workshop.as_ref())?;
+        search_dirs()?
+            .into_iter()
+            .rev()
+            .map(|dir| dir.join(workshop))
+            .find(|dir| dir.is_dir())
+            .ok_or(fs::Error::WorkshopDataDirNotFound.into())
+    }
+
+    /// Remove the given workshop from the application data directory as well as the copy, and
+    /// the progress in it, from the `.workshops` directory
+    pub fn uninstall_workshop<S: AsRef<str>>(workshop: S) -> Result<(), Error> {
+        let workshop = check_name(workshop.as_ref())?;
+        let mut dirs = vec![data_dir()?.join(workshop)];
+        if let Some(workshops_dir) = workshops::data_dir() {
+            dirs.push(workshops_dir.join(workshop));
+        }
+        let keys = workshops::progress_keys(workshop);
+        let mut removed = false;
+        for dir in dirs.iter().filter(|d| d.is_dir()) {
+            info!("Removing workshop data: {}", dir.display());
+            std::fs::remove_dir_all(dir)?;
+            removed = true;
+        }
+        if removed {
+            fs::install::Trust::remove(workshop)?;
+            keys.iter().try_for_each(|key| fs::progress::remove(key))
+        } else {
+            Err(fs::Error::WorkshopDataDirNotFound.into())
+        }
+    }
+
+    /// Get when the installed workshop in the given directory last changed, which is when it was
+    /// installed or, for workshops installed from git, last updated
+    pub fn last_updated<T: AsRef<Path>>(workshop_dir: T) -> Option<SystemTime> {
+        let workshop_dir = workshop_dir.as_ref();
+        // pulling rewrites the git index even when the top level directory doesn't change
+        [
+            workshop_dir.to_path_buf(),
+            workshop_dir.join(".git").join("index"),
+            workshop_dir.join(".git").join("FETCH_HEAD"),
+        ]
+        .iter()
+        .filter_map(|path| {
+            path.metadata()
+                .and_then(|metadata| metadata.modified())
+                .ok()
+        })
+        .max()
+    }
 
-        // create the config directory if it doesn't exist
-        std::fs::create_dir_all(&config_dir)?;
+    /// Get the lesson directories of the installed workshop in the given directory that have a
+    /// `docker-compose.yaml` file for the environment the lesson checks run in, in path order
+    pub fn lesson_compose_dirs<T: AsRef<Path>>(workshop_dir: T) -> Vec<PathBuf> {
+        fn subdirs(dir: &Path) -> Vec<PathBuf> {
+            std::fs::read_dir(dir)
+                .map(|entries| {
+                    entries
+                        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                        .filter(|path| path.is_dir())
+                        .collect()
+                })
+                .unwrap_or_default()
+        }
 
-        Ok(config_dir)
+        // the lessons are in the programming language folders of the spoken language folders
+        let mut dirs = subdirs(workshop_dir.as_ref())
+            .iter()
+            .flat_map(|spoken| subdirs(spoken))
+            .flat_map(|programming| subdirs(&programming))
+            .filter(|lesson| lesson.join("docker-compose.yaml").is_file())
+            .collect::<Vec<_>>();
+        dirs.sort();
+        dirs
     }
 
-    /// Get all of the workshops data objects for all workshops in the application data directory
+    /// Get all of the workshops data objects for all workshops in the search directories and the
+    /// `.workshops` directory, which takes precedence over all of them
     pub fn all_workshops() -> Result<HashMap<String, workshop::WorkshopData>, Error> {
-        let mut workshops_data = workshops::load_workshop_data(data_dir()?)?;
+        let mut workshops_data = HashMap::new();
+        for dir in search_dirs()? {
+            debug!("Loading workshops from: {}", dir.display());
+            workshops_data.extend(workshops::load_workshop_data(dir)?);
+        }
         if let Some(workshops_dir) = workshops::data_dir() {
             // If the workshops directory exists, load the workshop data from there
             workshops_data.extend(workshops::load_workshop_data(workshops_dir)?);
@@ -434,19 +996,91 @@ pub mod application {
         Ok(workshops_data)
     }
 
-    /// Get all of the workshops in the application data directory, that support the given spoken
-    /// and programming languages
+    /// The installed workshops found by a scan, along with the ones that failed to load
+    #[derive(Clone, Debug, Default)]
+    pub struct Scan {
+        /// the workshops that loaded
+        pub workshops: HashMap<String, workshop::WorkshopData>,
+        /// the workshops that failed to load and why
+        pub failed: BTreeMap<String, String>,
+    }
+
+    /// Get all of the workshops data objects like [`all_workshops`] but load every workshop
+    /// directory in parallel, which is a lot faster with many workshops on a network filesystem.
+    /// A workshop that fails to load is reported in the scan instead of failing all of it.
+    pub async fn scan_workshops() -> Result<Scan, Error> {
+        let mut dirs = search_dirs()?;
+        dirs.extend(workshops::data_dir());
+
+        // start loading all of the workshops, keeping the directory precedence order
+        let mut loads = Vec::new();
+        for dir in dirs {
+            debug!("Scanning workshops in: {}", dir.display());
+            for workshop_name in workshops::workshop_names(&dir)? {
+                let dir = dir.clone();
+                loads.push(tokio::task::spawn_blocking(move || {
+                    let workshop_data = workshop::Loader::new(&workshop_name).path(&dir).try_load();
+                    (workshop_name, workshop_data)
+                }));
+            }
+        }
+
+        // a workshop in a later directory replaces the one in an earlier directory, even when
+        // it fails to load
+        let mut scan = Scan::default();
+        for load in loads {
+            let (workshop_name, workshop_data) = load.await?;
+            match workshop_data {
+                Ok(workshop_data) => {
+                    scan.failed.remove(&workshop_name);
+                    scan.workshops.insert(workshop_name, workshop_data);
+                }
+                Err(e) => {
+                    warn!("Failed to load workshop {workshop_name}: {e}");
+                    scan.workshops.remove(&workshop_name);
+                    scan.failed.insert(workshop_name, e.to_string());
+                }
+            }
+        }
+        Ok(scan)
+    }
+
+    /// Get all of the installed workshops that support the given spoken and programming languages
     pub fn all_workshops_filtered(
         spoken_language: Option<spoken::Code>,
         programming_language: Option<programming::Code>,
     ) -> Result<HashMap<String, workshop::WorkshopData>, Error> {
-        let workshops = all_workshops()?;
-        Ok(workshops
+        Ok(filter_workshops(
+            all_workshops()?,
+            spoken_language,
+            programming_language,
+        ))
+    }
+
+    /// Get all of the installed workshops that support the given spoken and programming
+    /// languages, scanning the workshop directories in parallel
+    pub async fn scan_workshops_filtered(
+        spoken_language: Option<spoken::Code>,
+        programming_language: Option<programming::Code>,
+    ) -> Result<Scan, Error> {
+        let scan = scan_workshops().await?;
+        Ok(Scan {
+            workshops: filter_workshops(scan.workshops, spoken_language, programming_language),
+            failed: scan.failed,
+        })
+    }
+
+    fn filter_workshops(
+        workshops: HashMap<String, workshop::WorkshopData>,
+        spoken_language: Option<spoken::Code>,
+        programming_language: Option<programming::Code>,
+    ) -> HashMap<String, workshop::WorkshopData> {
+        workshops
             .into_iter()
             .filter(|(_, workshop_data)| {
                 workshop_data.is_selected(spoken_language, programming_language)
             })
-            .collect())
+            .collect()
     }
 
     /// Get all of the spoken languages supported by all workshops in the application data
@@ -497,8 +1131,9 @@ pub mod application {
 pub mod workshops {
     use super::*;
 
-    // recursively copy the folder from the source path to the target path
-    fn copy_tree<P: AsRef<Path>>(source: P, target: P) -> Result<(), Error> {
+    /// Recursively copy the folder from the source path to the target path, leaving out any git
+    /// metadata
+    pub fn copy_tree<P: AsRef<Path>>(source: P, target: P) -> Result<(), Error> {
         let source = source.as_ref();
         let target = target.as_ref();
 
@@ -514,7 +1149,9 @@ pub mod workshops {
             let entry_path = entry.path();
             let target_path = target.join(entry.file_name());
 
-            if entry_path.is_dir() {
+            if entry.file_name() == ".git" {
+                continue;
+            } else if entry_path.is_dir() {
                 copy_tree(entry_path, target_path)?;
             } else {
                 std::fs::copy(entry_path, target_path)?;
@@ -523,6 +1160,51 @@ pub mod workshops {
         Ok(())
     }
 
+    /// Copy the files of the source directory that are missing from the target directory or
+    /// differ from the ones in it, skipping `.git`. Returns the number of files copied.
+    pub fn sync_tree<P: AsRef<Path>>(source: P, target: P) -> Result<usize, Error> {
+        let source = source.as_ref();
+        let target = target.as_ref();
+
+        if !source.exists() || !source.is_dir() {
+            return Err(fs::Error::WorkshopDataDirNotFound.into());
+        }
+        std::fs::create_dir_all(target)?;
+
+        let mut copied = 0;
+        for entry in std::fs::read_dir(source)? {
+            let entry = entry?;
+            let entry_path = entry.path();
+            let target_path = target.join(entry.file_name());
+
+            if entry.file_name() == ".git" {
+                continue;
+            } else if entry_path.is_dir() {
+                copied += sync_tree(entry_path, target_path)?;
+            } else if std::fs::read(&target_path).ok() != Some(std::fs::read(&entry_path)?) {
+                debug!("Copying changed file {}", entry_path.display());
+                std::fs::copy(entry_path, target_path)?;
+                copied += 1;
+            }
+        }
+        Ok(copied)
+    }
+
+    /// Copy the changed files of the installed workshop into its copy in the `.workshops`
+    /// directory, so that the edits made to the workshop show up when it is loaded again. Returns
+    /// the number of files copied, none when the workshop hasn't been copied yet.
+    pub fn refresh<S: AsRef<str>>(workshop: S) -> Result<usize, Error> {
+        let workshop = check_name(workshop.as_ref())?;
+        let Some(target_path) = data_dir().map(|dir| dir.join(workshop)) else {
+            return Ok(0);
+        };
+        if !target_path.is_dir() {
+            return Ok(0);
+        }
+        let workshop_path = application::workshop_dir(workshop)?;
+        sync_tree(workshop_path, target_path)
+    }
+
     /// Initialize the present working directory (pwd) by creating a `.workshops` directory, if
     /// missing, and then recursively copying the selected workshop from the application data
     /// directory to the `.workshops` directory. Then return the path to the `.workshops`
@@ -536,10 +1218,10 @@ pub mod workshops {
         std::fs::create_dir_all(&workshops_dir)?;
 
         // Copy the selected workshop to the workshops directory
-        let data_dir = application::data_dir()?;
-        let workshop_path = data_dir.join(workshop.as_ref());
+        let workshop = check_name(workshop.as_ref())?;
+        let workshop_path = application::workshop_dir(workshop)?;
         if workshop_path.exists() && workshop_path.is_dir() {
-            let target_path = workshops_dir.join(workshop.as_ref());
+            let target_path = workshops_dir.join(workshop);
             debug!(
                 "Copying workshop data from {} to {}",
                 workshop_path.display(),
@@ -575,9 +1257,10 @@ pub mod workshops {
     /// Get the given workshop in the `.workshops` directory, if it exists.
     pub fn load<S: AsRef<str>>(workshop: S) -> Option<workshop::WorkshopData> {
         let workshops_dir = data_dir()?;
-        let workshop_path = workshops_dir.join(workshop.as_ref());
+        let workshop = check_name(workshop.as_ref()).ok()?;
+        let workshop_path = workshops_dir.join(workshop);
         if workshop_path.exists() && workshop_path.is_dir() {
-            return workshop::Loader::new(workshop.as_ref())
+            return workshop::Loader::new(workshop)
                 .path(&workshops_dir)
                 .try_load()
                 .ok();
@@ -585,6 +1268,81 @@ pub mod workshops {
         None
     }
 
+    /// Reset the progress stored in the `.workshops` directory. A single lesson is marked as not
+    /// started in the given languages while the progress of workshops is removed, along with
+    /// their copies so that they get copied fresh from the installed workshop the next time they
+    /// are selected.
+    pub async fn reset_progress(
+        scope: &crate::status::ProgressScope,
+        spoken_language: Option<spoken::Code>,
+        programming_language: Option<programming::Code>,
+    ) -> Result<(), Error> {
+        use crate::{models::lesson, status::ProgressScope};
+
+        let Some(workshops_dir) = data_dir() else {
+            return Ok(());
+        };
+        match scope {
+            ProgressScope::Lesson(workshop, lesson_key) => {
+                let workshop_data = load(workshop).ok_or(fs::Error::WorkshopDataDirNotFound)?;
+                let lessons = workshop_data
+                    .get_lessons_data(spoken_language, programming_language)
+                    .await?;
+                if let Some(lesson_data) = lessons.get(lesson_key) {
+                    info!("Resetting lesson progress: {workshop}/{lesson_key}");
+                    lesson_data
+                        .update_status(lesson::Status::NotStarted)
+                        .await?;
+                }
+                let metadata = workshop_data.get_metadata(spoken_language).await?;
+                if matches!(metadata.status, workshop::Status::Completed) {
+                    workshop_data
+                        .update_status(spoken_language, workshop::Status::InProgress)
+                        .await?;
+                }
+            }
+            ProgressScope::Workshop(workshop) => {
+                let workshop = check_name(workshop)?;
+                let keys = progress_keys(workshop);
+                let workshop_dir = workshops_dir.join(workshop);
+                if workshop_dir.is_dir() {
+                    info!("Resetting workshop progress: {}", workshop_dir.display());
+                    std::fs::remove_dir_all(workshop_dir)?;
+                }
+                for key in keys {
+                    fs::progress::remove(&key)?;
+                }
+            }
+            ProgressScope::All => {
+                for entry in std::fs::read_dir(&workshops_dir)? {
+                    let path = entry?.path();
+                    if is_workshop_dir(&path) {
+                        info!("Resetting workshop progress: {}", path.display());
+                        std::fs::remove_dir_all(path)?;
+                    }
+                }
+                fs::progress::remove_all()?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Get the keys the progress in the workshop may be stored under, from its copy in the
+    /// `.workshops` directory and from its installed directory, which differ for the workshops
+    /// pinned to a revision
+    pub fn progress_keys(workshop: &str) -> BTreeSet<String> {
+        if check_name(workshop).is_err() {
+            return BTreeSet::new();
+        }
+        let mut keys = BTreeSet::from([workshop.to_string()]);
+        let dirs = [
+            data_dir().map(|dir| dir.join(workshop)),
+            application::workshop_dir(workshop).ok(),
+        ];
+        keys.extend(dirs.into_iter().flatten().filter_map(fs::progress::key));
+        keys
+    }
+
     /// Get all workshop data objects for workshops in the given folder
     pub fn load_workshop_data<T: AsRef<Path>>(
         data_dir: T,
@@ -594,18 +1352,187 @@ pub mod workshops {
             return Err(fs::Error::WorkshopDataDirNotFound.into());
         }
 
+        // one broken workshop shouldn't hide all of the others
         let mut workshops = HashMap::new();
+        for workshop_name in workshop_names(data_dir)? {
+            match workshop::Loader::new(&workshop_name)
+                .path(data_dir)
+                .try_load()
+            {
+                Ok(workshop_data) => {
+                    workshops.insert(workshop_name, workshop_data);
+                }
+                Err(e) => warn!("Failed to load workshop {workshop_name}: {e}"),
+            }
+        }
+        Ok(workshops)
+    }
+
+    /// The directories next to the workshops in the `.workshops` directory that don't hold one
+    const NON_WORKSHOP_DIRS: [&str; 3] = ["notes", "certificates", "progress"];
+
+    /// Is the path a workshop directory? Every directory is one except for the hidden ones and
+    /// the ones the application keeps its own data in.
+    pub fn is_workshop_dir<T: AsRef<Path>>(path: T) -> bool {
+        let path = path.as_ref();
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        path.is_dir() && !name.starts_with('.') && !NON_WORKSHOP_DIRS.contains(&name.as_ref())
+    }
+
+    /// Get the names of the workshop directories in the given data directory
+    pub fn workshop_names<T: AsRef<Path>>(data_dir: T) -> Result<Vec<String>, Error> {
+        let data_dir = data_dir.as_ref();
+        if !data_dir.exists() || !data_dir.is_dir() {
+            return Err(fs::Error::WorkshopDataDirNotFound.into());
+        }
+
+        let mut names = Vec::new();
         for entry in std::fs::read_dir(data_dir)? {
             let entry = entry?;
-            if entry.path().is_dir() {
+            // a workshop that is missing its defaults is still listed, so that it can be reported
+            // as broken instead of silently disappearing
+            if is_workshop_dir(entry.path()) {
                 let workshop_name = entry.file_name().to_string_lossy().to_string();
                 info!("... {workshop_name}");
-                let workshop_data = workshop::Loader::new(&workshop_name)
-                    .path(data_dir)
-                    .try_load()?;
-                workshops.insert(workshop_name, workshop_data);
+                names.push(workshop_name);
             }
         }
-        Ok(workshops)
+        Ok(names)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_tool_version() {
+        let parse = application::parse_tool_version;
+        assert_eq!(parse("Python 3.12.1"), Version::parse("3.12.1").ok());
+        assert_eq!(
+            parse("Docker Compose version v2.36.2"),
+            Version::parse("2.36.2").ok()
+        );
+        assert_eq!(
+            parse("docker-compose version 1.29.2, build 5becea4c"),
+            Version::parse("1.29.2").ok()
+        );
+        assert_eq!(
+            parse("git version 2.43.0.windows.1"),
+            Version::parse("2.43.0").ok()
+        );
+        assert_eq!(parse("git version 2.39"), Version::parse("2.39.0").ok());
+        assert_eq!(
+            parse("go version go1.22.1 linux/amd64"),
+            Version::parse("1.22.1").ok()
+        );
+        assert_eq!(parse("no version here"), None);
+    }
+
+    #[tokio::test]
+    async fn test_unmet_requirements() {
+        let requires = BTreeMap::from([
+            (application::Tool::Node, "18.0.0".to_string()),
+            (application::Tool::Git, "not a version".to_string()),
+        ]);
+        let executables =
+            HashMap::from([(application::Tool::Node, "/nonexistent/node".to_string())]);
+        let unmet = application::unmet_requirements(&requires, &executables).await;
+        assert_eq!(unmet.len(), 1);
+        assert!(unmet[0].contains("Node.js 18.0.0 or newer"));
+        assert!(unmet[0].contains("/nonexistent/node was not found"));
+    }
+
+    #[test]
+    fn test_write_atomic_keeps_backup() {
+        let dir = std::env::temp_dir().join(format!("workshop-atomic-{}", std::process::id()));
+        let path = dir.join("status.yaml");
+
+        write_atomic(&path, "first").unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "first");
+        assert!(!backup_path(&path).exists());
+
+        write_atomic(&path, "second").unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "second");
+        assert_eq!(
+            std::fs::read_to_string(backup_path(&path)).unwrap(),
+            "first"
+        );
+
+        // concurrent writes each finish whole and leave no temporary files behind
+        let writers: Vec<_> = (0..8)
+            .map(|i| {
+                let path = path.clone();
+                std::thread::spawn(move || write_atomic(&path, i.to_string().repeat(4096)))
+            })
+            .collect();
+        for writer in writers {
+            writer.join().unwrap().unwrap();
+        }
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(content, content[..1].repeat(4096));
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 2);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_lesson_compose_dirs() {
+        let dir = std::env::temp_dir().join(format!("workshop-compose-{}", std::process::id()));
+        let lesson = dir.join("en").join("rs").join("01-hello");
+        std::fs::create_dir_all(&lesson).unwrap();
+        std::fs::create_dir_all(dir.join("en").join("rs").join("02-no-checks")).unwrap();
+        std::fs::create_dir_all(dir.join(".git").join("refs")).unwrap();
+        std::fs::write(lesson.join("docker-compose.yaml"), "services: {}\n").unwrap();
+
+        assert_eq!(application::lesson_compose_dirs(&dir), vec![lesson]);
+        assert!(application::lesson_compose_dirs(dir.join("missing")).is_empty());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_check_name() {
+        assert!(check_name("workshop-demo@v1.2.0").is_ok());
+        for name in ["", ".", "..", "../demo", "demo/..", "demo/", "/demo", "a/b"] {
+            assert!(check_name(name).is_err(), "{name:?} is accepted");
+        }
+    }
+
+    #[test]
+    fn test_sync_tree() {
+        let dir = std::env::temp_dir().join(format!("workshop-sync-{}", std::process::id()));
+        let (source, target) = (dir.join("source"), dir.join("target"));
+        std::fs::create_dir_all(source.join("en").join(".git")).unwrap();
+        std::fs::write(source.join("defaults.yaml"), "spoken_language: en\n").unwrap();
+        std::fs::write(source.join("en").join("workshop.md"), "old").unwrap();
+        workshops::copy_tree(&source, &target).unwrap();
+
+        // only the changed and the new files are copied
+        std::fs::write(source.join("en").join("workshop.md"), "new").unwrap();
+        std::fs::write(source.join("en").join("setup.md"), "setup").unwrap();
+        assert_eq!(workshops::sync_tree(&source, &target).unwrap(), 2);
+        assert_eq!(
+            std::fs::read_to_string(target.join("en").join("workshop.md")).unwrap(),
+            "new"
+        );
+        assert_eq!(workshops::sync_tree(&source, &target).unwrap(), 0);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_broken_workshop_is_skipped() {
+        let dir = std::env::temp_dir().join(format!("workshop-broken-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("broken").join(".git")).unwrap();
+        std::fs::create_dir_all(dir.join("notes")).unwrap();
+        std::fs::create_dir_all(dir.join(".git")).unwrap();
+        std::fs::write(dir.join("status.yaml"), "").unwrap();
+
+        // the broken workshop is found but doesn't keep the others from loading
+        assert_eq!(workshops::workshop_names(&dir).unwrap(), vec!["broken"]);
+        assert!(workshops::load_workshop_data(&dir).unwrap().is_empty());
+
+        std::fs::remove_dir_all(&dir).unwrap();
     }
 }
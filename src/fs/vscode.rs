@@ -0,0 +1,222 @@
+use crate::{fs::write_atomic, Error};
+use serde_json::{json, Value};
+use std::path::{Path, PathBuf};
+use tracing::warn;
+
+/// The label of the task that checks the lesson
+pub const CHECK_TASK_LABEL: &str = "Run Check";
+
+/// Write the VS Code configuration for working on the given lesson into the workspace: a
+/// `Run Check` task in `.vscode/tasks.json` that runs `workshop check` on the lesson, and a
+/// `.devcontainer` that opens the workspace in the lesson's docker compose service. Other
+/// tasks in an existing `tasks.json` are kept, and a `.devcontainer` the student wrote is left
+/// alone. Returns the files written.
+pub fn write_config(
+    workspace_dir: &Path,
+    workshop: &str,
+    lesson: &str,
+    lesson_dir: &Path,
+) -> Result<Vec<PathBuf>, Error> {
+    let mut written = vec![write_tasks(workspace_dir, workshop, lesson)?];
+    written.extend(write_devcontainer(workspace_dir, lesson, lesson_dir)?);
+    Ok(written)
+}
+
+// add or replace the check task, refusing to touch a tasks.json we can't parse since it may
+// hold comments or tasks of the student's own
+fn write_tasks(workspace_dir: &Path, workshop: &str, lesson: &str) -> Result<PathBuf, Error> {
+    let path = workspace_dir.join(".vscode").join("tasks.json");
+    let mut tasks = match std::fs::read_to_string(&path) {
+        Ok(content) => serde_json::from_str::<Value>(&content).map_err(|e| {
+            Error::Command(format!(
+                "Not changing {} since it can't be parsed: {e}",
+                path.display()
+            ))
+        })?,
+        Err(_) => json!({ "version": "2.0.0", "tasks": [] }),
+    };
+    let list = match tasks.as_object_mut() {
+        Some(object) => object.entry("tasks").or_insert_with(|| json!([])),
+        None => {
+            return Err(Error::Command(format!(
+                "Not changing {} since it isn't a JSON object",
+                path.display()
+            )))
+        }
+    };
+    let Some(list) = list.as_array_mut() else {
+        return Err(Error::Command(format!(
+            "Not changing {} since its tasks aren't a list",
+            path.display()
+        )));
+    };
+    list.retain(|task| task["label"] != CHECK_TASK_LABEL);
+    list.push(check_task(workshop, lesson));
+    write_json(&path, &tasks)?;
+    Ok(path)
+}
+
+fn check_task(workshop: &str, lesson: &str) -> Value {
+    json!({
+        "label": CHECK_TASK_LABEL,
+        "detail": format!("Check the solution for {workshop}/{lesson}"),
+        "type": "process",
+        "command": "workshop",
+        "args": ["check", "--workshop", workshop, "--lesson", lesson],
+        "group": "test",
+        "presentation": { "reveal": "always", "clear": true },
+        "problemMatcher": [],
+    })
+}
+
+/// The first line of the files in `.devcontainer` that we wrote and may replace
+const MARKER: &str = "# written by workshop for the lesson devcontainer";
+
+// the lesson's compose file takes PROJECT_ROOT and LESSON_PATH from the environment, so an
+// empty compose file is listed first to make .devcontainer the compose project directory,
+// which is where compose reads the .env file with them from. A devcontainer of the student's own
+// is left alone.
+fn write_devcontainer(
+    workspace_dir: &Path,
+    lesson: &str,
+    lesson_dir: &Path,
+) -> Result<Vec<PathBuf>, Error> {
+    let compose_file = lesson_dir.join("docker-compose.yaml");
+    let Some(service) = first_service(&compose_file) else {
+        return Ok(Vec::new());
+    };
+    let Ok(lesson_path) = lesson_dir.strip_prefix(workspace_dir) else {
+        return Ok(Vec::new());
+    };
+
+    let dir = workspace_dir.join(".devcontainer");
+    let devcontainer = dir.join("devcontainer.json");
+    let base = dir.join("docker-compose.yaml");
+    let env = dir.join(".env");
+    if let Some(theirs) = [&devcontainer, &base, &env]
+        .into_iter()
+        .find(|path| path.exists() && !is_ours(path))
+    {
+        warn!("Not writing the devcontainer over {}", theirs.display());
+        return Ok(Vec::new());
+    }
+
+    write_json(
+        &devcontainer,
+        &json!({
+            "name": lesson,
+            "dockerComposeFile": [
+                "docker-compose.yaml",
+                Path::new("..").join(lesson_path).join("docker-compose.yaml"),
+            ],
+            "service": service,
+            "workspaceFolder": "/app",
+            "shutdownAction": "stopCompose",
+            "customizations": { "workshop": { "lesson": lesson } },
+        }),
+    )?;
+    write_atomic(&base, format!("{MARKER}\nservices: {{}}\n"))?;
+    write_atomic(
+        &env,
+        format!(
+            "{MARKER}\nPROJECT_ROOT={}\nLESSON_PATH={}\n",
+            workspace_dir.display(),
+            lesson_path.display()
+        ),
+    )?;
+    Ok(vec![devcontainer, base, env])
+}
+
+// did we write the file in .devcontainer, the json marked in its customizations and the others
+// by their first line
+fn is_ours(path: &Path) -> bool {
+    let Ok(content) = std::fs::read_to_string(path) else {
+        return false;
+    };
+    if path.extension().is_some_and(|ext| ext == "json") {
+        serde_json::from_str::<Value>(&content)
+            .is_ok_and(|value| value["customizations"]["workshop"].is_object())
+    } else {
+        content.starts_with(MARKER)
+    }
+}
+
+// the name of the first service in the compose file
+fn first_service(compose_file: &Path) -> Option<String> {
+    let content = std::fs::read_to_string(compose_file).ok()?;
+    let compose: serde_yaml::Value = serde_yaml::from_str(&content).ok()?;
+    compose
+        .get("services")?
+        .as_mapping()?
+        .keys()
+        .next()?
+        .as_str()
+        .map(String::from)
+}
+
+fn write_json(path: &Path, value: &Value) -> Result<(), Error> {
+    let mut content = serde_json::to_string_pretty(value)
+        .map_err(|e| Error::Command(format!("Failed to write {}: {e}", path.display())))?;
+    content.push('\n');
+    write_atomic(path, content)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_config_keeps_other_tasks() {
+        let workspace = std::env::temp_dir().join(format!("vscode-config-{}", std::process::id()));
+        let lesson_dir = workspace.join(".workshops/example/en/rs/01-intro");
+        std::fs::create_dir_all(&lesson_dir).unwrap();
+        std::fs::write(
+            lesson_dir.join("docker-compose.yaml"),
+            "services:\n  lesson:\n    build: .\n",
+        )
+        .unwrap();
+        std::fs::create_dir_all(workspace.join(".vscode")).unwrap();
+        std::fs::write(
+            workspace.join(".vscode/tasks.json"),
+            r#"{"version":"2.0.0","tasks":[{"label":"build"},{"label":"Run Check"}]}"#,
+        )
+        .unwrap();
+
+        let written = write_config(&workspace, "example", "01-intro", &lesson_dir).unwrap();
+        assert_eq!(written.len(), 4);
+
+        let tasks: Value =
+            serde_json::from_str(&std::fs::read_to_string(&written[0]).unwrap()).unwrap();
+        let labels: Vec<_> = tasks["tasks"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|task| task["label"].as_str().unwrap())
+            .collect();
+        assert_eq!(labels, vec!["build", CHECK_TASK_LABEL]);
+        assert_eq!(tasks["tasks"][1]["args"][4], "01-intro");
+
+        let devcontainer: Value =
+            serde_json::from_str(&std::fs::read_to_string(&written[1]).unwrap()).unwrap();
+        assert_eq!(devcontainer["service"], "lesson");
+
+        // the files we wrote are replaced, the student's own are not
+        assert_eq!(
+            write_config(&workspace, "example", "01-intro", &lesson_dir)
+                .unwrap()
+                .len(),
+            4
+        );
+        std::fs::write(workspace.join(".devcontainer/.env"), "MINE=1\n").unwrap();
+        assert_eq!(
+            write_config(&workspace, "example", "01-intro", &lesson_dir)
+                .unwrap()
+                .len(),
+            1
+        );
+        std::fs::write(workspace.join(".vscode/tasks.json"), "[]").unwrap();
+        assert!(write_config(&workspace, "example", "01-intro", &lesson_dir).is_err());
+
+        std::fs::remove_dir_all(&workspace).unwrap();
+    }
+}
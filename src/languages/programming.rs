@@ -202,6 +202,18 @@ generate_programming_enum! {
     (fs, "F#", "fs"),
 }
 
+impl Code {
+    /// Do workshops in the language need Node.js and npm
+    pub fn needs_node(&self) -> bool {
+        matches!(self, Code::js | Code::ts)
+    }
+
+    /// Do workshops in the language need the Go toolchain
+    pub fn needs_go(&self) -> bool {
+        matches!(self, Code::go)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
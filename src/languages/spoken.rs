@@ -263,6 +263,43 @@ generate_spoken_enum! {
     (sw, "Swahili", "Kiswahili", LeftToRight)
 }
 
+/// Get the Code from a locale name such as `ja_JP.UTF-8` or `pt-BR`
+pub fn get_language_code_from_locale(locale: &str) -> Option<Code> {
+    let language = locale.split(['_', '-', '.', '@']).next()?;
+    match language {
+        // the old code for Hebrew is still used by some systems
+        "iw" => Some(Code::he),
+        _ => get_language_code(language),
+    }
+}
+
+/// Detect the spoken language of the user from the system locale
+pub fn system_language() -> Option<Code> {
+    system_locale().and_then(|locale| get_language_code_from_locale(&locale))
+}
+
+// the locale for messages is the first of these that is set and not empty
+#[cfg(not(windows))]
+fn system_locale() -> Option<String> {
+    ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .find(|locale| !locale.is_empty())
+}
+
+#[cfg(windows)]
+fn system_locale() -> Option<String> {
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn GetUserDefaultLocaleName(name: *mut u16, len: i32) -> i32;
+    }
+
+    // LOCALE_NAME_MAX_LENGTH is 85 including the terminating null
+    let mut name = [0u16; 85];
+    let len = unsafe { GetUserDefaultLocaleName(name.as_mut_ptr(), name.len() as i32) };
+    (len > 1).then(|| String::from_utf16_lossy(&name[..len as usize - 1]))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -358,4 +395,14 @@ mod tests {
 
         assert!(Language::try_from("Vogon").is_err());
     }
+
+    #[test]
+    fn test_get_language_code_from_locale() {
+        assert_eq!(get_language_code_from_locale("ja_JP.UTF-8"), Some(Code::ja));
+        assert_eq!(get_language_code_from_locale("pt-BR"), Some(Code::pt));
+        assert_eq!(get_language_code_from_locale("de_DE@euro"), Some(Code::de));
+        assert_eq!(get_language_code_from_locale("iw_IL"), Some(Code::he));
+        assert_eq!(get_language_code_from_locale("C.UTF-8"), None);
+        assert_eq!(get_language_code_from_locale("POSIX"), None);
+    }
 }
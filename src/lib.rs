@@ -1,5 +1,6 @@
 pub mod app;
 pub use app::App;
+pub mod cli;
 pub mod command;
 pub mod config;
 pub use config::Config;
@@ -11,5 +12,5 @@ pub mod log;
 pub use log::Log;
 pub mod models;
 pub mod status;
-pub use status::Status;
+pub use status::{Status, StatusHandle};
 pub mod ui;
@@ -7,7 +7,6 @@ use std::{
     path::Path,
     sync::Mutex,
 };
-use tokio::sync::mpsc::{self, Receiver, Sender};
 use tracing::{
     field::{Field, Visit},
     Event, Id, Subscriber,
@@ -16,13 +15,19 @@ use tracing_subscriber::{
     filter::EnvFilter, layer::Context, prelude::*, registry::LookupSpan, Layer,
 };
 
+pub mod buffer;
+pub use buffer::LogBuffer;
+
+/// The number of log lines kept for the Log screen
+pub const MAX_LOG_LINES: usize = 10000;
+
 thread_local! {
     static INDENT_LEVEL: RefCell<usize> = const { RefCell::new(0) };
 }
 
-// Custom tracing layer to send log events over mpsc
-struct MpscLayer {
-    sender: Sender<String>,
+// Custom tracing layer to add log events to the log buffer
+struct BufferLayer {
+    buffer: LogBuffer,
     file: Mutex<Option<File>>,
 }
 
@@ -44,7 +49,7 @@ impl Visit for FieldVisitor {
     }
 }
 
-impl<S> Layer<S> for MpscLayer
+impl<S> Layer<S> for BufferLayer
 where
     S: Subscriber + for<'a> LookupSpan<'a>,
 {
@@ -61,7 +66,7 @@ where
                     let _ = file.flush();
                 }
             }
-            let _ = self.sender.try_send(msg);
+            self.buffer.push(msg);
         }
 
         // Increase the indent level when entering a span
@@ -89,7 +94,7 @@ where
                     let _ = file.flush();
                 }
             }
-            let _ = self.sender.try_send(msg);
+            self.buffer.push(msg);
         }
     }
 
@@ -137,19 +142,20 @@ where
             }
         }
 
-        // send the log message over the mpsc channel
-        let _ = self.sender.try_send(msg);
+        // add the log message to the log buffer
+        self.buffer.push(msg);
     }
 }
 
-/// Async tracing logger wrapper that filters and feeds log messages over an mpsc channel for
+/// Tracing logger wrapper that filters and feeds log messages into a bounded log buffer for
 /// integration into the TUI gui.
 pub struct Log;
 
 impl Log {
-    /// Starts the logger and returns the task handle and receiver for the log messages.
-    pub fn init<T: AsRef<Path>>(log: Option<T>) -> Result<Receiver<String>, Error> {
-        let (sender, receiver) = mpsc::channel(16);
+    /// Starts the logger and returns the buffer the log messages are added to, which keeps at
+    /// most `max_lines` lines.
+    pub fn init<T: AsRef<Path>>(log: Option<T>, max_lines: usize) -> Result<LogBuffer, Error> {
+        let buffer = LogBuffer::new(max_lines);
         let file = if let Some(path) = log {
             Mutex::new(Some(
                 OpenOptions::new()
@@ -163,10 +169,14 @@ impl Log {
         };
 
         let filter = EnvFilter::from_default_env();
-        let layer = MpscLayer { sender, file }.with_filter(filter);
+        let layer = BufferLayer {
+            buffer: buffer.clone(),
+            file,
+        }
+        .with_filter(filter);
 
         tracing_subscriber::registry().with(layer).init();
 
-        Ok(receiver)
+        Ok(buffer)
     }
 }
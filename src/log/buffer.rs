@@ -0,0 +1,166 @@
+use std::{
+    collections::{HashSet, VecDeque},
+    sync::{Arc, Mutex},
+};
+use tokio::sync::Notify;
+
+/// A bounded ring buffer of log lines shared by the logger, the command runner, and the Log
+/// screen. Writing never waits on the UI: the oldest lines are dropped, and counted, once the
+/// buffer is full, and the UI is only told that something changed. Repeated lines, like the
+/// progress output of docker, are interned so that they are only stored once.
+#[derive(Clone, Debug)]
+pub struct LogBuffer {
+    inner: Arc<Mutex<Inner>>,
+    changed: Arc<Notify>,
+}
+
+#[derive(Debug, Default)]
+struct Inner {
+    /// the log lines, oldest first
+    lines: VecDeque<Arc<str>>,
+    /// the maximum number of lines kept
+    capacity: usize,
+    /// the distinct lines currently in the buffer
+    interned: HashSet<Arc<str>>,
+    /// the number of lines dropped to make room for newer ones
+    dropped: usize,
+    /// the last line of command output
+    last_output: Option<Arc<str>>,
+}
+
+impl LogBuffer {
+    /// Create a new log buffer holding at most `capacity` lines
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(Inner {
+                capacity: capacity.max(1),
+                ..Default::default()
+            })),
+            changed: Arc::new(Notify::new()),
+        }
+    }
+
+    /// Add a log line. The first two characters of the line are the prefix that picks its emoji.
+    pub fn push<S: AsRef<str>>(&self, line: S) {
+        self.push_line(line.as_ref(), false);
+    }
+
+    /// Add a line of command output, which is also shown in the status bar of the Log screen
+    pub fn push_output<S: AsRef<str>>(&self, line: S) {
+        self.push_line(line.as_ref(), true);
+    }
+
+    fn push_line(&self, line: &str, output: bool) {
+        let Ok(mut inner) = self.inner.lock() else {
+            return;
+        };
+        let line = inner.intern(line);
+        if output {
+            if let Some(previous) = inner.last_output.replace(line.clone()) {
+                inner.release(previous);
+            }
+        }
+        inner.lines.push_back(line);
+        while inner.lines.len() > inner.capacity {
+            if let Some(oldest) = inner.lines.pop_front() {
+                inner.release(oldest);
+            }
+            inner.dropped += 1;
+        }
+        drop(inner);
+        self.changed.notify_one();
+    }
+
+    /// Call the closure with the log lines, oldest first
+    pub fn with_lines<R>(&self, f: impl FnOnce(&VecDeque<Arc<str>>) -> R) -> R {
+        match self.inner.lock() {
+            Ok(inner) => f(&inner.lines),
+            Err(_) => f(&VecDeque::new()),
+        }
+    }
+
+    /// The number of lines dropped to make room for newer ones
+    pub fn dropped(&self) -> usize {
+        self.inner.lock().map(|inner| inner.dropped).unwrap_or(0)
+    }
+
+    /// The number of lines in the buffer
+    pub fn line_count(&self) -> usize {
+        self.inner
+            .lock()
+            .map(|inner| inner.lines.len())
+            .unwrap_or(0)
+    }
+
+    /// The number of bytes taken up by the distinct lines in the buffer
+    pub fn interned_bytes(&self) -> usize {
+        self.inner
+            .lock()
+            .map(|inner| inner.interned.iter().map(|line| line.len()).sum())
+            .unwrap_or(0)
+    }
+
+    /// The last line of command output
+    pub fn last_output(&self) -> Option<String> {
+        self.inner
+            .lock()
+            .ok()
+            .and_then(|inner| inner.last_output.as_deref().map(str::to_string))
+    }
+
+    /// Forget the last line of command output, for when a new command starts
+    pub fn clear_output(&self) {
+        if let Ok(mut inner) = self.inner.lock() {
+            if let Some(previous) = inner.last_output.take() {
+                inner.release(previous);
+            }
+        }
+    }
+
+    /// Wait until a line is added. Lines added while nobody is waiting wake the next waiter once.
+    pub async fn changed(&self) {
+        self.changed.notified().await;
+    }
+}
+
+impl Inner {
+    // get the shared copy of the line
+    fn intern(&mut self, line: &str) -> Arc<str> {
+        if let Some(interned) = self.interned.get(line) {
+            return interned.clone();
+        }
+        let line: Arc<str> = Arc::from(line);
+        self.interned.insert(line.clone());
+        line
+    }
+
+    // forget an interned line once the last copy of it leaves the buffer
+    fn release(&mut self, line: Arc<str>) {
+        // the interned set holds one reference and the released line holds the other
+        if Arc::strong_count(&line) == 2 {
+            self.interned.remove(&line);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_log_buffer_is_bounded() {
+        let buffer = LogBuffer::new(3);
+        for line in ["i one", "i two", "i one", "i three", "i four"] {
+            buffer.push(line);
+        }
+        let lines =
+            buffer.with_lines(|lines| lines.iter().map(|l| l.to_string()).collect::<Vec<_>>());
+        assert_eq!(lines, vec!["i one", "i three", "i four"]);
+        assert_eq!(buffer.dropped(), 2);
+
+        // repeated lines share their storage and are forgotten once they all leave the buffer
+        let inner = buffer.inner.lock().unwrap();
+        assert_eq!(inner.interned.len(), 3);
+        assert!(!inner.interned.contains("i two"));
+    }
+}
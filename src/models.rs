@@ -1,8 +1,26 @@
+pub mod catalog;
+pub use catalog::Catalog;
+
+pub mod certificate;
+pub use certificate::Certificate;
+
 pub mod error;
 pub use error::Error;
 
+pub mod glossary;
+pub use glossary::Glossary;
+
 pub mod lesson;
 pub use lesson::{Lesson, LessonData};
 
+pub mod license;
+
+pub mod repository;
+pub use repository::Repository;
+
+pub mod setup;
+
+pub mod template;
+
 pub mod workshop;
 pub use workshop::{Loader, Workshop, WorkshopData};
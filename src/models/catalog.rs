@@ -0,0 +1,105 @@
+use crate::{fs::install::Source, models::Error as ModelError, Error};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use tokio::process::Command;
+use tracing::{debug, warn};
+
+/// A published workshop listed in the catalog index
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
+pub struct CatalogEntry {
+    /// the workshop name, this is the directory name once installed
+    pub name: String,
+    /// the title of the workshop
+    pub title: String,
+    /// the description of the workshop
+    #[serde(default)]
+    pub description: String,
+    /// the difficulty of the workshop
+    #[serde(default)]
+    pub difficulty: String,
+    /// the URL to install the workshop from, anything `workshop install` accepts
+    pub url: String,
+    /// the spoken language codes mapped to the programming language codes
+    #[serde(default)]
+    pub languages: BTreeMap<String, Vec<String>>,
+}
+
+/// The curated index of published workshops
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
+pub struct Catalog {
+    /// the published workshops
+    pub workshops: Vec<CatalogEntry>,
+}
+
+impl Catalog {
+    /// Parse the catalog from its JSON representation, leaving out the workshops that can't be
+    /// installed safely from a remote catalog, like ones at local paths
+    pub fn from_json<S: AsRef<str>>(json: S) -> Result<Self, Error> {
+        let mut catalog: Self = serde_json::from_str(json.as_ref())
+            .map_err(|e| ModelError::InvalidCatalog(e.to_string()))?;
+        catalog.workshops.retain(|entry| {
+            let safe = Source::parse(&entry.url).is_catalog_safe();
+            if !safe {
+                warn!("Ignoring catalog workshop {} at {}", entry.name, entry.url);
+            }
+            safe
+        });
+        Ok(catalog)
+    }
+
+    /// Fetch the catalog index from the given URL using curl
+    pub async fn fetch<S: AsRef<str>>(url: S) -> Result<Self, Error> {
+        debug!("Fetching workshop catalog from: {}", url.as_ref());
+        let output = Command::new("curl")
+            .args(["-sSL", "--fail", url.as_ref()])
+            .output()
+            .await?;
+        if !output.status.success() {
+            return Err(ModelError::CatalogFetchFailed(
+                String::from_utf8_lossy(&output.stderr).trim().to_string(),
+            )
+            .into());
+        }
+        Self::from_json(String::from_utf8_lossy(&output.stdout))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_catalog_from_json() {
+        let json = r#"{
+            "workshops": [
+                {
+                    "name": "hello-world",
+                    "title": "Hello, World!",
+                    "description": "Print a greeting",
+                    "difficulty": "Beginner",
+                    "url": "https://github.com/libp2p/hello-world.git",
+                    "languages": { "en": ["rs", "py"] }
+                },
+                {
+                    "name": "minimal",
+                    "title": "Minimal",
+                    "url": "https://example.com/minimal.tar.gz"
+                },
+                {
+                    "name": "local",
+                    "title": "Local",
+                    "url": "/home/student/local.tar.gz"
+                }
+            ]
+        }"#;
+        let catalog = Catalog::from_json(json).unwrap();
+        assert_eq!(catalog.workshops.len(), 2);
+        assert_eq!(catalog.workshops[0].languages["en"], vec!["rs", "py"]);
+        assert!(catalog.workshops[1].description.is_empty());
+    }
+
+    #[test]
+    fn test_catalog_from_invalid_json() {
+        assert!(Catalog::from_json("{ \"workshops\": [ { \"name\": 1 } ] }").is_err());
+    }
+}
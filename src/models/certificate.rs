@@ -0,0 +1,201 @@
+use crate::{fs, Error};
+use std::{
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// A certificate of completion for a workshop
+#[derive(Clone, Debug, Default)]
+pub struct Certificate {
+    /// the name of the workshop directory
+    pub workshop: String,
+    /// the title of the workshop
+    pub title: String,
+    /// the name of the student
+    pub student: String,
+    /// the date the workshop was completed, as YYYY-MM-DD
+    pub date: String,
+    /// the titles of the completed lessons, in order
+    pub lessons: Vec<String>,
+    /// the URL of a badge image to share, if badges are enabled
+    pub badge_url: Option<String>,
+}
+
+impl Certificate {
+    /// Create a certificate dated today, with a badge from the given badge service unless it is
+    /// empty
+    pub fn new(
+        workshop: &str,
+        title: &str,
+        student: &str,
+        lessons: Vec<String>,
+        badge_service: &str,
+    ) -> Self {
+        let badge_url = (!badge_service.is_empty()).then(|| {
+            format!(
+                "{}/{}-completed-brightgreen",
+                badge_service.trim_end_matches('/'),
+                badge_text(title)
+            )
+        });
+        Self {
+            workshop: workshop.to_string(),
+            title: title.to_string(),
+            student: student.to_string(),
+            date: today(),
+            lessons,
+            badge_url,
+        }
+    }
+
+    /// The certificate as markdown
+    pub fn to_markdown(&self) -> String {
+        let mut md = format!(
+            "# Certificate of Completion\n\n**{}** completed the **{}** workshop on {}.\n\n## Lessons\n\n",
+            self.student, self.title, self.date
+        );
+        for lesson in &self.lessons {
+            md.push_str(&format!("- {lesson}\n"));
+        }
+        if let Some(badge_url) = &self.badge_url {
+            md.push_str(&format!("\n![{}]({badge_url})\n", self.title));
+        }
+        md
+    }
+
+    /// The certificate as a standalone HTML page
+    pub fn to_html(&self) -> String {
+        let lessons = self
+            .lessons
+            .iter()
+            .map(|lesson| format!("<li>{}</li>", escape(lesson)))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let badge = self
+            .badge_url
+            .as_ref()
+            .map(|url| format!("<p><img src=\"{}\" alt=\"badge\"></p>\n", escape(url)))
+            .unwrap_or_default();
+        format!(
+            r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>Certificate of Completion: {title}</title>
+<style>
+body {{ font-family: sans-serif; max-width: 40em; margin: 4em auto; padding: 2em; border: 6px double #444; text-align: center; }}
+ul {{ text-align: left; display: inline-block; }}
+</style>
+</head>
+<body>
+<h1>Certificate of Completion</h1>
+<p>This certifies that</p>
+<h2>{student}</h2>
+<p>completed the <strong>{title}</strong> workshop on {date}.</p>
+<ul>
+{lessons}
+</ul>
+{badge}</body>
+</html>
+"#,
+            title = escape(&self.title),
+            student = escape(&self.student),
+            date = self.date,
+        )
+    }
+
+    /// Save the certificate as `<workshop>.md` and `<workshop>.html` in the given directory,
+    /// returning the files written
+    pub fn save(&self, dir: &Path) -> Result<Vec<PathBuf>, Error> {
+        let md = dir.join(format!("{}.md", self.workshop));
+        fs::write_atomic(&md, self.to_markdown())?;
+        let html = dir.join(format!("{}.html", self.workshop));
+        fs::write_atomic(&html, self.to_html())?;
+        Ok(vec![md, html])
+    }
+}
+
+/// Get the directory the certificates are saved in: `certificates` in the `.workshops` directory
+/// so they are synced with the progress, or in the state directory when there is no project
+pub fn certificates_dir() -> Result<PathBuf, Error> {
+    let dir = match fs::workshops::data_dir() {
+        Some(dir) => dir.join("certificates"),
+        None => fs::application::state_dir()?.join("certificates"),
+    };
+    std::fs::create_dir_all(&dir)?;
+    Ok(dir)
+}
+
+// shields.io uses dashes to separate the badge fields, so literal dashes and underscores are
+// doubled and spaces become underscores
+fn badge_text(text: &str) -> String {
+    text.chars()
+        .map(|c| match c {
+            '-' => "--".to_string(),
+            '_' => "__".to_string(),
+            ' ' => "_".to_string(),
+            c if c.is_ascii_alphanumeric() || ".~".contains(c) => c.to_string(),
+            c => c.to_string().bytes().map(|b| format!("%{b:02X}")).collect(),
+        })
+        .collect()
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+// today's date in UTC as YYYY-MM-DD
+fn today() -> String {
+    let days = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() / 86_400)
+        .unwrap_or_default();
+    let (year, month, day) = civil_from_days(days as i64);
+    format!("{year:04}-{month:02}-{day:02}")
+}
+
+// convert days since the unix epoch into a (year, month, day) in the proleptic Gregorian calendar
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_certificate() {
+        assert_eq!(civil_from_days(0), (1970, 1, 1));
+        assert_eq!(civil_from_days(19_723), (2024, 1, 1));
+        assert_eq!(civil_from_days(19_782), (2024, 2, 29));
+
+        let certificate = Certificate::new(
+            "example",
+            "Intro to <libp2p>",
+            "Ada",
+            vec!["Hello".to_string(), "Dial a peer".to_string()],
+            "https://img.shields.io/badge/",
+        );
+        assert_eq!(
+            certificate.badge_url.as_deref(),
+            Some("https://img.shields.io/badge/Intro_to_%3Clibp2p%3E-completed-brightgreen")
+        );
+        assert!(certificate.to_markdown().contains("- Dial a peer\n"));
+        assert!(certificate.to_html().contains("Intro to &lt;libp2p&gt;"));
+
+        let certificate = Certificate::new("example", "Intro", "Ada", Vec::new(), "");
+        assert_eq!(certificate.badge_url, None);
+    }
+}
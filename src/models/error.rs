@@ -89,4 +89,12 @@ pub enum Error {
     /// No lesson specified
     #[error("No lesson specified")]
     NoLessonSpecified,
+
+    /// The catalog index could not be fetched
+    #[error("Failed to fetch the workshop catalog: {0}")]
+    CatalogFetchFailed(String),
+
+    /// The catalog index is not valid
+    #[error("Invalid workshop catalog: {0}")]
+    InvalidCatalog(String),
 }
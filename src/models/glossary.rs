@@ -0,0 +1,129 @@
+use crate::languages::spoken;
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, path::Path};
+use tracing::warn;
+
+/// The definitions of the terms the lessons of a workshop link to with `[[term]]`, from the
+/// `glossary.yaml` files of the workshop
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(transparent)]
+pub struct Glossary(BTreeMap<String, String>);
+
+impl Glossary {
+    /// Load the glossary of the workshop in the given directory, the definitions in the
+    /// `glossary.yaml` of the spoken language take the place of the ones in the `glossary.yaml` at
+    /// the top of the workshop. A missing glossary is empty and a broken one is ignored.
+    pub fn load(workshop_dir: &Path, spoken: Option<spoken::Code>) -> Self {
+        let mut paths = vec![workshop_dir.join("glossary.yaml")];
+        paths.extend(
+            spoken.map(|spoken| workshop_dir.join(spoken.to_string()).join("glossary.yaml")),
+        );
+
+        let mut glossary = Glossary::default();
+        for path in paths {
+            let Ok(content) = std::fs::read_to_string(&path) else {
+                continue;
+            };
+            match serde_yaml::from_str::<Glossary>(&content) {
+                Ok(definitions) => glossary.0.extend(definitions.0),
+                Err(e) => warn!("Ignoring broken glossary {}: {e}", path.display()),
+            }
+        }
+        glossary
+    }
+
+    /// Is the glossary empty?
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Get the term as it is written in the glossary and its definition, ignoring case
+    pub fn define(&self, term: &str) -> Option<(&str, &str)> {
+        self.0
+            .get_key_value(term)
+            .or_else(|| {
+                self.0
+                    .iter()
+                    .find(|(known, _)| known.to_lowercase() == term.to_lowercase())
+            })
+            .map(|(term, definition)| (term.as_str(), definition.as_str()))
+    }
+
+    /// Get every term with its definition, in alphabetical order
+    pub fn terms(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.0
+            .iter()
+            .map(|(term, definition)| (term.as_str(), definition.as_str()))
+    }
+}
+
+/// Split the text into the plain text and the terms linked with `[[term]]`, true marking the
+/// terms. Brackets in inline code don't link to terms.
+pub fn split_links(text: &str) -> Vec<(bool, &str)> {
+    let mut parts = Vec::new();
+    let mut in_code = false;
+    let mut start = 0;
+    let mut pos = 0;
+    while pos < text.len() {
+        let rest = &text[pos..];
+        if rest.starts_with('`') {
+            in_code = !in_code;
+        } else if !in_code && rest.starts_with("[[") {
+            let term = rest[2..]
+                .split_once("]]")
+                .map(|(term, _)| term)
+                .filter(|term| !term.trim().is_empty() && !term.contains(['[', '\n', '`']));
+            if let Some(term) = term {
+                if start < pos {
+                    parts.push((false, &text[start..pos]));
+                }
+                parts.push((true, term.trim()));
+                pos += term.len() + 4;
+                start = pos;
+                continue;
+            }
+        }
+        pos += rest.chars().next().map_or(1, char::len_utf8);
+    }
+    if start < text.len() {
+        parts.push((false, &text[start..]));
+    }
+    parts
+}
+
+/// Get the terms the text links to with `[[term]]`, in order
+pub fn links(text: &str) -> Vec<&str> {
+    split_links(text)
+        .into_iter()
+        .filter_map(|(is_term, text)| is_term.then_some(text))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_links() {
+        assert_eq!(
+            split_links("A [[multiaddr]] names the [[ PeerId ]]."),
+            vec![
+                (false, "A "),
+                (true, "multiaddr"),
+                (false, " names the "),
+                (true, "PeerId"),
+                (false, "."),
+            ]
+        );
+        assert_eq!(
+            links("Use `[[u8; 2]]` or [[]] and [[NAT hole punching]]"),
+            vec!["NAT hole punching"]
+        );
+
+        let glossary: Glossary =
+            serde_yaml::from_str("multiaddr: A self-describing address.\nPeerId: A peer's id.")
+                .unwrap();
+        assert_eq!(glossary.define("peerid"), Some(("PeerId", "A peer's id.")));
+        assert!(glossary.define("relay").is_none());
+    }
+}
@@ -1,14 +1,17 @@
 use crate::{
-    fs::{LazyLoader, TryLoad},
+    fs::{self, application::Tool, cache, LazyLoader, TryLoad},
     languages::{programming, spoken},
     models::Error as ModelError,
     Error,
 };
 use serde::{Deserialize, Serialize};
 use std::{
+    cmp::Ordering,
+    collections::{BTreeMap, HashMap},
     fmt,
-    path::{Path, PathBuf},
+    path::{Component, Path, PathBuf},
     sync::Arc,
+    time::Duration,
 };
 use tokio::sync::RwLock;
 use tracing::trace;
@@ -35,20 +38,212 @@ impl fmt::Display for Status {
     }
 }
 
-/// Represents a workshop's metadata
+/// Represents a lesson's metadata, read from its `lesson.yaml` file
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct Lesson {
     pub title: String,
     pub description: String,
     pub status: Status,
+    /// where the lesson comes in the workshop, before the lessons with a higher order and the
+    /// ones without one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub order: Option<u32>,
+    /// about how many minutes the lesson takes
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub estimated_minutes: Option<u32>,
+    /// the topics the lesson covers
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// how the solution of the lesson is checked
+    #[serde(default, skip_serializing_if = "Check::is_default")]
+    pub check: Check,
+    /// the minimum versions of the tools the lesson needs on top of the ones the workshop needs
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub requires: BTreeMap<Tool, String>,
+    /// the helper commands the student can run from the lesson
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub commands: Vec<Command>,
+    /// the external code the lesson bundles and the licenses it is under
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub third_party: Vec<ThirdParty>,
+}
+
+impl Lesson {
+    /// Get the problems with the metadata that parsing it doesn't catch, like empty fields and
+    /// paths leaving the lesson directory
+    pub fn validate(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+        if self.title.trim().is_empty() {
+            problems.push("the title is empty".to_string());
+        }
+        if self.estimated_minutes == Some(0) {
+            problems.push("the estimated time is zero minutes".to_string());
+        }
+        if self.tags.iter().any(|tag| tag.trim().is_empty()) {
+            problems.push("a tag is empty".to_string());
+        }
+        for (tool, minimum) in &self.requires {
+            if fs::application::parse_tool_version(minimum).is_none() {
+                problems.push(format!(
+                    "the minimum version of {tool} is not a version: {minimum}"
+                ));
+            }
+        }
+        for command in &self.commands {
+            if command.name.trim().is_empty() || command.run.is_empty() {
+                problems.push(format!(
+                    "the command \"{}\" needs a name and a program to run",
+                    command.name
+                ));
+            }
+        }
+        for code in &self.third_party {
+            if code.name.trim().is_empty() || code.license.trim().is_empty() {
+                problems.push("the third-party code needs a name and a license".to_string());
+            }
+        }
+        let script = Path::new(&self.check.script);
+        if !script
+            .components()
+            .all(|component| matches!(component, Component::Normal(_)))
+        {
+            problems.push(format!(
+                "the check script is not in the lesson directory: {}",
+                self.check.script
+            ));
+        }
+        if self.check.timeout == Some(0) {
+            problems.push("the check timeout is zero seconds".to_string());
+        }
+        problems
+    }
+}
+
+/// How the solution of a lesson is checked
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct Check {
+    /// the Python script that checks the solution, relative to the lesson directory
+    #[serde(default = "Check::default_script")]
+    pub script: String,
+    /// how many seconds each command of the check may run, instead of the configured timeout
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout: Option<u64>,
+}
+
+impl Check {
+    fn default_script() -> String {
+        "check.py".to_string()
+    }
+
+    fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Get the time each command of the check may run, if the lesson sets it
+    pub fn timeout(&self) -> Option<Duration> {
+        self.timeout.map(Duration::from_secs)
+    }
+}
+
+impl Default for Check {
+    fn default() -> Self {
+        Self {
+            script: Self::default_script(),
+            timeout: None,
+        }
+    }
+}
+
+/// External code bundled with a lesson, like an example from another project
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct ThirdParty {
+    /// the name of the project the code comes from
+    pub name: String,
+    /// the SPDX identifier of the license the code is under
+    pub license: String,
+    /// where the code comes from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+}
+
+/// A named helper command a lesson offers, like starting a relay or following the logs, run in
+/// the lesson directory
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct Command {
+    /// the name shown in the list of commands
+    pub name: String,
+    /// the program and its arguments
+    pub run: Vec<String>,
+}
+
+/// Compare two lesson keys the way people read them, with the runs of digits compared as
+/// numbers so that `2-identify` comes before `10-kademlia`
+pub fn compare_keys(a: &str, b: &str) -> Ordering {
+    let (mut a, mut b) = (a, b);
+    loop {
+        let (Some(x), Some(y)) = (a.chars().next(), b.chars().next()) else {
+            return a.len().cmp(&b.len());
+        };
+        let ordering = if x.is_ascii_digit() && y.is_ascii_digit() {
+            let (x, rest_a) = split_digits(a);
+            let (y, rest_b) = split_digits(b);
+            a = rest_a;
+            b = rest_b;
+            // leading zeros don't change the number
+            let (x, y) = (x.trim_start_matches('0'), y.trim_start_matches('0'));
+            x.len().cmp(&y.len()).then_with(|| x.cmp(y))
+        } else {
+            a = &a[x.len_utf8()..];
+            b = &b[y.len_utf8()..];
+            x.cmp(&y)
+        };
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+}
+
+// split the leading digits off the string
+fn split_digits(s: &str) -> (&str, &str) {
+    let end = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+    s.split_at(end)
+}
+
+/// Get the lesson keys in lesson order, by the `order` in their metadata and then by their keys,
+/// the lessons without an order after the ones with one
+pub async fn ordered_keys(lessons: &HashMap<String, LessonData>) -> Result<Vec<String>, Error> {
+    let mut keys = Vec::with_capacity(lessons.len());
+    for (key, lesson_data) in lessons {
+        let order = lesson_data.get_metadata().await?.order;
+        keys.push((order, key.clone()));
+    }
+    keys.sort_by(|(order_a, a), (order_b, b)| {
+        order_a
+            .unwrap_or(u32::MAX)
+            .cmp(&order_b.unwrap_or(u32::MAX))
+            .then_with(|| compare_keys(a, b))
+            .then_with(|| a.cmp(b))
+    });
+    Ok(keys.into_iter().map(|(_, key)| key).collect())
 }
 
 #[async_trait::async_trait]
 impl TryLoad for Lesson {
     type Error = Error;
     async fn try_load(path: &Path) -> Result<Self, Error> {
-        let content = std::fs::read_to_string(path)?;
-        Ok(serde_yaml::from_str(&content)?)
+        let content = fs::read_to_string(path).await?;
+        let lesson_dir = path.parent().map(Path::to_path_buf);
+        // parsing a big file, or writing its cache entry, would hold up the runtime
+        Ok(tokio::task::spawn_blocking(move || {
+            let mut lesson: Lesson =
+                cache::parse_cached("lesson", &content, |content| serde_yaml::from_str(content))?;
+            // the status in the file is only the starting point, the progress is kept apart
+            if let Some(status) = lesson_dir.and_then(fs::progress::lesson_status) {
+                lesson.status = status;
+            }
+            Ok::<_, Error>(lesson)
+        })
+        .await??)
     }
 }
 
@@ -106,18 +301,17 @@ impl LessonData {
         metadata.try_load().await.cloned()
     }
 
-    /// updates the lesson status and saves it to the lesson.yaml file
+    /// updates the lesson status and saves it to the progress store, leaving the lesson.yaml
+    /// file of the workshop as it is
     pub async fn update_status(&self, new_status: Status) -> Result<(), Error> {
         let mut metadata = self.metadata.write().await;
 
         // Ensure metadata is loaded
         let mut lesson = metadata.try_load().await.cloned()?;
-        lesson.status = new_status;
+        lesson.status = new_status.clone();
 
-        // Save the updated metadata back to the file
-        let lesson_yaml_path = self.path.join("lesson.yaml");
-        let content = serde_yaml::to_string(&lesson)?;
-        std::fs::write(&lesson_yaml_path, content)?;
+        // Save the status in the progress store
+        fs::progress::set_lesson_status(&self.path, new_status)?;
 
         // Update the cached metadata
         *metadata = crate::fs::LazyLoader::Loaded(lesson);
@@ -254,3 +448,51 @@ impl Loader {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compare_keys() {
+        let mut keys = vec![
+            "10-kademlia",
+            "02-identify",
+            "1-ping",
+            "9-gossipsub",
+            "intro",
+            "02-identify-b",
+        ];
+        keys.sort_by(|a, b| compare_keys(a, b));
+        assert_eq!(
+            keys,
+            [
+                "1-ping",
+                "02-identify",
+                "02-identify-b",
+                "9-gossipsub",
+                "10-kademlia",
+                "intro"
+            ]
+        );
+        assert_eq!(compare_keys("01-a", "01-a"), Ordering::Equal);
+    }
+
+    #[test]
+    fn test_validate() {
+        let lesson: Lesson = serde_yaml::from_str(
+            "title: Ping\ndescription: Ping a peer\nstatus: NotStarted\nestimated_minutes: 20\ntags: [ping]\n",
+        )
+        .unwrap();
+        assert_eq!(lesson.check, Check::default());
+        assert!(lesson.validate().is_empty());
+        // the defaults aren't written back
+        assert!(!serde_yaml::to_string(&lesson).unwrap().contains("check"));
+
+        let lesson: Lesson = serde_yaml::from_str(
+            "title: ''\ndescription: ''\nstatus: NotStarted\nrequires:\n  python: latest\ncheck:\n  script: ../check.py\n  timeout: 0\n",
+        )
+        .unwrap();
+        assert_eq!(lesson.validate().len(), 4);
+    }
+}
@@ -0,0 +1,163 @@
+/// A well known license with a plain summary of what it allows
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Known {
+    /// the SPDX identifier
+    pub id: &'static str,
+    /// the full name of the license
+    pub name: &'static str,
+    /// what the license allows and asks for, in a sentence
+    pub summary: &'static str,
+    /// phrases that all appear in the license text, in lowercase
+    phrases: &'static [&'static str],
+}
+
+/// The licenses that are recognized, the more specific ones before the ones they contain
+const KNOWN: &[Known] = &[
+    Known {
+        id: "AGPL-3.0",
+        name: "GNU Affero General Public License v3.0",
+        summary: "Share changes under the same license, including when the software is only used over a network.",
+        phrases: &["gnu affero general public license", "version 3"],
+    },
+    Known {
+        id: "LGPL-3.0",
+        name: "GNU Lesser General Public License v3.0",
+        summary: "Share changes to the library under the same license; software that only links to it may use any license.",
+        phrases: &["gnu lesser general public license", "version 3"],
+    },
+    Known {
+        id: "LGPL-2.1",
+        name: "GNU Lesser General Public License v2.1",
+        summary: "Share changes to the library under the same license; software that only links to it may use any license.",
+        phrases: &["gnu lesser general public license", "version 2.1"],
+    },
+    Known {
+        id: "GPL-3.0",
+        name: "GNU General Public License v3.0",
+        summary: "Share the source of the software and of any changes to it under the same license.",
+        phrases: &["gnu general public license", "version 3"],
+    },
+    Known {
+        id: "GPL-2.0",
+        name: "GNU General Public License v2.0",
+        summary: "Share the source of the software and of any changes to it under the same license.",
+        phrases: &["gnu general public license", "version 2"],
+    },
+    Known {
+        id: "Apache-2.0",
+        name: "Apache License 2.0",
+        summary: "Use, change, and share freely, keeping the notices and stating the changes; grants a patent license.",
+        phrases: &["apache license", "version 2.0"],
+    },
+    Known {
+        id: "MPL-2.0",
+        name: "Mozilla Public License 2.0",
+        summary: "Use freely; share changes to the licensed files under the same license.",
+        phrases: &["mozilla public license", "2.0"],
+    },
+    Known {
+        id: "BSD-3-Clause",
+        name: "BSD 3-Clause \"New\" or \"Revised\" License",
+        summary: "Use, change, and share freely, keeping the copyright notice and not using the authors' names for endorsement.",
+        phrases: &["redistribution and use in source and binary forms", "neither the name"],
+    },
+    Known {
+        id: "BSD-2-Clause",
+        name: "BSD 2-Clause \"Simplified\" License",
+        summary: "Use, change, and share freely, keeping the copyright notice.",
+        phrases: &["redistribution and use in source and binary forms"],
+    },
+    Known {
+        id: "MIT",
+        name: "MIT License",
+        summary: "Use, change, and share freely, keeping the copyright notice.",
+        phrases: &["permission is hereby granted, free of charge"],
+    },
+    Known {
+        id: "ISC",
+        name: "ISC License",
+        summary: "Use, change, and share freely, keeping the copyright notice.",
+        phrases: &["permission to use, copy, modify, and/or distribute this software"],
+    },
+    Known {
+        id: "Unlicense",
+        name: "The Unlicense",
+        summary: "Dedicated to the public domain; use it in any way without conditions.",
+        phrases: &["this is free and unencumbered software released into the public domain"],
+    },
+    Known {
+        id: "CC0-1.0",
+        name: "Creative Commons Zero v1.0 Universal",
+        summary: "Dedicated to the public domain; use it in any way without conditions.",
+        phrases: &["cc0 1.0 universal"],
+    },
+    Known {
+        id: "CC-BY-SA-4.0",
+        name: "Creative Commons Attribution Share Alike 4.0 International",
+        summary: "Share and adapt with credit to the authors, sharing adaptations under the same license.",
+        phrases: &["attribution-sharealike 4.0 international"],
+    },
+    Known {
+        id: "CC-BY-4.0",
+        name: "Creative Commons Attribution 4.0 International",
+        summary: "Share and adapt for any purpose with credit to the authors.",
+        phrases: &["attribution 4.0 international"],
+    },
+];
+
+/// Find the known license with the given SPDX identifier, ignoring case and the `-only` and
+/// `-or-later` suffixes of the GNU licenses
+pub fn by_id(id: &str) -> Option<&'static Known> {
+    let id = id.trim();
+    let id = id
+        .strip_suffix("-only")
+        .or_else(|| id.strip_suffix("-or-later"))
+        .or_else(|| id.strip_suffix('+'))
+        .unwrap_or(id);
+    KNOWN.iter().find(|known| known.id.eq_ignore_ascii_case(id))
+}
+
+/// Detect the license of the license text, from its `SPDX-License-Identifier` line if it has one
+/// and otherwise from the wording of the well known licenses
+pub fn detect(text: &str) -> Option<&'static Known> {
+    let tagged = text.lines().find_map(|line| {
+        let (_, id) = line.split_once("SPDX-License-Identifier:")?;
+        by_id(id)
+    });
+    if tagged.is_some() {
+        return tagged;
+    }
+
+    // the line breaks in license texts fall in different places, so compare the words only
+    let words = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    let words = words.to_lowercase();
+    KNOWN
+        .iter()
+        .find(|known| known.phrases.iter().all(|phrase| words.contains(phrase)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect() {
+        let mit = "MIT License\n\nCopyright (c) 2024 libp2p\n\nPermission is hereby granted, free\nof charge, to any person obtaining a copy";
+        assert_eq!(detect(mit).map(|known| known.id), Some("MIT"));
+
+        let bsd = "Redistribution and use in source and binary forms, with or without\nmodification, are permitted. Neither the name of the copyright holder";
+        assert_eq!(detect(bsd).map(|known| known.id), Some("BSD-3-Clause"));
+
+        let lgpl = "GNU LESSER GENERAL PUBLIC LICENSE\nVersion 3, 29 June 2007";
+        assert_eq!(detect(lgpl).map(|known| known.id), Some("LGPL-3.0"));
+
+        let tagged = "// SPDX-License-Identifier: GPL-3.0-or-later\nsome text";
+        assert_eq!(detect(tagged).map(|known| known.id), Some("GPL-3.0"));
+
+        assert!(detect("All rights reserved.").is_none());
+        assert_eq!(
+            by_id("apache-2.0").map(|known| known.id),
+            Some("Apache-2.0")
+        );
+    }
+}
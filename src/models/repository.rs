@@ -0,0 +1,114 @@
+use crate::{
+    fs,
+    languages::{programming, spoken},
+    models::{lesson, WorkshopData},
+};
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use tokio_util::sync::CancellationToken;
+use tracing::debug;
+
+// give the lesson being opened a head start on the disk before prefetching the next one
+const PREFETCH_DELAY: Duration = Duration::from_millis(250);
+
+/// The workshops loaded from the `.workshops` directory, shared by all of the screens so that
+/// the lazily loaded data is only loaded once and progress updates are seen everywhere. Cloning
+/// a repository is cheap and the clones share the loaded workshops.
+#[derive(Clone, Debug, Default)]
+pub struct Repository {
+    workshops: Arc<Mutex<HashMap<String, Arc<WorkshopData>>>>,
+    /// cancels the running prefetch
+    prefetch: Arc<Mutex<Option<CancellationToken>>>,
+}
+
+impl Repository {
+    /// Get the given workshop from the `.workshops` directory, loading it if it hasn't been
+    /// loaded yet
+    pub fn workshop<S: AsRef<str>>(&self, workshop: S) -> Option<Arc<WorkshopData>> {
+        let mut workshops = self.workshops.lock().ok()?;
+        if let Some(workshop_data) = workshops.get(workshop.as_ref()) {
+            return Some(workshop_data.clone());
+        }
+        debug!(
+            "Loading workshop into the repository: {}",
+            workshop.as_ref()
+        );
+        let workshop_data = Arc::new(fs::workshops::load(workshop.as_ref())?);
+        workshops.insert(workshop.as_ref().to_string(), workshop_data.clone());
+        Some(workshop_data)
+    }
+
+    /// The number of workshops loaded so far
+    pub fn loaded(&self) -> usize {
+        self.workshops
+            .lock()
+            .map(|workshops| workshops.len())
+            .unwrap_or(0)
+    }
+
+    /// Forget the given workshop so that it is loaded again the next time it is needed, or all
+    /// workshops if none is given. This must be called after the workshop is changed on disk.
+    pub fn invalidate(&self, workshop: Option<&str>) {
+        if let Ok(mut workshops) = self.workshops.lock() {
+            match workshop {
+                Some(workshop) => {
+                    workshops.remove(workshop);
+                }
+                None => workshops.clear(),
+            }
+        }
+    }
+    /// Load the metadata and text of the lesson after the given one in the background, so that
+    /// advancing to it is instant. The loaded data lives in the shared lesson loaders so nothing
+    /// has to be handed back. Starting a new prefetch cancels the one still running.
+    pub fn prefetch_next_lesson(
+        &self,
+        workshop: &str,
+        lesson: &str,
+        spoken: Option<spoken::Code>,
+        programming: Option<programming::Code>,
+    ) {
+        let token = CancellationToken::new();
+        if let Ok(mut prefetch) = self.prefetch.lock() {
+            if let Some(previous) = prefetch.replace(token.clone()) {
+                previous.cancel();
+            }
+        }
+
+        let repository = self.clone();
+        let workshop = workshop.to_string();
+        let lesson = lesson.to_string();
+        tokio::spawn(async move {
+            let prefetched = async {
+                tokio::time::sleep(PREFETCH_DELAY).await;
+                let workshop_data = repository.workshop(&workshop)?;
+                let lessons = workshop_data
+                    .get_lessons_data(spoken, programming)
+                    .await
+                    .ok()?;
+                let keys = lesson::ordered_keys(&lessons).await.ok()?;
+                let next = keys.iter().skip_while(|key| **key != lesson).nth(1)?;
+                debug!("Prefetching lesson: {workshop}/{next}");
+                let lesson_data = lessons.get(next)?;
+                lesson_data.get_metadata().await.ok()?;
+                lesson_data.get_text().await.ok()
+            };
+            tokio::select! {
+                _ = token.cancelled() => {}
+                _ = prefetched => {}
+            }
+        });
+    }
+
+    /// Cancel the running prefetch so that it doesn't compete with a foreground command
+    pub fn cancel_prefetch(&self) {
+        if let Ok(mut prefetch) = self.prefetch.lock() {
+            if let Some(token) = prefetch.take() {
+                token.cancel();
+            }
+        }
+    }
+}
@@ -0,0 +1,83 @@
+/// Split the setup instructions into the steps of a checklist. When the instructions have a list,
+/// each top-level list item is a step and the text before the list is left out. Otherwise each
+/// paragraph is a step. Code blocks stay with the step they follow and headings are left out.
+pub fn steps(markdown: &str) -> Vec<String> {
+    let has_list = markdown.lines().any(is_list_item);
+    let mut steps = Vec::new();
+    let mut current: Option<String> = None;
+    let mut in_code = false;
+    let mut blank = true;
+
+    for line in markdown.lines() {
+        let fence = line.trim_start().starts_with("```");
+        if !in_code && line.starts_with('#') {
+            steps.extend(current.take());
+            blank = true;
+            continue;
+        }
+        let starts_step = !in_code
+            && !fence
+            && if has_list {
+                is_list_item(line)
+            } else {
+                blank && !line.trim().is_empty()
+            };
+        if starts_step {
+            steps.extend(current.take());
+            current = Some(String::new());
+        }
+        if let Some(step) = current.as_mut() {
+            step.push_str(line);
+            step.push('\n');
+        }
+        if fence {
+            in_code = !in_code;
+        }
+        blank = line.trim().is_empty();
+    }
+    steps.extend(current);
+
+    steps
+        .into_iter()
+        .map(|step| step.trim().to_string())
+        .filter(|step| !step.is_empty())
+        .collect()
+}
+
+// is the line a top-level bulleted or numbered list item
+fn is_list_item(line: &str) -> bool {
+    if line.starts_with("- ") || line.starts_with("* ") || line.starts_with("+ ") {
+        return true;
+    }
+    let digits = line.chars().take_while(char::is_ascii_digit).count();
+    digits > 0 && (line[digits..].starts_with(". ") || line[digits..].starts_with(") "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_steps() {
+        let paragraphs =
+            "Set up a project:\n\n```sh\ncargo new hello\n\ncd hello\n```\n\nThen run it again.\n";
+        assert_eq!(
+            steps(paragraphs),
+            vec![
+                "Set up a project:\n\n```sh\ncargo new hello\n\ncd hello\n```",
+                "Then run it again.",
+            ]
+        );
+
+        let list = "# Setup\n\nDo these:\n\n1. Install Rust\n   from rustup.rs\n2. Make a project\n\n   ```sh\n   cargo new hello\n   ```\n- Run it\n";
+        assert_eq!(
+            steps(list),
+            vec![
+                "1. Install Rust\n   from rustup.rs",
+                "2. Make a project\n\n   ```sh\n   cargo new hello\n   ```",
+                "- Run it",
+            ]
+        );
+        assert!(steps("# Nothing to do\n").is_empty());
+    }
+}
@@ -0,0 +1,88 @@
+use std::collections::HashMap;
+
+/// The name of the variable holding the student's peer id
+pub const PEER_ID: &str = "peer_id";
+/// The name of the variable holding the student's workspace directory
+pub const WORKSPACE_DIR: &str = "workspace_dir";
+/// The name of the variable holding the relay multiaddr used by the lessons
+pub const RELAY_ADDR: &str = "relay_addr";
+
+/// The variables available for substitution in lesson text
+pub type Variables = HashMap<String, String>;
+
+/// Substitute all `{{name}}` placeholders in the text with the matching value from the given
+/// variables. Whitespace inside of the braces is ignored so `{{ name }}` also works. Placeholders
+/// for unknown variables are left untouched so lesson authors can spot them when previewing.
+pub fn render<S: AsRef<str>>(text: S, variables: &Variables) -> String {
+    let text = text.as_ref();
+    let mut rendered = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(start) = rest.find("{{") {
+        let (before, after) = rest.split_at(start);
+        rendered.push_str(before);
+        match after[2..].find("}}") {
+            Some(end) => {
+                let name = after[2..2 + end].trim();
+                match variables.get(name) {
+                    Some(value) => rendered.push_str(value),
+                    None => rendered.push_str(&after[..end + 4]),
+                }
+                rest = &after[end + 4..];
+            }
+            None => {
+                // no closing braces so the rest of the text is copied as is
+                rendered.push_str(after);
+                rest = "";
+            }
+        }
+    }
+    rendered.push_str(rest);
+    rendered
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn variables() -> Variables {
+        Variables::from([
+            (PEER_ID.to_string(), "12D3KooWTest".to_string()),
+            (WORKSPACE_DIR.to_string(), "/home/student".to_string()),
+            (
+                RELAY_ADDR.to_string(),
+                "/ip4/127.0.0.1/tcp/4001".to_string(),
+            ),
+        ])
+    }
+
+    #[test]
+    fn test_render_substitutes_variables() {
+        let text = "Dial {{relay_addr}} as {{ peer_id }} from {{workspace_dir}}.";
+        assert_eq!(
+            render(text, &variables()),
+            "Dial /ip4/127.0.0.1/tcp/4001 as 12D3KooWTest from /home/student."
+        );
+    }
+
+    #[test]
+    fn test_render_keeps_unknown_variables() {
+        let text = "Unknown {{ missing }} and {{peer_id}}";
+        assert_eq!(
+            render(text, &variables()),
+            "Unknown {{ missing }} and 12D3KooWTest"
+        );
+    }
+
+    #[test]
+    fn test_render_unterminated_placeholder() {
+        let text = "Broken {{peer_id and more";
+        assert_eq!(render(text, &variables()), text);
+    }
+
+    #[test]
+    fn test_render_without_placeholders() {
+        let text = "No variables here, just `code { }`";
+        assert_eq!(render(text, &variables()), text);
+    }
+}
@@ -1,12 +1,12 @@
 use crate::{
-    fs::{Error as FsError, LazyLoader, TryLoad},
+    fs::{self, application::Tool, cache, Error as FsError, LazyLoader, TryLoad},
     languages::{programming, spoken},
-    models::{lesson, Error as ModelError, LessonData},
+    models::{Error as ModelError, LessonData},
     Error,
 };
 use serde::{Deserialize, Serialize};
 use std::{
-    collections::HashMap,
+    collections::{BTreeMap, HashMap},
     fmt,
     path::{Path, PathBuf},
     sync::Arc,
@@ -36,6 +36,65 @@ impl fmt::Display for Status {
     }
 }
 
+/// The difficulty level of a workshop, the levels that aren't one of the known ones are kept as
+/// they are written in the metadata
+#[derive(Clone, Debug, Eq, Hash, PartialEq, Deserialize, Serialize)]
+#[serde(from = "String", into = "String")]
+pub enum Difficulty {
+    Beginner,
+    Intermediate,
+    Advanced,
+    Other(String),
+}
+
+impl Difficulty {
+    /// The known difficulty levels, from the easiest to the hardest
+    pub const KNOWN: [Difficulty; 3] = [
+        Difficulty::Beginner,
+        Difficulty::Intermediate,
+        Difficulty::Advanced,
+    ];
+
+    /// The position of the difficulty when ordering from the easiest to the hardest, the other
+    /// levels come after the known ones
+    pub fn rank(&self) -> usize {
+        match self {
+            Difficulty::Beginner => 0,
+            Difficulty::Intermediate => 1,
+            Difficulty::Advanced => 2,
+            Difficulty::Other(_) => 3,
+        }
+    }
+}
+
+impl From<String> for Difficulty {
+    fn from(difficulty: String) -> Self {
+        match difficulty.trim().to_lowercase().as_str() {
+            "beginner" => Difficulty::Beginner,
+            "intermediate" => Difficulty::Intermediate,
+            "advanced" => Difficulty::Advanced,
+            _ => Difficulty::Other(difficulty),
+        }
+    }
+}
+
+impl From<Difficulty> for String {
+    fn from(difficulty: Difficulty) -> Self {
+        difficulty.to_string()
+    }
+}
+
+impl fmt::Display for Difficulty {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Difficulty::Beginner => write!(f, "Beginner"),
+            Difficulty::Intermediate => write!(f, "Intermediate"),
+            Difficulty::Advanced => write!(f, "Advanced"),
+            Difficulty::Other(difficulty) => write!(f, "{difficulty}"),
+        }
+    }
+}
+
 /// Represents a workshop's metadata
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct Workshop {
@@ -44,8 +103,11 @@ pub struct Workshop {
     pub copyright: String,
     pub license: String,
     pub homepage: String,
-    pub difficulty: String,
+    pub difficulty: Difficulty,
     pub status: Status,
+    /// the minimum versions of the tools the workshop needs
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub requires: BTreeMap<Tool, String>,
 }
 
 /// Represents the default spoken and programming language for a workshop
@@ -59,8 +121,28 @@ pub struct Defaults {
 impl TryLoad for Workshop {
     type Error = Error;
     async fn try_load(path: &Path) -> Result<Self, Error> {
-        let content = std::fs::read_to_string(path)?;
-        Ok(serde_yaml::from_str(&content)?)
+        let content = fs::read_to_string(path).await?;
+        // the file is in `<workshop>/<spoken>/workshop.yaml`
+        let spoken_dir = path.parent();
+        let spoken = spoken_dir
+            .and_then(Path::file_name)
+            .and_then(|spoken| spoken::Code::try_from(spoken.to_string_lossy().as_ref()).ok());
+        let workshop_dir = spoken_dir.and_then(Path::parent).map(Path::to_path_buf);
+        // parsing a big file, or writing its cache entry, would hold up the runtime
+        Ok(tokio::task::spawn_blocking(move || {
+            let mut workshop: Workshop = cache::parse_cached("workshop", &content, |content| {
+                serde_yaml::from_str(content)
+            })?;
+            // the status in the file is only the starting point, the progress is kept apart
+            if let Some(status) = workshop_dir
+                .zip(spoken)
+                .and_then(|(dir, spoken)| fs::progress::workshop_status(dir, spoken))
+            {
+                workshop.status = status;
+            }
+            Ok::<_, Error>(workshop)
+        })
+        .await??)
     }
 }
 
@@ -68,7 +150,7 @@ impl TryLoad for Workshop {
 impl TryLoad for String {
     type Error = Error;
     async fn try_load(path: &Path) -> Result<Self, Error> {
-        Ok(std::fs::read_to_string(path)?)
+        Ok(fs::read_to_string(path).await?)
     }
 }
 
@@ -131,6 +213,17 @@ impl WorkshopData {
         &self.languages
     }
 
+    /// returns the spoken and programming language pairs the workshop is available in that keep
+    /// one of the given languages, the ones keeping the programming language first, or none when
+    /// the workshop is available in the given pair
+    pub fn get_languages(
+        &self,
+        spoken_language: spoken::Code,
+        programming_language: programming::Code,
+    ) -> Vec<(spoken::Code, programming::Code)> {
+        alternative_languages(&self.languages, spoken_language, programming_language)
+    }
+
     /// returns the set of programming languages given a spoken language
     pub fn get_programming_languages_for_spoken_language(
         &self,
@@ -348,6 +441,30 @@ impl WorkshopData {
             "(engine) WorkshopData::get_metadata({})",
             spoken_language.map_or("Any".to_string(), |s| s.get_name_in_english().to_string())
         );
+        let mut metadata = self.metadata_loader(spoken_language)?.write().await;
+        // try to load the metadata, if it fails, return the error
+        metadata.try_load().await.cloned()
+    }
+
+    /// returns the metadata for the workshop in the given spoken language if it has already been
+    /// loaded, without waiting for it
+    pub fn get_loaded_metadata(&self, spoken_language: Option<spoken::Code>) -> Option<Workshop> {
+        let metadata = self
+            .metadata_loader(spoken_language)
+            .ok()?
+            .try_read()
+            .ok()?;
+        match &*metadata {
+            LazyLoader::Loaded(workshop) => Some(workshop.clone()),
+            LazyLoader::NotLoaded(_) => None,
+        }
+    }
+
+    // get the metadata loader for the given spoken language, falling back to any language
+    fn metadata_loader(
+        &self,
+        spoken_language: Option<spoken::Code>,
+    ) -> Result<&Arc<RwLock<LazyLoader<Workshop>>>, Error> {
         if self.metadata.is_empty() {
             return Err(ModelError::WorkshopNoMetadata.into());
         }
@@ -366,19 +483,12 @@ impl WorkshopData {
             }
         };
 
-        let mut metadata = self
-            .metadata
-            .get(&spoken_language)
-            .ok_or::<Error>(
-                ModelError::WorkshopSpokenLanguageNotFound(
-                    spoken_language.get_name_in_english().to_string(),
-                )
-                .into(),
-            )?
-            .write() // get a write lock on the Arc<RwLock<LazyLoader<Workshop>>>
-            .await;
-        // try to load the metadata, if it fails, return the error
-        metadata.try_load().await.cloned()
+        self.metadata.get(&spoken_language).ok_or::<Error>(
+            ModelError::WorkshopSpokenLanguageNotFound(
+                spoken_language.get_name_in_english().to_string(),
+            )
+            .into(),
+        )
     }
 
     /// returns the list of LessonData structs for the given spoken and programming language
@@ -500,6 +610,42 @@ impl WorkshopData {
             .join("deps.py"))
     }
 
+    /// Find the optional setup.py or setup.sh script that performs the setup instructions, using
+    /// status languages or defaults
+    pub fn get_setup_script_path(
+        &self,
+        status_spoken: Option<spoken::Code>,
+        status_programming: Option<programming::Code>,
+    ) -> Option<PathBuf> {
+        // Use status languages or fall back to defaults
+        let spoken = status_spoken.unwrap_or(self.defaults.spoken_language);
+        let programming = status_programming.unwrap_or(self.defaults.programming_language);
+
+        // Construct path: {workshop_data_dir}/{workshop_name}/{spoken}/{programming}/setup.py
+        let dir = crate::fs::workshops::data_dir()?
+            .join(&self.name)
+            .join(spoken.to_string())
+            .join(programming.to_string());
+        ["setup.py", "setup.sh"]
+            .into_iter()
+            .map(|script| dir.join(script))
+            .find(|script| script.is_file())
+    }
+
+    /// Find the optional deps.py script of a specific lesson, checking the tools only that lesson
+    /// needs, using status languages or defaults
+    pub fn get_lesson_deps_script_path(
+        &self,
+        lesson_name: &str,
+        status_spoken: Option<spoken::Code>,
+        status_programming: Option<programming::Code>,
+    ) -> Option<PathBuf> {
+        self.get_lesson_dir_path(lesson_name, status_spoken, status_programming)
+            .ok()
+            .map(|lesson_dir| lesson_dir.join("deps.py"))
+            .filter(|deps_script| deps_script.is_file())
+    }
+
     /// Calculate the path to the check.py script for a specific lesson using status languages or defaults
     pub fn get_check_script_path(
         &self,
@@ -545,47 +691,9 @@ impl WorkshopData {
             .join(lesson_name))
     }
 
-    /// Calculate the workshop status based on lesson completion
-    pub async fn calculate_status(
-        &self,
-        status_spoken: Option<spoken::Code>,
-        status_programming: Option<programming::Code>,
-    ) -> Result<Status, Error> {
-        // Use status languages or fall back to defaults
-        let spoken = status_spoken.unwrap_or(self.defaults.spoken_language);
-        let programming = status_programming.unwrap_or(self.defaults.programming_language);
-
-        let lessons = self
-            .get_lessons_data(Some(spoken), Some(programming))
-            .await?;
-
-        if lessons.is_empty() {
-            return Ok(Status::NotStarted);
-        }
-
-        let mut completed_count = 0;
-        let mut in_progress_count = 0;
-        let total_count = lessons.len();
-
-        for lesson_data in lessons.values() {
-            let lesson = lesson_data.get_metadata().await?;
-            match lesson.status {
-                lesson::Status::Completed => completed_count += 1,
-                lesson::Status::InProgress => in_progress_count += 1,
-                lesson::Status::NotStarted => {}
-            }
-        }
-
-        if completed_count == total_count {
-            Ok(Status::Completed)
-        } else if in_progress_count > 0 || completed_count > 0 {
-            Ok(Status::InProgress)
-        } else {
-            Ok(Status::NotStarted)
-        }
-    }
-
-    /// updates the workshop status and saves it to the workshop.yaml file
+    /// updates the workshop status and saves it to the progress store. The saved status is
+    /// the progress summary the workshop list shows, so it must be kept up to date as lessons
+    /// are started and completed rather than recomputed from the lessons.
     pub async fn update_status(
         &self,
         status_spoken: Option<spoken::Code>,
@@ -616,12 +724,10 @@ impl WorkshopData {
 
         // Ensure workshop is loaded
         let mut workshop = metadata.try_load().await.cloned()?;
-        workshop.status = new_status;
+        workshop.status = new_status.clone();
 
-        // Save the updated metadata back to the file
-        let workshop_yaml_path = self.get_workshop_path(Some(spoken))?;
-        let content = serde_yaml::to_string(&workshop)?;
-        std::fs::write(&workshop_yaml_path, content)?;
+        // Save the status in the progress store, leaving the workshop.yaml file as it is
+        fs::progress::set_workshop_status(self.path.join(&self.name), spoken, new_status)?;
 
         // Update the cached metadata
         *metadata = crate::fs::LazyLoader::Loaded(workshop);
@@ -842,7 +948,7 @@ impl Loader {
             .path
             .clone()
             .ok_or::<Error>(FsError::WorkshopDataDirNotFound.into())?;
-        let workshop_path = path.join(&name);
+        let workshop_path = path.join(crate::fs::check_name(&name)?);
         workshop_path
             .exists()
             .then_some(())
@@ -880,3 +986,77 @@ impl Loader {
         })
     }
 }
+
+// the language pairs that keep one of the languages of a pair the workshop isn't available in
+fn alternative_languages(
+    languages: &HashMap<spoken::Code, Vec<programming::Code>>,
+    spoken_language: spoken::Code,
+    programming_language: programming::Code,
+) -> Vec<(spoken::Code, programming::Code)> {
+    if languages
+        .get(&spoken_language)
+        .is_some_and(|programming| programming.contains(&programming_language))
+    {
+        return Vec::new();
+    }
+    let mut same_programming = languages
+        .iter()
+        .filter(|(_, programming)| programming.contains(&programming_language))
+        .map(|(spoken, _)| (*spoken, programming_language))
+        .collect::<Vec<_>>();
+    same_programming.sort_by_key(|(spoken, _)| spoken.to_string());
+    let mut same_spoken = languages
+        .get(&spoken_language)
+        .into_iter()
+        .flatten()
+        .map(|programming| (spoken_language, *programming))
+        .collect::<Vec<_>>();
+    same_spoken.sort_by_key(|(_, programming)| programming.to_string());
+    same_programming.into_iter().chain(same_spoken).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_alternative_languages() {
+        let languages = HashMap::from([
+            (
+                spoken::Code::en,
+                vec![programming::Code::go, programming::Code::rs],
+            ),
+            (spoken::Code::es, vec![programming::Code::rs]),
+        ]);
+        assert!(
+            alternative_languages(&languages, spoken::Code::es, programming::Code::rs).is_empty()
+        );
+        assert_eq!(
+            alternative_languages(&languages, spoken::Code::es, programming::Code::go),
+            vec![
+                (spoken::Code::en, programming::Code::go),
+                (spoken::Code::es, programming::Code::rs),
+            ]
+        );
+        assert!(
+            alternative_languages(&languages, spoken::Code::fr, programming::Code::py).is_empty()
+        );
+    }
+
+    #[test]
+    fn test_difficulty() {
+        let workshop: Workshop = serde_yaml::from_str(
+            "title: Hello\nauthors: []\ncopyright: ''\nlicense: MIT\nhomepage: ''\ndifficulty: intermediate\nstatus: NotStarted\n",
+        )
+        .unwrap();
+        assert_eq!(workshop.difficulty, Difficulty::Intermediate);
+        assert!(serde_yaml::to_string(&workshop)
+            .unwrap()
+            .contains("difficulty: Intermediate"));
+
+        let custom = Difficulty::from("Expert ".to_string());
+        assert_eq!(custom, Difficulty::Other("Expert ".to_string()));
+        assert_eq!(custom.to_string(), "Expert ");
+        assert!(Difficulty::Advanced.rank() < custom.rank());
+    }
+}
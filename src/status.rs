@@ -1,10 +1,137 @@
 use crate::{
-    fs,
+    fs::{self, application::Tool},
     languages::{programming, spoken},
+    models::{
+        template::{self, Variables},
+        Repository,
+    },
+    ui::tui::{
+        palette::{ColorVision, Theme},
+        widgets::TextPosition,
+    },
     Config, Error,
 };
 use serde::{Deserialize, Serialize};
-use tracing::{info, info_span};
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    hash::{BuildHasher, Hasher, RandomState},
+    path::PathBuf,
+    time::Duration,
+};
+use tracing::{info, info_span, warn};
+
+pub mod achievements;
+pub use achievements::{Achievement, Achievements};
+pub mod handle;
+pub use handle::{StatusHandle, StatusWriteGuard};
+
+/// The version of the status file format written by this version of the application
+pub const STATUS_VERSION: u32 = 1;
+
+/// The environment variable holding the multiaddr of the companion peer for the check scripts
+pub const RELAY_ADDR_ENV: &str = "RELAY_ADDR";
+
+/// The languages and last lesson remembered for a workshop so that switching between workshops
+/// resumes each one where it was left
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct WorkshopContext {
+    /// the spoken language the workshop was last taken in
+    pub spoken_language: Option<spoken::Code>,
+    /// the programming language the workshop was last taken in
+    pub programming_language: Option<programming::Code>,
+    /// the last lesson opened in the workshop
+    pub lesson: Option<String>,
+    /// the seconds of active time spent on each lesson
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub time: BTreeMap<String, u64>,
+    /// where the student was reading in each lesson
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub positions: BTreeMap<String, ReadingPosition>,
+    /// the places in the lessons the student bookmarked
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub bookmarks: Vec<Bookmark>,
+    /// when the workshop was last opened, in seconds since the Unix epoch
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub opened: Option<u64>,
+    /// how far the student got through the setup instructions in each programming language
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub setup: BTreeMap<programming::Code, SetupProgress>,
+    /// the lessons whose own dependencies were met, in each programming language
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub lesson_deps: BTreeMap<programming::Code, BTreeSet<String>>,
+}
+
+/// How far the student got through the setup instructions of a workshop
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct SetupProgress {
+    /// the steps ticked off, by index
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub done: BTreeSet<usize>,
+    /// the student confirmed that they finished the setup
+    #[serde(default)]
+    pub finished: bool,
+}
+
+/// Where the student was reading in a lesson
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq, Serialize)]
+pub struct ReadingPosition {
+    /// the highlighted line
+    pub highlighted: TextPosition,
+    /// the line at the top of the view
+    pub top: TextPosition,
+}
+
+/// A bookmarked place in a lesson
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct Bookmark {
+    /// the lesson the bookmark is in
+    pub lesson: String,
+    /// the title of the lesson
+    pub title: String,
+    /// the place in the lesson text
+    pub position: TextPosition,
+    /// the text of the bookmarked line
+    pub label: String,
+}
+
+/// How much of the student's progress to reset
+#[derive(Clone, Debug, PartialEq)]
+pub enum ProgressScope {
+    /// a single lesson in a workshop
+    Lesson(String, String),
+    /// a whole workshop
+    Workshop(String),
+    /// every workshop
+    All,
+}
+
+impl ProgressScope {
+    /// Parse the scope from `all`, `<workshop>`, or `<workshop>/<lesson>`
+    pub fn parse<S: AsRef<str>>(scope: S) -> Self {
+        match scope.as_ref().split_once('/') {
+            _ if scope.as_ref() == "all" => ProgressScope::All,
+            Some((workshop, lesson)) => {
+                ProgressScope::Lesson(workshop.to_string(), lesson.to_string())
+            }
+            None => ProgressScope::Workshop(scope.as_ref().to_string()),
+        }
+    }
+
+    /// Whether resetting the scope destroys more than a single lesson's progress
+    pub fn is_destructive(&self) -> bool {
+        !matches!(self, ProgressScope::Lesson(_, _))
+    }
+}
+
+impl std::fmt::Display for ProgressScope {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ProgressScope::Lesson(workshop, lesson) => write!(f, "{workshop}/{lesson}"),
+            ProgressScope::Workshop(workshop) => write!(f, "{workshop}"),
+            ProgressScope::All => write!(f, "all"),
+        }
+    }
+}
 
 /// This stores the currently active context for the application. It includes the spoken language,
 /// programming language, selected workshop, and selected lesson. It serialzies to the status.yaml
@@ -12,15 +139,33 @@ use tracing::{info, info_span};
 /// from the Config object when first created.
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
 pub struct Status {
+    #[serde(default)]
+    version: u32,
     python_executable: Option<String>,
     docker_compose_executable: Option<String>,
     git_executable: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    node_executable: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    npm_executable: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    go_executable: Option<String>,
     spoken_language: Option<spoken::Code>,
     programming_language: Option<programming::Code>,
     workshop: Option<String>,
     lesson: Option<String>,
+    #[serde(default = "generate_peer_id")]
+    peer_id: String,
+    #[serde(default)]
+    workshops: BTreeMap<String, WorkshopContext>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pinned: Vec<String>,
+    #[serde(default)]
+    achievements: Achievements,
     #[serde(skip)]
     config: Config,
+    #[serde(skip)]
+    repository: Repository,
 }
 
 impl Status {
@@ -30,35 +175,75 @@ impl Status {
         let _enter = span.enter();
 
         let config = Config::load()?;
-        if let Some(path) = fs::workshops::data_dir().map(|d| d.join("status.yaml")) {
-            if path.exists() {
-                // try to load it from the file
-                let mut status: Status = serde_yaml::from_reader(std::fs::File::open(&path)?)?;
-                status.config = config;
-                return Ok(status);
+        // a new project starts from the status saved before there was a `.workshops` directory
+        let saved = [fs::workshops::data_dir(), fs::application::state_dir().ok()]
+            .into_iter()
+            .flatten()
+            .map(|d| d.join("status.yaml"))
+            .find(|path| path.exists());
+        if let Some(path) = saved {
+            // try to load it from the file, falling back to the backup if it is corrupt
+            match Self::read(&path).or_else(|e| {
+                warn!("Failed to read {}: {e}", path.display());
+                Self::read(fs::backup_path(&path))
+            }) {
+                Ok(mut status) => {
+                    // saving would drop the fields a newer version wrote, so the status of a
+                    // newer version is only read
+                    if status.version > STATUS_VERSION {
+                        warn!(
+                            "Status file version {} is newer than {STATUS_VERSION}, changes to it won't be saved",
+                            status.version
+                        );
+                    } else {
+                        status.version = STATUS_VERSION;
+                    }
+                    status.config = config;
+                    return Ok(status);
+                }
+                Err(e) => {
+                    // keep the corrupt file around for inspection and start over
+                    warn!("Failed to recover the status, starting fresh: {e}");
+                    std::fs::rename(&path, path.with_extension("yaml.corrupt"))?;
+                }
             }
         }
 
         // otherwise, create the status
         Ok(Status {
+            version: STATUS_VERSION,
             python_executable: config.python_executable(),
             docker_compose_executable: config.docker_compose_executable(),
             git_executable: config.git_executable(),
+            node_executable: config.node_executable(),
+            npm_executable: config.npm_executable(),
+            go_executable: config.go_executable(),
             spoken_language: config.spoken_language(),
             programming_language: config.programming_language(),
             workshop: None,
             lesson: None,
+            peer_id: generate_peer_id(),
+            workshops: BTreeMap::default(),
+            pinned: Vec::new(),
+            achievements: Achievements::default(),
             config,
+            repository: Repository::default(),
         })
     }
 
+    // read the status from the given file
+    fn read<P: AsRef<std::path::Path>>(path: P) -> Result<Self, Error> {
+        Ok(serde_yaml::from_reader(std::fs::File::open(path)?)?)
+    }
+
     /// save the status to the given path
     pub fn save(&self) -> Result<(), Error> {
-        // if there is a workshops data directory, save the status there
-        if let Some(path) = fs::workshops::data_dir().map(|d| d.join("status.yaml")) {
-            std::fs::create_dir_all(path.parent().unwrap())?;
+        // save the status in the project, or in the state directory outside of one, unless it
+        // was written by a newer version
+        let path = fs::application::status_dir().map(|d| d.join("status.yaml"));
+        if let Some(path) = path.filter(|_| self.version <= STATUS_VERSION) {
+            fs::write_atomic(&path, serde_yaml::to_string(&self)?)?;
             info!("Status saved to: {}", path.display());
-            serde_yaml::to_writer(std::fs::File::create(path)?, &self)?;
         }
         // save the config as well
         self.config.save()?;
@@ -90,11 +275,146 @@ impl Status {
         self.git_executable.as_deref()
     }
 
+    /// Get the detected or configured executables of the tools
+    pub fn tool_executables(&self) -> HashMap<Tool, String> {
+        [
+            (Tool::Python, self.python_executable()),
+            (Tool::DockerCompose, self.docker_compose_executable()),
+            (Tool::Git, self.git_executable()),
+            (Tool::Node, self.node_executable()),
+            (Tool::Npm, self.npm_executable()),
+            (Tool::Go, self.go_executable()),
+        ]
+        .into_iter()
+        .filter_map(|(tool, executable)| Some((tool, executable?.to_string())))
+        .collect()
+    }
+
     /// Get the minimum required Git version
     pub fn git_minimum_version(&self) -> &str {
         self.config.git_minimum_version()
     }
 
+    /// Get the preferred Node.js executable
+    pub fn node_executable(&self) -> Option<&str> {
+        self.node_executable.as_deref()
+    }
+
+    /// Get the minimum required Node.js version
+    pub fn node_minimum_version(&self) -> &str {
+        self.config.node_minimum_version()
+    }
+
+    /// Get the preferred npm executable
+    pub fn npm_executable(&self) -> Option<&str> {
+        self.npm_executable.as_deref()
+    }
+
+    /// Get the minimum required npm version
+    pub fn npm_minimum_version(&self) -> &str {
+        self.config.npm_minimum_version()
+    }
+
+    /// Get the preferred Go executable
+    pub fn go_executable(&self) -> Option<&str> {
+        self.go_executable.as_deref()
+    }
+
+    /// Get the minimum required Go version
+    pub fn go_minimum_version(&self) -> &str {
+        self.config.go_minimum_version()
+    }
+
+    /// Get the maximum time a command may run before it is killed
+    pub fn command_timeout(&self) -> Option<Duration> {
+        match self.config.command_timeout() {
+            0 => None,
+            secs => Some(Duration::from_secs(secs)),
+        }
+    }
+
+    /// Get the environment variables passed to the commands run for the lessons, so that check
+    /// scripts and docker compose files can dial the same companion peer as the lesson text and
+    /// run the same tools the application detected
+    pub fn command_env(&self) -> Vec<(String, String)> {
+        let mut env = vec![(
+            RELAY_ADDR_ENV.to_string(),
+            self.config.relay_addr().to_string(),
+        )];
+        let mut tools = self.tool_executables().into_iter().collect::<Vec<_>>();
+        tools.sort();
+        env.extend(
+            tools
+                .into_iter()
+                .map(|(tool, executable)| (tool.env_var().to_string(), executable)),
+        );
+        env
+    }
+
+    /// Get the URL of the workshop catalog index
+    pub fn catalog_url(&self) -> &str {
+        self.config.catalog_url()
+    }
+
+    /// Get the trusted workshop signers in the ssh allowed signers format
+    pub fn trusted_signers(&self) -> &[String] {
+        self.config.trusted_signers()
+    }
+
+    /// Get whether workshops without a trusted signature are rejected at install time
+    pub fn require_signatures(&self) -> bool {
+        self.config.require_signatures()
+    }
+
+    /// Get whether to render with ASCII glyphs only, `None` means detect it from the terminal
+    pub fn ascii(&self) -> Option<bool> {
+        self.config.ascii()
+    }
+
+    /// Get the color theme
+    pub fn theme(&self) -> Theme {
+        self.config.theme()
+    }
+
+    /// Get the shared repository of the workshops loaded from the `.workshops` directory
+    pub fn repository(&self) -> Repository {
+        self.repository.clone()
+    }
+
+    /// Get the color vision the status colors are adjusted for
+    pub fn color_vision(&self) -> ColorVision {
+        self.config.color_vision()
+    }
+
+    /// Should the spinners and other animations be turned off
+    pub fn reduced_motion(&self) -> bool {
+        self.config.reduced_motion()
+    }
+
+    /// Get the name printed on completion certificates, defaulting to the login name
+    pub fn student_name(&self) -> String {
+        self.config
+            .student_name()
+            .map(String::from)
+            .or_else(|| std::env::var("USER").ok())
+            .or_else(|| std::env::var("USERNAME").ok())
+            .unwrap_or_else(|| "Student".to_string())
+    }
+
+    /// Get the base URL of the badge service, empty means no badges
+    pub fn badge_service(&self) -> &str {
+        self.config.badge_service()
+    }
+
+    /// Get the progress sync, if a progress remote is configured
+    pub fn progress_sync(&self) -> Option<fs::ProgressSync> {
+        fs::ProgressSync::new(
+            self.git_executable().unwrap_or("git"),
+            self.config.progress_remote(),
+            &self.peer_id,
+        )
+    }
+
     /// Get the preferred spoken language
     pub fn spoken_language(&self) -> Option<spoken::Code> {
         self.spoken_language
@@ -105,6 +425,16 @@ impl Status {
         self.programming_language
     }
 
+    /// Get the spoken language set as the default
+    pub fn default_spoken_language(&self) -> Option<spoken::Code> {
+        self.config.spoken_language()
+    }
+
+    /// Get the programming language set as the default
+    pub fn default_programming_language(&self) -> Option<programming::Code> {
+        self.config.programming_language()
+    }
+
     /// Get the selected workshop
     pub fn workshop(&self) -> Option<&str> {
         self.workshop.as_deref()
@@ -115,6 +445,32 @@ impl Status {
         self.lesson.as_deref()
     }
 
+    /// Get the per-student peer id used in lesson text
+    pub fn peer_id(&self) -> &str {
+        &self.peer_id
+    }
+
+    /// Get the variables used to render the templated lesson text
+    pub fn template_variables(&self) -> Variables {
+        // the workspace is the directory containing the .workshops directory
+        let workspace_dir = fs::workshops::data_dir()
+            .and_then(|d| d.parent().map(PathBuf::from))
+            .or_else(|| std::env::current_dir().ok())
+            .unwrap_or_else(|| PathBuf::from("."));
+
+        Variables::from([
+            (template::PEER_ID.to_string(), self.peer_id.clone()),
+            (
+                template::WORKSPACE_DIR.to_string(),
+                workspace_dir.display().to_string(),
+            ),
+            (
+                template::RELAY_ADDR.to_string(),
+                self.config.relay_addr().to_string(),
+            ),
+        ])
+    }
+
     /// Set the preferred Python executable with optional default
     pub fn set_python_executable(&mut self, python_executable: &str, default: bool) {
         self.python_executable = Some(python_executable.to_string());
@@ -144,6 +500,30 @@ impl Status {
         }
     }
 
+    /// Set the preferred Node.js executable with optional default
+    pub fn set_node_executable(&mut self, node_executable: &str, default: bool) {
+        self.node_executable = Some(node_executable.to_string());
+        if default {
+            self.config.set_node_executable(node_executable);
+        }
+    }
+
+    /// Set the preferred npm executable with optional default
+    pub fn set_npm_executable(&mut self, npm_executable: &str, default: bool) {
+        self.npm_executable = Some(npm_executable.to_string());
+        if default {
+            self.config.set_npm_executable(npm_executable);
+        }
+    }
+
+    /// Set the preferred Go executable with optional default
+    pub fn set_go_executable(&mut self, go_executable: &str, default: bool) {
+        self.go_executable = Some(go_executable.to_string());
+        if default {
+            self.config.set_go_executable(go_executable);
+        }
+    }
+
     /// Set the spoken language with optional default
     pub fn set_spoken_language(&mut self, spoken_language: Option<spoken::Code>, default: bool) {
         self.spoken_language = spoken_language;
@@ -164,13 +544,274 @@ impl Status {
         }
     }
 
-    /// Set the selected workshop
+    /// Set the selected workshop and remember the languages it is being taken in
     pub fn set_workshop(&mut self, workshop: Option<String>) {
+        if let Some(name) = &workshop {
+            let context = self.workshops.entry(name.clone()).or_default();
+            context.spoken_language = self.spoken_language;
+            context.programming_language = self.programming_language;
+            context.opened = std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .ok();
+        }
         self.workshop = workshop;
     }
 
-    /// Set the selected lesson
+    /// Get the workshops that have been opened, most recently opened first
+    pub fn recent_workshops(&self) -> Vec<String> {
+        let mut opened = self
+            .workshops
+            .iter()
+            .filter_map(|(workshop, context)| Some((context.opened?, workshop.clone())))
+            .collect::<Vec<_>>();
+        opened.sort_by(|a, b| b.cmp(a));
+        opened.into_iter().map(|(_, workshop)| workshop).collect()
+    }
+
+    /// Get the workshops pinned to the top of the workshop list
+    pub fn pinned_workshops(&self) -> &[String] {
+        &self.pinned
+    }
+
+    /// Pin the workshop to the top of the workshop list, or unpin it if it is pinned. Returns
+    /// whether it is pinned now.
+    pub fn toggle_pinned(&mut self, workshop: &str) -> bool {
+        if let Some(index) = self.pinned.iter().position(|w| w == workshop) {
+            self.pinned.remove(index);
+            false
+        } else {
+            self.pinned.push(workshop.to_string());
+            true
+        }
+    }
+
+    /// Set the selected lesson and remember it as the last lesson of the selected workshop
     pub fn set_lesson(&mut self, lesson: Option<String>) {
+        if let (Some(workshop), Some(lesson)) = (&self.workshop, &lesson) {
+            self.workshops.entry(workshop.clone()).or_default().lesson = Some(lesson.clone());
+        }
         self.lesson = lesson;
     }
+
+    /// Get the languages and last lesson remembered for the given workshop
+    pub fn workshop_context(&self, workshop: &str) -> Option<&WorkshopContext> {
+        self.workshops.get(workshop)
+    }
+
+    /// Clear the selection and the remembered context covered by the reset scope
+    pub fn reset_progress(&mut self, scope: &ProgressScope) {
+        match scope {
+            ProgressScope::Lesson(workshop, lesson) => {
+                if self.workshop.as_ref() == Some(workshop) && self.lesson.as_ref() == Some(lesson)
+                {
+                    self.lesson = None;
+                }
+                if let Some(context) = self.workshops.get_mut(workshop) {
+                    if context.lesson.as_ref() == Some(lesson) {
+                        context.lesson = None;
+                    }
+                    context.time.remove(lesson);
+                    context.positions.remove(lesson);
+                }
+            }
+            ProgressScope::Workshop(workshop) => {
+                if self.workshop.as_ref() == Some(workshop) {
+                    self.workshop = None;
+                    self.lesson = None;
+                }
+                self.forget_workshop(workshop);
+            }
+            ProgressScope::All => {
+                self.workshop = None;
+                self.lesson = None;
+                self.workshops.clear();
+            }
+        }
+    }
+
+    /// Get the achievements the student has unlocked
+    pub fn achievements(&self) -> &Achievements {
+        &self.achievements
+    }
+
+    /// Record a completed lesson, returning the achievements it unlocked
+    pub fn record_completion(&mut self, hints_used: bool, elapsed: Duration) -> Vec<Achievement> {
+        self.achievements.record(hints_used, elapsed)
+    }
+
+    /// Add active time spent on a lesson, only whole seconds are kept
+    pub fn add_lesson_time(&mut self, workshop: &str, lesson: &str, time: Duration) {
+        let context = self.workshops.entry(workshop.to_string()).or_default();
+        *context.time.entry(lesson.to_string()).or_default() += time.as_secs();
+    }
+
+    /// Get the active time spent on a lesson
+    pub fn lesson_time(&self, workshop: &str, lesson: &str) -> Duration {
+        Duration::from_secs(
+            self.workshops
+                .get(workshop)
+                .and_then(|context| context.time.get(lesson))
+                .copied()
+                .unwrap_or_default(),
+        )
+    }
+
+    /// Get the active time spent on all of the lessons in a workshop
+    pub fn workshop_time(&self, workshop: &str) -> Duration {
+        Duration::from_secs(
+            self.workshops
+                .get(workshop)
+                .map(|context| context.time.values().sum())
+                .unwrap_or_default(),
+        )
+    }
+
+    /// Remember where the student is reading in a lesson
+    pub fn set_reading_position(
+        &mut self,
+        workshop: &str,
+        lesson: &str,
+        position: ReadingPosition,
+    ) {
+        let context = self.workshops.entry(workshop.to_string()).or_default();
+        context.positions.insert(lesson.to_string(), position);
+    }
+
+    /// Get where the student was reading in a lesson
+    pub fn reading_position(&self, workshop: &str, lesson: &str) -> Option<ReadingPosition> {
+        self.workshops
+            .get(workshop)
+            .and_then(|context| context.positions.get(lesson))
+            .copied()
+    }
+
+    /// Bookmark a place in a lesson, replacing any bookmark already at the same place
+    pub fn add_bookmark(&mut self, workshop: &str, bookmark: Bookmark) {
+        let context = self.workshops.entry(workshop.to_string()).or_default();
+        context
+            .bookmarks
+            .retain(|b| b.lesson != bookmark.lesson || b.position != bookmark.position);
+        context.bookmarks.push(bookmark);
+    }
+
+    /// Remove the bookmark at the given index in the workshop's bookmarks
+    pub fn remove_bookmark(&mut self, workshop: &str, index: usize) {
+        if let Some(context) = self.workshops.get_mut(workshop) {
+            if index < context.bookmarks.len() {
+                context.bookmarks.remove(index);
+            }
+        }
+    }
+
+    /// Get the bookmarks in a workshop, oldest first
+    pub fn bookmarks(&self, workshop: &str) -> &[Bookmark] {
+        self.workshops
+            .get(workshop)
+            .map(|context| context.bookmarks.as_slice())
+            .unwrap_or_default()
+    }
+
+    /// Get how far the student got through the setup instructions of a workshop
+    pub fn setup_progress(
+        &self,
+        workshop: &str,
+        programming_language: programming::Code,
+    ) -> SetupProgress {
+        self.workshops
+            .get(workshop)
+            .and_then(|context| context.setup.get(&programming_language))
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Tick off a step of the setup instructions, or untick it if it is ticked off
+    pub fn toggle_setup_step(
+        &mut self,
+        workshop: &str,
+        programming_language: programming::Code,
+        step: usize,
+    ) {
+        let context = self.workshops.entry(workshop.to_string()).or_default();
+        let progress = context.setup.entry(programming_language).or_default();
+        if !progress.done.remove(&step) {
+            progress.done.insert(step);
+        }
+    }
+
+    /// Remember that the student finished the setup of a workshop
+    pub fn finish_setup(&mut self, workshop: &str, programming_language: programming::Code) {
+        let context = self.workshops.entry(workshop.to_string()).or_default();
+        context
+            .setup
+            .entry(programming_language)
+            .or_default()
+            .finished = true;
+    }
+
+    /// Were the dependencies of the lesson already met
+    pub fn lesson_deps_checked(
+        &self,
+        workshop: &str,
+        programming_language: programming::Code,
+        lesson: &str,
+    ) -> bool {
+        self.workshops
+            .get(workshop)
+            .and_then(|context| context.lesson_deps.get(&programming_language))
+            .is_some_and(|lessons| lessons.contains(lesson))
+    }
+
+    /// Remember that the dependencies of the lesson are met so they aren't checked again
+    pub fn set_lesson_deps_checked(
+        &mut self,
+        workshop: &str,
+        programming_language: programming::Code,
+        lesson: &str,
+    ) {
+        let context = self.workshops.entry(workshop.to_string()).or_default();
+        context
+            .lesson_deps
+            .entry(programming_language)
+            .or_default()
+            .insert(lesson.to_string());
+    }
+
+    /// Forget everything remembered for the given workshop
+    pub fn forget_workshop(&mut self, workshop: &str) {
+        self.workshops.remove(workshop);
+    }
+}
+
+/// Format a time spent as hours and minutes, like `1h 05m` or `12m`
+pub fn format_time(time: Duration) -> String {
+    let minutes = time.as_secs() / 60;
+    match minutes / 60 {
+        0 => format!("{minutes}m"),
+        hours => format!("{hours}h {:02}m", minutes % 60),
+    }
+}
+
+/// Generate a unique identifier for the student so that each student gets their own values in the
+/// lesson text. It is an opaque random id, not the PeerId of a key.
+fn generate_peer_id() -> String {
+    let mut random = [0u8; 16];
+    let read = std::fs::File::open("/dev/urandom")
+        .and_then(|mut urandom| std::io::Read::read_exact(&mut urandom, &mut random));
+    if read.is_err() {
+        // the id only has to differ between students, so hashed time is enough without urandom
+        for (i, chunk) in random.chunks_mut(8).enumerate() {
+            let mut hasher = RandomState::new().build_hasher();
+            hasher.write_usize(i);
+            hasher.write_u128(
+                std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .map(|d| d.as_nanos())
+                    .unwrap_or_default(),
+            );
+            chunk.copy_from_slice(&hasher.finish().to_be_bytes());
+        }
+    }
+    let hex: String = random.iter().map(|b| format!("{b:02x}")).collect();
+    format!("student-{hex}")
 }
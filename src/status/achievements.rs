@@ -0,0 +1,161 @@
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeSet,
+    fmt,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+/// The number of days in a row with a completed lesson that earns the streak achievement
+pub const STREAK_DAYS: usize = 5;
+
+/// A lesson completed within this much active time earns the speed-run achievement
+pub const SPEED_RUN: Duration = Duration::from_secs(5 * 60);
+
+/// The achievements a student can unlock
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "kebab-case")]
+pub enum Achievement {
+    /// completed a lesson for the first time
+    FirstLesson,
+    /// completed a lesson without opening any of its hints
+    NoHints,
+    /// completed lessons on five days in a row
+    Streak,
+    /// completed a lesson within five minutes of active time
+    SpeedRun,
+}
+
+impl Achievement {
+    /// All of the achievements in the order they are shown
+    pub const ALL: [Achievement; 4] = [
+        Achievement::FirstLesson,
+        Achievement::NoHints,
+        Achievement::Streak,
+        Achievement::SpeedRun,
+    ];
+
+    /// What it takes to unlock the achievement
+    pub fn description(&self) -> String {
+        match self {
+            Achievement::FirstLesson => "Complete your first lesson".to_string(),
+            Achievement::NoHints => "Complete a lesson without a hint".to_string(),
+            Achievement::Streak => format!("Complete lessons {STREAK_DAYS} days in a row"),
+            Achievement::SpeedRun => format!(
+                "Complete a lesson within {} minutes",
+                SPEED_RUN.as_secs() / 60
+            ),
+        }
+    }
+}
+
+impl fmt::Display for Achievement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Achievement::FirstLesson => write!(f, "First Lesson"),
+            Achievement::NoHints => write!(f, "No Hints"),
+            Achievement::Streak => write!(f, "{STREAK_DAYS}-Day Streak"),
+            Achievement::SpeedRun => write!(f, "Speed Run"),
+        }
+    }
+}
+
+/// The unlocked achievements and the days with completed lessons that the streak is computed from
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct Achievements {
+    /// the days since the unix epoch on which a lesson was completed
+    #[serde(default)]
+    days: BTreeSet<u64>,
+    /// the unlocked achievements
+    #[serde(default)]
+    unlocked: BTreeSet<Achievement>,
+}
+
+impl Achievements {
+    /// Record a lesson completed today after the given active time, returning the achievements it
+    /// unlocked
+    pub fn record(&mut self, hints_used: bool, elapsed: Duration) -> Vec<Achievement> {
+        self.record_on(today(), hints_used, elapsed)
+    }
+
+    // record a lesson completed on the given day
+    fn record_on(&mut self, day: u64, hints_used: bool, elapsed: Duration) -> Vec<Achievement> {
+        self.days.insert(day);
+        let mut earned = vec![Achievement::FirstLesson];
+        if !hints_used {
+            earned.push(Achievement::NoHints);
+        }
+        if self.streak_on(day) >= STREAK_DAYS {
+            earned.push(Achievement::Streak);
+        }
+        if elapsed <= SPEED_RUN {
+            earned.push(Achievement::SpeedRun);
+        }
+        earned.retain(|achievement| self.unlocked.insert(*achievement));
+        earned
+    }
+
+    /// Is the achievement unlocked
+    pub fn is_unlocked(&self, achievement: Achievement) -> bool {
+        self.unlocked.contains(&achievement)
+    }
+
+    /// The number of days in a row, up to today, with a completed lesson. A streak that ended
+    /// yesterday still counts since today's lesson may not be done yet.
+    pub fn streak(&self) -> usize {
+        let today = today();
+        match self.streak_on(today) {
+            0 => self.streak_on(today.saturating_sub(1)),
+            streak => streak,
+        }
+    }
+
+    // the number of days in a row ending on the given day
+    fn streak_on(&self, day: u64) -> usize {
+        self.days
+            .range(..=day)
+            .rev()
+            .zip((0..=day).rev())
+            .take_while(|(completed, expected)| completed == &expected)
+            .count()
+    }
+}
+
+// the days since the unix epoch, in UTC
+fn today() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() / 86_400)
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_achievements_unlock_once() {
+        let slow = SPEED_RUN * 2;
+        let mut achievements = Achievements::default();
+        assert_eq!(
+            achievements.record_on(100, true, slow),
+            vec![Achievement::FirstLesson]
+        );
+        assert_eq!(
+            achievements.record_on(101, false, SPEED_RUN),
+            vec![Achievement::NoHints, Achievement::SpeedRun]
+        );
+
+        // a gap resets the streak
+        for day in [103, 104, 105, 106] {
+            assert!(achievements.record_on(day, true, slow).is_empty());
+        }
+        assert_eq!(achievements.streak_on(106), 4);
+        assert_eq!(
+            achievements.record_on(107, true, slow),
+            vec![Achievement::Streak]
+        );
+        assert!(Achievement::ALL
+            .iter()
+            .all(|achievement| achievements.is_unlocked(*achievement)));
+    }
+}
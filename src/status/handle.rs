@@ -0,0 +1,112 @@
+use crate::Status;
+use std::{
+    ops::{Deref, DerefMut},
+    sync::Arc,
+};
+use tokio::sync::{watch, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+/// The shared application status. Cloning a handle is cheap and all of the clones share the
+/// same status. The lock is async so that waiting on it never blocks the runtime and it can't be
+/// poisoned by a panicking task. Every change through [`StatusHandle::write`] or
+/// [`StatusHandle::update`] notifies the receivers from [`StatusHandle::subscribe`].
+#[derive(Clone, Debug)]
+pub struct StatusHandle {
+    status: Arc<RwLock<Status>>,
+    changed: Arc<watch::Sender<u64>>,
+}
+
+/// Write access to the status that notifies the subscribers when it is dropped
+pub struct StatusWriteGuard<'a> {
+    status: RwLockWriteGuard<'a, Status>,
+    changed: &'a watch::Sender<u64>,
+}
+
+impl StatusHandle {
+    /// Share the given status
+    pub fn new(status: Status) -> Self {
+        Self {
+            status: Arc::new(RwLock::new(status)),
+            changed: Arc::new(watch::Sender::new(0)),
+        }
+    }
+
+    /// Get read access to the status
+    pub async fn read(&self) -> RwLockReadGuard<'_, Status> {
+        self.status.read().await
+    }
+
+    /// Get write access to the status
+    pub async fn write(&self) -> StatusWriteGuard<'_> {
+        StatusWriteGuard {
+            status: self.status.write().await,
+            changed: &self.changed,
+        }
+    }
+
+    /// Get a value from the status
+    pub async fn get<R>(&self, f: impl FnOnce(&Status) -> R) -> R {
+        f(&*self.read().await)
+    }
+
+    /// Change the status
+    pub async fn update<R>(&self, f: impl FnOnce(&mut Status) -> R) -> R {
+        f(&mut *self.write().await)
+    }
+
+    /// Get a receiver that is marked changed every time the status changes
+    pub fn subscribe(&self) -> watch::Receiver<u64> {
+        self.changed.subscribe()
+    }
+
+    /// The selected workshop
+    pub async fn workshop(&self) -> Option<String> {
+        self.read().await.workshop().map(String::from)
+    }
+
+    /// The selected lesson
+    pub async fn lesson(&self) -> Option<String> {
+        self.read().await.lesson().map(String::from)
+    }
+}
+
+impl Deref for StatusWriteGuard<'_> {
+    type Target = Status;
+
+    fn deref(&self) -> &Status {
+        &self.status
+    }
+}
+
+impl DerefMut for StatusWriteGuard<'_> {
+    fn deref_mut(&mut self) -> &mut Status {
+        &mut self.status
+    }
+}
+
+impl Drop for StatusWriteGuard<'_> {
+    fn drop(&mut self) {
+        self.changed
+            .send_modify(|version| *version = version.wrapping_add(1));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_status_changes_are_notified() {
+        let handle = StatusHandle::new(Status::default());
+        let mut changed = handle.subscribe();
+        assert!(!changed.has_changed().unwrap());
+
+        // reading doesn't notify
+        let _ = handle.lesson().await;
+        assert!(!changed.has_changed().unwrap());
+
+        handle.update(|status| status.set_lesson(None)).await;
+        assert!(changed.has_changed().unwrap());
+        changed.mark_unchanged();
+        assert_eq!(handle.clone().lesson().await, None);
+    }
+}
@@ -1,5 +1,12 @@
 pub mod events;
-pub use events::{Event, Evt};
+pub use events::{AsDefault, ChangeLanguage, Event, Evt, Then};
+
+pub mod flow;
+pub use flow::Flow;
+
+pub mod coalesce;
+pub mod glyphs;
+pub mod palette;
 
 pub mod screens;
 pub use screens::{Screen, Screens};
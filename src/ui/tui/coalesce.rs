@@ -0,0 +1,81 @@
+use crate::ui::tui::screens;
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use std::collections::VecDeque;
+
+// the navigation key of a key event that moves the selection or scroll position on every screen,
+// if it is one
+fn nav(event: &screens::Event) -> Option<KeyEvent> {
+    let screens::Event::Input(Event::Key(key)) = event else {
+        return None;
+    };
+    if key.kind != KeyEventKind::Press || !(key.modifiers - KeyModifiers::SHIFT).is_empty() {
+        return None;
+    }
+    match key.code {
+        KeyCode::Char('j' | 'J' | 'k' | 'K')
+        | KeyCode::Down
+        | KeyCode::Up
+        | KeyCode::PageUp
+        | KeyCode::PageDown => Some(*key),
+        _ => None,
+    }
+}
+
+/// Take the run of navigation key events at the front of the queued events and coalesce it into
+/// the keys with how many times in a row they were pressed. Everything before the last jump to
+/// the start or the end is dropped since the jump ends up in the same place. Steps in opposite
+/// directions are kept in order, since a step at the end of a list doesn't move. This lets a
+/// screen move by a whole run of `j`/`k` presses at once when the key is held down.
+pub fn navigation(events: &mut VecDeque<screens::Event>) -> Vec<(KeyEvent, usize)> {
+    let mut runs: Vec<(KeyEvent, usize)> = Vec::new();
+    while let Some(key) = events.front().and_then(nav) {
+        events.pop_front();
+        let jump = matches!(key.code, KeyCode::PageUp | KeyCode::PageDown);
+        if jump {
+            runs.clear();
+        }
+        match runs.last_mut() {
+            Some((last, count)) if *last == key && !jump => *count += 1,
+            _ => runs.push((key, 1)),
+        }
+    }
+    runs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(code: KeyCode) -> screens::Event {
+        screens::Event::Input(Event::Key(KeyEvent::new(code, KeyModifiers::NONE)))
+    }
+
+    #[test]
+    fn test_navigation_is_coalesced() {
+        let mut events = VecDeque::from([
+            key(KeyCode::Char('j')),
+            key(KeyCode::Char('j')),
+            key(KeyCode::PageDown),
+            key(KeyCode::Char('k')),
+            key(KeyCode::Char('k')),
+            key(KeyCode::Down),
+            key(KeyCode::Enter),
+            key(KeyCode::Char('j')),
+        ]);
+        let coalesced: Vec<_> = navigation(&mut events)
+            .into_iter()
+            .map(|(key, count)| (key.code, count))
+            .collect();
+        assert_eq!(
+            coalesced,
+            vec![
+                (KeyCode::PageDown, 1),
+                (KeyCode::Char('k'), 2),
+                (KeyCode::Down, 1)
+            ]
+        );
+
+        // the run stops at the first event that isn't navigation
+        assert_eq!(events.len(), 2);
+    }
+}
@@ -1,8 +1,19 @@
 use crate::{
     command::CommandResult,
+    fs::{
+        application::Scan,
+        search::Match,
+        usage::{Kind, Usage},
+    },
     languages::{programming, spoken},
-    ui::tui::{screens::Screens, widgets::StatusMode},
+    models::{catalog::CatalogEntry, lesson},
+    status::ProgressScope,
+    ui::tui::{
+        screens::{lessons::Loaded, workshops::Batch, Screens},
+        widgets::{Dialog, StatusMode},
+    },
 };
+use crossterm::event::KeyEvent;
 use std::collections::HashMap;
 use tokio::time::Duration;
 
@@ -19,6 +30,88 @@ macro_rules! evt {
     };
 }
 
+/// The events to send after an operation, depending on how it went
+#[derive(Clone, Debug, Default)]
+pub struct Then {
+    /// sent when the operation succeeds
+    pub success: Option<Evt>,
+    /// sent when the operation fails
+    pub failure: Option<Evt>,
+}
+
+impl Then {
+    /// Send the event only when the operation succeeds
+    pub fn on_success(success: Evt) -> Self {
+        Self {
+            success: Some(success),
+            failure: None,
+        }
+    }
+
+    /// Send the event whether the operation succeeds or fails
+    pub fn always(next: Option<Evt>) -> Self {
+        Self {
+            success: next.clone(),
+            failure: next,
+        }
+    }
+
+    /// Also send the event when the operation fails
+    pub fn or_else(mut self, failure: Evt) -> Self {
+        self.failure = Some(failure);
+        self
+    }
+}
+
+/// Whether a chosen language becomes the default
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum AsDefault {
+    /// ask with a yes/no dialog
+    #[default]
+    Ask,
+    /// make it the default
+    Yes,
+    /// only use it for now
+    No,
+}
+
+/// What the spoken or programming language picker offers
+#[derive(Clone, Debug)]
+pub struct ChangeLanguage<L> {
+    /// the programming languages of the workshops in each spoken language
+    pub languages: HashMap<spoken::Code, Vec<programming::Code>>,
+    /// the language selected when the picker is shown
+    pub selected: Option<L>,
+    /// show the "Any" option
+    pub allow_any: bool,
+    /// the event to send once the language is chosen
+    pub next: Option<Evt>,
+}
+
+impl<L> ChangeLanguage<L> {
+    /// Offer the languages, with the "Any" option, and nothing selected
+    pub fn new(languages: HashMap<spoken::Code, Vec<programming::Code>>) -> Self {
+        Self {
+            languages,
+            selected: None,
+            allow_any: true,
+            next: None,
+        }
+    }
+
+    /// Select the language when the picker is shown
+    pub fn selected(mut self, selected: Option<L>) -> Self {
+        self.selected = selected;
+        self
+    }
+
+    /// Show the "Any" option or not
+    pub fn allow_any(mut self, allow_any: bool) -> Self {
+        self.allow_any = allow_any;
+        self
+    }
+}
+
 /// UI events
 #[derive(Clone, Debug)]
 pub enum Event {
@@ -28,6 +121,10 @@ pub enum Event {
     ToggleLog,
     /// show the log
     ShowLog(Option<Evt>),
+    /// show a short notification for a few seconds
+    Toast(String),
+    /// hide the notification with the given id, if it is still showing
+    HideToast(u64),
     /// hide the log
     HideLog(Option<Evt>),
     /// delay
@@ -36,8 +133,22 @@ pub enum Event {
     Quit,
     /// show the specified screen
     Show(Screens),
+    /// load the home page
+    LoadWelcome,
+    /// the catalog workshops to highlight on the home page
+    WelcomeHighlights(Vec<CatalogEntry>),
+    /// go back to the screen shown before
+    Back,
+    /// go forward to the screen gone back from
+    Forward,
     /// load the workshops
     LoadWorkshops,
+    /// rescan the data directories for installed or removed workshops
+    RefreshWorkshops,
+    /// forget the loaded files of the current workshop and show them again as they are on disk
+    Reload,
+    /// the metadata for the given workshop has been loaded in the background
+    WorkshopLoaded(String),
     /// set the workshop
     SetWorkshop(
         Option<String>,
@@ -45,34 +156,18 @@ pub enum Event {
     ),
     /// load the license for a workshop
     ShowLicense(String),
+    /// find the text in the text the screen shows
+    Find(String),
     /// change the spoken language
-    ChangeSpokenLanguage(
-        HashMap<spoken::Code, Vec<programming::Code>>,
-        Option<spoken::Code>,
-        bool,        // show the "Any" option?
-        Option<Evt>, // the event to send when language is selected
-    ),
-    /// set the default spoken language
-    SetSpokenLanguage(Option<spoken::Code>, Option<bool>, Option<Evt>),
+    ChangeSpokenLanguage(ChangeLanguage<spoken::Code>),
+    /// set the spoken language, then send the event
+    SetSpokenLanguage(Option<spoken::Code>, AsDefault, Option<Evt>),
     /// change the programming language
-    ChangeProgrammingLanguage(
-        HashMap<spoken::Code, Vec<programming::Code>>,
-        Option<programming::Code>,
-        bool,        // show the "Any" option?
-        Option<Evt>, // the event to send language is selected
-    ),
-    /// set the default programming language
-    SetProgrammingLanguage(
-        Option<programming::Code>,
-        Option<bool>,
-        Option<Evt>, // the event to send after setting language
-    ),
-    /// initialize the "set default" dialog
-    SetDefault(
-        String,
-        Option<Evt>, // the event to send when they select "yes"
-        Option<Evt>, // the event to send when they select "no"
-    ),
+    ChangeProgrammingLanguage(ChangeLanguage<programming::Code>),
+    /// set the programming language, then send the event
+    SetProgrammingLanguage(Option<programming::Code>, AsDefault, Option<Evt>),
+    /// show a dialog over the current screen
+    Dialog(Dialog),
     /// load lessons
     LoadLessons,
     /// set the lesson
@@ -80,19 +175,159 @@ pub enum Event {
     /// load the selected lesson
     LoadLesson,
     /// check dependendcies for the specified workshop
-    CheckDeps(String, Option<Evt>, Option<Evt>),
+    CheckDeps(String, Then),
     /// check the solutionto the lesson
-    CheckSolution(Option<Evt>, Option<Evt>),
+    CheckSolution(Then),
+    /// check the tools a single lesson needs before its first solution check
+    CheckLessonDeps(String, String, Then),
+    /// the tools the lesson needs are installed, remember it and send the event
+    LessonDepsChecked(String, programming::Code, String, Option<Evt>),
+    /// press the key, sent by the command palette to do the action bound to it
+    Key(KeyEvent),
+    /// run one of the helper commands of the selected lesson
+    RunLessonCommand(lesson::Command),
+    /// the result of the last solution check, for the lesson screen to show
+    CheckResult(Box<CommandResult>),
     /// the solution is correct
     SolutionComplete,
     /// the solution is incorrect
     SolutionIncomplete,
+    /// every lesson of the given workshop is completed
+    WorkshopCompleted(String),
     /// command started (show log screen)
     CommandStarted(StatusMode, String),
     /// command output
     CommandOutput(String, Option<u8>),
     /// command completed
-    CommandCompleted(CommandResult, Option<Evt>, Option<Evt>),
+    CommandCompleted(Box<CommandResult>, Then),
     /// install a workshop from a URL
     InstallWorkshop(String, Option<Evt>),
+    /// cancel any running commands
+    CancelCommand,
+    /// update an installed workshop from its git remote
+    UpdateWorkshop(String),
+    /// remove an installed workshop and the progress for it, then send the event, or refresh the
+    /// workshops if there is none
+    UninstallWorkshop(String, Option<Evt>),
+    /// run the operation on each of the workshops in turn and report the results in the log
+    BatchWorkshops(Batch, Vec<String>),
+    /// reset an installed git workshop back to its pinned commit, or its HEAD if not pinned
+    ResetWorkshop(String, Option<Evt>),
+    /// compare an installed workshop against the manifest recorded at install time
+    CheckIntegrity(String),
+    /// reset the progress in the given scope
+    ResetProgress(ProgressScope, Option<Evt>),
+    /// save the status and commit the progress to the progress remote, if configured
+    SyncProgress(String),
+    /// the lessons of the workshop in the spoken and programming languages have been read
+    LessonsLoaded(
+        String,
+        Option<spoken::Code>,
+        Option<programming::Code>,
+        Result<Box<Loaded>, String>,
+    ),
+    /// the workshops in the spoken and programming languages have been scanned
+    WorkshopsLoaded(
+        Option<spoken::Code>,
+        Option<programming::Code>,
+        Result<Box<Scan>, String>,
+    ),
+    /// the metadata for the given workshop failed to load, with why
+    WorkshopLoadFailed(String, String),
+    /// fetch and show the workshop catalog
+    LoadCatalog,
+    /// the workshop catalog has been fetched, or failed to with why
+    CatalogLoaded(Result<Vec<CatalogEntry>, String>),
+    /// measure and show the disk usage
+    LoadDiskUsage,
+    /// the disk usage has been measured
+    DiskUsageLoaded(Vec<Usage>),
+    /// remove the docker images or caches and measure the disk usage again
+    PurgeDiskUsage(Kind),
+    /// search the installed workshops for the query and show the matches
+    Search(String),
+    /// the installed workshops have been searched for the query
+    SearchResults(String, Vec<Match>),
+    /// show the setup checklist for the workshop, then send the event once the setup is finished
+    LoadSetup(String, Option<Evt>),
+    /// the setup of the workshop is finished, send the event
+    FinishSetup(String, Option<Evt>),
+    /// run the setup script of the workshop, then send the event to go back
+    RunSetupScript(String, Option<Evt>),
+    /// open the workshop in the languages, going straight to the lesson if there is one
+    OpenLesson(
+        String,
+        spoken::Code,
+        Option<programming::Code>,
+        Option<String>,
+    ),
+}
+
+impl Event {
+    /// Send the event once this event has finished, after the events already chained to it.
+    /// Returns false if this event can't be continued.
+    pub fn and_then(&mut self, next: Evt) -> bool {
+        let slot = match self {
+            Event::ShowLog(slot)
+            | Event::HideLog(slot)
+            | Event::Delay(_, slot)
+            | Event::SetSpokenLanguage(_, _, slot)
+            | Event::SetProgrammingLanguage(_, _, slot)
+            | Event::LessonDepsChecked(_, _, _, slot)
+            | Event::InstallWorkshop(_, slot)
+            | Event::UninstallWorkshop(_, slot)
+            | Event::ResetWorkshop(_, slot)
+            | Event::ResetProgress(_, slot)
+            | Event::LoadSetup(_, slot)
+            | Event::FinishSetup(_, slot)
+            | Event::RunSetupScript(_, slot) => slot,
+            Event::ChangeSpokenLanguage(change) => &mut change.next,
+            Event::ChangeProgrammingLanguage(change) => &mut change.next,
+            Event::CheckDeps(_, then)
+            | Event::CheckSolution(then)
+            | Event::CheckLessonDeps(_, _, then)
+            | Event::CommandCompleted(_, then) => &mut then.success,
+            _ => return false,
+        };
+        match slot {
+            Some((_, event)) => event.and_then(next),
+            None => {
+                *slot = Some(next);
+                true
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_and_then() {
+        let mut set = Event::SetSpokenLanguage(None, AsDefault::No, None);
+        assert!(set.and_then(evt!(Screens::Workshops, Event::LoadWorkshops)));
+        let Event::SetSpokenLanguage(_, _, Some((Some(Screens::Workshops), load))) = set else {
+            panic!("the event follows the spoken language");
+        };
+        assert!(matches!(*load, Event::LoadWorkshops));
+
+        // chaining onto an event that already continues appends to its continuation
+        let mut check = Event::CheckSolution(Then::on_success(evt!(None, Event::ShowLog(None))));
+        assert!(check.and_then(evt!(None, Event::Quit)));
+        let Event::CheckSolution(Then {
+            success: Some((_, show_log)),
+            failure: None,
+        }) = check
+        else {
+            panic!("only the success continues");
+        };
+        let Event::ShowLog(Some((None, quit))) = *show_log else {
+            panic!("the event follows the log");
+        };
+        assert!(matches!(*quit, Event::Quit));
+
+        // events that can't continue end the chain
+        assert!(!Event::Quit.and_then(evt!(None, Event::ToggleLog)));
+    }
 }
@@ -0,0 +1,245 @@
+use crate::{
+    evt,
+    languages::{programming, spoken},
+    ui::tui::{widgets::Dialog, AsDefault, ChangeLanguage, Event, Evt, Screens, Then},
+};
+use std::collections::HashMap;
+use tracing::warn;
+
+/// A step of a flow
+#[derive(Clone, Debug)]
+pub enum Step {
+    /// send the event and go on once it has finished
+    Send(Evt),
+    /// ask a yes or no question and go on with the flow for the answer, cancelling answers no
+    Ask(String, Flow, Flow),
+    /// pick one of the choices and go on with the flow for it
+    Choose(String, Vec<(String, Flow)>),
+}
+
+/// A sequence of steps shown to the student one after the other, declared up front and turned
+/// into the events that carry it out, each event continuing with the rest of the flow. The
+/// branches of questions and choices go on with the steps after them.
+#[derive(Clone, Debug, Default)]
+pub struct Flow {
+    /// the steps in order
+    steps: Vec<Step>,
+    /// the event to send when the student cancels a choice
+    cancel: Option<Evt>,
+}
+
+impl Flow {
+    /// Create an empty flow
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Send the event, if there is one
+    pub fn send(mut self, event: impl Into<Option<Evt>>) -> Self {
+        self.steps.extend(event.into().map(Step::Send));
+        self
+    }
+
+    /// Ask a yes or no question
+    pub fn ask<S: AsRef<str>>(mut self, question: S, yes: Flow, no: Flow) -> Self {
+        self.steps
+            .push(Step::Ask(question.as_ref().to_string(), yes, no));
+        self
+    }
+
+    /// Pick one of the choices
+    pub fn choose<S: AsRef<str>>(mut self, title: S, choices: Vec<(String, Flow)>) -> Self {
+        self.steps
+            .push(Step::Choose(title.as_ref().to_string(), choices));
+        self
+    }
+
+    /// Send the event when the student cancels a choice, the rest of the flow is skipped
+    pub fn on_cancel(mut self, cancel: Evt) -> Self {
+        self.cancel = Some(cancel);
+        self
+    }
+
+    /// Turn the flow into the event that starts it
+    pub fn build(self) -> Option<Evt> {
+        self.build_then(None)
+    }
+
+    // build the steps from the last to the first, each continuing with the ones after it
+    fn build_then(self, rest: Option<Evt>) -> Option<Evt> {
+        let cancel = self.cancel;
+        self.steps.into_iter().rev().fold(rest, |rest, step| {
+            let event = match step {
+                Step::Send((screen, mut event)) => {
+                    if let Some(rest) = rest {
+                        if !event.and_then(rest) {
+                            warn!("Dropping the rest of the flow after: {event:?}");
+                        }
+                    }
+                    return Some((screen, event));
+                }
+                Step::Ask(question, yes, no) => {
+                    Dialog::yes_no(question, yes.build_then(rest.clone()), no.build_then(rest))
+                }
+                Step::Choose(title, choices) => {
+                    let choices = choices
+                        .into_iter()
+                        .map(|(name, flow)| (name, flow.build_then(rest.clone())))
+                        .collect();
+                    Dialog::choice(title, choices, cancel.clone())
+                }
+            };
+            Some(evt!(None, Event::Dialog(event)))
+        })
+    }
+}
+
+/// Pick the spoken and then the programming language, any of them allowed, then send the event
+pub fn choose_languages(
+    all_languages: HashMap<spoken::Code, Vec<programming::Code>>,
+    then: Evt,
+) -> Flow {
+    Flow::new()
+        .send(evt!(
+            Screens::Spoken,
+            Event::ChangeSpokenLanguage(ChangeLanguage::new(all_languages.clone()))
+        ))
+        .send(evt!(
+            Screens::Programming,
+            Event::ChangeProgrammingLanguage(ChangeLanguage::new(all_languages))
+        ))
+        .send(then)
+}
+
+/// Use the languages without making them the default, then send the event
+pub fn use_languages(
+    spoken: Option<spoken::Code>,
+    programming: Option<programming::Code>,
+    then: Evt,
+) -> Flow {
+    Flow::new()
+        .send(evt!(
+            None,
+            Event::SetSpokenLanguage(spoken, AsDefault::No, None)
+        ))
+        .send(evt!(
+            None,
+            Event::SetProgrammingLanguage(programming, AsDefault::No, None)
+        ))
+        .send(then)
+}
+
+/// Ask whether the chosen language becomes the default, the event for the answer sets it, then
+/// send the event if there is one
+pub fn ask_default(set: impl Fn(AsDefault) -> Evt, then: Option<Evt>) -> Flow {
+    Flow::new()
+        .ask(
+            "Set as Default?",
+            Flow::new().send(set(AsDefault::Yes)),
+            Flow::new().send(set(AsDefault::No)),
+        )
+        .send(then)
+}
+
+/// Check the tools the workshop needs, then hide the log and send the event to show it
+pub fn open_workshop(workshop: String, load: Evt) -> Flow {
+    Flow::new()
+        .send(evt!(None, Event::CheckDeps(workshop, Then::default())))
+        .send(evt!(None, Event::HideLog(None)))
+        .send(load)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui::tui::widgets::Response;
+    use crossterm::event::KeyCode;
+
+    // the dialog the event shows
+    fn dialog(event: Option<Evt>) -> Dialog {
+        match event {
+            Some((None, event)) => match *event {
+                Event::Dialog(dialog) => dialog,
+                event => panic!("expected a dialog, got {event:?}"),
+            },
+            event => panic!("expected a dialog, got {event:?}"),
+        }
+    }
+
+    // the event a key closes the dialog with
+    fn answer(mut dialog: Dialog, keys: &[KeyCode]) -> Option<Evt> {
+        for key in keys {
+            if let Response::Closed(event) = dialog.handle_key(*key) {
+                return event;
+            }
+        }
+        panic!("the dialog is still open");
+    }
+
+    #[test]
+    fn test_open_workshop() {
+        let load = evt!(Screens::Lessons, Event::LoadLessons);
+        let Some((None, check)) = open_workshop("ws".to_string(), load).build() else {
+            panic!("the flow starts with the check");
+        };
+        let Event::CheckDeps(workshop, Then { success, failure }) = *check else {
+            panic!("the flow starts with the check");
+        };
+        assert_eq!(workshop, "ws");
+        assert!(failure.is_none());
+        let Some((None, hide)) = success else {
+            panic!("the log is hidden after the check");
+        };
+        let Event::HideLog(Some((Some(Screens::Lessons), load))) = *hide else {
+            panic!("the lessons are loaded after hiding the log");
+        };
+        assert!(matches!(*load, Event::LoadLessons));
+    }
+
+    #[test]
+    fn test_branches() {
+        // both answers go on with the steps after the question
+        let flow = ask_default(
+            |default| evt!(None, Event::SetSpokenLanguage(None, default, None)),
+            Some(evt!(None, Event::Quit)),
+        );
+        for (keys, expected) in [
+            (&[KeyCode::Enter][..], AsDefault::Yes),
+            (&[KeyCode::Down, KeyCode::Enter][..], AsDefault::No),
+            (&[KeyCode::Esc][..], AsDefault::No),
+        ] {
+            let answered = answer(dialog(flow.clone().build()), keys);
+            let Some((None, set)) = answered else {
+                panic!("answering sets the language");
+            };
+            let Event::SetSpokenLanguage(None, default, Some((None, then))) = *set else {
+                panic!("the rest of the flow follows the answer");
+            };
+            assert_eq!(default, expected);
+            assert!(matches!(*then, Event::Quit));
+        }
+
+        // cancelling a choice skips the rest of the flow
+        let flow = Flow::new()
+            .choose(
+                "Pick",
+                vec![(
+                    "Show the log".to_string(),
+                    Flow::new().send(evt!(None, Event::ShowLog(None))),
+                )],
+            )
+            .send(evt!(None, Event::Quit))
+            .on_cancel(evt!(None, Event::Back));
+        let picked = answer(dialog(flow.clone().build()), &[KeyCode::Enter]);
+        let Some((None, show_log)) = picked else {
+            panic!("picking goes on with the choice");
+        };
+        let Event::ShowLog(Some((None, quit))) = *show_log else {
+            panic!("the rest of the flow follows the choice");
+        };
+        assert!(matches!(*quit, Event::Quit));
+        let cancelled = answer(dialog(flow.build()), &[KeyCode::Esc]);
+        assert!(matches!(cancelled, Some((None, ref event)) if matches!(**event, Event::Back)));
+        assert!(Flow::new().build().is_none());
+    }
+}
@@ -0,0 +1,133 @@
+use ratatui::{buffer::Buffer, layout::Rect};
+use std::time::Duration;
+use unicode_width::UnicodeWidthStr;
+
+/// The frames of the spinner shown while something is running or loading
+const SPINNER: [char; 10] = ['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
+
+/// Get the spinner frame to show after the time has passed, 100ms per frame, or a still frame
+/// when motion is reduced
+pub fn spinner(elapsed: Duration, reduced_motion: bool) -> char {
+    if reduced_motion {
+        '⠿'
+    } else {
+        SPINNER[(elapsed.as_millis() / 100) as usize % SPINNER.len()]
+    }
+}
+
+/// Detect whether the terminal is unlikely to show box-drawing characters and emoji
+pub fn detect_ascii() -> bool {
+    // the legacy Windows console doesn't have the fonts for them, Windows Terminal does
+    if cfg!(windows) {
+        return std::env::var_os("WT_SESSION").is_none();
+    }
+
+    let term = std::env::var("TERM").ok();
+    let locale = ["LC_ALL", "LC_CTYPE", "LANG"]
+        .iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .find(|locale| !locale.is_empty());
+    is_ascii_terminal(term.as_deref(), locale.as_deref())
+}
+
+// the Linux virtual console has no emoji and a locale without UTF-8 has nothing but ASCII
+fn is_ascii_terminal(term: Option<&str>, locale: Option<&str>) -> bool {
+    if matches!(term, Some("linux") | Some("dumb")) {
+        return true;
+    }
+    locale.is_some_and(|locale| {
+        let locale = locale.to_lowercase();
+        !(locale.contains("utf-8") || locale.contains("utf8"))
+    })
+}
+
+/// Get the ASCII replacement for a glyph. Emoji are two columns wide so they are replaced with
+/// two characters.
+pub fn ascii_glyph(glyph: &str) -> Option<&'static str> {
+    let replacement = match glyph.chars().next()? {
+        '┌' | '┐' | '└' | '┘' | '├' | '┤' | '┬' | '┴' | '┼' => "+",
+        '─' => "-",
+        '│' => "|",
+        '█' => "#",
+        // the braille dots of the spinner
+        '\u{2800}'..='\u{28ff}' => "*",
+        '•' => "*",
+        '▶' | '⇥' | '→' | '↪' => ">",
+        '◀' | '←' | '↩' | '↵' => "<",
+        '▼' | '↓' | '⤓' => "v",
+        '↑' | '⤒' => "^",
+        '✅' => "v ",
+        '❌' => "x ",
+        '🤔' => "? ",
+        '⭐' => "* ",
+        '🚀' => "> ",
+        '🎉' => "! ",
+        '🏆' => "# ",
+        '📌' => "+ ",
+        '🕘' => "~ ",
+        '😢' => ": ",
+        '❗' | '⚠' => "! ",
+        'ℹ' => "i ",
+        _ => return None,
+    };
+    Some(replacement)
+}
+
+/// Replace the box-drawing characters, arrows, and emoji in the rendered area with ASCII
+pub fn to_ascii(area: Rect, buf: &mut Buffer) {
+    for y in area.top()..area.bottom() {
+        let mut x = area.left();
+        while x < area.right() {
+            let Some(cell) = buf.cell_mut((x, y)) else {
+                break;
+            };
+            let width = cell.symbol().width();
+            if let Some(replacement) = ascii_glyph(cell.symbol()) {
+                let mut chars = replacement.chars();
+                if let Some(first) = chars.next() {
+                    cell.set_char(first);
+                }
+                // fill the column hidden behind a wide glyph
+                if width > 1 {
+                    if let Some(next) = buf.cell_mut((x + 1, y)) {
+                        next.set_char(chars.next().unwrap_or(' '));
+                    }
+                }
+            }
+            x += width.max(1) as u16;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::widgets::{Block, Borders, Widget};
+
+    #[test]
+    fn test_is_ascii_terminal() {
+        assert!(is_ascii_terminal(Some("linux"), Some("en_US.UTF-8")));
+        assert!(is_ascii_terminal(Some("xterm"), Some("C")));
+        assert!(!is_ascii_terminal(
+            Some("xterm-256color"),
+            Some("en_US.utf8")
+        ));
+        assert!(!is_ascii_terminal(Some("xterm-256color"), None));
+    }
+
+    #[test]
+    fn test_to_ascii() {
+        let area = Rect::new(0, 0, 8, 3);
+        let mut buf = Buffer::empty(area);
+        Block::default()
+            .borders(Borders::ALL)
+            .render(area, &mut buf);
+        buf.set_string(1, 1, "✅ ok", ratatui::style::Style::default());
+        to_ascii(area, &mut buf);
+
+        assert_eq!(
+            buf,
+            Buffer::with_lines(["+------+", "|v  ok |", "+------+"])
+        );
+    }
+}
@@ -0,0 +1,254 @@
+use ratatui::{buffer::Buffer, layout::Rect, style::Color};
+use serde::{Deserialize, Serialize};
+
+/// The color theme the screens are rendered with
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Theme {
+    /// Detect the terminal background and pick the dark or light theme
+    #[default]
+    Auto,
+    /// Light text on a dark background, the colors the screens are written with
+    Dark,
+    /// Dark text on a light background
+    Light,
+    /// Bright text and borders on a dark background
+    HighContrast,
+}
+
+/// Adjusts the status colors for color vision deficiencies
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ColorVision {
+    #[default]
+    Normal,
+    /// Red-green color blindness with reduced sensitivity to green
+    Deuteranopia,
+    /// Red-green color blindness with reduced sensitivity to red
+    Protanopia,
+}
+
+/// Maps the colors the screens are written with to the selected theme and color vision
+#[derive(Clone, Copy, Debug)]
+pub struct Palette {
+    theme: Theme,
+    color_vision: ColorVision,
+}
+
+impl Default for Palette {
+    fn default() -> Self {
+        Self {
+            theme: Theme::Dark,
+            color_vision: ColorVision::Normal,
+        }
+    }
+}
+
+impl Palette {
+    /// Create a new palette, detecting the terminal background if the theme is `Auto`. This must
+    /// be called after raw mode is enabled and before the input events are read.
+    pub fn new(theme: Theme, color_vision: ColorVision) -> Self {
+        let theme = match theme {
+            Theme::Auto => detect_theme(),
+            theme => theme,
+        };
+        Self {
+            theme,
+            color_vision,
+        }
+    }
+
+    /// Does this palette change any colors?
+    pub fn is_identity(&self) -> bool {
+        self.theme == Theme::Dark && self.color_vision == ColorVision::Normal
+    }
+
+    /// Map a foreground color
+    pub fn fg(&self, color: Color) -> Color {
+        let color = match (self.theme, color) {
+            (Theme::Light, Color::White) => Color::Black,
+            (Theme::Light, Color::Black) => Color::White,
+            (Theme::Light, Color::Gray) => Color::DarkGray,
+            (Theme::Light, Color::LightYellow) => Color::Yellow,
+            (Theme::Light, Color::LightBlue) => Color::Blue,
+            (Theme::HighContrast, Color::DarkGray | Color::Gray) => Color::White,
+            (_, color) => color,
+        };
+        self.status(color)
+    }
+
+    /// Map a background color
+    pub fn bg(&self, color: Color) -> Color {
+        let color = match (self.theme, color) {
+            (Theme::Light, Color::Black) => Color::White,
+            (Theme::Light, Color::White) => Color::Black,
+            (Theme::HighContrast, Color::DarkGray) => Color::Blue,
+            (_, color) => color,
+        };
+        self.status(color)
+    }
+
+    // swap the red and green pass/fail colors for blue and yellow which stay distinguishable
+    fn status(&self, color: Color) -> Color {
+        match (self.color_vision, color) {
+            (ColorVision::Normal, color) => color,
+            (_, Color::Green) => Color::Blue,
+            (_, Color::LightGreen) => Color::LightBlue,
+            (ColorVision::Deuteranopia, Color::Red) => Color::Yellow,
+            (ColorVision::Protanopia, Color::Red) => Color::LightYellow,
+            (_, Color::LightRed) => Color::LightYellow,
+            (_, color) => color,
+        }
+    }
+
+    /// Map the colors of the rendered area
+    pub fn apply(&self, area: Rect, buf: &mut Buffer) {
+        if self.is_identity() {
+            return;
+        }
+        for y in area.top()..area.bottom() {
+            for x in area.left()..area.right() {
+                if let Some(cell) = buf.cell_mut((x, y)) {
+                    cell.fg = self.fg(cell.fg);
+                    cell.bg = self.bg(cell.bg);
+                }
+            }
+        }
+    }
+}
+
+/// Detect whether the terminal has a light or dark background, first from the `COLORFGBG`
+/// variable set by some terminals and then by asking the terminal for its background color
+pub fn detect_theme() -> Theme {
+    if let Some(light) = std::env::var("COLORFGBG")
+        .ok()
+        .and_then(|colors| is_light_colorfgbg(&colors))
+    {
+        return if light { Theme::Light } else { Theme::Dark };
+    }
+    match query_background() {
+        Some(response) if is_light_background(&response) == Some(true) => Theme::Light,
+        _ => Theme::Dark,
+    }
+}
+
+// COLORFGBG is "<fg>;<bg>" or "<fg>;default;<bg>" with ANSI color numbers
+fn is_light_colorfgbg(colors: &str) -> Option<bool> {
+    let bg = colors.rsplit(';').next()?.parse::<u8>().ok()?;
+    Some(matches!(bg, 7 | 9..=15))
+}
+
+// parse an OSC 11 response like "\x1b]11;rgb:ffff/ffff/ffff\x1b\\" and check its luminance
+fn is_light_background(response: &str) -> Option<bool> {
+    let rgb = response.split("rgb:").nth(1)?;
+    let rgb = rgb
+        .trim_end_matches(['\x07', '\\'])
+        .trim_end_matches('\x1b');
+    let mut channels = rgb.split('/').map(|channel| {
+        let max = 16f64.powi(channel.len() as i32) - 1.0;
+        u32::from_str_radix(channel, 16)
+            .ok()
+            .map(|value| value as f64 / max)
+    });
+    let (r, g, b) = (channels.next()??, channels.next()??, channels.next()??);
+    Some(0.2126 * r + 0.7152 * g + 0.0722 * b > 0.5)
+}
+
+// ask the terminal for its background color. The query is followed by a request for the device
+// attributes, which every terminal answers and answers in order, so that a terminal that ignores
+// the query is known without waiting for a timeout. Whatever is left of a late answer is flushed
+// so that it isn't read as keys.
+#[cfg(unix)]
+fn query_background() -> Option<String> {
+    use rustix::{
+        event::{poll, PollFd, PollFlags, Timespec},
+        termios::{tcflush, QueueSelector},
+    };
+    use std::{
+        io::{IsTerminal, Write},
+        time::{Duration, Instant},
+    };
+
+    let (stdin, mut stdout) = (std::io::stdin(), std::io::stdout());
+    if !stdin.is_terminal() || !stdout.is_terminal() {
+        return None;
+    }
+    stdout.write_all(b"\x1b]11;?\x1b\\\x1b[c").ok()?;
+    stdout.flush().ok()?;
+
+    // read the reply byte by byte straight from the file descriptor so that nothing is left
+    // in a buffer the input event reader can't see
+    let deadline = Instant::now() + Duration::from_millis(500);
+    let mut response = Vec::new();
+    let mut background = None;
+    loop {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        let timeout = Timespec::try_from(remaining).ok()?;
+        let mut fds = [PollFd::new(&stdin, PollFlags::IN)];
+        if remaining.is_zero() || !matches!(poll(&mut fds, Some(&timeout)), Ok(1..)) {
+            let _ = tcflush(&stdin, QueueSelector::IFlush);
+            return None;
+        }
+        let mut byte = [0u8];
+        if !matches!(rustix::io::read(&stdin, &mut byte), Ok(1)) {
+            return None;
+        }
+        // anything typed before the replies is dropped
+        if byte[0] == 0x1b && !response.starts_with(b"\x1b]") {
+            response.clear();
+        }
+        response.push(byte[0]);
+        if response.starts_with(b"\x1b]") && (byte[0] == 0x07 || response.ends_with(b"\x1b\\")) {
+            background = String::from_utf8(std::mem::take(&mut response)).ok();
+        } else if response.starts_with(b"\x1b[?") && byte[0] == b'c' {
+            // the device attributes come last
+            return background;
+        }
+    }
+}
+
+#[cfg(not(unix))]
+fn query_background() -> Option<String> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_background_detection() {
+        assert_eq!(is_light_colorfgbg("0;15"), Some(true));
+        assert_eq!(is_light_colorfgbg("15;default;0"), Some(false));
+        assert_eq!(is_light_colorfgbg("garbage"), None);
+
+        assert_eq!(
+            is_light_background("\x1b]11;rgb:ffff/ffff/f0f0\x1b\\"),
+            Some(true)
+        );
+        assert_eq!(is_light_background("\x1b]11;rgb:1e/1e/2e\x07"), Some(false));
+        assert_eq!(is_light_background("\x1b]11;?\x07"), None);
+    }
+
+    #[test]
+    fn test_palette_colors() {
+        let light = Palette {
+            theme: Theme::Light,
+            color_vision: ColorVision::Normal,
+        };
+        assert_eq!(light.fg(Color::White), Color::Black);
+        assert_eq!(light.bg(Color::Black), Color::White);
+        // highlighted lines stay inverted
+        assert_eq!(light.fg(Color::Black), Color::White);
+        assert_eq!(light.bg(Color::White), Color::Black);
+        assert_eq!(light.fg(Color::Green), Color::Green);
+
+        let deuteranopia = Palette {
+            theme: Theme::Dark,
+            color_vision: ColorVision::Deuteranopia,
+        };
+        assert_eq!(deuteranopia.fg(Color::Green), Color::Blue);
+        assert_eq!(deuteranopia.fg(Color::Red), Color::Yellow);
+        assert_eq!(deuteranopia.fg(Color::White), Color::White);
+    }
+}
@@ -1,15 +1,23 @@
+pub mod catalog;
+pub use catalog::Catalog;
+pub mod completion;
+pub use completion::Completion;
+pub mod disk_usage;
+pub use disk_usage::DiskUsage;
 pub mod lesson;
 pub use lesson::Lesson;
 pub mod lessons;
 pub use lessons::Lessons;
+pub mod search;
+pub use search::Search;
+pub mod setup;
+pub use setup::Setup;
 pub mod license;
 pub use license::License;
 pub mod log;
 pub use log::Log;
 pub mod programming;
 pub use programming::Programming;
-pub mod set_default;
-pub use set_default::SetDefault;
 pub mod spoken;
 pub use spoken::Spoken;
 pub mod welcome;
@@ -17,13 +25,10 @@ pub use welcome::Welcome;
 pub mod workshops;
 pub use workshops::Workshops;
 
-use crate::{ui::tui, Error, Status};
-use crossterm::event;
+use crate::{ui::tui, Error, StatusHandle};
+use crossterm::event::{self, KeyCode, KeyEvent, KeyModifiers};
 use ratatui::{buffer::Buffer, layout::Rect};
-use std::{
-    fmt,
-    sync::{Arc, Mutex},
-};
+use std::fmt;
 use tokio::sync::mpsc::Sender;
 
 /// The screens
@@ -37,14 +42,18 @@ pub enum Screens {
     License,
     Spoken,
     Programming,
-    SetDefault,
     Lessons,
     Lesson,
+    Catalog,
+    Completion,
+    DiskUsage,
+    Search,
+    Setup,
 }
 
 impl Screens {
     pub fn iter() -> impl Iterator<Item = Screens> {
-        (0..=8).map(Screens::from)
+        (0..=12).map(Screens::from)
     }
 }
 
@@ -57,9 +66,13 @@ impl fmt::Display for Screens {
             Screens::License => write!(f, "License"),
             Screens::Spoken => write!(f, "Spoken"),
             Screens::Programming => write!(f, "Programming"),
-            Screens::SetDefault => write!(f, "Set Default"),
             Screens::Lessons => write!(f, "Lessons"),
             Screens::Lesson => write!(f, "Lesson"),
+            Screens::Catalog => write!(f, "Catalog"),
+            Screens::Completion => write!(f, "Completion"),
+            Screens::DiskUsage => write!(f, "Disk Usage"),
+            Screens::Search => write!(f, "Search"),
+            Screens::Setup => write!(f, "Setup"),
         }
     }
 }
@@ -79,9 +92,13 @@ impl From<u8> for Screens {
             3 => Screens::License,
             4 => Screens::Spoken,
             5 => Screens::Programming,
-            6 => Screens::SetDefault,
-            7 => Screens::Lessons,
-            8 => Screens::Lesson,
+            6 => Screens::Lessons,
+            7 => Screens::Lesson,
+            8 => Screens::Catalog,
+            9 => Screens::Completion,
+            10 => Screens::DiskUsage,
+            11 => Screens::Search,
+            12 => Screens::Setup,
             _ => panic!("Invalid screen value"),
         }
     }
@@ -112,6 +129,41 @@ impl From<tui::Evt> for Event {
     }
 }
 
+/// An action listed in the command palette, done by pressing its key on the screen
+#[derive(Clone, Debug)]
+pub struct Action {
+    /// what the action does
+    pub name: String,
+    /// the key the action is bound to
+    pub key: KeyEvent,
+}
+
+impl Action {
+    /// An action bound to the key
+    pub fn new<S: AsRef<str>>(name: S, code: KeyCode) -> Self {
+        Self {
+            name: name.as_ref().to_string(),
+            key: KeyEvent::new(code, KeyModifiers::NONE),
+        }
+    }
+
+    /// An action bound to the key pressed with alt
+    pub fn alt<S: AsRef<str>>(name: S, code: KeyCode) -> Self {
+        Self {
+            name: name.as_ref().to_string(),
+            key: KeyEvent::new(code, KeyModifiers::ALT),
+        }
+    }
+
+    /// An action bound to the key pressed with control
+    pub fn ctrl<S: AsRef<str>>(name: S, code: KeyCode) -> Self {
+        Self {
+            name: name.as_ref().to_string(),
+            key: KeyEvent::new(code, KeyModifiers::CONTROL),
+        }
+    }
+}
+
 /// The State trait
 #[async_trait::async_trait]
 pub trait Screen: Send + Sync {
@@ -120,9 +172,52 @@ pub trait Screen: Send + Sync {
         &mut self,
         event: Event,
         to_ui: Sender<Event>,
-        status: Arc<Mutex<Status>>,
+        status: StatusHandle,
     ) -> Result<(), Error>;
 
     /// Render the screen
     fn render_screen(&mut self, area: Rect, buf: &mut Buffer) -> Result<(), Error>;
+
+    /// Called when the screen is shown in place of another one, the log showing over the screens
+    /// doesn't count
+    async fn on_enter(
+        &mut self,
+        _to_ui: Sender<Event>,
+        _status: StatusHandle,
+    ) -> Result<(), Error> {
+        Ok(())
+    }
+
+    /// Called when another screen is shown in place of this one
+    async fn on_leave(
+        &mut self,
+        _to_ui: Sender<Event>,
+        _status: StatusHandle,
+    ) -> Result<(), Error> {
+        Ok(())
+    }
+
+    /// Called on every screen, shown or not, when the terminal is resized to the columns and rows
+    fn on_resize(&mut self, _width: u16, _height: u16) {}
+
+    /// Does the screen change as time passes without any events
+    fn is_animating(&self) -> bool {
+        false
+    }
+
+    /// Move the selection as if the navigation key was pressed the given number of times, updating
+    /// the screen once. Returns false if the screen doesn't, and then it gets every key press.
+    fn navigate(&mut self, _key: KeyEvent, _count: usize) -> bool {
+        false
+    }
+
+    /// Does the screen take text input, so that every key goes to it
+    fn takes_text_input(&self) -> bool {
+        false
+    }
+
+    /// The actions the screen offers right now, for the command palette
+    fn actions(&self) -> Vec<Action> {
+        Vec::new()
+    }
 }
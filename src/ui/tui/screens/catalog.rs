@@ -0,0 +1,357 @@
+use crate::{
+    evt, fs,
+    models::{catalog::CatalogEntry, Catalog as CatalogIndex},
+    ui::tui::{self, screens, widgets::ScrollBox, Screen, Screens},
+    Error, StatusHandle,
+};
+use crossterm::event::{self, KeyCode, KeyEvent};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Alignment, Constraint, Flex, Layout, Rect},
+    style::{Color, Modifier, Style},
+    symbols::border::Set,
+    text::{Line, Span},
+    widgets::{block::Position, Block, Borders, List, ListState, Padding, StatefulWidget, Widget},
+};
+use std::collections::HashSet;
+use tokio::sync::mpsc::Sender;
+use tracing::{debug, info, warn};
+
+const TOP_LEFT_BORDER: Set = Set {
+    top_left: "┌",
+    top_right: "┐",
+    bottom_left: "│",
+    bottom_right: "│",
+    vertical_left: "│",
+    vertical_right: "│",
+    horizontal_top: "─",
+    horizontal_bottom: " ",
+};
+
+const TOP_BOX_BORDER: Set = Set {
+    top_left: "─",
+    top_right: "┐",
+    bottom_left: " ",
+    bottom_right: "│",
+    vertical_left: " ",
+    vertical_right: "│",
+    horizontal_top: "─",
+    horizontal_bottom: " ",
+};
+
+const STATUS_BORDER: Set = Set {
+    top_left: " ",
+    top_right: " ",
+    bottom_left: "└",
+    bottom_right: "┘",
+    vertical_left: "│",
+    vertical_right: "│",
+    horizontal_top: " ",
+    horizontal_bottom: "─",
+};
+
+#[derive(Clone, Debug, Default)]
+pub struct Catalog<'a> {
+    /// the published workshops
+    entries: Vec<CatalogEntry>,
+    /// the names of the installed workshops
+    installed: HashSet<String>,
+    /// the cached list
+    titles: List<'a>,
+    /// the list state of the workshop titles
+    titles_state: ListState,
+    /// the details of the selected workshop
+    details: ScrollBox<'a>,
+    /// set while the catalog is being fetched
+    loading: bool,
+    /// why fetching the catalog failed
+    error: Option<String>,
+}
+
+impl Catalog<'_> {
+    /// set the catalog entries
+    fn init(&mut self, entries: Vec<CatalogEntry>) -> Result<(), Error> {
+        self.installed = fs::application::all_workshops()
+            .map(|w| w.into_keys().collect())
+            .unwrap_or_default();
+        self.entries = entries;
+        self.entries.sort_by(|a, b| a.title.cmp(&b.title));
+        self.loading = false;
+        self.error = None;
+
+        let titles = self
+            .entries
+            .iter()
+            .map(|entry| {
+                let indicator = if self.installed.contains(&entry.name) {
+                    "✅ "
+                } else {
+                    "   "
+                };
+                format!("{indicator} {}", entry.title)
+            })
+            .collect::<Vec<_>>();
+
+        self.titles = List::new(titles)
+            .highlight_style(
+                Style::default()
+                    .fg(Color::Black)
+                    .bg(Color::White)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .style(Style::default().fg(Color::White))
+            .highlight_symbol("> ");
+
+        if self.entries.is_empty() {
+            self.titles_state.select(None);
+        } else {
+            self.titles_state.select_first();
+        }
+        self.cache_selected();
+        Ok(())
+    }
+
+    // update the details box for the selected workshop
+    fn cache_selected(&mut self) {
+        let details = match self.selected() {
+            Some(entry) => format!(
+                "Installed: {}\nDifficulty: {}\nSource: {}\nLanguages:\n{}\n\n{}",
+                if self.installed.contains(&entry.name) {
+                    "Yes"
+                } else {
+                    "No"
+                },
+                entry.difficulty,
+                entry.url,
+                entry
+                    .languages
+                    .iter()
+                    .map(|(spoken, programming)| format!(" - {spoken}: {}", programming.join(", ")))
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+                entry.description,
+            ),
+            None if self.loading => "Loading the workshop catalog...".to_string(),
+            None if self.error.is_some() => format!(
+                "Failed to load the workshop catalog: {}",
+                self.error.as_deref().unwrap_or_default()
+            ),
+            None => "No workshops in the catalog".to_string(),
+        };
+        self.details.set_text(details);
+        self.details.scroll_top();
+    }
+
+    // get the selected catalog entry
+    fn selected(&self) -> Option<&CatalogEntry> {
+        self.titles_state
+            .selected()
+            .and_then(|index| self.entries.get(index))
+    }
+
+    /// render the catalog list and details
+    fn render_catalog(&mut self, area: Rect, buf: &mut Buffer) {
+        let [titles_area, details_area] =
+            Layout::horizontal([Constraint::Percentage(30), Constraint::Percentage(70)])
+                .areas(area);
+
+        let title = Line::from(vec![
+            Span::styled("─", Style::default().fg(Color::DarkGray)),
+            Span::styled("/ Workshop Catalog /", Style::default().fg(Color::White)),
+        ]);
+        let titles = self.titles.clone().block(
+            Block::default()
+                .title(title)
+                .padding(Padding::uniform(1))
+                .style(Style::default().fg(Color::White))
+                .border_style(Style::default().fg(Color::DarkGray))
+                .borders(Borders::LEFT | Borders::TOP | Borders::RIGHT)
+                .border_set(TOP_LEFT_BORDER),
+        );
+        StatefulWidget::render(titles, titles_area, buf, &mut self.titles_state);
+
+        let title = Line::from(vec![
+            Span::styled("─", Style::default().fg(Color::DarkGray)),
+            Span::styled("/ Details /", Style::default().fg(Color::DarkGray)),
+        ]);
+        self.details.block(
+            Block::default()
+                .title(title)
+                .padding(Padding::uniform(1))
+                .style(Style::default().fg(Color::White))
+                .border_style(Style::default().fg(Color::DarkGray))
+                .borders(Borders::LEFT | Borders::TOP | Borders::RIGHT)
+                .border_set(TOP_BOX_BORDER),
+        );
+        Widget::render(&mut self.details, details_area, buf);
+    }
+
+    // render the keyboard shortcuts
+    fn render_status(&mut self, area: Rect, buf: &mut Buffer) {
+        let title = Line::from(vec![
+            Span::styled("─", Style::default().fg(Color::DarkGray)),
+            Span::styled(
+                "/ j,k scroll / ↵ install / b back / q quit /",
+                Style::default().fg(Color::White),
+            ),
+        ]);
+        let block = Block::default()
+            .title(title)
+            .title_style(Style::default().fg(Color::White))
+            .title_position(Position::Bottom)
+            .title_alignment(Alignment::Left)
+            .style(Style::default().fg(Color::DarkGray))
+            .borders(Borders::LEFT | Borders::BOTTOM | Borders::RIGHT)
+            .border_set(STATUS_BORDER)
+            .padding(Padding::horizontal(1));
+
+        Widget::render(block, area, buf);
+    }
+
+    /// handle UI events
+    pub async fn handle_ui_event(
+        &mut self,
+        event: tui::Event,
+        to_ui: Sender<screens::Event>,
+        status: StatusHandle,
+    ) -> Result<(), Error> {
+        match event {
+            tui::Event::LoadCatalog => {
+                let url = {
+                    let status = status.read().await;
+                    status.catalog_url().to_string()
+                };
+                info!("Loading workshop catalog from: {url}");
+                self.init(Vec::default())?;
+                self.loading = true;
+                self.cache_selected();
+                to_ui
+                    .send((None, tui::Event::Show(Screens::Catalog)).into())
+                    .await?;
+
+                // fetch the catalog in the background so the UI stays responsive
+                tokio::spawn(async move {
+                    let loaded = CatalogIndex::fetch(&url)
+                        .await
+                        .map(|catalog| catalog.workshops)
+                        .map_err(|e| e.to_string());
+                    let event = evt!(Screens::Catalog, tui::Event::CatalogLoaded(loaded));
+                    let _ = to_ui.send(event.into()).await;
+                });
+            }
+            tui::Event::CatalogLoaded(Ok(entries)) => {
+                debug!("Catalog loaded with {} workshops", entries.len());
+                self.init(entries)?;
+            }
+            tui::Event::CatalogLoaded(Err(e)) => {
+                warn!("Failed to load the catalog: {e}");
+                self.loading = false;
+                self.error = Some(e);
+                self.cache_selected();
+            }
+            _ => {
+                debug!("Ignoring UI event: {:?}", event);
+            }
+        }
+        Ok(())
+    }
+
+    /// handle input events
+    pub async fn handle_input_event(
+        &mut self,
+        event: event::Event,
+        to_ui: Sender<screens::Event>,
+        _status: StatusHandle,
+    ) -> Result<(), Error> {
+        if let event::Event::Key(key) = event {
+            match key.code {
+                KeyCode::PageUp => {
+                    self.titles_state.select_first();
+                    self.cache_selected();
+                }
+                KeyCode::PageDown => {
+                    self.titles_state.select_last();
+                    self.cache_selected();
+                }
+                KeyCode::Char('j') | KeyCode::Char('J') | KeyCode::Down => {
+                    self.titles_state.select_next();
+                    self.cache_selected();
+                }
+                KeyCode::Char('k') | KeyCode::Char('K') | KeyCode::Up => {
+                    self.titles_state.select_previous();
+                    self.cache_selected();
+                }
+                KeyCode::Enter => {
+                    if let Some(entry) = self.selected() {
+                        // install the workshop and go back to the refreshed workshops list
+                        let load_workshops = evt!(Screens::Workshops, tui::Event::LoadWorkshops);
+                        let hide_log = evt!(None, tui::Event::HideLog(Some(load_workshops)));
+                        let install = evt!(
+                            None,
+                            tui::Event::InstallWorkshop(entry.url.clone(), Some(hide_log)),
+                        );
+                        to_ui.send(install.into()).await?;
+                    }
+                }
+                KeyCode::Char('b') | KeyCode::Char('B') | KeyCode::Esc => {
+                    to_ui
+                        .send((None, tui::Event::Show(Screens::Workshops)).into())
+                        .await?;
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl Screen for Catalog<'_> {
+    async fn handle_event(
+        &mut self,
+        event: screens::Event,
+        to_ui: Sender<screens::Event>,
+        status: StatusHandle,
+    ) -> Result<(), Error> {
+        match event {
+            screens::Event::Input(input_event) => {
+                self.handle_input_event(input_event, to_ui, status).await
+            }
+            screens::Event::Ui(_, ui_event) => self.handle_ui_event(ui_event, to_ui, status).await,
+        }
+    }
+
+    fn navigate(&mut self, key: KeyEvent, count: usize) -> bool {
+        match key.code {
+            KeyCode::Char('j') | KeyCode::Char('J') | KeyCode::Down => {
+                (0..count).for_each(|_| self.titles_state.select_next())
+            }
+            KeyCode::Char('k') | KeyCode::Char('K') | KeyCode::Up => {
+                (0..count).for_each(|_| self.titles_state.select_previous())
+            }
+            _ => return false,
+        }
+        self.cache_selected();
+        true
+    }
+
+    fn actions(&self) -> Vec<screens::Action> {
+        vec![
+            screens::Action::new("Install the highlighted workshop", KeyCode::Enter),
+            screens::Action::new("Back to the workshops", KeyCode::Char('b')),
+        ]
+    }
+
+    fn render_screen(&mut self, area: Rect, buf: &mut Buffer) -> Result<(), Error> {
+        // this splits the screen into a top area and a one-line bottom area
+        let [catalog_area, status_area] =
+            Layout::vertical([Constraint::Percentage(100), Constraint::Min(1)])
+                .flex(Flex::End)
+                .areas(area);
+
+        self.render_catalog(catalog_area, buf);
+        self.render_status(status_area, buf);
+
+        Ok(())
+    }
+}
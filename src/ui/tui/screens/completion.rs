@@ -0,0 +1,283 @@
+use crate::{
+    evt,
+    models::{certificate, lesson, Certificate},
+    ui::tui::{
+        self,
+        screens::{self, Screens},
+        Screen,
+    },
+    Error, StatusHandle,
+};
+use crossterm::event::{self, KeyCode};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Alignment, Constraint, Flex, Layout, Rect},
+    style::{Color, Modifier, Style},
+    symbols::border::Set,
+    text::{Line, Span},
+    widgets::{block::Position, Block, Borders, Clear, Padding, Paragraph, Widget, Wrap},
+};
+use std::path::PathBuf;
+use tokio::sync::mpsc::Sender;
+use tracing::{error, info};
+
+const TOP_DIALOG_BORDER: Set = Set {
+    top_left: "┌",
+    top_right: "┐",
+    bottom_left: "│",
+    bottom_right: "│",
+    vertical_left: "│",
+    vertical_right: "│",
+    horizontal_top: "─",
+    horizontal_bottom: " ",
+};
+
+const STATUS_BORDER: Set = Set {
+    top_left: " ",
+    top_right: " ",
+    bottom_left: "└",
+    bottom_right: "┘",
+    vertical_left: " ",
+    vertical_right: " ",
+    horizontal_top: " ",
+    horizontal_bottom: "─",
+};
+
+/// The screen shown when the last lesson of a workshop is completed
+#[derive(Clone, Debug, Default)]
+pub struct Completion {
+    /// the certificate for the completed workshop
+    certificate: Certificate,
+    /// the files the certificate was saved to
+    saved: Vec<PathBuf>,
+    /// the cached rect from last render
+    area: Rect,
+    /// the cached calculated rect
+    centered: Rect,
+}
+
+impl Completion {
+    fn recalculate_rect(&mut self, area: Rect) {
+        if self.area != area {
+            let [_, hc, _] = Layout::horizontal([
+                Constraint::Percentage(15),
+                Constraint::Min(1),
+                Constraint::Percentage(15),
+            ])
+            .areas(area);
+            [_, self.centered, _] = Layout::vertical([
+                Constraint::Percentage(15),
+                Constraint::Min(1),
+                Constraint::Percentage(15),
+            ])
+            .areas(hc);
+            self.area = area;
+        }
+    }
+
+    // build the certificate for the completed workshop and save it
+    async fn complete(&mut self, workshop: &str, status: &StatusHandle) -> Result<(), Error> {
+        let (spoken, programming, student, badge_service, repository) = {
+            let status = status.read().await;
+            (
+                status.spoken_language(),
+                status.programming_language(),
+                status.student_name(),
+                status.badge_service().to_string(),
+                status.repository(),
+            )
+        };
+
+        let mut title = workshop.to_string();
+        let mut lessons = Vec::new();
+        if let Some(workshop_data) = repository.workshop(workshop) {
+            title = workshop_data.get_metadata(spoken).await?.title;
+            let lessons_data = workshop_data.get_lessons_data(spoken, programming).await?;
+            for key in lesson::ordered_keys(&lessons_data).await? {
+                lessons.push(lessons_data[&key].get_metadata().await?.title);
+            }
+        }
+
+        self.certificate = Certificate::new(workshop, &title, &student, lessons, &badge_service);
+        self.saved =
+            match certificate::certificates_dir().and_then(|dir| self.certificate.save(&dir)) {
+                Ok(saved) => saved,
+                Err(e) => {
+                    error!("Failed to save the certificate for {workshop}: {e}");
+                    Vec::new()
+                }
+            };
+        Ok(())
+    }
+
+    // render the congratulations and where the certificate is
+    fn render_certificate(&mut self, area: Rect, buf: &mut Buffer) {
+        let title = Line::from(vec![
+            Span::styled("─", Style::default().fg(Color::DarkGray)),
+            Span::styled("/ Workshop Completed /", Style::default().fg(Color::White)),
+        ]);
+
+        let block = Block::default()
+            .title(title)
+            .padding(Padding::uniform(1))
+            .style(Style::default().fg(Color::DarkGray))
+            .borders(Borders::LEFT | Borders::RIGHT | Borders::TOP)
+            .border_set(TOP_DIALOG_BORDER);
+
+        let bold = Style::default()
+            .fg(Color::White)
+            .add_modifier(Modifier::BOLD);
+        let mut lines = vec![
+            Line::styled(
+                format!("Congratulations, {}!", self.certificate.student),
+                bold,
+            ),
+            Line::default(),
+            Line::from(format!(
+                "You completed {} on {}.",
+                self.certificate.title, self.certificate.date
+            )),
+            Line::default(),
+        ];
+        for lesson in &self.certificate.lessons {
+            lines.push(Line::from(format!("  ✅ {lesson}")));
+        }
+        lines.push(Line::default());
+        if self.saved.is_empty() {
+            lines.push(Line::styled(
+                "The certificate could not be saved, see the log for details.",
+                Style::default().fg(Color::Red),
+            ));
+        } else {
+            lines.push(Line::from("Your certificate is saved to:"));
+            for path in &self.saved {
+                lines.push(Line::styled(format!("  {}", path.display()), bold));
+            }
+        }
+        if let Some(badge_url) = &self.certificate.badge_url {
+            lines.push(Line::default());
+            lines.push(Line::from("Share your badge:"));
+            lines.push(Line::styled(format!("  {badge_url}"), bold));
+        }
+
+        Paragraph::new(lines)
+            .block(block)
+            .style(Style::default().fg(Color::White))
+            .wrap(Wrap { trim: false })
+            .render(area, buf);
+    }
+
+    // render the status bar at the bottom
+    fn render_status(&mut self, area: Rect, buf: &mut Buffer) {
+        let line = Line::from(vec![
+            Span::styled("─", Style::default().fg(Color::DarkGray)),
+            Span::styled(
+                "/ o open certificate / b back / q quit /",
+                Style::default().fg(Color::White),
+            ),
+        ]);
+        let block = Block::default()
+            .title(line)
+            .title_style(Style::default().fg(Color::White))
+            .title_position(Position::Bottom)
+            .title_alignment(Alignment::Left)
+            .style(Style::default().fg(Color::DarkGray))
+            .borders(Borders::LEFT | Borders::BOTTOM | Borders::RIGHT)
+            .border_set(STATUS_BORDER)
+            .padding(Padding::horizontal(1));
+
+        Widget::render(block, area, buf);
+    }
+
+    /// handle UI events
+    pub async fn handle_ui_event(
+        &mut self,
+        event: tui::Event,
+        to_ui: Sender<screens::Event>,
+        status: StatusHandle,
+    ) -> Result<(), Error> {
+        match event {
+            tui::Event::WorkshopCompleted(workshop) => {
+                info!("Workshop completed: {workshop}");
+                self.complete(&workshop, &status).await?;
+                to_ui
+                    .send((None, tui::Event::Show(Screens::Completion)).into())
+                    .await?;
+            }
+            _ => {
+                info!("Ignoring UI event: {:?}", event);
+            }
+        }
+        Ok(())
+    }
+
+    /// handle input events
+    pub async fn handle_input_event(
+        &mut self,
+        event: event::Event,
+        to_ui: Sender<screens::Event>,
+        _status: StatusHandle,
+    ) -> Result<(), Error> {
+        if let event::Event::Key(key) = event {
+            match key.code {
+                KeyCode::Char('o') => {
+                    // the html certificate is the last file saved
+                    if let Some(path) = self.saved.last() {
+                        if let Err(e) = webbrowser::open(&path.display().to_string()) {
+                            error!("Failed to open {}: {e}", path.display());
+                        }
+                    }
+                }
+                KeyCode::Char('b') | KeyCode::Esc | KeyCode::Enter => {
+                    let set_workshop = evt!(
+                        None,
+                        tui::Event::SetWorkshop(None, std::collections::HashMap::default())
+                    );
+                    to_ui.send(set_workshop.into()).await?;
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl Screen for Completion {
+    async fn handle_event(
+        &mut self,
+        event: screens::Event,
+        to_ui: Sender<screens::Event>,
+        status: StatusHandle,
+    ) -> Result<(), Error> {
+        match event {
+            screens::Event::Input(input_event) => {
+                self.handle_input_event(input_event, to_ui, status).await
+            }
+            screens::Event::Ui(_, ui_event) => self.handle_ui_event(ui_event, to_ui, status).await,
+        }
+    }
+
+    fn actions(&self) -> Vec<screens::Action> {
+        vec![
+            screens::Action::new("Open the certificate", KeyCode::Char('o')),
+            screens::Action::new("Back to the workshops", KeyCode::Char('b')),
+        ]
+    }
+
+    fn render_screen(&mut self, area: Rect, buf: &mut Buffer) -> Result<(), Error> {
+        self.recalculate_rect(area);
+
+        // clear area around the popup
+        Widget::render(Clear, self.centered, buf);
+
+        let [certificate_area, status_area] =
+            Layout::vertical([Constraint::Percentage(100), Constraint::Min(1)])
+                .flex(Flex::End)
+                .areas(self.centered);
+
+        self.render_certificate(certificate_area, buf);
+        self.render_status(status_area, buf);
+        Ok(())
+    }
+}
@@ -0,0 +1,433 @@
+use crate::{
+    evt,
+    fs::{
+        self,
+        usage::{self, Kind, Usage},
+    },
+    status::ProgressScope,
+    ui::tui::{
+        self, screens,
+        widgets::{Dialog, ScrollBox},
+        Screen, Screens,
+    },
+    Error, StatusHandle,
+};
+use crossterm::event::{self, KeyCode, KeyEvent};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Alignment, Constraint, Flex, Layout, Rect},
+    style::{Color, Modifier, Style},
+    symbols::border::Set,
+    text::{Line, Span},
+    widgets::{block::Position, Block, Borders, List, ListState, Padding, StatefulWidget, Widget},
+};
+use tokio::sync::mpsc::Sender;
+use tracing::{debug, info};
+
+const TOP_LEFT_BORDER: Set = Set {
+    top_left: "┌",
+    top_right: "┐",
+    bottom_left: "│",
+    bottom_right: "│",
+    vertical_left: "│",
+    vertical_right: "│",
+    horizontal_top: "─",
+    horizontal_bottom: " ",
+};
+
+const TOP_BOX_BORDER: Set = Set {
+    top_left: "─",
+    top_right: "┐",
+    bottom_left: " ",
+    bottom_right: "│",
+    vertical_left: " ",
+    vertical_right: "│",
+    horizontal_top: "─",
+    horizontal_bottom: " ",
+};
+
+const STATUS_BORDER: Set = Set {
+    top_left: " ",
+    top_right: " ",
+    bottom_left: "└",
+    bottom_right: "┘",
+    vertical_left: "│",
+    vertical_right: "│",
+    horizontal_top: " ",
+    horizontal_bottom: "─",
+};
+
+/// The screen summarizing the disk space taken up by the workshops, docker images, and caches
+#[derive(Clone, Debug, Default)]
+pub struct DiskUsage<'a> {
+    /// the measured disk usage, largest first
+    usage: Vec<Usage>,
+    /// the cached list
+    items: List<'a>,
+    /// the list state of the items
+    items_state: ListState,
+    /// the details of the selected item
+    details: ScrollBox<'a>,
+    /// the docker executable used to measure and remove the images
+    docker: Option<String>,
+    /// set while the disk usage is being measured
+    loading: bool,
+}
+
+impl DiskUsage<'_> {
+    /// set the measured disk usage, keeping the selection if possible
+    fn init(&mut self, usage: Vec<Usage>) {
+        let selected = self.selected().map(|usage| usage.kind.to_string());
+        self.usage = usage;
+        self.loading = false;
+
+        let items = self
+            .usage
+            .iter()
+            .map(|usage| format!("{:>9}  {}", usage::format_size(usage.bytes), usage.kind))
+            .collect::<Vec<_>>();
+        self.items = List::new(items)
+            .highlight_style(
+                Style::default()
+                    .fg(Color::Black)
+                    .bg(Color::White)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .style(Style::default().fg(Color::White))
+            .highlight_symbol("> ");
+
+        let index = selected
+            .and_then(|selected| {
+                self.usage
+                    .iter()
+                    .position(|usage| usage.kind.to_string() == selected)
+            })
+            .or((!self.usage.is_empty()).then_some(0));
+        self.items_state.select(index);
+        self.cache_selected();
+    }
+
+    // update the details box for the selected item
+    fn cache_selected(&mut self) {
+        let details = match self.selected() {
+            Some(usage) => {
+                let (what, purge) = match &usage.kind {
+                    Kind::Installed(workshop) => (
+                        format!("The installed copy of the {workshop} workshop."),
+                        "Removing it uninstalls the workshop.",
+                    ),
+                    Kind::Workspace(workshop) => (
+                        format!(
+                            "The copy of the {workshop} workshop in this project, with your progress in it."
+                        ),
+                        "Removing it resets your progress in the workshop.",
+                    ),
+                    Kind::Images(ids) => (
+                        format!(
+                            "The docker images built by the lesson checks:\n{}",
+                            ids.iter()
+                                .map(|id| format!(" - {id}"))
+                                .collect::<Vec<_>>()
+                                .join("\n")
+                        ),
+                        "Removing them makes the next check build them again.",
+                    ),
+                    Kind::Cache(paths) => (
+                        format!(
+                            "The downloads and parsed files in the cache:\n{}",
+                            paths
+                                .iter()
+                                .map(|path| format!(" - {}", path.display()))
+                                .collect::<Vec<_>>()
+                                .join("\n")
+                        ),
+                        "Removing them makes the next start a little slower.",
+                    ),
+                };
+                format!(
+                    "Size: {}\n\n{what}\n\n{purge}",
+                    usage::format_size(usage.bytes)
+                )
+            }
+            None if self.loading => "Measuring the disk usage...".to_string(),
+            None => "Nothing is taking up disk space".to_string(),
+        };
+        self.details.set_text(details);
+        self.details.scroll_top();
+    }
+
+    // get the selected item
+    fn selected(&self) -> Option<&Usage> {
+        self.items_state
+            .selected()
+            .and_then(|index| self.usage.get(index))
+    }
+
+    /// render the list of items and the details
+    fn render_usage(&mut self, area: Rect, buf: &mut Buffer) {
+        let [items_area, details_area] =
+            Layout::horizontal([Constraint::Percentage(40), Constraint::Percentage(60)])
+                .areas(area);
+
+        let total = self.usage.iter().map(|usage| usage.bytes).sum();
+        let title = Line::from(vec![
+            Span::styled("─", Style::default().fg(Color::DarkGray)),
+            Span::styled(
+                format!("/ Disk Usage: {} /", usage::format_size(total)),
+                Style::default().fg(Color::White),
+            ),
+        ]);
+        let items = self.items.clone().block(
+            Block::default()
+                .title(title)
+                .padding(Padding::uniform(1))
+                .style(Style::default().fg(Color::White))
+                .border_style(Style::default().fg(Color::DarkGray))
+                .borders(Borders::LEFT | Borders::TOP | Borders::RIGHT)
+                .border_set(TOP_LEFT_BORDER),
+        );
+        StatefulWidget::render(items, items_area, buf, &mut self.items_state);
+
+        let title = Line::from(vec![
+            Span::styled("─", Style::default().fg(Color::DarkGray)),
+            Span::styled("/ Details /", Style::default().fg(Color::DarkGray)),
+        ]);
+        self.details.block(
+            Block::default()
+                .title(title)
+                .padding(Padding::uniform(1))
+                .style(Style::default().fg(Color::White))
+                .border_style(Style::default().fg(Color::DarkGray))
+                .borders(Borders::LEFT | Borders::TOP | Borders::RIGHT)
+                .border_set(TOP_BOX_BORDER),
+        );
+        Widget::render(&mut self.details, details_area, buf);
+    }
+
+    // render the keyboard shortcuts
+    fn render_status(&mut self, area: Rect, buf: &mut Buffer) {
+        let title = Line::from(vec![
+            Span::styled("─", Style::default().fg(Color::DarkGray)),
+            Span::styled(
+                "/ j,k scroll / x remove / r refresh / b back / q quit /",
+                Style::default().fg(Color::White),
+            ),
+        ]);
+        let block = Block::default()
+            .title(title)
+            .title_style(Style::default().fg(Color::White))
+            .title_position(Position::Bottom)
+            .title_alignment(Alignment::Left)
+            .style(Style::default().fg(Color::DarkGray))
+            .borders(Borders::LEFT | Borders::BOTTOM | Borders::RIGHT)
+            .border_set(STATUS_BORDER)
+            .padding(Padding::horizontal(1));
+
+        Widget::render(block, area, buf);
+    }
+
+    /// handle UI events
+    pub async fn handle_ui_event(
+        &mut self,
+        event: tui::Event,
+        to_ui: Sender<screens::Event>,
+        status: StatusHandle,
+    ) -> Result<(), Error> {
+        match event {
+            tui::Event::LoadDiskUsage => {
+                info!("Measuring the disk usage");
+                if self.docker.is_none() {
+                    let (docker, min_version) = {
+                        let status = status.read().await;
+                        (
+                            status.docker_compose_executable().map(str::to_string),
+                            status.docker_compose_minimum_version().to_string(),
+                        )
+                    };
+                    self.docker = match docker {
+                        Some(docker) => Some(docker),
+                        None => fs::application::find_docker_compose_executable(min_version)
+                            .await
+                            .ok(),
+                    };
+                }
+                // keep showing the last measurement until the new one is done
+                self.loading = true;
+                if self.usage.is_empty() {
+                    self.cache_selected();
+                }
+                to_ui
+                    .send((None, tui::Event::Show(Screens::DiskUsage)).into())
+                    .await?;
+
+                // measure in the background since walking big workshops takes a while
+                let docker = self.docker.clone();
+                tokio::spawn(async move {
+                    let usage = usage::measure(docker.as_deref()).await;
+                    let _ = to_ui
+                        .send((Some(Screens::DiskUsage), tui::Event::DiskUsageLoaded(usage)).into())
+                        .await;
+                });
+            }
+            tui::Event::DiskUsageLoaded(usage) => {
+                debug!("Disk usage measured for {} items", usage.len());
+                self.init(usage);
+            }
+            tui::Event::PurgeDiskUsage(kind) => {
+                info!("Removing: {kind}");
+                let docker = self.docker.clone();
+                tokio::spawn(async move {
+                    if let Err(e) = usage::purge(&kind, docker.as_deref()).await {
+                        let log = evt!(
+                            Screens::Log,
+                            tui::Event::Log(format!("! failed to remove {kind}: {e}")),
+                        );
+                        let _ = to_ui.send(log.into()).await;
+                    }
+                    let load = evt!(Screens::DiskUsage, tui::Event::LoadDiskUsage);
+                    let _ = to_ui.send(load.into()).await;
+                });
+            }
+            _ => {
+                debug!("Ignoring UI event: {:?}", event);
+            }
+        }
+        Ok(())
+    }
+
+    /// handle input events
+    pub async fn handle_input_event(
+        &mut self,
+        event: event::Event,
+        to_ui: Sender<screens::Event>,
+        _status: StatusHandle,
+    ) -> Result<(), Error> {
+        if let event::Event::Key(key) = event {
+            match key.code {
+                KeyCode::PageUp => {
+                    self.items_state.select_first();
+                    self.cache_selected();
+                }
+                KeyCode::PageDown => {
+                    self.items_state.select_last();
+                    self.cache_selected();
+                }
+                KeyCode::Char('j') | KeyCode::Char('J') | KeyCode::Down => {
+                    self.items_state.select_next();
+                    self.cache_selected();
+                }
+                KeyCode::Char('k') | KeyCode::Char('K') | KeyCode::Up => {
+                    self.items_state.select_previous();
+                    self.cache_selected();
+                }
+                KeyCode::Char('x') | KeyCode::Char('X') => {
+                    if let Some(usage) = self.selected() {
+                        let load = evt!(Screens::DiskUsage, tui::Event::LoadDiskUsage);
+                        let (title, remove) = match &usage.kind {
+                            Kind::Installed(workshop) => (
+                                "Uninstall Workshop?",
+                                evt!(
+                                    None,
+                                    tui::Event::UninstallWorkshop(workshop.clone(), Some(load))
+                                ),
+                            ),
+                            Kind::Workspace(workshop) => (
+                                "Reset Workshop Progress?",
+                                evt!(
+                                    None,
+                                    tui::Event::ResetProgress(
+                                        ProgressScope::Workshop(workshop.clone()),
+                                        Some(load)
+                                    ),
+                                ),
+                            ),
+                            Kind::Images(_) => (
+                                "Remove Docker Images?",
+                                evt!(
+                                    Screens::DiskUsage,
+                                    tui::Event::PurgeDiskUsage(usage.kind.clone())
+                                ),
+                            ),
+                            Kind::Cache(_) => (
+                                "Clear Caches?",
+                                evt!(
+                                    Screens::DiskUsage,
+                                    tui::Event::PurgeDiskUsage(usage.kind.clone())
+                                ),
+                            ),
+                        };
+                        let confirm = evt!(
+                            None,
+                            tui::Event::Dialog(Dialog::yes_no(title, Some(remove), None)),
+                        );
+                        to_ui.send(confirm.into()).await?;
+                    }
+                }
+                KeyCode::Char('r') | KeyCode::Char('R') => {
+                    to_ui
+                        .send((Some(Screens::DiskUsage), tui::Event::LoadDiskUsage).into())
+                        .await?;
+                }
+                KeyCode::Char('b') | KeyCode::Char('B') | KeyCode::Esc => {
+                    to_ui
+                        .send((Some(Screens::Workshops), tui::Event::RefreshWorkshops).into())
+                        .await?;
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl Screen for DiskUsage<'_> {
+    async fn handle_event(
+        &mut self,
+        event: screens::Event,
+        to_ui: Sender<screens::Event>,
+        status: StatusHandle,
+    ) -> Result<(), Error> {
+        match event {
+            screens::Event::Input(input_event) => {
+                self.handle_input_event(input_event, to_ui, status).await
+            }
+            screens::Event::Ui(_, ui_event) => self.handle_ui_event(ui_event, to_ui, status).await,
+        }
+    }
+
+    fn navigate(&mut self, key: KeyEvent, count: usize) -> bool {
+        match key.code {
+            KeyCode::Char('j') | KeyCode::Char('J') | KeyCode::Down => {
+                (0..count).for_each(|_| self.items_state.select_next())
+            }
+            KeyCode::Char('k') | KeyCode::Char('K') | KeyCode::Up => {
+                (0..count).for_each(|_| self.items_state.select_previous())
+            }
+            _ => return false,
+        }
+        self.cache_selected();
+        true
+    }
+
+    fn actions(&self) -> Vec<screens::Action> {
+        vec![
+            screens::Action::new("Remove the highlighted item", KeyCode::Char('x')),
+            screens::Action::new("Refresh the disk usage", KeyCode::Char('r')),
+            screens::Action::new("Back to the workshops", KeyCode::Char('b')),
+        ]
+    }
+
+    fn render_screen(&mut self, area: Rect, buf: &mut Buffer) -> Result<(), Error> {
+        // this splits the screen into a top area and a one-line bottom area
+        let [usage_area, status_area] =
+            Layout::vertical([Constraint::Percentage(100), Constraint::Min(1)])
+                .flex(Flex::End)
+                .areas(area);
+
+        self.render_usage(usage_area, buf);
+        self.render_status(status_area, buf);
+
+        Ok(())
+    }
+}
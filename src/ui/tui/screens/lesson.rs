@@ -2,27 +2,34 @@ use crate::{
     command::CommandResult,
     evt, fs,
     languages::{programming, spoken},
-    models::{lesson, workshop, Error as ModelError, LessonData},
+    models::{lesson, template, workshop, Error as ModelError, Glossary, LessonData},
+    status::{self, Bookmark, ReadingPosition},
     ui::tui::{
         self,
         screens::{self, Screens},
-        widgets::{LessonBox, LessonBoxState},
+        widgets::{Dialog, LessonBox, LessonBoxState, TextPosition},
         Screen,
     },
-    Error, Status,
+    Error, StatusHandle,
 };
 use crossterm::event::{self, KeyCode};
 use ratatui::{
     buffer::Buffer,
     layout::{Alignment, Constraint, Flex, Layout, Rect},
-    style::{Color, Style},
+    style::{Color, Modifier, Style},
     symbols::border::Set,
     text::{Line, Span},
-    widgets::{block::Position, Block, Borders, Padding, StatefulWidget, Widget},
+    widgets::{
+        block::Position, Block, Borders, Clear, List, ListState, Padding, Paragraph,
+        StatefulWidget, Widget,
+    },
+};
+use std::{
+    path::PathBuf,
+    time::{Duration, Instant},
 };
-use std::sync::{Arc, Mutex};
 use tokio::sync::mpsc::Sender;
-use tracing::{debug, info};
+use tracing::{debug, error, info};
 
 const TOP_BORDER: Set = Set {
     top_left: "┌",
@@ -58,8 +65,48 @@ pub struct Lesson {
     spoken_language: Option<spoken::Code>,
     /// the currently selected programming language
     programming_language: Option<programming::Code>,
+    /// the workshop and lesson being timed
+    timed: Option<(String, String)>,
+    /// the last input while the lesson was showing and the terminal had focus
+    last_input: Option<Instant>,
+    /// the active time not yet added to the status since only whole seconds are kept
+    unflushed: Duration,
+    /// the active time spent on the lesson
+    time_spent: Duration,
+    /// if a hint was opened since the lesson was first opened
+    hints_used: bool,
+    /// the student's markdown notes for the lesson
+    notes: String,
+    /// the rendered notes
+    notes_state: LessonBoxState,
+    /// is the notes pane showing
+    show_notes: bool,
+    /// are the notes being edited
+    editing_notes: bool,
+    /// the bookmarks in the workshop, while the bookmarks popup is showing
+    bookmarks: Option<(Vec<Bookmark>, ListState)>,
+    /// the headings of the lesson, while the outline sidebar is showing
+    outline: Option<(Vec<(TextPosition, String)>, ListState)>,
+    /// the place to jump to once the bookmarked lesson is loaded
+    pending_position: Option<TextPosition>,
+    /// the result of the last solution check, while it failed and wasn't hidden
+    failed_check: Option<Box<CommandResult>>,
+    /// the helper commands of the lesson
+    commands: Vec<lesson::Command>,
+    /// the directory of the lesson, the quiz answers are stored for it
+    lesson_dir: Option<PathBuf>,
+    /// the glossary of the workshop
+    glossary: Glossary,
+    /// the terms with their definitions, while the glossary popup is showing
+    definitions: Option<Vec<(String, String)>>,
 }
 
+/// Bookmark labels are cut to this many characters
+const BOOKMARK_LABEL_LEN: usize = 60;
+
+/// Time since the last input beyond this isn't counted as active time spent on the lesson
+const IDLE_TIMEOUT: Duration = Duration::from_secs(120);
+
 impl Lesson {
     /// set the lessons
     async fn init<S: AsRef<str>>(
@@ -72,12 +119,247 @@ impl Lesson {
     ) -> Result<(), Error> {
         self.workshop_title = workshop_title.as_ref().to_string();
         self.lesson_title = lesson_title.as_ref().to_string();
-        self.lesson_state = LessonBoxState::from_markdown(text.as_ref());
+        self.lesson_state = LessonBoxState::from_markdown(text.as_ref())
+            .direction(spoken_language.unwrap_or_default().get_text_direction());
         self.spoken_language = spoken_language;
         self.programming_language = programming_language;
         Ok(())
     }
 
+    // count the time since the last input, up to the idle timeout, as time spent on the lesson
+    // and keep counting from now unless the timer is paused
+    async fn count_time(&mut self, status: &StatusHandle, pause: bool) {
+        let now = Instant::now();
+        if let Some(last_input) = self.last_input {
+            self.unflushed += now.duration_since(last_input).min(IDLE_TIMEOUT);
+        }
+        self.last_input = (!pause).then_some(now);
+
+        let Some((workshop, lesson)) = &self.timed else {
+            return;
+        };
+        let seconds = Duration::from_secs(self.unflushed.as_secs());
+        if !seconds.is_zero() {
+            self.unflushed -= seconds;
+            self.time_spent += seconds;
+            status
+                .update(|status| status.add_lesson_time(workshop, lesson, seconds))
+                .await;
+        }
+    }
+
+    // show the notes pane, or hide it if it is showing
+    fn toggle_notes(&mut self) {
+        self.show_notes = !self.show_notes;
+        self.render_notes_markdown();
+    }
+
+    // stop editing the notes and save them
+    fn save_notes(&mut self) {
+        self.editing_notes = false;
+        self.render_notes_markdown();
+        if let Some((workshop, lesson)) = &self.timed {
+            if let Err(e) = fs::notes::save(workshop, lesson, &self.notes) {
+                error!("Failed to save the notes for {workshop}/{lesson}: {e}");
+            }
+        }
+    }
+
+    fn render_notes_markdown(&mut self) {
+        let notes = if self.notes.trim().is_empty() {
+            "*No notes yet, press `i` to write some.*"
+        } else {
+            &self.notes
+        };
+        self.notes_state = LessonBoxState::from_markdown(notes).direction(
+            self.spoken_language
+                .unwrap_or_default()
+                .get_text_direction(),
+        );
+        self.notes_state.set_highlighted_line(false);
+    }
+
+    // edit the notes with the key
+    fn edit_notes(&mut self, key: KeyCode) {
+        match key {
+            KeyCode::Char(c) => self.notes.push(c),
+            KeyCode::Enter => self.notes.push('\n'),
+            KeyCode::Tab => self.notes.push_str("    "),
+            KeyCode::Backspace => {
+                self.notes.pop();
+            }
+            _ => {}
+        }
+    }
+
+    // remember where the student is reading so that reopening the lesson resumes there
+    async fn remember_position(&self, status: &StatusHandle) {
+        let Some((workshop, lesson)) = &self.timed else {
+            return;
+        };
+        let position = ReadingPosition {
+            highlighted: self.lesson_state.position(),
+            top: self.lesson_state.top_position(),
+        };
+        if status.get(|s| s.reading_position(workshop, lesson)).await != Some(position) {
+            status
+                .update(|s| s.set_reading_position(workshop, lesson, position))
+                .await;
+        }
+    }
+
+    // bookmark the highlighted line
+    async fn add_bookmark(
+        &mut self,
+        to_ui: &Sender<screens::Event>,
+        status: &StatusHandle,
+    ) -> Result<(), Error> {
+        let Some((workshop, lesson)) = self.timed.clone() else {
+            return Ok(());
+        };
+        let text = self.lesson_state.highlighted_text();
+        let mut label = text.chars().take(BOOKMARK_LABEL_LEN).collect::<String>();
+        if label.len() < text.len() {
+            label.push('…');
+        }
+        let bookmark = Bookmark {
+            lesson,
+            title: self.lesson_title.clone(),
+            position: self.lesson_state.position(),
+            label,
+        };
+        let toast = format!("Bookmarked: {}", bookmark.label);
+        status
+            .update(|status| status.add_bookmark(&workshop, bookmark))
+            .await;
+        to_ui.send((None, tui::Event::Toast(toast)).into()).await?;
+        Ok(())
+    }
+
+    // show the workshop's bookmarks, most recent first
+    async fn show_bookmarks(&mut self, status: &StatusHandle) {
+        let Some((workshop, _)) = &self.timed else {
+            return;
+        };
+        let bookmarks = status.read().await.bookmarks(workshop).to_vec();
+        let mut list_state = ListState::default();
+        list_state.select((!bookmarks.is_empty()).then_some(bookmarks.len() - 1));
+        self.bookmarks = Some((bookmarks, list_state));
+    }
+
+    // handle a key while the bookmarks popup is showing
+    async fn handle_bookmarks_key(
+        &mut self,
+        key: KeyCode,
+        to_ui: &Sender<screens::Event>,
+        status: &StatusHandle,
+    ) -> Result<(), Error> {
+        let (Some((bookmarks, list_state)), Some((workshop, lesson))) =
+            (&mut self.bookmarks, &self.timed)
+        else {
+            return Ok(());
+        };
+        match key {
+            KeyCode::Char('j') | KeyCode::Char('J') | KeyCode::Down => list_state.select_previous(),
+            KeyCode::Char('k') | KeyCode::Char('K') | KeyCode::Up => list_state.select_next(),
+            KeyCode::Char('d') | KeyCode::Char('D') | KeyCode::Delete => {
+                if let Some(index) = list_state.selected().filter(|i| *i < bookmarks.len()) {
+                    bookmarks.remove(index);
+                    status
+                        .update(|status| status.remove_bookmark(workshop, index))
+                        .await;
+                    if index == bookmarks.len() {
+                        list_state.select(index.checked_sub(1));
+                    }
+                }
+            }
+            KeyCode::Enter => {
+                if let Some(bookmark) = list_state.selected().and_then(|i| bookmarks.get(i)) {
+                    if &bookmark.lesson == lesson {
+                        self.lesson_state.go_to(bookmark.position);
+                    } else {
+                        self.pending_position = Some(bookmark.position);
+                        let set_lesson = tui::Event::SetLesson(Some(bookmark.lesson.clone()));
+                        to_ui.send((None, set_lesson).into()).await?;
+                    }
+                }
+                self.bookmarks = None;
+            }
+            KeyCode::Char('\'') | KeyCode::Char('b') | KeyCode::Esc => self.bookmarks = None,
+            _ => {}
+        }
+        Ok(())
+    }
+
+    // show the definitions of the terms linked from the highlighted line, or the whole glossary
+    // when it links to none
+    fn show_definitions(&mut self) {
+        let terms = self.lesson_state.highlighted_terms();
+        let definitions = if terms.is_empty() {
+            self.glossary
+                .terms()
+                .map(|(term, definition)| (term.to_string(), definition.to_string()))
+                .collect()
+        } else {
+            terms
+                .into_iter()
+                .map(|term| match self.glossary.define(&term) {
+                    Some((term, definition)) => (term.to_string(), definition.to_string()),
+                    None => (term, "This term isn't in the glossary.".to_string()),
+                })
+                .collect()
+        };
+        self.definitions = Some(definitions);
+    }
+
+    // show the outline of the lesson with the heading of the highlighted section selected
+    fn show_outline(&mut self) {
+        let highlighted = self.lesson_state.position();
+        let headings = self
+            .lesson_state
+            .outline()
+            .into_iter()
+            .map(|(position, heading)| {
+                let indent = "  ".repeat(heading.level.saturating_sub(1) as usize);
+                (position, format!("{indent}{}", heading.text))
+            })
+            .collect::<Vec<_>>();
+        let mut list_state = ListState::default();
+        list_state.select(
+            headings
+                .iter()
+                .rposition(|(position, _)| position.block <= highlighted.block)
+                .or((!headings.is_empty()).then_some(0)),
+        );
+        self.outline = Some((headings, list_state));
+    }
+
+    // handle a key while the outline sidebar is showing, the lesson follows the selection
+    fn handle_outline_key(&mut self, key: KeyCode) {
+        let Some((headings, list_state)) = &mut self.outline else {
+            return;
+        };
+        match key {
+            KeyCode::Char('j') | KeyCode::Char('J') | KeyCode::Down => list_state.select_next(),
+            KeyCode::Char('k') | KeyCode::Char('K') | KeyCode::Up => list_state.select_previous(),
+            KeyCode::Char('o')
+            | KeyCode::Char('O')
+            | KeyCode::Char('b')
+            | KeyCode::Esc
+            | KeyCode::Enter => {
+                self.outline = None;
+                return;
+            }
+            _ => return,
+        }
+        let selected = list_state
+            .selected()
+            .map(|index| index.min(headings.len().saturating_sub(1)));
+        if let Some((position, _)) = selected.and_then(|index| headings.get(index)) {
+            self.lesson_state.go_to(*position);
+        }
+    }
+
     /// check if all lessons in the workshop are completed
     async fn check_all_lessons_completed(
         &self,
@@ -117,11 +399,217 @@ impl Lesson {
         StatefulWidget::render(lesson_widget, area, buf, &mut self.lesson_state);
     }
 
+    /// render the summary of the failed solution check below the lesson
+    fn render_failed_check(&mut self, area: Rect, buf: &mut Buffer) {
+        let Some(result) = &self.failed_check else {
+            return;
+        };
+        let title = Line::from(vec![
+            Span::styled("─", Style::default().fg(Color::DarkGray)),
+            Span::styled("/ Last check failed /", Style::default().fg(Color::Red)),
+        ]);
+        let block = Block::default()
+            .title(title)
+            .padding(Padding::horizontal(1))
+            .style(Style::default().fg(Color::DarkGray))
+            .borders(Borders::LEFT | Borders::TOP | Borders::RIGHT)
+            .border_set(TOP_BORDER);
+        let mut lines = result.summary();
+        lines.push(result.last_line.clone());
+        Paragraph::new(lines.into_iter().map(Line::from).collect::<Vec<_>>())
+            .block(block)
+            .style(Style::default().fg(Color::White))
+            .render(area, buf);
+    }
+
+    /// render the bookmarks popup over the lesson, the list is drawn bottom up so the most recent
+    /// bookmark is on top
+    fn render_bookmarks(&mut self, area: Rect, buf: &mut Buffer) {
+        let Some((bookmarks, list_state)) = &mut self.bookmarks else {
+            return;
+        };
+        let height = (bookmarks.len().max(1) as u16 + 4).min(area.height);
+        let [popup] = Layout::horizontal([Constraint::Percentage(70)])
+            .flex(Flex::Center)
+            .areas(area);
+        let [popup] = Layout::vertical([Constraint::Length(height)])
+            .flex(Flex::Center)
+            .areas(popup);
+
+        let title = Line::from(vec![
+            Span::styled("─", Style::default().fg(Color::DarkGray)),
+            Span::styled(
+                "/ Bookmarks / ↵ jump / d delete / esc close /",
+                Style::default().fg(Color::White),
+            ),
+        ]);
+        let block = Block::default()
+            .title(title)
+            .padding(Padding::uniform(1))
+            .style(Style::default().fg(Color::DarkGray))
+            .borders(Borders::ALL);
+
+        Widget::render(Clear, popup, buf);
+        if bookmarks.is_empty() {
+            Paragraph::new("No bookmarks yet, press m to bookmark the highlighted line.")
+                .block(block)
+                .style(Style::default().fg(Color::White))
+                .render(popup, buf);
+            return;
+        }
+        let items = bookmarks
+            .iter()
+            .map(|bookmark| format!("{}: {}", bookmark.title, bookmark.label))
+            .collect::<Vec<_>>();
+        let list = List::new(items)
+            .block(block)
+            .direction(ratatui::widgets::ListDirection::BottomToTop)
+            .highlight_style(
+                Style::default()
+                    .fg(Color::Black)
+                    .bg(Color::White)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .style(Style::default().fg(Color::White))
+            .highlight_symbol("> ");
+        StatefulWidget::render(list, popup, buf, list_state);
+    }
+
+    /// render the glossary popup over the lesson
+    fn render_definitions(&self, area: Rect, buf: &mut Buffer) {
+        let Some(definitions) = &self.definitions else {
+            return;
+        };
+        let [popup] = Layout::horizontal([Constraint::Percentage(70)])
+            .flex(Flex::Center)
+            .areas(area);
+        let title = Line::from(vec![
+            Span::styled("─", Style::default().fg(Color::DarkGray)),
+            Span::styled(
+                "/ Glossary / esc close /",
+                Style::default().fg(Color::White),
+            ),
+        ]);
+        let block = Block::default()
+            .title(title)
+            .padding(Padding::uniform(1))
+            .style(Style::default().fg(Color::DarkGray))
+            .borders(Borders::ALL);
+
+        let width = block.inner(popup).width.max(10) as usize;
+        let mut lines = Vec::new();
+        for (term, definition) in definitions {
+            if !lines.is_empty() {
+                lines.push(Line::from(""));
+            }
+            lines.push(Line::from(Span::styled(
+                term.clone(),
+                Style::default()
+                    .fg(Color::LightCyan)
+                    .add_modifier(Modifier::BOLD),
+            )));
+            lines.extend(
+                textwrap::wrap(definition, width)
+                    .into_iter()
+                    .map(|line| Line::from(line.into_owned())),
+            );
+        }
+        if lines.is_empty() {
+            lines.push(Line::from("This workshop has no glossary."));
+        }
+        let height = (lines.len() as u16 + 4).min(area.height);
+        let [popup] = Layout::vertical([Constraint::Length(height)])
+            .flex(Flex::Center)
+            .areas(popup);
+
+        Widget::render(Clear, popup, buf);
+        Paragraph::new(lines)
+            .block(block)
+            .style(Style::default().fg(Color::White))
+            .render(popup, buf);
+    }
+
+    /// render the outline sidebar
+    fn render_outline(&mut self, area: Rect, buf: &mut Buffer) {
+        let Some((headings, list_state)) = &mut self.outline else {
+            return;
+        };
+        let title = Line::from(vec![
+            Span::styled("─", Style::default().fg(Color::DarkGray)),
+            Span::styled("/ Outline /", Style::default().fg(Color::White)),
+        ]);
+        let block = Block::default()
+            .title(title)
+            .padding(Padding::uniform(1))
+            .style(Style::default().fg(Color::DarkGray))
+            .borders(Borders::LEFT | Borders::TOP)
+            .border_set(TOP_BORDER);
+
+        if headings.is_empty() {
+            Paragraph::new("This lesson has no headings.")
+                .block(block)
+                .style(Style::default().fg(Color::White))
+                .render(area, buf);
+            return;
+        }
+        let list = List::new(headings.iter().map(|(_, text)| text.as_str()))
+            .block(block)
+            .highlight_style(
+                Style::default()
+                    .fg(Color::Black)
+                    .bg(Color::White)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .style(Style::default().fg(Color::White))
+            .highlight_symbol("> ");
+        StatefulWidget::render(list, area, buf, list_state);
+    }
+
+    /// render the notes pane, as markdown unless the notes are being edited
+    fn render_notes(&mut self, area: Rect, buf: &mut Buffer) {
+        let fg = if self.editing_notes {
+            Color::White
+        } else {
+            Color::DarkGray
+        };
+        let title = Line::from(vec![
+            Span::styled("─", Style::default().fg(Color::DarkGray)),
+            Span::styled("/ Notes /", Style::default().fg(fg)),
+        ]);
+        let block = Block::default()
+            .title(title)
+            .title_style(Style::default().fg(Color::White))
+            .padding(Padding::uniform(1))
+            .style(Style::default().fg(Color::DarkGray))
+            .borders(Borders::TOP | Borders::RIGHT)
+            .border_set(TOP_BORDER);
+
+        if self.editing_notes {
+            // show the end of the text with a cursor after it
+            let text = format!("{}█", self.notes);
+            let inner = block.inner(area);
+            let lines = textwrap::wrap(&text, inner.width.max(1) as usize)
+                .into_iter()
+                .map(|line| Line::from(line.into_owned()))
+                .collect::<Vec<_>>();
+            let skip = lines.len().saturating_sub(inner.height as usize);
+            Paragraph::new(lines.into_iter().skip(skip).collect::<Vec<_>>())
+                .block(block)
+                .style(Style::default().fg(Color::White))
+                .render(area, buf);
+        } else {
+            let notes_widget = LessonBox::new()
+                .block(block)
+                .style(Style::default().fg(Color::White));
+            StatefulWidget::render(notes_widget, area, buf, &mut self.notes_state);
+        }
+    }
+
     // render the status bar at the bottom
     fn render_status(&mut self, area: Rect, buf: &mut Buffer) {
         // render the status bar at the bottom
         let [keys_area, langs_area] =
-            Layout::horizontal([Constraint::Min(1), Constraint::Length(46)]).areas(area);
+            Layout::horizontal([Constraint::Min(1), Constraint::Length(54)]).areas(area);
 
         self.render_keys(keys_area, buf);
         self.render_langs(langs_area, buf);
@@ -132,7 +620,19 @@ impl Lesson {
         let title = Line::from(vec![
             Span::styled("─", Style::default().fg(Color::DarkGray)),
             Span::styled(
-                "/ j,k scroll / ↵ expand hint / c check / b back / q quit /",
+                if self.editing_notes {
+                    "/ type your notes in markdown / esc save /"
+                } else if self.outline.is_some() {
+                    "/ j,k go to heading / ↵,o hide outline / q quit /"
+                } else if self.failed_check.is_some() {
+                    "/ j,k scroll / ↵ expand hint / c check / x hide check result / n notes / b back / q quit /"
+                } else if self.show_notes {
+                    "/ j,k scroll / ↵ expand hint / c check / n hide notes / i edit notes / b back / q quit /"
+                } else if !self.commands.is_empty() {
+                    "/ j,k scroll / ↵ expand hint / c check / p commands / e editor setup / o outline / g glossary / n notes / m mark / ' marks / b back / q quit /"
+                } else {
+                    "/ j,k scroll / ↵ expand hint / c check / e editor setup / o outline / g glossary / n notes / m mark / ' marks / b back / q quit /"
+                },
                 Style::default().fg(Color::White),
             ),
         ]);
@@ -163,7 +663,11 @@ impl Lesson {
 
         let title = Line::from(vec![
             Span::styled(
-                format!("/ {} / {spoken} / {programming} /", self.workshop_title),
+                format!(
+                    "/ {} / {spoken} / {programming} / {} /",
+                    self.workshop_title,
+                    status::format_time(self.time_spent)
+                ),
                 Style::default().fg(Color::White),
             ),
             Span::styled("─", Style::default().fg(Color::DarkGray)),
@@ -187,15 +691,13 @@ impl Lesson {
         &mut self,
         event: tui::Event,
         to_ui: Sender<screens::Event>,
-        status: Arc<Mutex<Status>>,
+        status: StatusHandle,
     ) -> Result<(), Error> {
         match event {
             tui::Event::LoadLesson => {
                 debug!("Loading lessons");
-                let (spoken, programming, workshop, lesson) = {
-                    let status = status
-                        .lock()
-                        .map_err(|e| Error::StatusLock(e.to_string()))?;
+                let (spoken, programming, workshop, lesson, variables, repository) = {
+                    let status = status.read().await;
                     (
                         status.spoken_language(),
                         status.programming_language(),
@@ -207,18 +709,33 @@ impl Lesson {
                             .lesson()
                             .map(String::from)
                             .ok_or(ModelError::NoLessonSpecified)?,
+                        status.template_variables(),
+                        status.repository(),
                     )
                 };
-                if let Some(workshop_data) = fs::workshops::load(&workshop) {
+                if let Some(workshop_data) = repository.workshop(&workshop) {
                     debug!("Loading lessons for workshop: {}", &workshop);
                     let lessons = workshop_data.get_lessons_data(spoken, programming).await?;
                     let workshop_title = workshop_data.get_metadata(spoken).await?.title;
                     let lesson_data = lessons
                         .get(&lesson)
                         .ok_or(ModelError::NoLessonData(lesson.to_string()))?;
-                    let lesson_text = lesson_data.get_text().await?;
+                    let lesson_text = template::render(lesson_data.get_text().await?, &variables);
                     let lesson_metadata = lesson_data.get_metadata().await?;
                     let lesson_title = lesson_metadata.title.clone();
+                    self.glossary = Glossary::load(workshop_data.get_path(), spoken);
+                    self.commands = lesson_metadata
+                        .commands
+                        .iter()
+                        .map(|command| lesson::Command {
+                            name: command.name.clone(),
+                            run: command
+                                .run
+                                .iter()
+                                .map(|arg| template::render(arg, &variables))
+                                .collect(),
+                        })
+                        .collect();
 
                     // Set lesson status to InProgress if it's NotStarted
                     if matches!(lesson_metadata.status, lesson::Status::NotStarted) {
@@ -228,6 +745,23 @@ impl Lesson {
                         debug!("Updated lesson status to InProgress: {}", lesson_title);
                     }
 
+                    // reloading the lesson after a failed check keeps timing it
+                    let timed = Some((workshop.clone(), lesson.clone()));
+                    if self.timed != timed {
+                        self.time_spent = status.read().await.lesson_time(&workshop, &lesson);
+                        self.notes = fs::notes::load(&workshop, &lesson);
+                        self.editing_notes = false;
+                        self.render_notes_markdown();
+                        self.timed = timed;
+                        self.unflushed = Duration::ZERO;
+                        self.last_input = None;
+                        self.hints_used = false;
+                        self.failed_check = None;
+                    }
+                    if self.last_input.is_none() {
+                        self.last_input = Some(Instant::now());
+                    }
+
                     self.init(
                         &workshop_title,
                         &lesson_title,
@@ -236,19 +770,36 @@ impl Lesson {
                         programming,
                     )
                     .await?;
+                    // show the quizzes the way the student answered them
+                    for (quiz, answer) in fs::progress::quiz_answers(lesson_data.get_path()) {
+                        self.lesson_state.answer_quiz(quiz, answer.choice, 80);
+                    }
+                    self.lesson_dir = Some(lesson_data.get_path().to_path_buf());
+                    if let Some(position) = self.pending_position.take() {
+                        self.lesson_state.go_to(position);
+                    } else if let Some(position) =
+                        status.read().await.reading_position(&workshop, &lesson)
+                    {
+                        self.lesson_state.go_to(position.highlighted);
+                        self.lesson_state.scroll_to(position.top);
+                    }
                     to_ui
                         .send((None, tui::Event::Show(screens::Screens::Lesson)).into())
                         .await?;
+
+                    // get the next lesson ready while this one is being read
+                    repository.prefetch_next_lesson(&workshop, &lesson, spoken, programming);
                 } else {
                     info!("Failed to load workshop data for: {}", &workshop);
                 }
             }
+            tui::Event::CheckResult(result) => {
+                self.failed_check = (!result.success).then_some(result);
+            }
             tui::Event::SolutionComplete => {
                 // Set the lesson status to completed
-                let (spoken, programming, workshop, lesson) = {
-                    let status = status
-                        .lock()
-                        .map_err(|e| Error::StatusLock(e.to_string()))?;
+                let (spoken, programming, workshop, lesson, repository) = {
+                    let status = status.read().await;
                     (
                         status.spoken_language(),
                         status.programming_language(),
@@ -260,15 +811,48 @@ impl Lesson {
                             .lesson()
                             .map(String::from)
                             .ok_or(ModelError::NoLessonSpecified)?,
+                        status.repository(),
                     )
                 };
 
-                if let Some(workshop_data) = fs::workshops::load(&workshop) {
+                if let Some(workshop_data) = repository.workshop(&workshop) {
                     let lessons = workshop_data.get_lessons_data(spoken, programming).await?;
                     if let Some(lesson_data) = lessons.get(&lesson) {
                         lesson_data.update_status(lesson::Status::Completed).await?;
                         debug!("Updated lesson status to Completed: {}", lesson);
 
+                        // unlock the achievements before the status is saved
+                        self.count_time(&status, true).await;
+                        self.timed = None;
+                        let (hints_used, time_spent) = (self.hints_used, self.time_spent);
+                        let unlocked = status
+                            .update(|status| status.record_completion(hints_used, time_spent))
+                            .await;
+                        if !unlocked.is_empty() {
+                            let names = unlocked
+                                .iter()
+                                .map(ToString::to_string)
+                                .collect::<Vec<_>>()
+                                .join(", ");
+                            to_ui
+                                .send(
+                                    (None, tui::Event::Toast(format!("🏆 Unlocked: {names}")))
+                                        .into(),
+                                )
+                                .await?;
+                        }
+                        to_ui
+                            .send(
+                                (
+                                    None,
+                                    tui::Event::SyncProgress(format!(
+                                        "Complete {workshop}/{lesson}"
+                                    )),
+                                )
+                                    .into(),
+                            )
+                            .await?;
+
                         // Check if all lessons are completed
                         let all_completed = self.check_all_lessons_completed(&lessons).await?;
 
@@ -277,22 +861,22 @@ impl Lesson {
                             workshop_data
                                 .update_status(spoken, workshop::Status::Completed)
                                 .await?;
-                            // Return to workshops screen if all lessons are completed
-                            let set_workshop = evt!(
-                                None,
-                                tui::Event::SetWorkshop(None, std::collections::HashMap::default())
+                            // Show the certificate if all lessons are completed
+                            let completed = evt!(
+                                Screens::Completion,
+                                tui::Event::WorkshopCompleted(workshop.clone())
                             );
-                            let hide_log = evt!(None, tui::Event::HideLog(Some(set_workshop)));
+                            let hide_log = evt!(None, tui::Event::HideLog(Some(completed)));
                             let workshop_complete = evt!(
                                 Screens::Log,
                                 tui::Event::CommandCompleted(
-                                    CommandResult {
+                                    Box::new(CommandResult {
                                         success: true,
                                         exit_code: 0,
-                                        last_line: "All lessons completed!".to_string()
-                                    },
-                                    Some(hide_log),
-                                    None
+                                        last_line: "All lessons completed!".to_string(),
+                                        ..Default::default()
+                                    }),
+                                    tui::Then::on_success(hide_log)
                                 )
                             );
                             to_ui.send(workshop_complete.into()).await?;
@@ -317,44 +901,204 @@ impl Lesson {
         Ok(())
     }
 
+    // write the VS Code tasks and devcontainer for the lesson into the workspace
+    async fn write_editor_config(
+        &self,
+        to_ui: &Sender<screens::Event>,
+        status: &StatusHandle,
+    ) -> Result<(), Error> {
+        let (spoken, programming, workshop, lesson, repository) = {
+            let status = status.read().await;
+            (
+                status.spoken_language(),
+                status.programming_language(),
+                status
+                    .workshop()
+                    .map(String::from)
+                    .ok_or(ModelError::NoWorkshopSpecified)?,
+                status
+                    .lesson()
+                    .map(String::from)
+                    .ok_or(ModelError::NoLessonSpecified)?,
+                status.repository(),
+            )
+        };
+        let Some(workshop_data) = repository.workshop(&workshop) else {
+            return Ok(());
+        };
+        let lessons = workshop_data.get_lessons_data(spoken, programming).await?;
+        let (Some(lesson_data), Some(workspace_dir)) = (
+            lessons.get(&lesson),
+            fs::workshops::data_dir().and_then(|d| d.parent().map(PathBuf::from)),
+        ) else {
+            return Ok(());
+        };
+
+        let lines = match fs::vscode::write_config(
+            &workspace_dir,
+            &workshop,
+            &lesson,
+            lesson_data.get_path(),
+        ) {
+            Ok(written) => written
+                .iter()
+                .map(|path| format!("v Wrote {}", path.display()))
+                .collect::<Vec<_>>(),
+            Err(e) => vec![format!("! Failed to write the editor setup: {e}")],
+        };
+        for line in lines {
+            to_ui
+                .send((Some(Screens::Log), tui::Event::Log(line)).into())
+                .await?;
+        }
+        to_ui.send((None, tui::Event::ShowLog(None)).into()).await?;
+        Ok(())
+    }
+
+    // answer the highlighted quiz with the choice numbered by the digit and record the answer
+    async fn answer_quiz(
+        &mut self,
+        digit: char,
+        to_ui: &Sender<screens::Event>,
+    ) -> Result<(), Error> {
+        let (Some((quiz, _)), Some(choice)) = (
+            self.lesson_state.highlighted_quiz(),
+            digit.to_digit(10).map(|choice| choice as usize),
+        ) else {
+            return Ok(());
+        };
+        let Some(correct) = self.lesson_state.answer_quiz(quiz, choice, 80) else {
+            return Ok(());
+        };
+        if let Some(lesson_dir) = &self.lesson_dir {
+            let answer = fs::progress::QuizAnswer { choice, correct };
+            if let Err(e) = fs::progress::set_quiz_answer(lesson_dir, quiz, answer) {
+                error!("Failed to record the quiz answer: {e}");
+            }
+        }
+        let toast = if correct {
+            "✓ Correct"
+        } else {
+            "✗ Not quite"
+        };
+        to_ui
+            .send((None, tui::Event::Toast(toast.to_string())).into())
+            .await?;
+        Ok(())
+    }
+
     /// handle input events
     pub async fn handle_input_event(
         &mut self,
         event: event::Event,
         to_ui: Sender<screens::Event>,
-        _status: Arc<Mutex<Status>>,
+        status: StatusHandle,
     ) -> Result<(), Error> {
+        match event {
+            event::Event::FocusLost => self.count_time(&status, true).await,
+            event::Event::FocusGained if self.timed.is_some() => {
+                self.last_input = Some(Instant::now());
+            }
+            event::Event::Key(_) => self.count_time(&status, false).await,
+            _ => {}
+        }
+
         if let event::Event::Key(key) = event {
-            match key.code {
-                KeyCode::PageUp => self.lesson_state.scroll_top(),
-                KeyCode::PageDown => self.lesson_state.scroll_bottom(),
-                KeyCode::Char('j') | KeyCode::Char('J') | KeyCode::Down => {
-                    self.lesson_state.highlight_down()
-                }
-                KeyCode::Char('k') | KeyCode::Char('K') | KeyCode::Up => {
-                    self.lesson_state.highlight_up()
-                }
-                KeyCode::Enter => {
-                    // Toggle hint if highlighted line is a hint title
-                    self.lesson_state.toggle_highlighted_hint(80); // Default width, could be dynamic
+            if self.editing_notes {
+                if key.code == KeyCode::Esc {
+                    self.save_notes();
+                } else {
+                    self.edit_notes(key.code);
                 }
-                KeyCode::Char('c') | KeyCode::Char('C') => {
-                    // Check solution
-                    let success = evt!(Screens::Lesson, tui::Event::SolutionComplete);
-                    let failure = evt!(Screens::Lesson, tui::Event::SolutionIncomplete);
-                    let check_solution = evt!(
-                        None,
-                        tui::Event::CheckSolution(Some(success), Some(failure)),
-                    );
-                    to_ui.send(check_solution.into()).await?;
+                return Ok(());
+            }
+            if self.definitions.is_some() {
+                if matches!(
+                    key.code,
+                    KeyCode::Char('g') | KeyCode::Char('b') | KeyCode::Esc | KeyCode::Enter
+                ) {
+                    self.definitions = None;
                 }
-                KeyCode::Char('b') | KeyCode::Esc => {
-                    to_ui
-                        .send((None, tui::Event::SetLesson(None)).into())
-                        .await?;
+            } else if self.bookmarks.is_some() {
+                self.handle_bookmarks_key(key.code, &to_ui, &status).await?;
+            } else if self.outline.is_some() {
+                self.handle_outline_key(key.code);
+            } else {
+                match key.code {
+                    KeyCode::PageUp => self.lesson_state.scroll_top(),
+                    KeyCode::PageDown => self.lesson_state.scroll_bottom(),
+                    KeyCode::Char('j') | KeyCode::Char('J') | KeyCode::Down => {
+                        self.lesson_state.highlight_down()
+                    }
+                    KeyCode::Char('k') | KeyCode::Char('K') | KeyCode::Up => {
+                        self.lesson_state.highlight_up()
+                    }
+                    KeyCode::Enter => {
+                        // Toggle hint if highlighted line is a hint title, the default width
+                        // could be dynamic
+                        self.hints_used |= self.lesson_state.toggle_highlighted_hint(80);
+                    }
+                    KeyCode::Char(c @ '1'..='9')
+                        if self.lesson_state.highlighted_quiz().is_some() =>
+                    {
+                        self.answer_quiz(c, &to_ui).await?;
+                    }
+                    KeyCode::Char(' ') => {
+                        self.lesson_state.play_pause_highlighted_transcript();
+                    }
+                    KeyCode::Char('.') => {
+                        self.lesson_state.step_highlighted_transcript();
+                    }
+                    KeyCode::Char('c') | KeyCode::Char('C') => {
+                        // Check solution
+                        let success = evt!(Screens::Lesson, tui::Event::SolutionComplete);
+                        let failure = evt!(Screens::Lesson, tui::Event::SolutionIncomplete);
+                        let check_solution = evt!(
+                            None,
+                            tui::Event::CheckSolution(
+                                tui::Then::on_success(success).or_else(failure)
+                            ),
+                        );
+                        to_ui.send(check_solution.into()).await?;
+                    }
+                    KeyCode::Char('b') | KeyCode::Esc => {
+                        to_ui
+                            .send((None, tui::Event::SetLesson(None)).into())
+                            .await?;
+                    }
+                    KeyCode::Char('e') | KeyCode::Char('E') => {
+                        self.write_editor_config(&to_ui, &status).await?;
+                    }
+                    KeyCode::Char('n') | KeyCode::Char('N') => self.toggle_notes(),
+                    KeyCode::Char('i') | KeyCode::Char('I') if self.show_notes => {
+                        self.editing_notes = true;
+                    }
+                    KeyCode::Char('m') | KeyCode::Char('M') => {
+                        self.add_bookmark(&to_ui, &status).await?;
+                    }
+                    KeyCode::Char('\'') => self.show_bookmarks(&status).await,
+                    KeyCode::Char('o') | KeyCode::Char('O') => self.show_outline(),
+                    KeyCode::Char('g') | KeyCode::Char('G') => self.show_definitions(),
+                    KeyCode::Char('x') | KeyCode::Char('X') => self.failed_check = None,
+                    KeyCode::Char('p') | KeyCode::Char('P') if !self.commands.is_empty() => {
+                        let choices = self
+                            .commands
+                            .iter()
+                            .map(|command| {
+                                let run = evt!(None, tui::Event::RunLessonCommand(command.clone()));
+                                (command.name.clone(), Some(run))
+                            })
+                            .collect();
+                        let palette = evt!(
+                            None,
+                            tui::Event::Dialog(Dialog::choice("Lesson Commands", choices, None))
+                        );
+                        to_ui.send(palette.into()).await?;
+                    }
+                    _ => {}
                 }
-                _ => {}
             }
+            self.remember_position(&status).await;
         }
         Ok(())
     }
@@ -366,7 +1110,7 @@ impl Screen for Lesson {
         &mut self,
         event: screens::Event,
         to_ui: Sender<screens::Event>,
-        status: Arc<Mutex<Status>>,
+        status: StatusHandle,
     ) -> Result<(), Error> {
         match event {
             screens::Event::Input(input_event) => {
@@ -376,6 +1120,99 @@ impl Screen for Lesson {
         }
     }
 
+    async fn on_enter(
+        &mut self,
+        _to_ui: Sender<screens::Event>,
+        _status: StatusHandle,
+    ) -> Result<(), Error> {
+        if self.timed.is_some() {
+            self.last_input = Some(Instant::now());
+        }
+        Ok(())
+    }
+
+    // leaving the lesson pauses the timer and keeps the notes being written
+    async fn on_leave(
+        &mut self,
+        _to_ui: Sender<screens::Event>,
+        status: StatusHandle,
+    ) -> Result<(), Error> {
+        self.count_time(&status, true).await;
+        if self.editing_notes {
+            self.save_notes();
+        }
+        Ok(())
+    }
+
+    fn takes_text_input(&self) -> bool {
+        self.editing_notes
+    }
+
+    fn actions(&self) -> Vec<screens::Action> {
+        // the popups and the notes editor take the keys while they are showing
+        if self.editing_notes
+            || self.bookmarks.is_some()
+            || self.outline.is_some()
+            || self.definitions.is_some()
+        {
+            return Vec::new();
+        }
+        let mut actions = vec![
+            screens::Action::new("Check the solution", KeyCode::Char('c')),
+            screens::Action::new("Expand or collapse the highlighted hint", KeyCode::Enter),
+        ];
+        if let Some(transcript) = self.lesson_state.highlighted_transcript() {
+            let play = if transcript.is_playing() {
+                "Pause the transcript"
+            } else {
+                "Play the transcript"
+            };
+            actions.extend([
+                screens::Action::new(play, KeyCode::Char(' ')),
+                screens::Action::new("Print the next line of the transcript", KeyCode::Char('.')),
+            ]);
+        }
+        if let Some((_, quiz)) = self.lesson_state.highlighted_quiz() {
+            actions.extend(quiz.choices.iter().zip('1'..='9').map(|(choice, digit)| {
+                screens::Action::new(format!("Answer: {choice}"), KeyCode::Char(digit))
+            }));
+        }
+        if !self.commands.is_empty() {
+            actions.push(screens::Action::new(
+                "Run a lesson command",
+                KeyCode::Char('p'),
+            ));
+        }
+        if self.failed_check.is_some() {
+            actions.push(screens::Action::new(
+                "Hide the check result",
+                KeyCode::Char('x'),
+            ));
+        }
+        actions.extend([
+            screens::Action::new("Write the editor setup", KeyCode::Char('e')),
+            screens::Action::new("Show the outline", KeyCode::Char('o')),
+            screens::Action::new(
+                "Define the terms on the highlighted line",
+                KeyCode::Char('g'),
+            ),
+            screens::Action::new("Show or hide the notes", KeyCode::Char('n')),
+        ]);
+        if self.show_notes {
+            actions.push(screens::Action::new("Edit the notes", KeyCode::Char('i')));
+        }
+        actions.extend([
+            screens::Action::new("Bookmark the highlighted line", KeyCode::Char('m')),
+            screens::Action::new("Show the bookmarks", KeyCode::Char('\'')),
+            screens::Action::new("Back to the lessons", KeyCode::Char('b')),
+        ]);
+        actions
+    }
+
+    fn is_animating(&self) -> bool {
+        self.lesson_state.is_playing()
+    }
+
     fn render_screen(&mut self, area: Rect, buf: &mut Buffer) -> Result<(), Error> {
         // this splits the screen into a top area and a one-line bottom area
         let [lesson_area, status_area] =
@@ -383,7 +1220,43 @@ impl Screen for Lesson {
                 .flex(Flex::End)
                 .areas(area);
 
-        self.render_lesson(lesson_area, buf);
+        // the summary of a failed check takes the bottom of the lesson area
+        let lesson_area = match &self.failed_check {
+            Some(result) => {
+                let height = result.summary().len() as u16 + 2;
+                let [lesson_area, check_area] = Layout::vertical([
+                    Constraint::Min(1),
+                    Constraint::Length(height.min(lesson_area.height / 2)),
+                ])
+                .areas(lesson_area);
+                self.render_failed_check(check_area, buf);
+                lesson_area
+            }
+            None => lesson_area,
+        };
+
+        let lesson_area = if self.outline.is_some() {
+            let [outline_area, lesson_area] = Layout::horizontal([
+                Constraint::Length((lesson_area.width / 4).clamp(20, 40)),
+                Constraint::Min(1),
+            ])
+            .areas(lesson_area);
+            self.render_outline(outline_area, buf);
+            lesson_area
+        } else {
+            lesson_area
+        };
+        if self.show_notes {
+            let [lesson_area, notes_area] =
+                Layout::horizontal([Constraint::Percentage(60), Constraint::Percentage(40)])
+                    .areas(lesson_area);
+            self.render_lesson(lesson_area, buf);
+            self.render_notes(notes_area, buf);
+        } else {
+            self.render_lesson(lesson_area, buf);
+        }
+        self.render_bookmarks(lesson_area, buf);
+        self.render_definitions(lesson_area, buf);
         self.render_status(status_area, buf);
 
         Ok(())
@@ -1,9 +1,14 @@
 use crate::{
-    fs,
+    evt,
     languages::{self, programming, spoken},
-    models::{lesson, workshop, Error as ModelError, Lesson, LessonData},
-    ui::tui::{self, screens, widgets::ScrollBox, Screen, Screens},
-    Error, Status,
+    models::{lesson, workshop, Error as ModelError, Lesson, LessonData, Repository},
+    status::ProgressScope,
+    ui::tui::{
+        self, glyphs, screens,
+        widgets::{Dialog, ScrollBox},
+        Screen, Screens,
+    },
+    Error, StatusHandle,
 };
 use crossterm::event::{self, KeyCode};
 use ratatui::{
@@ -12,13 +17,12 @@ use ratatui::{
     style::{Color, Modifier, Style},
     symbols::border::Set,
     text::{Line, Span},
-    widgets::{block::Position, Block, Borders, List, ListState, Padding, StatefulWidget, Widget},
-};
-use std::{
-    collections::{BTreeMap, HashMap},
-    fmt,
-    sync::{Arc, Mutex},
+    widgets::{
+        block::Position, Block, Borders, List, ListState, Padding, Paragraph, StatefulWidget,
+        Widget,
+    },
 };
+use std::{collections::HashMap, fmt, time::Instant};
 use tokio::sync::mpsc::Sender;
 use tracing::{debug, info, info_span, warn};
 
@@ -84,6 +88,51 @@ impl fmt::Display for FocusedView {
     }
 }
 
+/// The lessons of a workshop read in the background
+#[derive(Clone, Debug)]
+pub struct Loaded {
+    /// the title of the workshop
+    workshop_title: String,
+    /// the lesson data, with the metadata of each lesson read
+    lessons: HashMap<String, LessonData>,
+    /// the lesson last opened in the workshop
+    last_lesson: Option<String>,
+}
+
+// read the lessons of the workshop in the languages and the metadata of each of them, and mark
+// the workshop as started
+async fn load_lessons(
+    repository: &Repository,
+    workshop: &str,
+    spoken: Option<spoken::Code>,
+    programming: Option<programming::Code>,
+) -> Result<(String, HashMap<String, LessonData>), String> {
+    let workshop_data = repository
+        .workshop(workshop)
+        .ok_or_else(|| format!("failed to load workshop data for: {workshop}"))?;
+    let load = async {
+        let lessons = workshop_data.get_lessons_data(spoken, programming).await?;
+        for lesson_data in lessons.values() {
+            lesson_data.get_metadata().await?;
+        }
+        let workshop_metadata = workshop_data.get_metadata(spoken).await?;
+
+        // Set lesson status to InProgress if it's NotStarted
+        debug!("Workshop status: {:?}", workshop_metadata.status);
+        if matches!(workshop_metadata.status, workshop::Status::NotStarted) {
+            workshop_data
+                .update_status(spoken, workshop::Status::InProgress)
+                .await?;
+            debug!(
+                "Updated workshop status to InProgress: {}",
+                workshop_metadata.title
+            );
+        }
+        Ok::<_, Error>((workshop_metadata.title.clone(), lessons))
+    };
+    load.await.map_err(|e| e.to_string())
+}
+
 #[derive(Clone, Debug, Default)]
 pub struct Lessons<'a> {
     /// the lesson data
@@ -92,8 +141,8 @@ pub struct Lessons<'a> {
     selected: Option<Lesson>,
     /// the title of the workshop
     workshop_title: String,
-    /// the map of lesson titles to lesson keys
-    titles_map: BTreeMap<String, String>,
+    /// the lesson keys and their titles, in lesson order
+    titles_map: Vec<(String, String)>,
     /// the cached list
     titles: List<'a>,
     /// the list state of lesson title
@@ -106,6 +155,12 @@ pub struct Lessons<'a> {
     spoken_language: Option<spoken::Code>,
     /// the currently selected programming language
     programming_language: Option<programming::Code>,
+    /// the workshop and languages of the lessons being shown or loaded
+    pending: Option<(String, Option<spoken::Code>, Option<programming::Code>)>,
+    /// when the lessons started loading, if they are still loading
+    loading: Option<Instant>,
+    /// show a still spinner while loading
+    reduced_motion: bool,
 }
 
 impl Lessons<'_> {
@@ -122,15 +177,21 @@ impl Lessons<'_> {
         }
     }
 
+    /// Show a still spinner instead of an animated one while the lessons are loading
+    pub fn reduced_motion(mut self, reduced_motion: bool) -> Self {
+        self.reduced_motion = reduced_motion;
+        self
+    }
+
     /// set the lessons
     async fn init<S: AsRef<str>>(
         &mut self,
-        lessons: &HashMap<String, LessonData>,
+        lessons: HashMap<String, LessonData>,
         workshop_title: S,
         spoken_language: Option<spoken::Code>,
         programming_language: Option<programming::Code>,
     ) -> Result<(), Error> {
-        self.lessons = lessons.clone();
+        self.lessons = lessons;
         self.workshop_title = workshop_title.as_ref().to_string();
         self.spoken_language = spoken_language;
         self.programming_language = programming_language;
@@ -160,25 +221,25 @@ impl Lessons<'_> {
     }
 
     // get the lesson titles with status indicators
-    async fn get_titles(&mut self) -> Result<Vec<String>, Error> {
+    async fn get_titles(&mut self) -> Result<Vec<Line<'static>>, Error> {
         info!("Caching lesson titles");
         self.titles_map.clear();
+        let rtl = self
+            .spoken_language
+            .is_some_and(|s| s.get_text_direction() == spoken::Direction::RightToLeft);
 
-        // Get lessons in sorted order
+        // Get lessons in lesson order
         let mut lessons_with_status: Vec<(String, String, lesson::Status)> = Vec::new();
-        for (key, ld) in self.lessons.iter() {
-            let lesson = ld.get_metadata().await?;
+        for key in lesson::ordered_keys(&self.lessons).await? {
+            let lesson = self.lessons[&key].get_metadata().await?;
             let status = lesson.status.clone();
             debug!(
                 "lesson key: {key}, title: {}, status: {status}",
                 lesson.title
             );
-            lessons_with_status.push((key.clone(), lesson.title.clone(), status));
+            lessons_with_status.push((key, lesson.title.clone(), status));
         }
 
-        // Sort by lesson key (which includes ordering like 01-, 02-, etc.)
-        lessons_with_status.sort_by(|a, b| a.0.cmp(&b.0));
-
         for (key, title, status) in lessons_with_status.iter() {
             let status_indicator = match status {
                 lesson::Status::Completed => "✅ ",
@@ -186,12 +247,25 @@ impl Lessons<'_> {
                 lesson::Status::NotStarted => "   ",
             };
 
-            let title_with_status = format!("{status_indicator}{title}");
-            self.titles_map
-                .insert(key.clone(), title_with_status.clone());
+            // right-to-left titles are right aligned with the status on the right
+            let title_with_status = if rtl {
+                format!("{title} {status_indicator}")
+            } else {
+                format!("{status_indicator}{title}")
+            };
+            self.titles_map.push((key.clone(), title_with_status));
         }
 
-        Ok(self.titles_map.values().cloned().collect())
+        let alignment = if rtl {
+            Alignment::Right
+        } else {
+            Alignment::Left
+        };
+        Ok(self
+            .titles_map
+            .iter()
+            .map(|(_, title)| Line::from(title.clone()).alignment(alignment))
+            .collect())
     }
 
     // check if a lesson can be selected based on its index
@@ -239,7 +313,16 @@ impl Lessons<'_> {
                 let lesson = lesson_data.get_metadata().await?;
                 for (v, b) in self.boxes.iter_mut() {
                     match v {
-                        FocusedView::Metadata => b.set_text(format!("Status: {}", lesson.status)),
+                        FocusedView::Metadata => {
+                            let mut metadata = format!("Status: {}", lesson.status);
+                            if let Some(minutes) = lesson.estimated_minutes {
+                                metadata.push_str(&format!("\nEstimated time: {minutes} min"));
+                            }
+                            if !lesson.tags.is_empty() {
+                                metadata.push_str(&format!("\nTags: {}", lesson.tags.join(", ")));
+                            }
+                            b.set_text(metadata)
+                        }
                         FocusedView::Description => b.set_text(&lesson.description),
                         _ => {}
                     }
@@ -349,9 +432,9 @@ impl Lessons<'_> {
         self.get_lesson_keys().get(selected_index).cloned()
     }
 
-    // get the sorted list of lesson keys
+    // get the lesson keys in lesson order
     fn get_lesson_keys(&self) -> Vec<String> {
-        self.titles_map.keys().cloned().collect()
+        self.titles_map.iter().map(|(key, _)| key.clone()).collect()
     }
 
     /// render the lesson list and info
@@ -372,20 +455,37 @@ impl Lessons<'_> {
             _ => Color::DarkGray,
         };
 
-        let title = Line::from(vec![
+        let mut title = vec![
             Span::styled("─", Style::default().fg(Color::DarkGray)),
             Span::styled("/ Select a Lesson /", Style::default().fg(fg)),
-        ]);
-        let titles = self.titles.clone().block(
-            Block::default()
-                .title(title)
-                .title_style(Style::default().fg(fg))
-                .padding(Padding::uniform(1))
-                .style(Style::default().fg(Color::DarkGray))
-                .borders(Borders::LEFT | Borders::TOP | Borders::RIGHT)
-                .border_set(TOP_LEFT_BORDER),
-        );
+        ];
+        let spinner = self
+            .loading
+            .map(|started| glyphs::spinner(started.elapsed(), self.reduced_motion));
+        if let Some(spinner) = spinner {
+            title.push(Span::styled(
+                format!(" {spinner} /"),
+                Style::default().fg(Color::White),
+            ));
+        }
+        let block = Block::default()
+            .title(Line::from(title))
+            .title_style(Style::default().fg(fg))
+            .padding(Padding::uniform(1))
+            .style(Style::default().fg(Color::DarkGray))
+            .borders(Borders::LEFT | Borders::TOP | Borders::RIGHT)
+            .border_set(TOP_LEFT_BORDER);
+
+        // there is nothing to list until the lessons are read
+        if let (Some(spinner), true) = (spinner, self.lessons.is_empty()) {
+            Paragraph::new(format!("{spinner} Loading the lessons..."))
+                .style(Style::default().fg(Color::White))
+                .block(block)
+                .render(area, buf);
+            return;
+        }
 
+        let titles = self.titles.clone().block(block);
         StatefulWidget::render(&titles, area, buf, &mut self.titles_state);
     }
 
@@ -450,7 +550,7 @@ impl Lessons<'_> {
         let title = Line::from(vec![
             Span::styled("─", Style::default().fg(Color::DarkGray)),
             Span::styled(
-                "/ j,k scroll / ⇥ focus / ↵ select / b back / q quit /",
+                "/ j,k scroll / ⇥ focus / ↵ select / p reset progress / b back / q quit /",
                 Style::default().fg(Color::White),
             ),
         ]);
@@ -497,52 +597,96 @@ impl Lessons<'_> {
         &mut self,
         event: tui::Event,
         to_ui: Sender<screens::Event>,
-        status: Arc<Mutex<Status>>,
+        status: StatusHandle,
     ) -> Result<(), Error> {
         match event {
             tui::Event::LoadLessons => {
                 let span = info_span!("Lessons");
                 let _enter = span.enter();
-                let (spoken, programming, workshop) = {
-                    let status = status
-                        .lock()
-                        .map_err(|e| Error::StatusLock(e.to_string()))?;
+                let (spoken, programming, workshop, last_lesson, repository) = {
+                    let status = status.read().await;
+                    let workshop = status
+                        .workshop()
+                        .map(String::from)
+                        .ok_or(ModelError::NoWorkshopSpecified)?;
+                    let last_lesson = status
+                        .workshop_context(&workshop)
+                        .and_then(|context| context.lesson.clone());
                     (
                         status.spoken_language(),
                         status.programming_language(),
-                        status
-                            .workshop()
-                            .map(String::from)
-                            .ok_or(ModelError::NoWorkshopSpecified)?,
+                        workshop,
+                        last_lesson,
+                        status.repository(),
                     )
                 };
-                if let Some(workshop_data) = fs::workshops::load(&workshop) {
-                    info!(
-                        "Loading lessons for workshop: {} (spoken: {:?}, programming: {:?})",
-                        &workshop,
-                        languages::spoken_name(spoken),
-                        languages::programming_name(programming),
-                    );
-                    let lessons = workshop_data.get_lessons_data(spoken, programming).await?;
-                    let workshop_metadata = workshop_data.get_metadata(spoken).await?;
-                    let workshop_title = workshop_metadata.title.clone();
-
-                    // Set lesson status to InProgress if it's NotStarted
-                    debug!("Workshop status: {:?}", workshop_metadata.status);
-                    if matches!(workshop_metadata.status, workshop::Status::NotStarted) {
-                        workshop_data
-                            .update_status(spoken, workshop::Status::InProgress)
+                info!(
+                    "Loading lessons for workshop: {} (spoken: {:?}, programming: {:?})",
+                    &workshop,
+                    languages::spoken_name(spoken),
+                    languages::programming_name(programming),
+                );
+
+                // the lessons of another workshop or languages shouldn't linger while these load
+                if self.pending.as_ref() != Some(&(workshop.clone(), spoken, programming)) {
+                    self.init(HashMap::new(), "", spoken, programming).await?;
+                }
+                self.pending = Some((workshop.clone(), spoken, programming));
+                self.loading = Some(Instant::now());
+                to_ui
+                    .send((None, tui::Event::Show(screens::Screens::Lessons)).into())
+                    .await?;
+
+                // reading the lessons takes a while on slow disks, so it is done in the background
+                tokio::spawn(async move {
+                    let loaded = load_lessons(&repository, &workshop, spoken, programming)
+                        .await
+                        .map(|(workshop_title, lessons)| {
+                            Box::new(Loaded {
+                                workshop_title,
+                                lessons,
+                                last_lesson,
+                            })
+                        });
+                    let _ = to_ui
+                        .send(
+                            (
+                                Some(Screens::Lessons),
+                                tui::Event::LessonsLoaded(workshop, spoken, programming, loaded),
+                            )
+                                .into(),
+                        )
+                        .await;
+                });
+            }
+            tui::Event::LessonsLoaded(workshop, spoken, programming, loaded) => {
+                // a newer load of another workshop or languages replaced this one
+                if self.pending.as_ref() != Some(&(workshop.clone(), spoken, programming)) {
+                    debug!("Ignoring lessons loaded for: {workshop}");
+                    return Ok(());
+                }
+                self.loading = None;
+                match loaded {
+                    Ok(loaded) => {
+                        let Loaded {
+                            workshop_title,
+                            lessons,
+                            last_lesson,
+                        } = *loaded;
+                        self.init(lessons, workshop_title, spoken, programming)
                             .await?;
-                        debug!("Updated workshop status to InProgress: {workshop_title}");
-                    }
 
-                    self.init(&lessons, workshop_title, spoken, programming)
-                        .await?;
-                    to_ui
-                        .send((None, tui::Event::Show(screens::Screens::Lessons)).into())
-                        .await?;
-                } else {
-                    warn!("Failed to load workshop data for: {}", &workshop);
+                        // resume at the lesson last opened in this workshop
+                        if let Some(index) = last_lesson
+                            .and_then(|key| self.get_lesson_keys().iter().position(|k| *k == key))
+                        {
+                            self.titles_state.select(Some(index));
+                            self.cache_selected().await?;
+                        }
+                    }
+                    Err(e) => {
+                        warn!("Failed to load the lessons of {workshop}: {e}");
+                    }
                 }
             }
             _ => {
@@ -557,7 +701,7 @@ impl Lessons<'_> {
         &mut self,
         event: event::Event,
         to_ui: Sender<screens::Event>,
-        _status: Arc<Mutex<Status>>,
+        status: StatusHandle,
     ) -> Result<(), Error> {
         if let event::Event::Key(key) = event {
             match key.code {
@@ -570,6 +714,33 @@ impl Lessons<'_> {
                         .send((Some(Screens::Workshops), tui::Event::LoadWorkshops).into())
                         .await?;
                 }
+                KeyCode::Char('p') => {
+                    let workshop = {
+                        let status = status.read().await;
+                        status.workshop().map(String::from)
+                    };
+                    if let (Some(workshop), Some(lesson_key)) =
+                        (workshop, self.get_selected_lesson_key())
+                    {
+                        let load_lessons = evt!(Screens::Lessons, tui::Event::LoadLessons);
+                        let reset = evt!(
+                            None,
+                            tui::Event::ResetProgress(
+                                ProgressScope::Lesson(workshop, lesson_key),
+                                Some(load_lessons),
+                            ),
+                        );
+                        let confirm = evt!(
+                            None,
+                            tui::Event::Dialog(Dialog::yes_no(
+                                "Reset Lesson Progress?",
+                                Some(reset),
+                                None
+                            )),
+                        );
+                        to_ui.send(confirm.into()).await?;
+                    }
+                }
                 KeyCode::Tab => {
                     if key.modifiers.contains(event::KeyModifiers::SHIFT) {
                         // switch focus to the previous view
@@ -617,7 +788,7 @@ impl Screen for Lessons<'_> {
         &mut self,
         event: screens::Event,
         to_ui: Sender<screens::Event>,
-        status: Arc<Mutex<Status>>,
+        status: StatusHandle,
     ) -> Result<(), Error> {
         match event {
             screens::Event::Input(input_event) => {
@@ -627,6 +798,19 @@ impl Screen for Lessons<'_> {
         }
     }
 
+    fn actions(&self) -> Vec<screens::Action> {
+        vec![
+            screens::Action::new("Open the highlighted lesson", KeyCode::Enter),
+            screens::Action::new("Reset the lesson progress", KeyCode::Char('p')),
+            screens::Action::new("Focus the next view", KeyCode::Tab),
+            screens::Action::new("Back to the workshops", KeyCode::Char('b')),
+        ]
+    }
+
+    fn is_animating(&self) -> bool {
+        self.loading.is_some()
+    }
+
     fn render_screen(&mut self, area: Rect, buf: &mut Buffer) -> Result<(), Error> {
         // this splits the screen into a top area and a one-line bottom area
         let [lessons_area, status_area] =
@@ -1,25 +1,31 @@
 use crate::{
+    evt,
     languages::spoken,
+    models::{
+        lesson::{self, ThirdParty},
+        license,
+    },
     ui::tui::{
         self,
         screens::{self, Screens},
-        widgets::ScrollText,
+        widgets::{Answer, Dialog, LessonBox, LessonBoxState},
         Screen,
     },
-    Error, Status,
+    Error, StatusHandle,
 };
 use crossterm::event::{self, KeyCode};
 use ratatui::{
     buffer::Buffer,
     layout::{Alignment, Constraint, Flex, Layout, Rect},
-    style::{Color, Style},
+    style::{Color, Modifier, Style},
     symbols::border::Set,
     text::{Line, Span},
-    widgets::{block::Position, Block, Borders, Clear, Padding, StatefulWidget, Widget},
+    widgets::{
+        block::Position, Block, Borders, Clear, Padding, Paragraph, StatefulWidget, Widget, Wrap,
+    },
 };
-use std::sync::{Arc, Mutex};
 use tokio::sync::mpsc::Sender;
-use tracing::info;
+use tracing::{info, warn};
 
 const TOP_DIALOG_BORDER: Set = Set {
     top_left: "┌",
@@ -43,43 +49,62 @@ const STATUS_BORDER: Set = Set {
     horizontal_bottom: "─",
 };
 
+/// The screen showing a workshop's license, with a summary of it when it is a well known one and
+/// the licenses of the external code bundled with the lessons
 #[derive(Clone, Debug, Default)]
 pub struct License<'a> {
-    /// license text
-    text: String,
     /// the cached rect from last render
     area: Rect,
     /// the cached calculated rect
     centered: Rect,
-    /// scroll text widget
-    st: ScrollText<'a>,
-    /// the currently selected spoken language
-    spoken_language: Option<spoken::Code>,
+    /// the license text
+    lb: LessonBox<'a>,
+    /// the state of the license text
+    lb_state: LessonBoxState,
+    /// the license the text was recognized as
+    known: Option<&'static license::Known>,
+    /// the text last searched for
+    find: String,
+    /// the workshop the license is for
+    workshop: Option<String>,
+    /// set when the license text was dropped on leaving the screen
+    released: bool,
 }
 
 impl License<'_> {
-    /// Create a new log Screen
+    /// Create a new license Screen
     pub fn new() -> Self {
-        let mut st = ScrollText::default();
-        st.scroll_top();
-        Self {
-            text: String::new(),
-            area: Rect::default(),
-            centered: Rect::default(),
-            st,
-            spoken_language: None,
-        }
+        Self::default()
     }
 
-    /// set the license text
-    pub async fn set_license(
+    /// set the license text, with the third-party code bundled with the lessons listed after it
+    pub fn set_license(
         &mut self,
-        text: String,
+        text: &str,
+        known: Option<&'static license::Known>,
+        third_party: &[(String, ThirdParty)],
         spoken_language: Option<spoken::Code>,
-    ) -> Result<(), Error> {
-        self.text = text;
-        self.spoken_language = spoken_language;
-        Ok(())
+    ) {
+        let mut markdown = text.to_string();
+        if !third_party.is_empty() {
+            markdown.push_str("\n\n## Third-party code in the lessons\n\n");
+            for (lesson, code) in third_party {
+                let name = license::by_id(&code.license)
+                    .map(|known| known.name)
+                    .unwrap_or(&code.license);
+                markdown.push_str(&format!("- {lesson}: {} under the {name}", code.name));
+                if let Some(url) = &code.url {
+                    markdown.push_str(&format!(" ({url})"));
+                }
+                markdown.push('\n');
+            }
+        }
+        let direction = spoken_language
+            .map(|spoken| spoken.get_text_direction())
+            .unwrap_or_default();
+        self.lb_state = LessonBoxState::from_markdown(&markdown).direction(direction);
+        self.known = known;
+        self.released = false;
     }
 
     fn recalculate_rect(&mut self, area: Rect) {
@@ -100,13 +125,17 @@ impl License<'_> {
         }
     }
 
-    // render the log messages
+    // render the license text with the summary above it
     fn render_license(&mut self, area: Rect, buf: &mut Buffer) {
         Widget::render(Clear, area, buf);
 
+        let name = match self.known {
+            Some(known) => format!("/ License: {} /", known.id),
+            None => "/ License /".to_string(),
+        };
         let title = Line::from(vec![
             Span::styled("─", Style::default().fg(Color::DarkGray)),
-            Span::styled("/ License /", Style::default().fg(Color::White)),
+            Span::styled(name, Style::default().fg(Color::White)),
         ]);
 
         let block = Block::default()
@@ -116,12 +145,31 @@ impl License<'_> {
             .style(Style::default().fg(Color::DarkGray))
             .borders(Borders::LEFT | Borders::RIGHT | Borders::TOP)
             .border_set(TOP_DIALOG_BORDER);
+        let inner = block.inner(area);
+        Widget::render(block, area, buf);
 
-        self.st.block(block);
-        self.st.style(Style::default().fg(Color::White));
+        let text_area = match self.known {
+            Some(known) => {
+                let [summary_area, text_area] =
+                    Layout::vertical([Constraint::Length(3), Constraint::Min(1)]).areas(inner);
+                let summary = Paragraph::new(vec![
+                    Line::styled(
+                        known.name,
+                        Style::default()
+                            .fg(Color::White)
+                            .add_modifier(Modifier::BOLD),
+                    ),
+                    Line::styled(known.summary, Style::default().fg(Color::Gray)),
+                ])
+                .wrap(Wrap { trim: true });
+                Widget::render(summary, summary_area, buf);
+                text_area
+            }
+            None => inner,
+        };
 
-        // render the scroll text
-        StatefulWidget::render(&mut self.st, area, buf, &mut self.text);
+        let lb = self.lb.clone().style(Style::default().fg(Color::White));
+        StatefulWidget::render(lb, text_area, buf, &mut self.lb_state);
     }
 
     // render the status bar at the bottom
@@ -129,7 +177,7 @@ impl License<'_> {
         let line = Line::from(vec![
             Span::styled("─", Style::default().fg(Color::DarkGray)),
             Span::styled(
-                "/ j,k scroll / ⤒ top / ⤓ bottom / b back / q quit /",
+                "/ j,k scroll / ⤒ top / ⤓ bottom / / find / n next / b back / q quit /",
                 Style::default().fg(Color::White),
             ),
         ]);
@@ -151,20 +199,71 @@ impl License<'_> {
         &mut self,
         event: tui::Event,
         to_ui: Sender<screens::Event>,
-        status: Arc<Mutex<Status>>,
+        status: StatusHandle,
     ) -> Result<(), Error> {
         match event {
-            tui::Event::ShowLicense(text) => {
-                info!("Setting license text");
-                let spoken = {
-                    let status = status.lock().unwrap();
-                    status.spoken_language()
+            tui::Event::ShowLicense(workshop) => {
+                info!("Loading the license for: {workshop}");
+                self.workshop = Some(workshop.clone());
+                let (spoken, programming, repository) = {
+                    let status = status.read().await;
+                    (
+                        status.spoken_language(),
+                        status.programming_language(),
+                        status.repository(),
+                    )
+                };
+                let Some(workshop_data) = repository.workshop(&workshop) else {
+                    warn!("Failed to load workshop data for: {workshop}");
+                    return Ok(());
+                };
+                let text = workshop_data.get_license().await?;
+                let defaults = workshop_data.get_defaults();
+                let spoken = spoken.unwrap_or(defaults.spoken_language);
+                let programming = programming.unwrap_or(defaults.programming_language);
+
+                // fall back to the license named in the metadata when the text isn't recognized
+                let known = match license::detect(&text) {
+                    Some(known) => Some(known),
+                    None => workshop_data
+                        .get_metadata(Some(spoken))
+                        .await
+                        .ok()
+                        .and_then(|metadata| license::by_id(&metadata.license)),
                 };
-                self.set_license(text, spoken).await?;
+
+                // list the external code bundled with each lesson, in lesson order
+                let lessons = workshop_data
+                    .get_lessons_data(Some(spoken), Some(programming))
+                    .await
+                    .unwrap_or_default();
+                let keys = lesson::ordered_keys(&lessons)
+                    .await
+                    .unwrap_or_else(|_| lessons.keys().cloned().collect());
+                let mut third_party = Vec::new();
+                for key in keys {
+                    if let Ok(lesson) = lessons[&key].get_metadata().await {
+                        third_party.extend(
+                            lesson
+                                .third_party
+                                .into_iter()
+                                .map(|code| (lesson.title.clone(), code)),
+                        );
+                    }
+                }
+
+                self.set_license(&text, known, &third_party, Some(spoken));
                 to_ui
                     .send((None, tui::Event::Show(Screens::License)).into())
                     .await?;
             }
+            tui::Event::Find(text) => {
+                if !self.lb_state.find_next(&text) {
+                    let toast = evt!(None, tui::Event::Toast(format!("\"{text}\" not found")));
+                    to_ui.send(toast.into()).await?;
+                }
+                self.find = text;
+            }
             _ => {
                 info!("Ignoring UI event: {:?}", event);
             }
@@ -177,19 +276,32 @@ impl License<'_> {
         &mut self,
         event: event::Event,
         to_ui: Sender<screens::Event>,
-        _status: Arc<Mutex<Status>>,
+        _status: StatusHandle,
     ) -> Result<(), Error> {
         if let event::Event::Key(key) = event {
             match key.code {
-                KeyCode::PageUp => self.st.scroll_top(),
-                KeyCode::PageDown => self.st.scroll_bottom(),
+                KeyCode::PageUp => self.lb_state.scroll_top(),
+                KeyCode::PageDown => self.lb_state.scroll_bottom(),
                 KeyCode::Char('b') | KeyCode::Esc => {
                     to_ui
                         .send((Some(Screens::Workshops), tui::Event::LoadWorkshops).into())
                         .await?;
                 }
-                KeyCode::Char('j') | KeyCode::Down => self.st.scroll_down(),
-                KeyCode::Char('k') | KeyCode::Up => self.st.scroll_up(),
+                KeyCode::Char('j') | KeyCode::Down => self.lb_state.highlight_down(),
+                KeyCode::Char('k') | KeyCode::Up => self.lb_state.highlight_up(),
+                KeyCode::Char('/') => {
+                    let find = Dialog::prompt(
+                        "Find in the License",
+                        &self.find,
+                        Answer::new(|text| evt!(Screens::License, tui::Event::Find(text))),
+                        None,
+                    );
+                    to_ui.send((None, tui::Event::Dialog(find)).into()).await?;
+                }
+                KeyCode::Char('n') if !self.find.is_empty() => {
+                    let find = evt!(Screens::License, tui::Event::Find(self.find.clone()));
+                    to_ui.send(find.into()).await?;
+                }
                 _ => {}
             }
         }
@@ -203,7 +315,7 @@ impl Screen for License<'_> {
         &mut self,
         event: screens::Event,
         to_ui: Sender<screens::Event>,
-        status: Arc<Mutex<Status>>,
+        status: StatusHandle,
     ) -> Result<(), Error> {
         match event {
             screens::Event::Input(input_event) => {
@@ -213,6 +325,37 @@ impl Screen for License<'_> {
         }
     }
 
+    // the license is reloaded when it is shown again, it is rarely looked at twice
+    async fn on_enter(
+        &mut self,
+        to_ui: Sender<screens::Event>,
+        _status: StatusHandle,
+    ) -> Result<(), Error> {
+        if let Some(workshop) = self.workshop.clone().filter(|_| self.released) {
+            let reload = evt!(Screens::License, tui::Event::ShowLicense(workshop));
+            to_ui.send(reload.into()).await?;
+        }
+        Ok(())
+    }
+
+    async fn on_leave(
+        &mut self,
+        _to_ui: Sender<screens::Event>,
+        _status: StatusHandle,
+    ) -> Result<(), Error> {
+        self.lb_state = LessonBoxState::default();
+        self.released = true;
+        Ok(())
+    }
+
+    fn actions(&self) -> Vec<screens::Action> {
+        vec![
+            screens::Action::new("Find in the license", KeyCode::Char('/')),
+            screens::Action::new("Find the next match", KeyCode::Char('n')),
+            screens::Action::new("Back", KeyCode::Char('b')),
+        ]
+    }
+
     fn render_screen(&mut self, area: Rect, buf: &mut Buffer) -> Result<(), Error> {
         self.recalculate_rect(area);
 
@@ -1,5 +1,6 @@
 use crate::{
     languages::spoken,
+    log::LogBuffer,
     ui::tui::{
         self,
         events::Evt,
@@ -7,7 +8,7 @@ use crate::{
         widgets::{ScrollLog, StatusBar, StatusMode},
         Screen,
     },
-    Error, Status,
+    Error, StatusHandle,
 };
 use crossterm::event::{self, KeyCode};
 use ratatui::{
@@ -16,11 +17,7 @@ use ratatui::{
     style::{Color, Style},
     symbols::border::Set,
     text::{Line, Span},
-    widgets::{block::Position, Block, Borders, Clear, Padding, StatefulWidget, Widget},
-};
-use std::{
-    collections::{HashMap, VecDeque},
-    sync::{Arc, Mutex, OnceLock},
+    widgets::{block::Position, Block, Borders, Clear, Padding, Widget},
 };
 use tokio::sync::mpsc::Sender;
 
@@ -46,33 +43,10 @@ const STATUS_BORDER: Set = Set {
     horizontal_bottom: "─",
 };
 
-// maps the log line prefix to the associated emoji
-static EMOJIS: OnceLock<HashMap<&'static str, String>> = OnceLock::new();
-
-fn emoji() -> &'static HashMap<&'static str, String> {
-    EMOJIS.get_or_init(|| {
-        let mut map = HashMap::new();
-        map.insert("* ", "⭐".to_string());
-        map.insert("v ", "✅".to_string());
-        map.insert("x ", "❌".to_string());
-        map.insert("r ", "🚀".to_string());
-        map.insert("y ", "🎉".to_string());
-        map.insert("n ", "😢".to_string());
-        map.insert("! ", "❗".to_string());
-        map.insert("^ ", "⚠️ ".to_string());
-        map.insert("i ", "ℹ️ ".to_string());
-        map.insert("> ", "↪️ ".to_string());
-        map.insert("< ", "↩️ ".to_string());
-        map
-    })
-}
-
 #[derive(Clone, Debug)]
 pub struct Log<'a> {
     /// the log messages
-    log: VecDeque<(Option<String>, String)>,
-    /// max log length
-    max_log: usize,
+    log: LogBuffer,
     /// scroll text widget
     st: ScrollLog<'a>,
     /// status bar widget
@@ -88,8 +62,8 @@ pub struct Log<'a> {
 }
 
 impl Log<'_> {
-    /// Create a new log Screen
-    pub fn new(max_log: usize) -> Self {
+    /// Create a new log Screen showing the messages in the given log buffer
+    pub fn new(log: LogBuffer) -> Self {
         let mut st = ScrollLog::default();
         st.scroll_newest();
         let mut sb = StatusBar::new();
@@ -101,8 +75,7 @@ impl Log<'_> {
         sb.set_block(block);
 
         Self {
-            log: VecDeque::default(),
-            max_log,
+            log,
             st,
             sb,
             area: Rect::default(),
@@ -112,6 +85,12 @@ impl Log<'_> {
         }
     }
 
+    /// Show a still throbber in the status bar instead of animating it
+    pub fn reduced_motion(mut self, reduced_motion: bool) -> Self {
+        self.sb.set_reduced_motion(reduced_motion);
+        self
+    }
+
     fn recalculate_rect(&mut self, area: Rect) {
         if self.area != area {
             let [_, hc, _] = Layout::horizontal([
@@ -131,20 +110,7 @@ impl Log<'_> {
     }
 
     fn add_message<S: AsRef<str>>(&mut self, msg: S) {
-        if msg.as_ref().len() < 2 {
-            // if the message is too short, we can't determine the type
-            return;
-        }
-
-        // add the message to the log
-        let msg = msg.as_ref().to_string();
-        self.log
-            .push_back((emoji().get(&msg[0..2]).cloned(), msg[2..].to_string()));
-
-        // if the log is too long, remove the oldest message
-        if self.log.len() > self.max_log {
-            self.log.pop_front();
-        }
+        self.log.push(msg);
     }
 
     // render the log messages
@@ -155,10 +121,17 @@ impl Log<'_> {
         let [log_area, status_bar_area] =
             Layout::vertical([Constraint::Percentage(100), Constraint::Min(1)]).areas(area);
 
-        let title = Line::from(vec![
+        let mut title = Line::from(vec![
             Span::styled("─", Style::default().fg(Color::DarkGray)),
             Span::styled("/ Log /", Style::default().fg(Color::White)),
         ]);
+        let dropped = self.log.dropped();
+        if dropped > 0 {
+            title.push_span(Span::styled(
+                format!(" {dropped} older lines dropped /"),
+                Style::default().fg(Color::DarkGray),
+            ));
+        }
 
         let block = Block::default()
             .title(title)
@@ -172,9 +145,13 @@ impl Log<'_> {
         self.st.style(Style::default().fg(Color::White));
 
         // render the scroll text
-        StatefulWidget::render(&mut self.st, log_area, buf, &mut self.log);
+        self.log
+            .with_lines(|lines| self.st.render_lines(log_area, buf, lines));
 
-        // render the command status line
+        // render the command status line, showing the latest command output
+        if let Some(output) = self.log.last_output() {
+            self.sb.update_message(output);
+        }
         Widget::render(&mut self.sb, status_bar_area, buf);
     }
 
@@ -187,7 +164,7 @@ impl Log<'_> {
             )
         } else {
             Span::styled(
-                "/ j,k scroll / ⤒ top / ⤓ bottom / ` back / q quit /",
+                "/ j,k scroll / ⤒ top / ⤓ bottom / x cancel / ` back / q quit /",
                 Style::default().fg(Color::White),
             )
         };
@@ -213,7 +190,7 @@ impl Log<'_> {
         &mut self,
         event: tui::Event,
         _to_ui: Sender<screens::Event>,
-        _status: Arc<Mutex<Status>>,
+        _status: StatusHandle,
     ) -> Result<(), Error> {
         match event {
             tui::Event::Log(msg) => self.add_message(msg),
@@ -231,8 +208,7 @@ impl Log<'_> {
                 }
             }
             tui::Event::CommandOutput(message, progress) => {
-                // Add to log as before
-                self.add_message(&message);
+                self.log.push_output(&message);
 
                 // Update status bar based on current mode
                 if let Some(progress_val) = progress {
@@ -241,8 +217,11 @@ impl Log<'_> {
                     self.sb.update_message(message);
                 }
             }
-            tui::Event::CommandCompleted(result, success, failure) => {
+            tui::Event::CommandCompleted(result, tui::Then { success, failure }) => {
                 self.sb.set_blank();
+                for line in result.summary() {
+                    self.add_message(format!("i {line}"));
+                }
                 if result.success {
                     self.add_message(format!("y {}", result.last_line));
                     self.add_message("< Press ↵ Enter to continue");
@@ -263,7 +242,7 @@ impl Log<'_> {
         &mut self,
         event: event::Event,
         to_ui: Sender<screens::Event>,
-        _status: Arc<Mutex<Status>>,
+        _status: StatusHandle,
     ) -> Result<(), Error> {
         if let event::Event::Key(key) = event {
             match key.code {
@@ -272,6 +251,9 @@ impl Log<'_> {
                 KeyCode::Char('j') | KeyCode::Char('J') | KeyCode::Down => self.st.scroll_newer(),
                 KeyCode::Char('k') | KeyCode::Char('K') | KeyCode::Up => self.st.scroll_older(),
                 KeyCode::Char('`') => to_ui.send((None, tui::Event::ToggleLog).into()).await?,
+                KeyCode::Char('x') | KeyCode::Char('X') => {
+                    to_ui.send((None, tui::Event::CancelCommand).into()).await?
+                }
                 KeyCode::Enter => {
                     if let Some(on_enter) = self.on_enter.take() {
                         to_ui.send(on_enter.into()).await?
@@ -290,12 +272,12 @@ impl Screen for Log<'_> {
         &mut self,
         event: screens::Event,
         to_ui: Sender<screens::Event>,
-        status: Arc<Mutex<Status>>,
+        status: StatusHandle,
     ) -> Result<(), Error> {
         match event {
             screens::Event::Input(input_event) => {
                 let spoken = {
-                    let status = status.lock().unwrap();
+                    let status = status.read().await;
                     status.spoken_language()
                 };
                 if self.spoken_language != spoken {
@@ -307,6 +289,13 @@ impl Screen for Log<'_> {
         }
     }
 
+    fn actions(&self) -> Vec<screens::Action> {
+        vec![
+            screens::Action::new("Continue", KeyCode::Enter),
+            screens::Action::new("Cancel the running command", KeyCode::Char('x')),
+        ]
+    }
+
     fn render_screen(&mut self, area: Rect, buf: &mut Buffer) -> Result<(), Error> {
         self.recalculate_rect(area);
 
@@ -322,4 +311,8 @@ impl Screen for Log<'_> {
         self.render_status(status_area, buf);
         Ok(())
     }
+
+    fn is_animating(&self) -> bool {
+        self.sb.is_animating()
+    }
 }
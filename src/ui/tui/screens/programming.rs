@@ -1,11 +1,12 @@
 use crate::{
-    languages::programming,
+    evt, fs,
+    languages::{self, programming},
     ui::tui::{
         self,
         screens::{self, Screens},
         Evt, Screen,
     },
-    Error, Status,
+    Error, StatusHandle,
 };
 use crossterm::event::{self, KeyCode};
 use ratatui::{
@@ -15,10 +16,11 @@ use ratatui::{
     symbols::border::Set,
     text::{Line, Span},
     widgets::{
-        block::Position, Block, Borders, Clear, List, ListState, Padding, StatefulWidget, Widget,
+        block::Position, Block, Borders, Clear, List, ListItem, ListState, Padding, StatefulWidget,
+        Widget,
     },
 };
-use std::sync::{Arc, Mutex};
+use std::collections::HashMap;
 use tokio::sync::mpsc::Sender;
 use tracing::debug;
 
@@ -64,6 +66,16 @@ pub struct Programming<'a> {
     list: List<'a>,
     /// programming language list state
     list_state: ListState,
+    /// how much there is in each language, workshops or lessons
+    counts: HashMap<programming::Code, usize>,
+    /// what is counted
+    unit: &'static str,
+    /// the typed text the languages are filtered by
+    filter: String,
+    /// is the filter being typed
+    filtering: bool,
+    /// the programming languages matching the filter
+    shown: Vec<programming::Code>,
 }
 
 impl Programming<'_> {
@@ -74,11 +86,16 @@ impl Programming<'_> {
         programming_language: Option<programming::Code>,
         allow_any: bool,
         event: Option<Evt>,
+        status: &StatusHandle,
     ) -> Result<(), Error> {
         self.programming_languages = programming_languages.to_vec();
         self.programming_language = programming_language;
         self.allow_any = allow_any;
         self.event = event;
+        self.count(status).await;
+        self.filter.clear();
+        self.filtering = false;
+        self.shown = self.programming_languages.clone();
 
         // calculate the vertical lines of the dialog
         self.lines = self.selection_lines(programming_languages) + 4;
@@ -87,14 +104,87 @@ impl Programming<'_> {
         self.area = Rect::default();
         self.centered = Rect::default();
 
+        self.cache_list();
+        self.list_state
+            .select(self.selection_from_language(self.programming_language));
+
+        Ok(())
+    }
+
+    // count the installed workshops in each language when filtering the workshops, otherwise the
+    // lessons of the workshop the language is picked for
+    async fn count(&mut self, status: &StatusHandle) {
+        let (workshop, spoken, repository) = {
+            let status = status.read().await;
+            (
+                status.workshop().map(String::from),
+                status.spoken_language(),
+                status.repository(),
+            )
+        };
+        self.counts.clear();
+        match workshop.filter(|_| !self.allow_any) {
+            Some(workshop) => {
+                self.unit = "lesson";
+                let Some(workshop_data) = repository.workshop(&workshop) else {
+                    return;
+                };
+                for &programming in &self.programming_languages {
+                    let count = workshop_data
+                        .get_lessons_data(spoken, Some(programming))
+                        .await
+                        .map(|lessons| lessons.len())
+                        .unwrap_or_default();
+                    self.counts.insert(programming, count);
+                }
+            }
+            None => {
+                self.unit = "workshop";
+                let workshops = fs::application::scan_workshops()
+                    .await
+                    .map(|scan| scan.workshops)
+                    .unwrap_or_default();
+                for &programming in &self.programming_languages {
+                    let count = workshops
+                        .values()
+                        .filter(|workshop_data| {
+                            workshop_data.is_selected(spoken, Some(programming))
+                        })
+                        .count();
+                    self.counts.insert(programming, count);
+                }
+            }
+        }
+    }
+
+    // filter the languages by the typed text and rebuild the list
+    fn apply_filter(&mut self) {
+        let filter = self.filter.to_lowercase();
+        self.shown = self
+            .programming_languages
+            .iter()
+            .filter(|code| {
+                code.get_name().to_lowercase().contains(&filter)
+                    || code.to_string().to_lowercase().contains(&filter)
+            })
+            .cloned()
+            .collect();
+        self.cache_list();
+        self.list_state.select(Some(0));
+    }
+
+    // rebuild the cached list
+    fn cache_list(&mut self) {
+        let title = if self.filtering || !self.filter.is_empty() {
+            format!("/ Select a Programming Language: {} /", self.filter)
+        } else {
+            "/ Select a Programming Language /".to_string()
+        };
         let title = Line::from(vec![
             Span::styled("─", Style::default().fg(Color::DarkGray)),
-            Span::styled(
-                "/ Select a Programming Language /",
-                Style::default().fg(Color::White),
-            ),
+            Span::styled(title, Style::default().fg(Color::White)),
         ]);
-        self.list = List::new(self.language_names())
+        self.list = List::new(self.language_items())
             .block(
                 Block::default()
                     .title(title)
@@ -112,10 +202,16 @@ impl Programming<'_> {
             )
             .style(Style::default().fg(Color::White))
             .highlight_symbol("> ");
-        self.list_state
-            .select(self.selection_from_language(self.programming_language));
+    }
 
-        Ok(())
+    // does the filter leave nothing to pick
+    fn filter_matches_nothing(&self) -> bool {
+        self.shown.is_empty() && !self.any_shown()
+    }
+
+    // is the "Any" choice shown, it doesn't match any filter
+    fn any_shown(&self) -> bool {
+        self.allow_any && self.filter.is_empty()
     }
 
     fn selection_lines<T, S: AsRef<[T]>>(&self, s: S) -> u16 {
@@ -128,7 +224,7 @@ impl Programming<'_> {
     }
 
     fn lang_to_selection(&self, index: usize) -> usize {
-        if self.allow_any {
+        if self.any_shown() {
             // If "Any" is allowed, the index is shifted by 1
             index + 1 // shift other indices by 1
         } else {
@@ -137,7 +233,7 @@ impl Programming<'_> {
     }
 
     fn selection_to_lang(&self, index: usize) -> usize {
-        if self.allow_any {
+        if self.any_shown() {
             // If "Any" is allowed, the index is shifted back by 1
             index.saturating_sub(1)
         } else {
@@ -145,35 +241,50 @@ impl Programming<'_> {
         }
     }
 
-    fn language_names(&self) -> Vec<String> {
-        let mut names = if self.allow_any {
-            vec!["Any".to_string()]
+    // the language names with how much there is in each, the ones without anything are grayed
+    fn language_items(&self) -> Vec<ListItem<'static>> {
+        let mut items = if self.any_shown() {
+            vec![ListItem::new("Any")]
         } else {
             vec![]
         };
-        names.extend(
-            self.programming_languages
-                .iter()
-                .map(|code| code.get_name().to_string()),
-        );
-        names
+        items.extend(self.shown.iter().map(|code| {
+            let name = code.get_name();
+            match self.counts.get(code) {
+                Some(&count) => {
+                    let plural = if count == 1 { "" } else { "s" };
+                    let line = Line::from(vec![
+                        Span::raw(format!("{name:<22}")),
+                        Span::styled(
+                            format!("{count} {}{plural}", self.unit),
+                            Style::default().fg(Color::Gray),
+                        ),
+                    ]);
+                    if count == 0 {
+                        ListItem::new(line).style(Style::default().fg(Color::DarkGray))
+                    } else {
+                        ListItem::new(line)
+                    }
+                }
+                None => ListItem::new(name.to_string()),
+            }
+        }));
+        items
     }
 
     fn language_from_selection(&self, index: usize) -> Option<programming::Code> {
-        if index == 0 && self.allow_any {
+        if index == 0 && self.any_shown() {
             // If "Any" is selected, return None
             None
         } else {
             // Otherwise, get the programming language from the list
-            self.programming_languages
-                .get(self.selection_to_lang(index))
-                .cloned()
+            self.shown.get(self.selection_to_lang(index)).cloned()
         }
     }
 
     fn selection_from_language(&self, lang: Option<programming::Code>) -> Option<usize> {
         match lang {
-            Some(code) => match self.programming_languages.iter().position(|&c| c == code) {
+            Some(code) => match self.shown.iter().position(|&c| c == code) {
                 Some(index) => Some(self.lang_to_selection(index)),
                 None => Some(0),
             },
@@ -213,7 +324,11 @@ impl Programming<'_> {
         let line = Line::from(vec![
             Span::styled("─", Style::default().fg(Color::DarkGray)),
             Span::styled(
-                "/ j,k scroll / ↵ select /",
+                if self.filtering {
+                    "/ type to filter / ↑,↓ scroll / ↵ select / esc clear /"
+                } else {
+                    "/ j,k scroll / ↵ select / / filter /"
+                },
                 Style::default().fg(Color::White),
             ),
         ]);
@@ -235,21 +350,24 @@ impl Programming<'_> {
         &mut self,
         event: tui::Event,
         to_ui: Sender<screens::Event>,
-        status: Arc<Mutex<Status>>,
+        status: StatusHandle,
     ) -> Result<(), Error> {
         match event {
-            tui::Event::ChangeProgrammingLanguage(all_languages, programming, allow_any, next) => {
+            tui::Event::ChangeProgrammingLanguage(tui::ChangeLanguage {
+                languages: all_languages,
+                selected: programming,
+                allow_any,
+                next,
+            }) => {
                 let spoken_language = {
-                    let status = status
-                        .lock()
-                        .map_err(|e| Error::StatusLock(e.to_string()))?;
+                    let status = status.read().await;
                     status.spoken_language()
                 };
                 match spoken_language {
                     Some(spoken_language) => {
                         if let Some(programming_languages) = all_languages.get(&spoken_language) {
                             debug!("Changing programming language");
-                            self.init(programming_languages, programming, allow_any, next)
+                            self.init(programming_languages, programming, allow_any, next, &status)
                                 .await?;
                             to_ui
                                 .send(
@@ -271,8 +389,14 @@ impl Programming<'_> {
                         programming_languages.sort();
                         programming_languages.dedup();
                         debug!("Changing programming language");
-                        self.init(&programming_languages, programming, allow_any, next)
-                            .await?;
+                        self.init(
+                            &programming_languages,
+                            programming,
+                            allow_any,
+                            next,
+                            &status,
+                        )
+                        .await?;
                         to_ui
                             .send((None, tui::Event::Show(screens::Screens::Programming)).into())
                             .await?;
@@ -291,10 +415,36 @@ impl Programming<'_> {
         &mut self,
         event: event::Event,
         to_ui: Sender<screens::Event>,
-        _status: Arc<Mutex<Status>>,
+        _status: StatusHandle,
     ) -> Result<(), Error> {
         if let event::Event::Key(key) = event {
+            // while the filter is typed, the letters go to it
+            if self.filtering {
+                match key.code {
+                    KeyCode::Char(c) => {
+                        self.filter.push(c);
+                        self.apply_filter();
+                        return Ok(());
+                    }
+                    KeyCode::Backspace => {
+                        self.filter.pop();
+                        self.apply_filter();
+                        return Ok(());
+                    }
+                    KeyCode::Esc => {
+                        self.filter.clear();
+                        self.filtering = false;
+                        self.apply_filter();
+                        return Ok(());
+                    }
+                    _ => {}
+                }
+            }
             match key.code {
+                KeyCode::Char('/') => {
+                    self.filtering = true;
+                    self.cache_list();
+                }
                 KeyCode::PageUp => self.list_state.select_first(),
                 KeyCode::PageDown => self.list_state.select_last(),
                 KeyCode::Char('b') | KeyCode::Esc => {
@@ -305,20 +455,33 @@ impl Programming<'_> {
                 KeyCode::Char('j') | KeyCode::Down => self.list_state.select_next(),
                 KeyCode::Char('k') | KeyCode::Up => self.list_state.select_previous(),
                 KeyCode::Enter => {
-                    // take the event leaving None in its place
-                    let event = self.event.take();
-                    if let Some(selected) = self.list_state.selected() {
-                        let programming_language = self.language_from_selection(selected);
-                        let set_programming_language = (
+                    let Some(selected) = self.list_state.selected() else {
+                        return Ok(());
+                    };
+                    let programming_language = self.language_from_selection(selected);
+                    if self.filter_matches_nothing()
+                        || programming_language
+                            .is_some_and(|programming| self.counts.get(&programming) == Some(&0))
+                    {
+                        let name = languages::programming_name(programming_language);
+                        let toast = evt!(
                             None,
-                            tui::Event::SetProgrammingLanguage(
-                                programming_language,
-                                None, // None, because we don't know if it should be the default
-                                event,
-                            ),
+                            tui::Event::Toast(format!("There are no {}s in {name}", self.unit))
                         );
-                        to_ui.send(set_programming_language.into()).await?;
+                        to_ui.send(toast.into()).await?;
+                        return Ok(());
                     }
+                    // take the event leaving None in its place
+                    let event = self.event.take();
+                    let set_programming_language = (
+                        None,
+                        tui::Event::SetProgrammingLanguage(
+                            programming_language,
+                            tui::AsDefault::Ask,
+                            event,
+                        ),
+                    );
+                    to_ui.send(set_programming_language.into()).await?;
                 }
                 _ => {}
             }
@@ -333,7 +496,7 @@ impl Screen for Programming<'_> {
         &mut self,
         event: screens::Event,
         to_ui: Sender<screens::Event>,
-        status: Arc<Mutex<Status>>,
+        status: StatusHandle,
     ) -> Result<(), Error> {
         match event {
             screens::Event::Input(input_event) => {
@@ -343,6 +506,18 @@ impl Screen for Programming<'_> {
         }
     }
 
+    fn takes_text_input(&self) -> bool {
+        self.filtering
+    }
+
+    fn actions(&self) -> Vec<screens::Action> {
+        vec![
+            screens::Action::new("Select the highlighted language", KeyCode::Enter),
+            screens::Action::new("Filter the languages", KeyCode::Char('/')),
+            screens::Action::new("Back", KeyCode::Char('b')),
+        ]
+    }
+
     fn render_screen(&mut self, area: Rect, buf: &mut Buffer) -> Result<(), Error> {
         self.recalculate_rect(area);
 
@@ -0,0 +1,358 @@
+use crate::{
+    evt,
+    fs::search::{self, Match},
+    ui::tui::{
+        self, screens,
+        widgets::{Answer, Dialog, ScrollBox},
+        Screen, Screens,
+    },
+    Error, StatusHandle,
+};
+use crossterm::event::{self, KeyCode, KeyEvent};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Alignment, Constraint, Flex, Layout, Rect},
+    style::{Color, Modifier, Style},
+    symbols::border::Set,
+    text::{Line, Span},
+    widgets::{block::Position, Block, Borders, List, ListState, Padding, StatefulWidget, Widget},
+};
+use tokio::sync::mpsc::Sender;
+use tracing::{debug, info};
+
+const TOP_LEFT_BORDER: Set = Set {
+    top_left: "┌",
+    top_right: "┐",
+    bottom_left: "│",
+    bottom_right: "│",
+    vertical_left: "│",
+    vertical_right: "│",
+    horizontal_top: "─",
+    horizontal_bottom: " ",
+};
+
+const TOP_BOX_BORDER: Set = Set {
+    top_left: "─",
+    top_right: "┐",
+    bottom_left: " ",
+    bottom_right: "│",
+    vertical_left: " ",
+    vertical_right: "│",
+    horizontal_top: "─",
+    horizontal_bottom: " ",
+};
+
+const STATUS_BORDER: Set = Set {
+    top_left: " ",
+    top_right: " ",
+    bottom_left: "└",
+    bottom_right: "┘",
+    vertical_left: "│",
+    vertical_right: "│",
+    horizontal_top: " ",
+    horizontal_bottom: "─",
+};
+
+/// The dialog asking what to search the installed workshops for
+pub fn search_dialog(query: &str) -> Dialog {
+    Dialog::prompt(
+        "Search Workshops",
+        query,
+        Answer::new(|query| evt!(Screens::Search, tui::Event::Search(query))),
+        None,
+    )
+}
+
+/// The screen listing the lines of the installed workshops that match a search
+#[derive(Clone, Debug, Default)]
+pub struct Search<'a> {
+    /// what was searched for
+    query: String,
+    /// the matching lines
+    matches: Vec<Match>,
+    /// the cached list
+    items: List<'a>,
+    /// the list state of the items
+    items_state: ListState,
+    /// the lines around the selected match
+    details: ScrollBox<'a>,
+    /// set while the workshops are being searched
+    searching: bool,
+}
+
+impl Search<'_> {
+    /// set the matches for the query
+    fn init(&mut self, query: String, matches: Vec<Match>) {
+        self.query = query;
+        self.matches = matches;
+        self.searching = false;
+
+        let items = self
+            .matches
+            .iter()
+            .map(|found| found.to_string())
+            .collect::<Vec<_>>();
+        self.items = List::new(items)
+            .highlight_style(
+                Style::default()
+                    .fg(Color::Black)
+                    .bg(Color::White)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .style(Style::default().fg(Color::White))
+            .highlight_symbol("> ");
+        self.items_state
+            .select((!self.matches.is_empty()).then_some(0));
+        self.cache_selected();
+    }
+
+    // update the details box for the selected match
+    fn cache_selected(&mut self) {
+        let details = match self.selected() {
+            Some(found) => {
+                let lines = found
+                    .context
+                    .iter()
+                    .map(|(number, line)| {
+                        let mark = if *number == found.line { ">" } else { " " };
+                        format!("{mark}{number:>5} │ {line}")
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                let open = if found.lesson().is_some() {
+                    "Press enter to open the lesson."
+                } else {
+                    "Press enter to open the workshop."
+                };
+                format!("{found}\n\n{lines}\n\n{open}")
+            }
+            None if self.searching => format!("Searching for \"{}\"...", self.query),
+            None => format!("Nothing matches \"{}\"", self.query),
+        };
+        self.details.set_text(details);
+        self.details.scroll_top();
+    }
+
+    // get the selected match
+    fn selected(&self) -> Option<&Match> {
+        self.items_state
+            .selected()
+            .and_then(|index| self.matches.get(index))
+    }
+
+    /// render the list of matches and the details
+    fn render_matches(&mut self, area: Rect, buf: &mut Buffer) {
+        let [items_area, details_area] =
+            Layout::horizontal([Constraint::Percentage(40), Constraint::Percentage(60)])
+                .areas(area);
+
+        let title = Line::from(vec![
+            Span::styled("─", Style::default().fg(Color::DarkGray)),
+            Span::styled(
+                format!("/ Search: {} ({}) /", self.query, self.matches.len()),
+                Style::default().fg(Color::White),
+            ),
+        ]);
+        let items = self.items.clone().block(
+            Block::default()
+                .title(title)
+                .padding(Padding::uniform(1))
+                .style(Style::default().fg(Color::White))
+                .border_style(Style::default().fg(Color::DarkGray))
+                .borders(Borders::LEFT | Borders::TOP | Borders::RIGHT)
+                .border_set(TOP_LEFT_BORDER),
+        );
+        StatefulWidget::render(items, items_area, buf, &mut self.items_state);
+
+        let title = Line::from(vec![
+            Span::styled("─", Style::default().fg(Color::DarkGray)),
+            Span::styled("/ Context /", Style::default().fg(Color::DarkGray)),
+        ]);
+        self.details.block(
+            Block::default()
+                .title(title)
+                .padding(Padding::uniform(1))
+                .style(Style::default().fg(Color::White))
+                .border_style(Style::default().fg(Color::DarkGray))
+                .borders(Borders::LEFT | Borders::TOP | Borders::RIGHT)
+                .border_set(TOP_BOX_BORDER),
+        );
+        Widget::render(&mut self.details, details_area, buf);
+    }
+
+    // render the keyboard shortcuts
+    fn render_status(&mut self, area: Rect, buf: &mut Buffer) {
+        let title = Line::from(vec![
+            Span::styled("─", Style::default().fg(Color::DarkGray)),
+            Span::styled(
+                "/ j,k scroll / ↵ open / s search again / b back / q quit /",
+                Style::default().fg(Color::White),
+            ),
+        ]);
+        let block = Block::default()
+            .title(title)
+            .title_style(Style::default().fg(Color::White))
+            .title_position(Position::Bottom)
+            .title_alignment(Alignment::Left)
+            .style(Style::default().fg(Color::DarkGray))
+            .borders(Borders::LEFT | Borders::BOTTOM | Borders::RIGHT)
+            .border_set(STATUS_BORDER)
+            .padding(Padding::horizontal(1));
+
+        Widget::render(block, area, buf);
+    }
+
+    /// handle UI events
+    pub async fn handle_ui_event(
+        &mut self,
+        event: tui::Event,
+        to_ui: Sender<screens::Event>,
+        _status: StatusHandle,
+    ) -> Result<(), Error> {
+        match event {
+            tui::Event::Search(query) => {
+                info!("Searching the workshops for: {query}");
+                self.query = query.clone();
+                self.matches.clear();
+                self.items = List::default();
+                self.items_state.select(None);
+                self.searching = true;
+                self.cache_selected();
+                to_ui
+                    .send((None, tui::Event::Show(Screens::Search)).into())
+                    .await?;
+
+                // search in the background since reading every lesson takes a while
+                tokio::spawn(async move {
+                    let matches = search::search(query.clone()).await;
+                    let _ = to_ui
+                        .send(
+                            (
+                                Some(Screens::Search),
+                                tui::Event::SearchResults(query, matches),
+                            )
+                                .into(),
+                        )
+                        .await;
+                });
+            }
+            tui::Event::SearchResults(query, matches) => {
+                // a newer search replaced this one
+                if query == self.query {
+                    debug!("{} matches for: {query}", matches.len());
+                    self.init(query, matches);
+                }
+            }
+            _ => {
+                debug!("Ignoring UI event: {:?}", event);
+            }
+        }
+        Ok(())
+    }
+
+    /// handle input events
+    pub async fn handle_input_event(
+        &mut self,
+        event: event::Event,
+        to_ui: Sender<screens::Event>,
+        _status: StatusHandle,
+    ) -> Result<(), Error> {
+        if let event::Event::Key(key) = event {
+            match key.code {
+                KeyCode::PageUp => {
+                    self.items_state.select_first();
+                    self.cache_selected();
+                }
+                KeyCode::PageDown => {
+                    self.items_state.select_last();
+                    self.cache_selected();
+                }
+                KeyCode::Char('j') | KeyCode::Char('J') | KeyCode::Down => {
+                    self.items_state.select_next();
+                    self.cache_selected();
+                }
+                KeyCode::Char('k') | KeyCode::Char('K') | KeyCode::Up => {
+                    self.items_state.select_previous();
+                    self.cache_selected();
+                }
+                KeyCode::Char('s') | KeyCode::Char('S') | KeyCode::Char('/') => {
+                    let dialog = evt!(None, tui::Event::Dialog(search_dialog(&self.query)));
+                    to_ui.send(dialog.into()).await?;
+                }
+                KeyCode::Enter => {
+                    if let Some(found) = self.selected() {
+                        let open = evt!(
+                            None,
+                            tui::Event::OpenLesson(
+                                found.workshop.clone(),
+                                found.spoken,
+                                found.programming(),
+                                found.lesson().map(str::to_string),
+                            )
+                        );
+                        to_ui.send(open.into()).await?;
+                    }
+                }
+                KeyCode::Char('b') | KeyCode::Char('B') | KeyCode::Esc => {
+                    to_ui
+                        .send((Some(Screens::Workshops), tui::Event::RefreshWorkshops).into())
+                        .await?;
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl Screen for Search<'_> {
+    async fn handle_event(
+        &mut self,
+        event: screens::Event,
+        to_ui: Sender<screens::Event>,
+        status: StatusHandle,
+    ) -> Result<(), Error> {
+        match event {
+            screens::Event::Input(input_event) => {
+                self.handle_input_event(input_event, to_ui, status).await
+            }
+            screens::Event::Ui(_, ui_event) => self.handle_ui_event(ui_event, to_ui, status).await,
+        }
+    }
+
+    fn navigate(&mut self, key: KeyEvent, count: usize) -> bool {
+        match key.code {
+            KeyCode::Char('j') | KeyCode::Char('J') | KeyCode::Down => {
+                (0..count).for_each(|_| self.items_state.select_next())
+            }
+            KeyCode::Char('k') | KeyCode::Char('K') | KeyCode::Up => {
+                (0..count).for_each(|_| self.items_state.select_previous())
+            }
+            _ => return false,
+        }
+        self.cache_selected();
+        true
+    }
+
+    fn actions(&self) -> Vec<screens::Action> {
+        vec![
+            screens::Action::new("Open the highlighted match", KeyCode::Enter),
+            screens::Action::new("Search again", KeyCode::Char('s')),
+            screens::Action::new("Back to the workshops", KeyCode::Char('b')),
+        ]
+    }
+
+    fn render_screen(&mut self, area: Rect, buf: &mut Buffer) -> Result<(), Error> {
+        // this splits the screen into a top area and a one-line bottom area
+        let [matches_area, status_area] =
+            Layout::vertical([Constraint::Percentage(100), Constraint::Min(1)])
+                .flex(Flex::End)
+                .areas(area);
+
+        self.render_matches(matches_area, buf);
+        self.render_status(status_area, buf);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,419 @@
+use crate::{
+    evt,
+    languages::programming,
+    models::{setup, Error as ModelError},
+    ui::tui::{
+        self, screens,
+        widgets::{Dialog, ScrollBox},
+        Evt, Screen, Screens,
+    },
+    Error, StatusHandle,
+};
+use crossterm::event::{self, KeyCode, KeyEvent};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Alignment, Constraint, Flex, Layout, Rect},
+    style::{Color, Modifier, Style},
+    symbols::border::Set,
+    text::{Line, Span},
+    widgets::{block::Position, Block, Borders, List, ListState, Padding, StatefulWidget, Widget},
+};
+use std::{
+    collections::{BTreeSet, HashMap},
+    path::PathBuf,
+};
+use tokio::sync::mpsc::Sender;
+use tracing::{debug, info};
+
+const TOP_LEFT_BORDER: Set = Set {
+    top_left: "┌",
+    top_right: "┐",
+    bottom_left: "│",
+    bottom_right: "│",
+    vertical_left: "│",
+    vertical_right: "│",
+    horizontal_top: "─",
+    horizontal_bottom: " ",
+};
+
+const TOP_BOX_BORDER: Set = Set {
+    top_left: "─",
+    top_right: "┐",
+    bottom_left: " ",
+    bottom_right: "│",
+    vertical_left: " ",
+    vertical_right: "│",
+    horizontal_top: "─",
+    horizontal_bottom: " ",
+};
+
+const STATUS_BORDER: Set = Set {
+    top_left: " ",
+    top_right: " ",
+    bottom_left: "└",
+    bottom_right: "┘",
+    vertical_left: "│",
+    vertical_right: "│",
+    horizontal_top: " ",
+    horizontal_bottom: "─",
+};
+
+/// The screen showing the setup instructions of a workshop as a checklist to tick off before the
+/// first dependency check
+#[derive(Clone, Debug, Default)]
+pub struct Setup<'a> {
+    /// the workshop being set up
+    workshop: String,
+    /// the programming language the workshop is set up for
+    programming_language: Option<programming::Code>,
+    /// the steps of the setup instructions
+    steps: Vec<String>,
+    /// the steps ticked off, by index
+    done: BTreeSet<usize>,
+    /// the script that performs the setup, if the workshop has one
+    script: Option<PathBuf>,
+    /// the cached list
+    items: List<'a>,
+    /// the list state of the items
+    items_state: ListState,
+    /// the full text of the selected step
+    details: ScrollBox<'a>,
+    /// the event to send once the setup is finished
+    next: Option<Evt>,
+}
+
+impl Setup<'_> {
+    // update the checklist, keeping the selection
+    fn set_items(&mut self) {
+        let items = self
+            .steps
+            .iter()
+            .enumerate()
+            .map(|(index, step)| {
+                let mark = if self.done.contains(&index) { "x" } else { " " };
+                let first_line = step.lines().next().unwrap_or_default();
+                format!("[{mark}] {first_line}")
+            })
+            .collect::<Vec<_>>();
+        self.items = List::new(items)
+            .highlight_style(
+                Style::default()
+                    .fg(Color::Black)
+                    .bg(Color::White)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .style(Style::default().fg(Color::White))
+            .highlight_symbol("> ");
+        if self.items_state.selected().is_none() && !self.steps.is_empty() {
+            self.items_state.select(Some(0));
+        }
+        self.cache_selected();
+    }
+
+    // update the details box for the selected step
+    fn cache_selected(&mut self) {
+        let details = match self.items_state.selected() {
+            Some(index) if index < self.steps.len() => format!(
+                "Step {} of {}\n\n{}",
+                index + 1,
+                self.steps.len(),
+                self.steps[index]
+            ),
+            _ => String::new(),
+        };
+        self.details.set_text(details);
+        self.details.scroll_top();
+    }
+
+    /// render the checklist and the selected step
+    fn render_setup(&mut self, area: Rect, buf: &mut Buffer) {
+        let [items_area, details_area] =
+            Layout::horizontal([Constraint::Percentage(40), Constraint::Percentage(60)])
+                .areas(area);
+
+        let title = Line::from(vec![
+            Span::styled("─", Style::default().fg(Color::DarkGray)),
+            Span::styled(
+                format!(
+                    "/ Setup {}: {} of {} done /",
+                    self.workshop,
+                    self.done.len(),
+                    self.steps.len()
+                ),
+                Style::default().fg(Color::White),
+            ),
+        ]);
+        let items = self.items.clone().block(
+            Block::default()
+                .title(title)
+                .padding(Padding::uniform(1))
+                .style(Style::default().fg(Color::White))
+                .border_style(Style::default().fg(Color::DarkGray))
+                .borders(Borders::LEFT | Borders::TOP | Borders::RIGHT)
+                .border_set(TOP_LEFT_BORDER),
+        );
+        StatefulWidget::render(items, items_area, buf, &mut self.items_state);
+
+        let title = Line::from(vec![
+            Span::styled("─", Style::default().fg(Color::DarkGray)),
+            Span::styled("/ Step /", Style::default().fg(Color::DarkGray)),
+        ]);
+        self.details.block(
+            Block::default()
+                .title(title)
+                .padding(Padding::uniform(1))
+                .style(Style::default().fg(Color::White))
+                .border_style(Style::default().fg(Color::DarkGray))
+                .borders(Borders::LEFT | Borders::TOP | Borders::RIGHT)
+                .border_set(TOP_BOX_BORDER),
+        );
+        Widget::render(&mut self.details, details_area, buf);
+    }
+
+    // render the keyboard shortcuts
+    fn render_status(&mut self, area: Rect, buf: &mut Buffer) {
+        let run = if self.script.is_some() {
+            " r run setup script /"
+        } else {
+            ""
+        };
+        let title = Line::from(vec![
+            Span::styled("─", Style::default().fg(Color::DarkGray)),
+            Span::styled(
+                format!(
+                    "/ j,k scroll / space tick off /{run} ↵ finished setup / b back / q quit /"
+                ),
+                Style::default().fg(Color::White),
+            ),
+        ]);
+        let block = Block::default()
+            .title(title)
+            .title_style(Style::default().fg(Color::White))
+            .title_position(Position::Bottom)
+            .title_alignment(Alignment::Left)
+            .style(Style::default().fg(Color::DarkGray))
+            .borders(Borders::LEFT | Borders::BOTTOM | Borders::RIGHT)
+            .border_set(STATUS_BORDER)
+            .padding(Padding::horizontal(1));
+
+        Widget::render(block, area, buf);
+    }
+
+    /// handle UI events
+    pub async fn handle_ui_event(
+        &mut self,
+        event: tui::Event,
+        to_ui: Sender<screens::Event>,
+        status: StatusHandle,
+    ) -> Result<(), Error> {
+        match event {
+            tui::Event::LoadSetup(workshop, next) => {
+                let (spoken, programming, repository) = {
+                    let status = status.read().await;
+                    (
+                        status.spoken_language(),
+                        status.programming_language(),
+                        status.repository(),
+                    )
+                };
+                let workshop_data = repository
+                    .workshop(&workshop)
+                    .ok_or(ModelError::NoWorkshopSpecified)?;
+                let programming =
+                    programming.unwrap_or(workshop_data.get_defaults().programming_language);
+                let steps = workshop_data
+                    .get_setup_instructions(spoken, Some(programming))
+                    .await
+                    .map(|instructions| setup::steps(&instructions))
+                    .unwrap_or_default();
+                let script = workshop_data.get_setup_script_path(spoken, Some(programming));
+
+                // there is nothing to do without setup instructions or a setup script
+                if steps.is_empty() && script.is_none() {
+                    debug!("No setup steps for workshop: {workshop}");
+                    let finish = evt!(Screens::Setup, tui::Event::FinishSetup(workshop, next));
+                    to_ui.send(finish.into()).await?;
+                    return Ok(());
+                }
+
+                info!("Showing {} setup steps for: {workshop}", steps.len());
+                self.done = status
+                    .get(|status| status.setup_progress(&workshop, programming).done)
+                    .await;
+                self.workshop = workshop;
+                self.programming_language = Some(programming);
+                self.steps = steps;
+                self.script = script;
+                self.next = next;
+                self.items_state.select(None);
+                self.set_items();
+                to_ui
+                    .send((None, tui::Event::Show(Screens::Setup)).into())
+                    .await?;
+            }
+            tui::Event::FinishSetup(workshop, next) => {
+                info!("Setup finished for: {workshop}");
+                {
+                    let mut status = status.write().await;
+                    let programming = self
+                        .programming_language
+                        .filter(|_| workshop == self.workshop)
+                        .or(status.programming_language());
+                    if let Some(programming) = programming {
+                        status.finish_setup(&workshop, programming);
+                    }
+                }
+                if let Some(next) = next {
+                    to_ui.send(next.into()).await?;
+                }
+            }
+            _ => {
+                debug!("Ignoring UI event: {:?}", event);
+            }
+        }
+        Ok(())
+    }
+
+    /// handle input events
+    pub async fn handle_input_event(
+        &mut self,
+        event: event::Event,
+        to_ui: Sender<screens::Event>,
+        status: StatusHandle,
+    ) -> Result<(), Error> {
+        if let event::Event::Key(key) = event {
+            match key.code {
+                KeyCode::PageUp => {
+                    self.items_state.select_first();
+                    self.cache_selected();
+                }
+                KeyCode::PageDown => {
+                    self.items_state.select_last();
+                    self.cache_selected();
+                }
+                KeyCode::Char('j') | KeyCode::Char('J') | KeyCode::Down => {
+                    self.items_state.select_next();
+                    self.cache_selected();
+                }
+                KeyCode::Char('k') | KeyCode::Char('K') | KeyCode::Up => {
+                    self.items_state.select_previous();
+                    self.cache_selected();
+                }
+                KeyCode::Char(' ') | KeyCode::Char('x') | KeyCode::Char('X') => {
+                    if let (Some(index), Some(programming)) =
+                        (self.items_state.selected(), self.programming_language)
+                    {
+                        if index < self.steps.len() {
+                            if !self.done.remove(&index) {
+                                self.done.insert(index);
+                            }
+                            let mut status = status.write().await;
+                            status.toggle_setup_step(&self.workshop, programming, index);
+                        }
+                        self.set_items();
+                    }
+                }
+                KeyCode::Char('r') | KeyCode::Char('R') => {
+                    if let Some(script) = &self.script {
+                        // the script changes the user's environment, so always ask first
+                        let title = format!("Run the setup script {}?", script.display());
+                        let back = evt!(None, tui::Event::Show(Screens::Setup));
+                        let run = evt!(
+                            None,
+                            tui::Event::RunSetupScript(self.workshop.clone(), Some(back))
+                        );
+                        let confirm = evt!(
+                            None,
+                            tui::Event::Dialog(Dialog::yes_no(title, Some(run), None)),
+                        );
+                        to_ui.send(confirm.into()).await?;
+                    }
+                }
+                KeyCode::Enter => {
+                    let title = if self.done.len() < self.steps.len() {
+                        "Not every step is ticked off, finished the setup anyway?"
+                    } else {
+                        "I finished the setup"
+                    };
+                    let finish = evt!(
+                        Screens::Setup,
+                        tui::Event::FinishSetup(self.workshop.clone(), self.next.clone())
+                    );
+                    let confirm = evt!(
+                        None,
+                        tui::Event::Dialog(Dialog::yes_no(title, Some(finish), None)),
+                    );
+                    to_ui.send(confirm.into()).await?;
+                }
+                KeyCode::Char('b') | KeyCode::Char('B') | KeyCode::Esc => {
+                    let back = evt!(None, tui::Event::SetWorkshop(None, HashMap::new()));
+                    to_ui.send(back.into()).await?;
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl Screen for Setup<'_> {
+    async fn handle_event(
+        &mut self,
+        event: screens::Event,
+        to_ui: Sender<screens::Event>,
+        status: StatusHandle,
+    ) -> Result<(), Error> {
+        match event {
+            screens::Event::Input(input_event) => {
+                self.handle_input_event(input_event, to_ui, status).await
+            }
+            screens::Event::Ui(_, ui_event) => self.handle_ui_event(ui_event, to_ui, status).await,
+        }
+    }
+
+    fn navigate(&mut self, key: KeyEvent, count: usize) -> bool {
+        match key.code {
+            KeyCode::Char('j') | KeyCode::Char('J') | KeyCode::Down => {
+                (0..count).for_each(|_| self.items_state.select_next())
+            }
+            KeyCode::Char('k') | KeyCode::Char('K') | KeyCode::Up => {
+                (0..count).for_each(|_| self.items_state.select_previous())
+            }
+            _ => return false,
+        }
+        self.cache_selected();
+        true
+    }
+
+    fn actions(&self) -> Vec<screens::Action> {
+        let mut actions = vec![screens::Action::new(
+            "Tick off the highlighted step",
+            KeyCode::Char(' '),
+        )];
+        if self.script.is_some() {
+            actions.push(screens::Action::new(
+                "Run the setup script",
+                KeyCode::Char('r'),
+            ));
+        }
+        actions.extend([
+            screens::Action::new("Finish the setup", KeyCode::Enter),
+            screens::Action::new("Back to the workshops", KeyCode::Char('b')),
+        ]);
+        actions
+    }
+
+    fn render_screen(&mut self, area: Rect, buf: &mut Buffer) -> Result<(), Error> {
+        // this splits the screen into a top area and a one-line bottom area
+        let [setup_area, status_area] =
+            Layout::vertical([Constraint::Percentage(100), Constraint::Min(1)])
+                .flex(Flex::End)
+                .areas(area);
+
+        self.render_setup(setup_area, buf);
+        self.render_status(status_area, buf);
+
+        Ok(())
+    }
+}
@@ -1,7 +1,8 @@
 use crate::{
-    languages::spoken,
+    evt, fs,
+    languages::{self, spoken},
     ui::tui::{self, screens, Evt, Screen},
-    Error, Status,
+    Error, StatusHandle,
 };
 use crossterm::event::{self, KeyCode};
 use ratatui::{
@@ -11,10 +12,11 @@ use ratatui::{
     symbols::border::Set,
     text::{Line, Span},
     widgets::{
-        block::Position, Block, Borders, Clear, List, ListState, Padding, StatefulWidget, Widget,
+        block::Position, Block, Borders, Clear, List, ListItem, ListState, Padding, StatefulWidget,
+        Widget,
     },
 };
-use std::sync::{Arc, Mutex};
+use std::collections::HashMap;
 use tokio::sync::mpsc::Sender;
 use tracing::debug;
 
@@ -60,6 +62,16 @@ pub struct Spoken<'a> {
     list: List<'a>,
     /// spoken language list state
     list_state: ListState,
+    /// how much there is in each language, workshops or lessons
+    counts: HashMap<spoken::Code, usize>,
+    /// what is counted
+    unit: &'static str,
+    /// the typed text the languages are filtered by
+    filter: String,
+    /// is the filter being typed
+    filtering: bool,
+    /// the spoken languages matching the filter
+    shown: Vec<spoken::Code>,
 }
 
 impl Spoken<'_> {
@@ -70,11 +82,16 @@ impl Spoken<'_> {
         spoken_language: Option<spoken::Code>,
         allow_any: bool,
         event: Option<Evt>,
+        status: &StatusHandle,
     ) -> Result<(), Error> {
         self.spoken_languages = spoken_languages.to_vec();
         self.spoken_language = spoken_language;
         self.allow_any = allow_any;
         self.event = event;
+        self.count(status).await;
+        self.filter.clear();
+        self.filtering = false;
+        self.shown = self.spoken_languages.clone();
 
         // calculate the vertical lines of the dialog
         self.lines = self.selection_lines(spoken_languages) + 4;
@@ -83,14 +100,87 @@ impl Spoken<'_> {
         self.area = Rect::default();
         self.centered = Rect::default();
 
+        self.cache_list();
+        self.list_state
+            .select(self.selection_from_language(self.spoken_language));
+
+        Ok(())
+    }
+
+    // count the installed workshops in each language when filtering the workshops, otherwise the
+    // lessons of the workshop the language is picked for
+    async fn count(&mut self, status: &StatusHandle) {
+        let (workshop, repository) = {
+            let status = status.read().await;
+            (status.workshop().map(String::from), status.repository())
+        };
+        self.counts.clear();
+        match workshop.filter(|_| !self.allow_any) {
+            Some(workshop) => {
+                self.unit = "lesson";
+                let Some(workshop_data) = repository.workshop(&workshop) else {
+                    return;
+                };
+                for &spoken in &self.spoken_languages {
+                    let mut lessons = 0;
+                    for programming in
+                        workshop_data.get_programming_languages_for_spoken_language(spoken)
+                    {
+                        let count = workshop_data
+                            .get_lessons_data(Some(spoken), Some(programming))
+                            .await
+                            .map(|lessons| lessons.len())
+                            .unwrap_or_default();
+                        lessons = lessons.max(count);
+                    }
+                    self.counts.insert(spoken, lessons);
+                }
+            }
+            None => {
+                self.unit = "workshop";
+                let workshops = fs::application::scan_workshops()
+                    .await
+                    .map(|scan| scan.workshops)
+                    .unwrap_or_default();
+                for &spoken in &self.spoken_languages {
+                    let count = workshops
+                        .values()
+                        .filter(|workshop_data| workshop_data.is_selected(Some(spoken), None))
+                        .count();
+                    self.counts.insert(spoken, count);
+                }
+            }
+        }
+    }
+
+    // filter the languages by the typed text and rebuild the list
+    fn apply_filter(&mut self) {
+        let filter = self.filter.to_lowercase();
+        self.shown = self
+            .spoken_languages
+            .iter()
+            .filter(|code| {
+                code.get_name_in_english().to_lowercase().contains(&filter)
+                    || code.get_name_in_native().to_lowercase().contains(&filter)
+            })
+            .cloned()
+            .collect();
+        self.cache_list();
+        self.list_state.select(Some(0));
+    }
+
+    // rebuild the cached list
+    fn cache_list(&mut self) {
+        let title = if self.filtering || !self.filter.is_empty() {
+            format!("/ Select a Spoken Language: {} /", self.filter)
+        } else {
+            "/ Select a Spoken Language /".to_string()
+        };
         let title = Line::from(vec![
             Span::styled("─", Style::default().fg(Color::DarkGray)),
-            Span::styled(
-                "/ Select a Spoken Language /",
-                Style::default().fg(Color::White),
-            ),
+            Span::styled(title, Style::default().fg(Color::White)),
         ]);
-        self.list = List::new(self.language_names())
+        self.list = List::new(self.language_items())
             .block(
                 Block::default()
                     .title(title)
@@ -108,10 +198,16 @@ impl Spoken<'_> {
             )
             .style(Style::default().fg(Color::White))
             .highlight_symbol("> ");
-        self.list_state
-            .select(self.selection_from_language(self.spoken_language));
+    }
 
-        Ok(())
+    // does the filter leave nothing to pick
+    fn filter_matches_nothing(&self) -> bool {
+        self.shown.is_empty() && !self.any_shown()
+    }
+
+    // is the "Any" choice shown, it doesn't match any filter
+    fn any_shown(&self) -> bool {
+        self.allow_any && self.filter.is_empty()
     }
 
     fn selection_lines<T, S: AsRef<[T]>>(&self, s: S) -> u16 {
@@ -124,7 +220,7 @@ impl Spoken<'_> {
     }
 
     fn lang_to_selection(&self, index: usize) -> usize {
-        if self.allow_any {
+        if self.any_shown() {
             // If "Any" is allowed, the index is shifted by 1
             index + 1 // shift other indices by 1
         } else {
@@ -133,7 +229,7 @@ impl Spoken<'_> {
     }
 
     fn selection_to_lang(&self, index: usize) -> usize {
-        if self.allow_any {
+        if self.any_shown() {
             // If "Any" is allowed, the index is shifted back by 1
             index.saturating_sub(1)
         } else {
@@ -141,35 +237,50 @@ impl Spoken<'_> {
         }
     }
 
-    fn language_names(&self) -> Vec<String> {
-        let mut names = if self.allow_any {
-            vec!["Any".to_string()]
+    // the language names with how much there is in each, the ones without anything are grayed
+    fn language_items(&self) -> Vec<ListItem<'static>> {
+        let mut items = if self.any_shown() {
+            vec![ListItem::new("Any")]
         } else {
             vec![]
         };
-        names.extend(
-            self.spoken_languages
-                .iter()
-                .map(|code| code.get_name_in_english().to_string()),
-        );
-        names
+        items.extend(self.shown.iter().map(|code| {
+            let name = code.get_name_in_english();
+            match self.counts.get(code) {
+                Some(&count) => {
+                    let plural = if count == 1 { "" } else { "s" };
+                    let line = Line::from(vec![
+                        Span::raw(format!("{name:<22}")),
+                        Span::styled(
+                            format!("{count} {}{plural}", self.unit),
+                            Style::default().fg(Color::Gray),
+                        ),
+                    ]);
+                    if count == 0 {
+                        ListItem::new(line).style(Style::default().fg(Color::DarkGray))
+                    } else {
+                        ListItem::new(line)
+                    }
+                }
+                None => ListItem::new(name.to_string()),
+            }
+        }));
+        items
     }
 
     fn language_from_selection(&self, index: usize) -> Option<spoken::Code> {
-        if index == 0 && self.allow_any {
+        if index == 0 && self.any_shown() {
             // If "Any" is selected, return None
             None
         } else {
             // Otherwise, get the programming language from the list
-            self.spoken_languages
-                .get(self.selection_to_lang(index))
-                .cloned()
+            self.shown.get(self.selection_to_lang(index)).cloned()
         }
     }
 
     fn selection_from_language(&self, lang: Option<spoken::Code>) -> Option<usize> {
         match lang {
-            Some(code) => match self.spoken_languages.iter().position(|&c| c == code) {
+            Some(code) => match self.shown.iter().position(|&c| c == code) {
                 Some(index) => Some(self.lang_to_selection(index)),
                 None => Some(0),
             },
@@ -209,7 +320,11 @@ impl Spoken<'_> {
         let line = Line::from(vec![
             Span::styled("─", Style::default().fg(Color::DarkGray)),
             Span::styled(
-                "/ j,k scroll / ↵ select /",
+                if self.filtering {
+                    "/ type to filter / ↑,↓ scroll / ↵ select / esc clear /"
+                } else {
+                    "/ j,k scroll / ↵ select / / filter /"
+                },
                 Style::default().fg(Color::White),
             ),
         ]);
@@ -231,14 +346,19 @@ impl Spoken<'_> {
         &mut self,
         event: tui::Event,
         to_ui: Sender<screens::Event>,
-        _status: Arc<Mutex<Status>>,
+        status: StatusHandle,
     ) -> Result<(), Error> {
         match event {
-            tui::Event::ChangeSpokenLanguage(all_languages, spoken, allow_any, next) => {
+            tui::Event::ChangeSpokenLanguage(tui::ChangeLanguage {
+                languages: all_languages,
+                selected: spoken,
+                allow_any,
+                next,
+            }) => {
                 let mut spoken_languages = all_languages.keys().cloned().collect::<Vec<_>>();
                 spoken_languages.sort();
                 debug!("Changing spoken language");
-                self.init(&spoken_languages, spoken, allow_any, next)
+                self.init(&spoken_languages, spoken, allow_any, next, &status)
                     .await?;
                 to_ui
                     .send((None, tui::Event::Show(screens::Screens::Spoken)).into())
@@ -256,10 +376,36 @@ impl Spoken<'_> {
         &mut self,
         event: event::Event,
         to_ui: Sender<screens::Event>,
-        _status: Arc<Mutex<Status>>,
+        _status: StatusHandle,
     ) -> Result<(), Error> {
         if let event::Event::Key(key) = event {
+            // while the filter is typed, the letters go to it
+            if self.filtering {
+                match key.code {
+                    KeyCode::Char(c) => {
+                        self.filter.push(c);
+                        self.apply_filter();
+                        return Ok(());
+                    }
+                    KeyCode::Backspace => {
+                        self.filter.pop();
+                        self.apply_filter();
+                        return Ok(());
+                    }
+                    KeyCode::Esc => {
+                        self.filter.clear();
+                        self.filtering = false;
+                        self.apply_filter();
+                        return Ok(());
+                    }
+                    _ => {}
+                }
+            }
             match key.code {
+                KeyCode::Char('/') => {
+                    self.filtering = true;
+                    self.cache_list();
+                }
                 KeyCode::PageUp => self.list_state.select_first(),
                 KeyCode::PageDown => self.list_state.select_last(),
                 KeyCode::Char('b') | KeyCode::Esc => {
@@ -271,20 +417,29 @@ impl Spoken<'_> {
                 KeyCode::Char('j') | KeyCode::Down => self.list_state.select_next(),
                 KeyCode::Char('k') | KeyCode::Up => self.list_state.select_previous(),
                 KeyCode::Enter => {
-                    // take the event leaving None in its place
-                    let event = self.event.take();
-                    if let Some(selected) = self.list_state.selected() {
-                        let spoken_language = self.language_from_selection(selected);
-                        let set_spoken_language = (
+                    let Some(selected) = self.list_state.selected() else {
+                        return Ok(());
+                    };
+                    let spoken_language = self.language_from_selection(selected);
+                    if self.filter_matches_nothing()
+                        || spoken_language
+                            .is_some_and(|spoken| self.counts.get(&spoken) == Some(&0))
+                    {
+                        let name = languages::spoken_name(spoken_language);
+                        let toast = evt!(
                             None,
-                            tui::Event::SetSpokenLanguage(
-                                spoken_language,
-                                None, // None, because we don't know if it should be the default
-                                event,
-                            ),
+                            tui::Event::Toast(format!("There are no {}s in {name}", self.unit))
                         );
-                        to_ui.send(set_spoken_language.into()).await?;
+                        to_ui.send(toast.into()).await?;
+                        return Ok(());
                     }
+                    // take the event leaving None in its place
+                    let event = self.event.take();
+                    let set_spoken_language = (
+                        None,
+                        tui::Event::SetSpokenLanguage(spoken_language, tui::AsDefault::Ask, event),
+                    );
+                    to_ui.send(set_spoken_language.into()).await?;
                 }
                 _ => {}
             }
@@ -299,7 +454,7 @@ impl Screen for Spoken<'_> {
         &mut self,
         event: screens::Event,
         to_ui: Sender<screens::Event>,
-        status: Arc<Mutex<Status>>,
+        status: StatusHandle,
     ) -> Result<(), Error> {
         match event {
             screens::Event::Input(input_event) => {
@@ -309,6 +464,18 @@ impl Screen for Spoken<'_> {
         }
     }
 
+    fn takes_text_input(&self) -> bool {
+        self.filtering
+    }
+
+    fn actions(&self) -> Vec<screens::Action> {
+        vec![
+            screens::Action::new("Select the highlighted language", KeyCode::Enter),
+            screens::Action::new("Filter the languages", KeyCode::Char('/')),
+            screens::Action::new("Back", KeyCode::Char('b')),
+        ]
+    }
+
     fn render_screen(&mut self, area: Rect, buf: &mut Buffer) -> Result<(), Error> {
         self.recalculate_rect(area);
 
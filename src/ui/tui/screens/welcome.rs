@@ -1,71 +1,253 @@
 use crate::{
-    ui::tui::{self, screens, Screen},
-    Error, Status,
+    evt, fs,
+    languages::{programming, spoken},
+    models::{catalog::CatalogEntry, Catalog as CatalogIndex},
+    ui::tui::{
+        self,
+        screens::{self, workshops, Screens},
+        widgets::Dialog,
+        Screen,
+    },
+    Error, StatusHandle,
 };
 use crossterm::event::{self, KeyCode};
 use ratatui::{
     buffer::Buffer,
     layout::{Alignment, Constraint, Flex, Layout, Offset, Rect},
-    style::{Color, Style},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
     widgets::{Block, Borders, Clear, Padding, Paragraph, Widget, Wrap},
 };
-use std::sync::{Arc, Mutex};
+use std::{
+    collections::HashSet,
+    time::{Duration, Instant, SystemTime},
+};
 use tokio::sync::mpsc::Sender;
+use tracing::debug;
+
+/// The number of recently updated workshops listed
+const RECENT_WORKSHOPS: usize = 5;
+
+/// The number of catalog workshops highlighted
+const HIGHLIGHTS: usize = 3;
+
+/// How long the intro takes to type out
+const INTRO_DURATION: Duration = Duration::from_millis(1200);
+
+/// The intro typed out when the home page is first shown
+const INTRO: &str = "Learn peer-to-peer networking one lesson at a time.";
 
+/// Where the student left off
 #[derive(Clone, Debug)]
-pub struct Welcome<'a> {
-    /// the cached rect from last render
-    area: Rect,
-    /// the cached calculated rect
-    centered: Rect,
-    /// the cached paragraph
-    text: Paragraph<'a>,
+struct Resume {
+    /// the workshop to resume
+    workshop: String,
+    /// the spoken language it was taken in
+    spoken: spoken::Code,
+    /// the programming language it was taken in
+    programming: Option<programming::Code>,
+    /// the lesson to resume, if one was opened
+    lesson: Option<String>,
+    /// the workshop and lesson titles
+    label: String,
 }
 
-impl Default for Welcome<'_> {
-    fn default() -> Self {
-        Self {
-            area: Rect::default(),
-            centered: Rect::default(),
-            text: Paragraph::new("")
-                .block(
-                    Block::default()
-                        .title(" Workshop v1.0 ")
-                        .padding(Padding::horizontal(1))
-                        .style(Style::default().fg(Color::White))
-                        .borders(Borders::ALL),
+/// An installed workshop with when it last changed
+#[derive(Clone, Debug)]
+struct Recent {
+    /// the workshop name
+    name: String,
+    /// the workshop title
+    title: String,
+    /// when the workshop was installed or last updated
+    updated: Option<SystemTime>,
+}
+
+/// The home page, with where to resume, the recently updated workshops, and a few workshops from
+/// the catalog
+#[derive(Clone, Debug, Default)]
+pub struct Welcome {
+    /// when the intro started typing out
+    started: Option<Instant>,
+    /// show the intro all at once
+    reduced_motion: bool,
+    /// where the student left off
+    resume: Option<Resume>,
+    /// the installed workshops, most recently updated first
+    recent: Vec<Recent>,
+    /// the names of all of the installed workshops
+    installed: HashSet<String>,
+    /// the catalog workshops that aren't installed yet
+    highlights: Vec<CatalogEntry>,
+}
+
+impl Welcome {
+    /// Show the intro all at once instead of typing it out
+    pub fn reduced_motion(mut self, reduced_motion: bool) -> Self {
+        self.reduced_motion = reduced_motion;
+        self
+    }
+
+    // load the resume target and the installed workshops
+    async fn load(&mut self, status: &StatusHandle) {
+        let (resume, repository) = {
+            let status = status.read().await;
+            let current = status.workshop().map(|workshop| {
+                (
+                    workshop.to_string(),
+                    status.spoken_language(),
+                    status.programming_language(),
+                    status.lesson().map(String::from),
                 )
-                .alignment(Alignment::Center)
-                .wrap(Wrap { trim: true }),
+            });
+            // when no workshop is selected, resume the one opened last
+            let last = status
+                .recent_workshops()
+                .into_iter()
+                .next()
+                .map(|workshop| {
+                    let context = status
+                        .workshop_context(&workshop)
+                        .cloned()
+                        .unwrap_or_default();
+                    (
+                        workshop,
+                        context.spoken_language,
+                        context.programming_language,
+                        context.lesson,
+                    )
+                });
+            (current.or(last), status.repository())
+        };
+
+        self.resume = None;
+        if let Some((workshop, spoken, programming, lesson)) = resume {
+            debug!("Resume target: {workshop} {lesson:?}");
+            if let Some(workshop_data) = repository.workshop(&workshop) {
+                let spoken = spoken.unwrap_or(workshop_data.get_defaults().spoken_language);
+                let mut label = match workshop_data.get_metadata(Some(spoken)).await {
+                    Ok(metadata) => metadata.title,
+                    Err(_) => workshop.clone(),
+                };
+                if let Some(lesson) = lesson.as_ref() {
+                    let lesson_title = match workshop_data
+                        .get_lessons_data(Some(spoken), programming)
+                        .await
+                    {
+                        Ok(lessons) => match lessons.get(lesson) {
+                            Some(lesson_data) => lesson_data
+                                .get_metadata()
+                                .await
+                                .map(|metadata| metadata.title)
+                                .ok(),
+                            None => None,
+                        },
+                        Err(_) => None,
+                    };
+                    label = format!("{label}: {}", lesson_title.unwrap_or(lesson.clone()));
+                }
+                self.resume = Some(Resume {
+                    workshop,
+                    spoken,
+                    programming,
+                    lesson,
+                    label,
+                });
+            }
         }
-    }
-}
 
-impl Welcome<'_> {
-    fn recalculate_rect(&mut self, area: Rect) {
-        if self.area != area {
-            let [_, hc, _] = Layout::horizontal([
-                Constraint::Fill(1),
-                Constraint::Max(44),
-                Constraint::Fill(1),
-            ])
-            .areas(area);
-            [_, self.centered, _] = Layout::vertical([
-                Constraint::Fill(1),
-                Constraint::Max(10),
-                Constraint::Fill(1),
-            ])
-            .areas(hc);
+        let workshops = fs::application::scan_workshops()
+            .await
+            .map(|scan| scan.workshops)
+            .unwrap_or_default();
+        self.installed = workshops.keys().cloned().collect();
+        let mut recent = Vec::new();
+        for (name, workshop_data) in workshops {
+            let spoken = workshop_data.get_defaults().spoken_language;
+            let title = match workshop_data.get_metadata(Some(spoken)).await {
+                Ok(metadata) => metadata.title,
+                Err(_) => name.clone(),
+            };
+            recent.push(Recent {
+                updated: fs::application::last_updated(workshop_data.get_path()),
+                name,
+                title,
+            });
         }
+        recent.sort_by(|a, b| b.updated.cmp(&a.updated).then(a.title.cmp(&b.title)));
+        recent.truncate(RECENT_WORKSHOPS);
+        self.recent = recent;
+        self.highlights
+            .retain(|entry| !self.installed.contains(&entry.name));
     }
 
-    // render the dialog
-    fn render_dialog(&mut self, area: Rect, buf: &mut Buffer) {
-        // clear popup area
-        Widget::render(Clear, area, buf);
+    // the lines of the home page
+    fn lines(&self) -> Vec<Line<'_>> {
+        let heading = Style::default()
+            .fg(Color::White)
+            .add_modifier(Modifier::BOLD);
+        let key = Style::default().fg(Color::Yellow);
+        let dim = Style::default().fg(Color::DarkGray);
 
-        // render the list of programming language names
-        Widget::render(&self.text, area, buf);
+        // type the intro out a character at a time
+        let typed = match self.started {
+            Some(started) if !self.reduced_motion => {
+                let chars = INTRO.chars().count();
+                let elapsed = started.elapsed().as_millis() as usize;
+                chars.min(elapsed * chars / INTRO_DURATION.as_millis() as usize)
+            }
+            _ => INTRO.chars().count(),
+        };
+        let mut lines = vec![
+            Line::styled("Welcome to the libp2p workshop", heading),
+            Line::from(INTRO.chars().take(typed).collect::<String>()),
+            Line::default(),
+        ];
+
+        if let Some(resume) = self.resume.as_ref() {
+            lines.push(Line::styled("Pick up where you left off", heading));
+            lines.push(Line::from(vec![
+                Span::styled(" r ", key),
+                Span::raw(resume.label.clone()),
+            ]));
+            lines.push(Line::default());
+        }
+
+        if !self.recent.is_empty() {
+            lines.push(Line::styled("Recently updated", heading));
+            for (index, recent) in self.recent.iter().enumerate() {
+                let mut spans = vec![
+                    Span::styled(format!(" {} ", index + 1), key),
+                    Span::raw(recent.title.clone()),
+                ];
+                if let Some(updated) = recent.updated {
+                    let seconds = SystemTime::now()
+                        .duration_since(updated)
+                        .unwrap_or_default()
+                        .as_secs();
+                    spans.push(Span::styled(format!("  updated {}", age(seconds)), dim));
+                }
+                lines.push(Line::from(spans));
+            }
+            lines.push(Line::default());
+        }
+
+        if !self.highlights.is_empty() {
+            lines.push(Line::styled("New in the catalog", heading));
+            for (index, entry) in self.highlights.iter().enumerate() {
+                let mut spans = vec![
+                    Span::styled(format!(" {} ", self.recent.len() + index + 1), key),
+                    Span::raw(entry.title.clone()),
+                ];
+                if !entry.difficulty.is_empty() {
+                    spans.push(Span::styled(format!("  {}", entry.difficulty), dim));
+                }
+                lines.push(Line::from(spans));
+            }
+            lines.push(Line::default());
+        }
+
+        lines
     }
 
     // render the status bar at the bottom
@@ -74,7 +256,7 @@ impl Welcome<'_> {
             .borders(Borders::NONE)
             .padding(Padding::horizontal(1));
 
-        let keys = Paragraph::new(" enter: Ok ")
+        let keys = Paragraph::new(" enter: workshops  r: resume  1-9: open  c: catalog  q: quit ")
             .block(block)
             .style(Style::default().fg(Color::Black).bg(Color::White))
             .wrap(Wrap { trim: true })
@@ -82,53 +264,258 @@ impl Welcome<'_> {
 
         Widget::render(keys, area, buf);
     }
+
+    /// handle UI events
+    pub async fn handle_ui_event(
+        &mut self,
+        event: tui::Event,
+        to_ui: Sender<screens::Event>,
+        status: StatusHandle,
+    ) -> Result<(), Error> {
+        match event {
+            tui::Event::LoadWelcome => {
+                self.load(&status).await;
+                self.started.get_or_insert_with(Instant::now);
+                to_ui
+                    .send((None, tui::Event::Show(Screens::Welcome)).into())
+                    .await?;
+
+                // fetch the catalog in the background, the home page is fine without it
+                let url = status.get(|status| status.catalog_url().to_string()).await;
+                tokio::spawn(async move {
+                    match CatalogIndex::fetch(&url).await {
+                        Ok(catalog) => {
+                            let highlights = evt!(
+                                Screens::Welcome,
+                                tui::Event::WelcomeHighlights(catalog.workshops)
+                            );
+                            let _ = to_ui.send(highlights.into()).await;
+                        }
+                        Err(e) => debug!("Failed to load the catalog highlights: {e}"),
+                    }
+                });
+            }
+            tui::Event::WelcomeHighlights(entries) => {
+                self.highlights = entries
+                    .into_iter()
+                    .filter(|entry| !self.installed.contains(&entry.name))
+                    .take(HIGHLIGHTS)
+                    .collect();
+            }
+            _ => {
+                debug!("Ignoring UI event: {:?}", event);
+            }
+        }
+        Ok(())
+    }
+
+    /// handle input events
+    pub async fn handle_input_event(
+        &mut self,
+        event: event::Event,
+        to_ui: Sender<screens::Event>,
+        status: StatusHandle,
+    ) -> Result<(), Error> {
+        if let event::Event::Key(key) = event {
+            match key.code {
+                KeyCode::Enter | KeyCode::Char('w') | KeyCode::Char('W') => {
+                    to_ui
+                        .send((Some(Screens::Workshops), tui::Event::LoadWorkshops).into())
+                        .await?;
+                }
+                KeyCode::Char('r') | KeyCode::Char('R') => {
+                    if let Some(resume) = self.resume.clone() {
+                        let open = evt!(
+                            None,
+                            tui::Event::OpenLesson(
+                                resume.workshop,
+                                resume.spoken,
+                                resume.programming,
+                                resume.lesson,
+                            )
+                        );
+                        to_ui.send(open.into()).await?;
+                    }
+                }
+                KeyCode::Char('c') | KeyCode::Char('C') => {
+                    to_ui
+                        .send((Some(Screens::Catalog), tui::Event::LoadCatalog).into())
+                        .await?;
+                }
+                KeyCode::Char(c @ '1'..='9') => {
+                    let index = c as usize - '1' as usize;
+                    if let Some(recent) = self.recent.get(index) {
+                        let repository = status.get(|status| status.repository()).await;
+                        if let Some(workshop_data) = repository.workshop(&recent.name) {
+                            workshops::choose_workshop(
+                                recent.name.clone(),
+                                &workshop_data,
+                                &to_ui,
+                                &status,
+                            )
+                            .await?;
+                        }
+                    } else if let Some(entry) = index
+                        .checked_sub(self.recent.len())
+                        .and_then(|index| self.highlights.get(index))
+                    {
+                        // install the workshop and go on to the refreshed workshops list
+                        let load_workshops = evt!(Screens::Workshops, tui::Event::LoadWorkshops);
+                        let hide_log = evt!(None, tui::Event::HideLog(Some(load_workshops)));
+                        let install = evt!(
+                            None,
+                            tui::Event::InstallWorkshop(entry.url.clone(), Some(hide_log)),
+                        );
+                        let confirm = evt!(
+                            None,
+                            tui::Event::Dialog(Dialog::yes_no(
+                                format!("Install {}?", entry.title),
+                                Some(install),
+                                None,
+                            )),
+                        );
+                        to_ui.send(confirm.into()).await?;
+                    }
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+// how long ago something happened, roughly
+fn age(seconds: u64) -> String {
+    const DAY: u64 = 24 * 60 * 60;
+    match seconds / DAY {
+        0 => "today".to_string(),
+        1 => "yesterday".to_string(),
+        days @ 2..=13 => format!("{days} days ago"),
+        days @ 14..=59 => format!("{} weeks ago", days / 7),
+        days @ 60..=729 => format!("{} months ago", days / 30),
+        days => format!("{} years ago", days / 365),
+    }
 }
 
 #[async_trait::async_trait]
-impl Screen for Welcome<'_> {
-    /// handle an input event
+impl Screen for Welcome {
     async fn handle_event(
         &mut self,
         event: screens::Event,
         to_ui: Sender<screens::Event>,
-        _status: Arc<Mutex<Status>>,
+        status: StatusHandle,
     ) -> Result<(), Error> {
-        if let screens::Event::Input(event::Event::Key(key)) = event {
-            if key.code == KeyCode::Enter {
-                to_ui
-                    .send((Some(screens::Screens::Workshops), tui::Event::LoadWorkshops).into())
-                    .await?;
+        match event {
+            screens::Event::Input(input_event) => {
+                self.handle_input_event(input_event, to_ui, status).await
             }
+            screens::Event::Ui(_, ui_event) => self.handle_ui_event(ui_event, to_ui, status).await,
         }
-        Ok(())
+    }
+
+    fn is_animating(&self) -> bool {
+        !self.reduced_motion
+            && self
+                .started
+                .is_some_and(|started| started.elapsed() < INTRO_DURATION)
+    }
+
+    fn actions(&self) -> Vec<screens::Action> {
+        let mut actions = vec![screens::Action::new("Browse the workshops", KeyCode::Enter)];
+        if let Some(resume) = self.resume.as_ref() {
+            actions.push(screens::Action::new(
+                format!("Resume {}", resume.label),
+                KeyCode::Char('r'),
+            ));
+        }
+        let mut key = '1';
+        for recent in &self.recent {
+            actions.push(screens::Action::new(
+                format!("Open {}", recent.title),
+                KeyCode::Char(key),
+            ));
+            key = (key as u8 + 1) as char;
+        }
+        for entry in &self.highlights {
+            actions.push(screens::Action::new(
+                format!("Install {}", entry.title),
+                KeyCode::Char(key),
+            ));
+            key = (key as u8 + 1) as char;
+        }
+        actions.push(screens::Action::new(
+            "Browse the workshop catalog",
+            KeyCode::Char('c'),
+        ));
+        actions
     }
 
     fn render_screen(&mut self, area: Rect, buf: &mut Buffer) -> Result<(), Error> {
-        self.recalculate_rect(area);
+        let lines = self.lines();
+
+        // center the page, sized to fit it
+        let height = lines.len() as u16 + 7;
+        let [_, hc, _] = Layout::horizontal([
+            Constraint::Fill(1),
+            Constraint::Max(72),
+            Constraint::Fill(1),
+        ])
+        .areas(area);
+        let [_, centered, _] = Layout::vertical([
+            Constraint::Fill(1),
+            Constraint::Max(height),
+            Constraint::Fill(1),
+        ])
+        .areas(hc);
 
         // clear area around the popup
-        Widget::render(Clear, self.centered, buf);
+        Widget::render(Clear, centered, buf);
 
         let centered_block = Block::default()
-            .padding(Padding::uniform(2))
+            .padding(Padding::uniform(1))
             .borders(Borders::NONE);
-        let working_area = centered_block.inner(self.centered);
+        let working_area = centered_block.inner(centered);
 
         // draw drop shadow
-        let mut shadow_area = working_area;
-        shadow_area = shadow_area.offset(Offset { x: 1, y: 1 });
+        let shadow_area = working_area.offset(Offset { x: 1, y: 1 });
         let block = Block::default()
             .borders(Borders::ALL)
             .border_style(Style::default().fg(Color::DarkGray).bg(Color::DarkGray));
         Widget::render(block, shadow_area, buf);
 
-        let [dialog_area, status_area] =
+        let [page_area, status_area] =
             Layout::vertical([Constraint::Percentage(100), Constraint::Min(1)])
                 .flex(Flex::End)
                 .areas(working_area);
 
-        self.render_dialog(dialog_area, buf);
+        Widget::render(Clear, page_area, buf);
+        let page = Paragraph::new(lines)
+            .block(
+                Block::default()
+                    .title(" Workshop v1.0 ")
+                    .padding(Padding::uniform(1))
+                    .style(Style::default().fg(Color::White))
+                    .borders(Borders::ALL),
+            )
+            .wrap(Wrap { trim: false });
+        Widget::render(page, page_area, buf);
         self.render_status(status_area, buf);
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_age() {
+        let day = 24 * 60 * 60;
+        assert_eq!(age(60), "today");
+        assert_eq!(age(day + 5), "yesterday");
+        assert_eq!(age(3 * day), "3 days ago");
+        assert_eq!(age(21 * day), "3 weeks ago");
+        assert_eq!(age(90 * day), "3 months ago");
+        assert_eq!(age(800 * day), "2 years ago");
+    }
+}
@@ -1,14 +1,18 @@
 use crate::{
     evt, fs,
     languages::{self, programming, spoken},
-    models::{workshop, Workshop, WorkshopData},
+    models::{
+        workshop::{self, Difficulty},
+        Workshop, WorkshopData,
+    },
+    status::{self, Achievement, Achievements, ProgressScope},
     ui::tui::{
-        self,
+        self, flow, glyphs,
         screens::{self, Screens},
-        widgets::{LessonBox, LessonBoxState, ScrollBox},
+        widgets::{Dialog, LessonBox, LessonBoxState, ScrollBox},
         Screen,
     },
-    Error, Status,
+    Error, StatusHandle,
 };
 use crossterm::event::{self, KeyCode};
 use ratatui::{
@@ -17,15 +21,78 @@ use ratatui::{
     style::{Color, Modifier, Style},
     symbols::border::Set,
     text::{Line, Span},
-    widgets::{block::Position, Block, Borders, List, ListState, Padding, StatefulWidget, Widget},
+    widgets::{
+        block::Position, Block, Borders, List, ListState, Padding, Paragraph, StatefulWidget,
+        Widget,
+    },
 };
 use std::{
     collections::{BTreeMap, HashMap},
     fmt,
-    sync::{Arc, Mutex},
+    sync::Arc,
+    time::{Duration, Instant},
 };
 use tokio::sync::mpsc::Sender;
-use tracing::{debug, error, info, info_span};
+use tracing::{debug, error, info, info_span, warn};
+
+/// The number of recently opened workshops listed at the top
+const RECENT_WORKSHOPS: usize = 3;
+
+/// An operation run on each of the marked workshops in turn, with a combined report at the end
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Batch {
+    /// update the workshops from their git remotes
+    Update,
+    /// uninstall the workshops and forget the progress in them
+    Uninstall,
+    /// pull the docker images the lesson checks of the workshops run in
+    PullImages,
+}
+
+impl Batch {
+    /// What is being done to the workshops, for the log
+    pub fn doing(&self) -> &'static str {
+        match self {
+            Batch::Update => "Updating",
+            Batch::Uninstall => "Uninstalling",
+            Batch::PullImages => "Pre-pulling the environments of",
+        }
+    }
+
+    /// What was done to the workshops, for the report
+    pub fn done(&self) -> &'static str {
+        match self {
+            Batch::Update => "updated",
+            Batch::Uninstall => "uninstalled",
+            Batch::PullImages => "pre-pulled",
+        }
+    }
+
+    /// The combined report of the batch given the workshops that failed and why
+    pub fn report(&self, total: usize, failed: &[(String, String)]) -> String {
+        let succeeded = total.saturating_sub(failed.len());
+        let mut report = format!("{succeeded} of {total} workshops {}", self.done());
+        if !failed.is_empty() {
+            let names = failed
+                .iter()
+                .map(|(workshop, _)| workshop.as_str())
+                .collect::<Vec<_>>()
+                .join(", ");
+            report.push_str(&format!(", failed: {names}"));
+        }
+        report
+    }
+}
+
+/// The color a difficulty is shown in, from green for the easiest to red for the hardest
+pub fn difficulty_color(difficulty: &Difficulty) -> Color {
+    match difficulty {
+        Difficulty::Beginner => Color::Green,
+        Difficulty::Intermediate => Color::Yellow,
+        Difficulty::Advanced => Color::Red,
+        Difficulty::Other(_) => Color::Gray,
+    }
+}
 
 const TOP_LEFT_BORDER: Set = Set {
     top_left: "┌",
@@ -38,6 +105,17 @@ const TOP_LEFT_BORDER: Set = Set {
     horizontal_bottom: " ",
 };
 
+const TROPHY_BORDER: Set = Set {
+    top_left: "├",
+    top_right: "┤",
+    bottom_left: "│",
+    bottom_right: "│",
+    vertical_left: "│",
+    vertical_right: "│",
+    horizontal_top: "─",
+    horizontal_bottom: " ",
+};
+
 const TOP_BOX_BORDER: Set = Set {
     top_left: "─",
     top_right: "┐",
@@ -129,17 +207,16 @@ impl Widget for &mut FocusedView<'_> {
 #[derive(Clone, Debug)]
 struct Cached {
     workshop: Workshop,
-    license: String,
 }
 
 #[derive(Clone, Debug, Default)]
 pub struct Workshops<'a> {
     /// the list of workshops
-    workshops: HashMap<String, WorkshopData>,
+    workshops: HashMap<String, Arc<WorkshopData>>,
     /// the currently selected workshop data
     selected: Option<Cached>,
-    /// the map of workshop titles to workshop keys in sorted order
-    titles_map: BTreeMap<String, String>,
+    /// the workshop titles and keys in the order they are listed
+    titles: Vec<(String, String)>,
     /// the views
     views: HashMap<&'static str, FocusedView<'a>>,
     /// currently focused view
@@ -148,6 +225,28 @@ pub struct Workshops<'a> {
     spoken_language: Option<spoken::Code>,
     /// the currently selected programming language
     programming_language: Option<programming::Code>,
+    /// the achievements shown in the trophy panel
+    achievements: Achievements,
+    /// the active time spent on each workshop
+    times: HashMap<String, Duration>,
+    /// the workshops pinned to the top of the list
+    pinned: Vec<String>,
+    /// the workshops opened before, most recently opened first
+    recent: Vec<String>,
+    /// only list the workshops of this difficulty
+    difficulty: Option<Difficulty>,
+    /// order the workshops from the easiest to the hardest
+    by_difficulty: bool,
+    /// the workshops marked for a batch operation, in the order they were marked
+    marked: Vec<String>,
+    /// the workshops that failed to load and why, listed after the others
+    failed: BTreeMap<String, String>,
+    /// when the workshops started loading, if they are still loading
+    loading: Option<Instant>,
+    /// the workshop to keep selected once the workshops are loaded
+    keep_selected: Option<String>,
+    /// show a still spinner while loading
+    reduced_motion: bool,
 }
 
 impl Workshops<'_> {
@@ -176,29 +275,105 @@ impl Workshops<'_> {
         }
     }
 
+    /// Show a still spinner instead of an animated one while the workshops are loading
+    pub fn reduced_motion(mut self, reduced_motion: bool) -> Self {
+        self.reduced_motion = reduced_motion;
+        self
+    }
+
+    // scan the data directories for the workshops in the languages in the background, the
+    // workshops are set once the scan finishes
+    fn scan(&mut self, to_ui: Sender<screens::Event>) {
+        self.loading = Some(Instant::now());
+        let (spoken, programming) = (self.spoken_language, self.programming_language);
+        tokio::spawn(async move {
+            let workshops = fs::application::scan_workshops_filtered(spoken, programming)
+                .await
+                .map(Box::new)
+                .map_err(|e| e.to_string());
+            let _ = to_ui
+                .send(
+                    (
+                        Some(Screens::Workshops),
+                        tui::Event::WorkshopsLoaded(spoken, programming, workshops),
+                    )
+                        .into(),
+                )
+                .await;
+        });
+    }
+
     /// set the workshops
     async fn init(
         &mut self,
-        workshops: &HashMap<String, WorkshopData>,
+        workshops: HashMap<String, WorkshopData>,
         spoken_language: Option<spoken::Code>,
         programming_language: Option<programming::Code>,
     ) -> Result<(), Error> {
-        self.workshops = workshops.clone();
+        self.workshops = workshops
+            .into_iter()
+            .map(|(key, workshop_data)| (key, Arc::new(workshop_data)))
+            .collect();
         self.spoken_language = spoken_language;
         self.programming_language = programming_language;
+        self.marked
+            .retain(|key| self.workshops.contains_key(key) || self.failed.contains_key(key));
 
-        // get the workshop titles
-        let t = self.get_titles().await?;
+        // set the workshop titles
+        self.set_titles();
 
-        if let Some(FocusedView::List(titles, state)) = self.views.get_mut("list") {
+        if let Some(FocusedView::List(_, state)) = self.views.get_mut("list") {
             // set the initial focus
-            if self.workshops.is_empty() {
+            if self.titles.is_empty() {
                 state.select(None);
             } else {
                 state.select_first();
             }
+        }
+
+        // cache all of the data for the selected workshop
+        self.cache_selected().await?;
+
+        Ok(())
+    }
+
+    // load the metadata of the workshops in the background, the titles are updated as each one
+    // finishes loading
+    fn load_metadata(&self, to_ui: Sender<screens::Event>) {
+        for (key, wd) in self.workshops.iter() {
+            if wd.get_loaded_metadata(self.spoken_language).is_some() {
+                continue;
+            }
+            let key = key.clone();
+            let wd = wd.clone();
+            let spoken_language = self.spoken_language;
+            let to_ui = to_ui.clone();
+            tokio::spawn(async move {
+                let event = match wd.get_metadata(spoken_language).await {
+                    Ok(_) => tui::Event::WorkshopLoaded(key),
+                    Err(e) => tui::Event::WorkshopLoadFailed(key, e.to_string()),
+                };
+                let _ = to_ui
+                    .send((Some(screens::Screens::Workshops), event).into())
+                    .await;
+            });
+        }
+    }
+
+    // set the list of workshop titles, keeping the selected workshop selected
+    fn set_titles(&mut self) {
+        let selected = self.get_selected_workshop_key();
+        let t = self.get_titles();
+        let index = selected
+            .and_then(|selected| self.get_workshop_keys().iter().position(|k| *k == selected));
 
-            // set the titles
+        if let Some(FocusedView::List(titles, state)) = self.views.get_mut("list") {
+            if index.is_some() {
+                state.select(index);
+            } else if state.selected().is_some() {
+                // the selected workshop is no longer listed
+                state.select((!t.is_empty()).then_some(0));
+            }
             *titles = List::new(t)
                 .highlight_style(
                     Style::default()
@@ -209,42 +384,158 @@ impl Workshops<'_> {
                 .style(Style::default().fg(Color::White))
                 .highlight_symbol("> ");
         }
-
-        // cache all of the data for the selected workshop
-        self.cache_selected().await?;
-
-        Ok(())
     }
 
-    // get the workshop titles with status indicators
-    async fn get_titles(&mut self) -> Result<Vec<String>, Error> {
+    // get the workshop titles with status indicators and difficulties, the workshops with metadata
+    // that is still loading are listed by their name. The pinned workshops come first, then the
+    // most recently opened ones, then the rest by title, or by difficulty when ordering by it.
+    // When filtering by difficulty, only the workshops of that difficulty are listed, along with
+    // the ones that are still loading.
+    fn get_titles(&mut self) -> Vec<Line<'static>> {
         debug!("Caching workshop titles");
-        self.titles_map.clear();
+        self.titles.clear();
 
         // Get workshops with their calculated status
-        let mut workshops_with_status: Vec<(String, String, workshop::Status)> = Vec::new();
+        let mut workshops_with_status = Vec::new();
         for (key, wd) in self.workshops.iter() {
-            let workshop = wd.get_metadata(self.spoken_language).await?;
-            let status = workshop.status.clone();
-            workshops_with_status.push((key.clone(), workshop.title.clone(), status));
+            match wd.get_loaded_metadata(self.spoken_language) {
+                Some(workshop) => {
+                    if self
+                        .difficulty
+                        .as_ref()
+                        .is_some_and(|difficulty| *difficulty != workshop.difficulty)
+                    {
+                        continue;
+                    }
+                    workshops_with_status.push((
+                        key.clone(),
+                        workshop.title,
+                        Some((workshop.status, workshop.difficulty)),
+                    ))
+                }
+                None => workshops_with_status.push((key.clone(), key.clone(), None)),
+            }
         }
 
-        // Sort by workshop title
-        workshops_with_status.sort_by(|a, b| a.1.cmp(&b.1));
-
+        // the pinned and recent workshops go at the top in their order, the rest by title
+        let recent = self
+            .recent
+            .iter()
+            .filter(|key| !self.pinned.contains(key))
+            .take(RECENT_WORKSHOPS)
+            .collect::<Vec<_>>();
+        let section = |key: &String| -> (usize, usize) {
+            if let Some(index) = self.pinned.iter().position(|k| k == key) {
+                (0, index)
+            } else if let Some(index) = recent.iter().position(|k| *k == key) {
+                (1, index)
+            } else {
+                (2, 0)
+            }
+        };
+        let mut titles = Vec::new();
         for (key, title, status) in workshops_with_status.iter() {
-            let status_indicator = match status {
-                workshop::Status::Completed => "✅ ",
-                workshop::Status::InProgress => "🤔 ",
-                workshop::Status::NotStarted => "   ",
+            let section = section(key);
+            let marker = match section.0 {
+                0 => "📌 ",
+                1 => "🕘 ",
+                _ => "",
+            };
+            let check = if self.marked.contains(key) {
+                "☑ "
+            } else {
+                ""
+            };
+            let title_with_status = match status {
+                Some((workshop::Status::Completed, _)) => format!("✅  {check}{marker}{title}"),
+                Some((workshop::Status::InProgress, _)) => format!("🤔  {check}{marker}{title}"),
+                Some((workshop::Status::NotStarted, _)) => format!("    {check}{marker}{title}"),
+                None => format!("    {check}{marker}{title} ..."),
             };
+            let difficulty = status.as_ref().map(|(_, difficulty)| difficulty.clone());
+            // the workshops that are still loading go after the ones of any difficulty
+            let rank = match &difficulty {
+                _ if !self.by_difficulty => 0,
+                Some(difficulty) => difficulty.rank(),
+                None => Difficulty::KNOWN.len() + 1,
+            };
+            titles.push((section, rank, title_with_status, key.clone(), difficulty));
+        }
+        titles.sort_by(|a, b| (a.0, a.1, &a.2, &a.3).cmp(&(b.0, b.1, &b.2, &b.3)));
+
+        let mut lines = Vec::new();
+        self.titles.clear();
+        for (_, _, title, key, difficulty) in titles {
+            let mut spans = vec![Span::raw(title.clone())];
+            if let Some(difficulty) = difficulty.filter(|d| !d.to_string().trim().is_empty()) {
+                spans.push(Span::styled(
+                    format!("  {difficulty}"),
+                    Style::default().fg(difficulty_color(&difficulty)),
+                ));
+            }
+            lines.push(Line::from(spans));
+            self.titles.push((title, key));
+        }
+
+        // the workshops that failed to load are grayed out at the end
+        for key in self.failed.keys() {
+            let check = if self.marked.contains(key) {
+                "☑ "
+            } else {
+                ""
+            };
+            let title = format!("⚠️  {check}{key} (failed to load)");
+            lines.push(Line::from(Span::styled(
+                title.clone(),
+                Style::default().fg(Color::DarkGray),
+            )));
+            self.titles.push((title, key.clone()));
+        }
+        lines
+    }
+
+    // show why the workshop failed to load in the info boxes
+    fn show_failure(&mut self, workshop_key: &str, error: &str) {
+        let text = format!(
+            "Failed to load {workshop_key}:\n{error}\n\nFix or update the workshop and press r to revalidate it, or press X to uninstall it."
+        );
+        for (_, v) in self.views.iter_mut() {
+            match v {
+                FocusedView::Metadata(scroll_box) => scroll_box.set_text(&text),
+                FocusedView::Description(_, state) | FocusedView::SetupInstructions(_, state) => {
+                    *state = LessonBoxState::from_markdown("");
+                }
+                _ => {}
+            }
+        }
+    }
 
-            let title_with_status = format!("{status_indicator} {title}");
-            self.titles_map
-                .insert(title_with_status.clone(), key.clone());
+    // the workshops a batch operation runs on, the marked ones or else the selected one
+    fn batch_targets(&self) -> Vec<String> {
+        if self.marked.is_empty() {
+            self.get_selected_workshop_key().into_iter().collect()
+        } else {
+            self.marked.clone()
         }
+    }
 
-        Ok(self.titles_map.keys().cloned().collect())
+    // cycle the difficulty filter through the known difficulties and back to all of them
+    fn next_difficulty(&mut self) {
+        self.difficulty = match &self.difficulty {
+            None => Difficulty::KNOWN.first().cloned(),
+            Some(difficulty) => Difficulty::KNOWN
+                .iter()
+                .skip_while(|known| *known != difficulty)
+                .nth(1)
+                .cloned(),
+        };
+        info!(
+            "Filtering workshops by difficulty: {}",
+            self.difficulty
+                .as_ref()
+                .map(Difficulty::to_string)
+                .unwrap_or_else(|| "any".to_string())
+        );
     }
 
     // cached selected workshop data
@@ -252,8 +543,18 @@ impl Workshops<'_> {
         debug!("Caching selected workshop data");
         self.selected = None;
         if let Some(workshop_key) = self.get_selected_workshop_key() {
+            if let Some(error) = self.failed.get(&workshop_key).cloned() {
+                self.show_failure(&workshop_key, &error);
+                return Ok(());
+            }
             if let Some(workshop_data) = self.workshops.get(&workshop_key) {
-                let workshop = workshop_data.get_metadata(self.spoken_language).await?;
+                let workshop = match workshop_data.get_metadata(self.spoken_language).await {
+                    Ok(workshop) => workshop,
+                    Err(e) => {
+                        self.show_failure(&workshop_key, &e.to_string());
+                        return Ok(());
+                    }
+                };
                 let languages = workshop_data.get_all_languages().clone();
                 let description = workshop_data
                     .get_description(self.spoken_language)
@@ -263,12 +564,24 @@ impl Workshops<'_> {
                     .get_setup_instructions(self.spoken_language, self.programming_language)
                     .await
                     .unwrap_or_default();
-                let license = workshop_data.get_license().await?;
+                let direction = self
+                    .spoken_language
+                    .unwrap_or(workshop_data.get_defaults().spoken_language)
+                    .get_text_direction();
+                let trust = match fs::install::Trust::load(&workshop_key) {
+                    fs::install::Trust::Verified(signer) => format!("✅ signed by {signer}"),
+                    fs::install::Trust::Unverified => {
+                        "⚠️  not signed by a trusted signer".to_string()
+                    }
+                    fs::install::Trust::Unknown => "Not checked".to_string(),
+                };
 
                 // update the scroll boxes
                 let metadata = format!(
-                    "Status: {}\nAuthors: {}\nCopyright: {}\nLicense: {}\nHomepage: {}\nDifficulty: {}\nLanguages:\n{}",
+                    "Status: {}\nTime spent: {}\nTrust: {}\nAuthors: {}\nCopyright: {}\nLicense: {}\nHomepage: {}\nDifficulty: {}\nLanguages:\n{}",
                     workshop.status,
+                    status::format_time(self.times.get(&workshop_key).copied().unwrap_or_default()),
+                    trust,
                     workshop
                         .authors
                         .iter()
@@ -300,12 +613,14 @@ impl Workshops<'_> {
                     match v {
                         FocusedView::Metadata(scroll_box) => scroll_box.set_text(&metadata),
                         FocusedView::Description(_, state) => {
-                            let mut lb = LessonBoxState::from_markdown(&description);
+                            let mut lb =
+                                LessonBoxState::from_markdown(&description).direction(direction);
                             lb.set_highlighted_line(false);
                             *state = lb;
                         }
                         FocusedView::SetupInstructions(_, state) => {
-                            let mut lb = LessonBoxState::from_markdown(&setup_instructions);
+                            let mut lb = LessonBoxState::from_markdown(&setup_instructions)
+                                .direction(direction);
                             lb.set_highlighted_line(false);
                             *state = lb;
                         }
@@ -313,7 +628,7 @@ impl Workshops<'_> {
                     }
                 }
 
-                self.selected = Some(Cached { workshop, license });
+                self.selected = Some(Cached { workshop });
 
                 return Ok(());
             }
@@ -342,7 +657,7 @@ impl Workshops<'_> {
         if let Some(v) = self.views.get_mut(self.focused) {
             match v {
                 FocusedView::List(_, state) => {
-                    if !self.workshops.is_empty() {
+                    if !self.titles.is_empty() {
                         state.select(Some(0));
                         self.cache_selected().await?;
                     }
@@ -387,7 +702,7 @@ impl Workshops<'_> {
         if let Some(v) = self.views.get_mut(self.focused) {
             match v {
                 FocusedView::List(_, state) => {
-                    if !self.workshops.is_empty() {
+                    if !self.titles.is_empty() {
                         let selected_index = state.selected().unwrap_or(0);
                         let next_index = (selected_index + 1).min(self.workshops.len() - 1);
                         state.select(Some(next_index));
@@ -412,7 +727,7 @@ impl Workshops<'_> {
         if let Some(v) = self.views.get_mut(self.focused) {
             match v {
                 FocusedView::List(_, state) => {
-                    if !self.workshops.is_empty() {
+                    if !self.titles.is_empty() {
                         let selected_index = state.selected().unwrap_or(0);
                         let prev_index = if selected_index > 0 {
                             selected_index - 1
@@ -439,7 +754,7 @@ impl Workshops<'_> {
 
     // get the selected workshop key
     fn get_selected_workshop_key(&self) -> Option<String> {
-        if self.workshops.is_empty() {
+        if self.titles.is_empty() {
             return None;
         }
         if let Some(FocusedView::List(_, state)) = self.views.get("list") {
@@ -450,9 +765,24 @@ impl Workshops<'_> {
         }
     }
 
+    // select the workshop with the given key, if it is in the list
+    async fn select_workshop_key(&mut self, workshop_key: &str) -> Result<(), Error> {
+        let index = self
+            .get_workshop_keys()
+            .iter()
+            .position(|k| k == workshop_key);
+        if let (Some(index), Some(FocusedView::List(_, state))) =
+            (index, self.views.get_mut("list"))
+        {
+            state.select(Some(index));
+            self.cache_selected().await?;
+        }
+        Ok(())
+    }
+
     // get the sorted list of workshop keys
     fn get_workshop_keys(&self) -> Vec<String> {
-        self.titles_map.values().cloned().collect()
+        self.titles.iter().map(|(_, key)| key.clone()).collect()
     }
 
     // get the cached URL for the selected workshop
@@ -464,25 +794,69 @@ impl Workshops<'_> {
         }
     }
 
-    // get the cached license text for the selected workshop
-    fn get_license(&self) -> Option<String> {
-        if let Some(Cached { license, .. }) = &self.selected {
-            Some(license.clone())
-        } else {
-            None
-        }
-    }
-
     /// render the workshop list and info
     fn render_workshops(&mut self, area: Rect, buf: &mut Buffer) {
         let [workshop_titles_area, workshop_info_area] =
             Layout::horizontal([Constraint::Percentage(30), Constraint::Percentage(70)])
                 .areas(area);
 
+        // the trophy panel has a row for each achievement, the streak, and the border
+        let [workshop_titles_area, trophy_area] = Layout::vertical([
+            Constraint::Min(1),
+            Constraint::Length(Achievement::ALL.len() as u16 + 2),
+        ])
+        .areas(workshop_titles_area);
+
         self.render_workshop_titles(workshop_titles_area, buf);
+        self.render_trophies(trophy_area, buf);
         self.render_workshop_info(workshop_info_area, buf);
     }
 
+    /// render the unlocked achievements and the current streak
+    fn render_trophies(&mut self, area: Rect, buf: &mut Buffer) {
+        let title = Line::from(vec![
+            Span::styled("─", Style::default().fg(Color::DarkGray)),
+            Span::styled("/ Trophies /", Style::default().fg(Color::DarkGray)),
+        ]);
+
+        let mut lines = Achievement::ALL
+            .iter()
+            .map(|achievement| {
+                if self.achievements.is_unlocked(*achievement) {
+                    Line::styled(
+                        format!("🏆 {achievement}"),
+                        Style::default().fg(Color::White),
+                    )
+                } else {
+                    Line::styled(
+                        format!("   {}", achievement.description()),
+                        Style::default().fg(Color::DarkGray),
+                    )
+                }
+            })
+            .collect::<Vec<_>>();
+        let streak = self.achievements.streak();
+        lines.push(Line::styled(
+            format!(
+                "   Streak: {streak} day{}",
+                if streak == 1 { "" } else { "s" }
+            ),
+            Style::default().fg(Color::White),
+        ));
+
+        Paragraph::new(lines)
+            .block(
+                Block::default()
+                    .title(title)
+                    .padding(Padding::horizontal(1))
+                    .style(Style::default().fg(Color::White))
+                    .border_style(Style::default().fg(Color::DarkGray))
+                    .borders(Borders::LEFT | Borders::TOP | Borders::RIGHT)
+                    .border_set(TROPHY_BORDER),
+            )
+            .render(area, buf);
+    }
+
     /// render the list of workshop titles
     fn render_workshop_titles(&mut self, area: Rect, buf: &mut Buffer) {
         // figure out the titles list border fg color based on what is focused
@@ -492,22 +866,45 @@ impl Workshops<'_> {
             Color::DarkGray
         };
 
-        let title = Line::from(vec![
+        let mut title = vec![
             Span::styled("─", Style::default().fg(Color::DarkGray)),
             Span::styled("/ Select a Workshop /", Style::default().fg(fg)),
-        ]);
+        ];
+        if let Some(difficulty) = &self.difficulty {
+            title.push(Span::styled(
+                format!(" {difficulty} /"),
+                Style::default().fg(difficulty_color(difficulty)),
+            ));
+        }
+        let spinner = self
+            .loading
+            .map(|started| glyphs::spinner(started.elapsed(), self.reduced_motion));
+        if let Some(spinner) = spinner {
+            title.push(Span::styled(
+                format!(" {spinner} /"),
+                Style::default().fg(Color::White),
+            ));
+        }
+        let title = Line::from(title);
+        let block = Block::default()
+            .title(title)
+            .padding(Padding::uniform(1))
+            .style(Style::default().fg(Color::White))
+            .border_style(Style::default().fg(Color::DarkGray))
+            .borders(Borders::LEFT | Borders::TOP | Borders::RIGHT)
+            .border_set(TOP_LEFT_BORDER);
+
+        // there is nothing to list until the first scan finishes
+        if let (Some(spinner), true) = (spinner, self.titles.is_empty()) {
+            Paragraph::new(format!("{spinner} Loading the workshops..."))
+                .block(block)
+                .render(area, buf);
+            return;
+        }
 
         if let Some(view) = self.views.get_mut("list") {
             if let FocusedView::List(list, _) = view {
-                *list = list.clone().block(
-                    Block::default()
-                        .title(title)
-                        .padding(Padding::uniform(1))
-                        .style(Style::default().fg(Color::White))
-                        .border_style(Style::default().fg(Color::DarkGray))
-                        .borders(Borders::LEFT | Borders::TOP | Borders::RIGHT)
-                        .border_set(TOP_LEFT_BORDER),
-                );
+                *list = list.clone().block(block);
             }
 
             Widget::render(view, area, buf);
@@ -596,7 +993,7 @@ impl Workshops<'_> {
         let title = Line::from(vec![
             Span::styled("─", Style::default().fg(Color::DarkGray)),
             Span::styled(
-                "/ j,k scroll / ⇥ focus / ↵ select / w homepage / l license / f filter / q quit /",
+                "/ j,k scroll / ⇥ focus / ↵ select / ^p commands / w homepage / l license / f filter / v level / o order / ^f search / m pin / ␣ mark / e pre-pull / r refresh / c catalog / u update / i integrity / p,P reset progress / X uninstall / d disk usage / h start / q quit /",
                 Style::default().fg(Color::White),
             ),
         ]);
@@ -643,14 +1040,14 @@ impl Workshops<'_> {
         &mut self,
         event: tui::Event,
         to_ui: Sender<screens::Event>,
-        status: Arc<Mutex<Status>>,
+        status: StatusHandle,
     ) -> Result<(), Error> {
         match event {
             tui::Event::LoadWorkshops => {
                 let span = info_span!("Workshops");
                 let _enter = span.enter();
                 let (spoken, programming) = {
-                    let status = status.lock().unwrap();
+                    let status = status.read().await;
                     (status.spoken_language(), status.programming_language())
                 };
                 info!(
@@ -658,12 +1055,87 @@ impl Workshops<'_> {
                     languages::spoken_name(spoken),
                     languages::programming_name(programming),
                 );
-                let workshops = fs::application::all_workshops_filtered(spoken, programming)?;
-                self.init(&workshops, spoken, programming).await?;
+                {
+                    let status = status.read().await;
+                    self.achievements = status.achievements().clone();
+                    self.pinned = status.pinned_workshops().to_vec();
+                    self.recent = status.recent_workshops();
+                }
+                // the workshops of other languages shouldn't linger while the new ones load
+                self.keep_selected = None;
+                self.init(HashMap::new(), spoken, programming).await?;
+                self.scan(to_ui.clone());
+                to_ui
+                    .send((None, tui::Event::Show(screens::Screens::Workshops)).into())
+                    .await?;
+            }
+            tui::Event::RefreshWorkshops => {
+                // rescan the data directories, keeping the current selection if possible
+                let selected = self.get_selected_workshop_key();
+                info!("Refreshing workshops");
+                {
+                    let status = status.read().await;
+                    self.pinned = status.pinned_workshops().to_vec();
+                    self.recent = status.recent_workshops();
+                }
+                self.keep_selected = selected;
+                self.scan(to_ui.clone());
                 to_ui
                     .send((None, tui::Event::Show(screens::Screens::Workshops)).into())
                     .await?;
             }
+            tui::Event::WorkshopsLoaded(spoken, programming, workshops) => {
+                // a newer load in other languages replaced this one
+                if (spoken, programming) != (self.spoken_language, self.programming_language) {
+                    debug!("Ignoring workshops loaded in other languages");
+                    return Ok(());
+                }
+                self.loading = None;
+                match workshops {
+                    Ok(scan) => {
+                        let fs::application::Scan { workshops, failed } = *scan;
+                        info!(
+                            "{} workshops loaded, {} failed to load",
+                            workshops.len(),
+                            failed.len()
+                        );
+                        self.failed = failed;
+                        {
+                            let status = status.read().await;
+                            self.times = workshops
+                                .keys()
+                                .map(|key| (key.clone(), status.workshop_time(key)))
+                                .collect();
+                        }
+                        self.init(workshops, spoken, programming).await?;
+                        if let Some(selected) = self.keep_selected.take() {
+                            self.select_workshop_key(&selected).await?;
+                        }
+                        self.load_metadata(to_ui.clone());
+                    }
+                    Err(e) => {
+                        error!("Failed to load the workshops: {e}");
+                    }
+                }
+            }
+            tui::Event::WorkshopLoadFailed(key, e) => {
+                warn!("Failed to load workshop {key}: {e}");
+                let selected = self.get_selected_workshop_key();
+                self.workshops.remove(&key);
+                self.failed.insert(key.clone(), e);
+                self.set_titles();
+                if selected.is_some_and(|selected| selected == key) {
+                    self.select_workshop_key(&key).await?;
+                }
+            }
+            tui::Event::WorkshopLoaded(key) => {
+                debug!("Workshop metadata loaded: {key}");
+                let selected = self.get_selected_workshop_key();
+                self.set_titles();
+                if self.get_selected_workshop_key() != selected {
+                    self.cache_selected().await?;
+                }
+            }
             _ => {
                 debug!("Ignoring UI event: {:?}", event);
             }
@@ -676,7 +1148,7 @@ impl Workshops<'_> {
         &mut self,
         event: event::Event,
         to_ui: Sender<screens::Event>,
-        status: Arc<Mutex<Status>>,
+        status: StatusHandle,
     ) -> Result<(), Error> {
         if let event::Event::Key(key) = event {
             match key.code {
@@ -685,12 +1157,12 @@ impl Workshops<'_> {
                 KeyCode::Char('j') | KeyCode::Char('J') | KeyCode::Down => self.next().await?,
                 KeyCode::Char('k') | KeyCode::Char('K') | KeyCode::Up => self.prev().await?,
                 KeyCode::Char('l') | KeyCode::Char('L') => {
-                    if let Some(license) = self.get_license() {
+                    if let Some(workshop_key) = self.get_selected_workshop_key() {
                         to_ui
                             .send(
                                 (
                                     Some(screens::Screens::License),
-                                    tui::Event::ShowLicense(license),
+                                    tui::Event::ShowLicense(workshop_key),
                                 )
                                     .into(),
                             )
@@ -699,38 +1171,182 @@ impl Workshops<'_> {
                         debug!("No selected workshop");
                     }
                 }
+                KeyCode::Char('f') if key.modifiers.contains(event::KeyModifiers::CONTROL) => {
+                    let dialog = evt!(None, tui::Event::Dialog(screens::search::search_dialog("")));
+                    to_ui.send(dialog.into()).await?;
+                }
                 KeyCode::Char('f') | KeyCode::Char('F') => {
                     // we're filtering workshops based on spoken and programming languages
                     // clear out the local status spoken and programming languages so we can
                     // set them from all valid selections
                     {
-                        let mut status = status
-                            .lock()
-                            .map_err(|e| Error::StatusLock(e.to_string()))?;
+                        let mut status = status.write().await;
                         status.set_spoken_language(None, false);
                         status.set_programming_language(None, false);
                     }
                     let all_languages = fs::application::get_all_languages()?;
-                    let set_workshop = evt!(Screens::Workshops, tui::Event::LoadWorkshops);
-                    let change_programming_language = evt!(
-                        Screens::Programming,
-                        tui::Event::ChangeProgrammingLanguage(
-                            all_languages.clone(),
+                    let load_workshops = evt!(Screens::Workshops, tui::Event::LoadWorkshops);
+                    let change_languages =
+                        flow::choose_languages(all_languages, load_workshops).build();
+                    if let Some(change_languages) = change_languages {
+                        to_ui.send(change_languages.into()).await?;
+                    }
+                }
+                KeyCode::Char('v') | KeyCode::Char('V') => {
+                    self.next_difficulty();
+                    self.set_titles();
+                    self.cache_selected().await?;
+                }
+                KeyCode::Char('o') | KeyCode::Char('O') => {
+                    self.by_difficulty = !self.by_difficulty;
+                    self.set_titles();
+                }
+                KeyCode::Char('r') | KeyCode::Char('R') => {
+                    to_ui
+                        .send((Some(Screens::Workshops), tui::Event::RefreshWorkshops).into())
+                        .await?;
+                }
+                KeyCode::Char('m') | KeyCode::Char('M') => {
+                    if let Some(workshop_key) = self.get_selected_workshop_key() {
+                        let pinned = {
+                            let mut status = status.write().await;
+                            let pinned = status.toggle_pinned(&workshop_key);
+                            self.pinned = status.pinned_workshops().to_vec();
+                            pinned
+                        };
+                        info!("Workshop {workshop_key} pinned: {pinned}");
+                        self.set_titles();
+                    }
+                }
+                KeyCode::Char('c') | KeyCode::Char('C') => {
+                    to_ui
+                        .send((Some(Screens::Catalog), tui::Event::LoadCatalog).into())
+                        .await?;
+                }
+                KeyCode::Char('h') | KeyCode::Char('H') => {
+                    to_ui
+                        .send((Some(Screens::Welcome), tui::Event::LoadWelcome).into())
+                        .await?;
+                }
+                KeyCode::Char('d') | KeyCode::Char('D') => {
+                    to_ui
+                        .send((Some(Screens::DiskUsage), tui::Event::LoadDiskUsage).into())
+                        .await?;
+                }
+                KeyCode::Char(' ') => {
+                    if let Some(workshop_key) = self.get_selected_workshop_key() {
+                        match self.marked.iter().position(|key| *key == workshop_key) {
+                            Some(index) => {
+                                self.marked.remove(index);
+                            }
+                            None => self.marked.push(workshop_key),
+                        }
+                        self.set_titles();
+                    }
+                }
+                KeyCode::Esc if !self.marked.is_empty() => {
+                    self.marked.clear();
+                    self.set_titles();
+                }
+                KeyCode::Char('u') | KeyCode::Char('U') if !self.marked.is_empty() => {
+                    let update = evt!(
+                        None,
+                        tui::Event::BatchWorkshops(Batch::Update, self.batch_targets())
+                    );
+                    to_ui.send(update.into()).await?;
+                    self.marked.clear();
+                }
+                KeyCode::Char('u') | KeyCode::Char('U') => {
+                    if let Some(workshop_key) = self.get_selected_workshop_key() {
+                        to_ui
+                            .send((None, tui::Event::UpdateWorkshop(workshop_key)).into())
+                            .await?;
+                    }
+                }
+                KeyCode::Char('e') | KeyCode::Char('E') => {
+                    let targets = self.batch_targets();
+                    if !targets.is_empty() {
+                        let pull =
+                            evt!(None, tui::Event::BatchWorkshops(Batch::PullImages, targets));
+                        to_ui.send(pull.into()).await?;
+                        self.marked.clear();
+                    }
+                }
+                KeyCode::Char('i') | KeyCode::Char('I') => {
+                    if let Some(workshop_key) = self.get_selected_workshop_key() {
+                        to_ui
+                            .send((None, tui::Event::CheckIntegrity(workshop_key)).into())
+                            .await?;
+                    }
+                }
+                KeyCode::Char('p') => {
+                    if let Some(workshop_key) = self.get_selected_workshop_key() {
+                        let refresh = evt!(Screens::Workshops, tui::Event::RefreshWorkshops);
+                        let reset = evt!(
+                            None,
+                            tui::Event::ResetProgress(
+                                ProgressScope::Workshop(workshop_key.clone()),
+                                Some(refresh)
+                            ),
+                        );
+                        let confirm = evt!(
+                            None,
+                            tui::Event::Dialog(Dialog::confirm(
+                                "Reset Workshop Progress?",
+                                workshop_key.as_str(),
+                                Some(reset),
+                                None,
+                            )),
+                        );
+                        to_ui.send(confirm.into()).await?;
+                    }
+                }
+                KeyCode::Char('P') => {
+                    let refresh = evt!(Screens::Workshops, tui::Event::RefreshWorkshops);
+                    let reset = evt!(
+                        None,
+                        tui::Event::ResetProgress(ProgressScope::All, Some(refresh)),
+                    );
+                    let confirm = evt!(
+                        None,
+                        tui::Event::Dialog(Dialog::confirm(
+                            "Reset All Progress?",
+                            "reset all",
+                            Some(reset),
                             None,
-                            true,
-                            Some(set_workshop)
-                        ),
+                        )),
+                    );
+                    to_ui.send(confirm.into()).await?;
+                }
+                KeyCode::Char('X') if !self.marked.is_empty() => {
+                    let uninstall = evt!(
+                        None,
+                        tui::Event::BatchWorkshops(Batch::Uninstall, self.batch_targets())
                     );
-                    let change_spoken_language = evt!(
-                        Screens::Spoken,
-                        tui::Event::ChangeSpokenLanguage(
-                            all_languages.clone(),
+                    let confirm = evt!(
+                        None,
+                        tui::Event::Dialog(Dialog::yes_no(
+                            format!("Uninstall {} Workshops?", self.marked.len()),
+                            Some(uninstall),
                             None,
-                            true,
-                            Some(change_programming_language),
-                        ),
+                        )),
                     );
-                    to_ui.send(change_spoken_language.into()).await?;
+                    to_ui.send(confirm.into()).await?;
+                }
+                KeyCode::Char('X') => {
+                    if let Some(workshop_key) = self.get_selected_workshop_key() {
+                        let uninstall =
+                            evt!(None, tui::Event::UninstallWorkshop(workshop_key, None));
+                        let confirm = evt!(
+                            None,
+                            tui::Event::Dialog(Dialog::yes_no(
+                                "Uninstall Workshop?",
+                                Some(uninstall),
+                                None,
+                            )),
+                        );
+                        to_ui.send(confirm.into()).await?;
+                    }
                 }
                 KeyCode::Char('w') | KeyCode::Char('W') => {
                     if let Some(url) = self.get_url() {
@@ -762,31 +1378,17 @@ impl Workshops<'_> {
                     }
                 }
                 KeyCode::Enter => {
-                    // we're choosing a workshop so clear out the local status spoken and
-                    // programming languages so we set them from the valid selections associated
-                    // with the selected workshop
-                    {
-                        let mut status = status
-                            .lock()
-                            .map_err(|e| Error::StatusLock(e.to_string()))?;
-                        status.set_spoken_language(None, false);
-                        status.set_programming_language(None, false);
-                    }
                     if let Some(workshop_key) = self.get_selected_workshop_key() {
-                        if let Some(workshop_data) = self.workshops.get(&workshop_key) {
-                            let all_languages = workshop_data.get_all_languages().clone();
-                            to_ui
-                                .send(
-                                    (
-                                        None,
-                                        tui::Event::SetWorkshop(
-                                            self.get_selected_workshop_key(),
-                                            all_languages,
-                                        ),
-                                    )
-                                        .into(),
-                                )
-                                .await?;
+                        if self.failed.contains_key(&workshop_key) {
+                            let toast = evt!(
+                                None,
+                                tui::Event::Toast(format!(
+                                    "{workshop_key} failed to load, press r to revalidate it"
+                                ))
+                            );
+                            to_ui.send(toast.into()).await?;
+                        } else if let Some(workshop_data) = self.workshops.get(&workshop_key) {
+                            choose_workshop(workshop_key, workshop_data, &to_ui, &status).await?;
                         }
                     }
                 }
@@ -797,13 +1399,109 @@ impl Workshops<'_> {
     }
 }
 
+/// Choose the workshop and go on to picking its languages, or straight to its lessons
+pub async fn choose_workshop(
+    workshop_key: String,
+    workshop_data: &WorkshopData,
+    to_ui: &Sender<screens::Event>,
+    status: &StatusHandle,
+) -> Result<(), Error> {
+    let all_languages = workshop_data.get_all_languages().clone();
+    // we're choosing a workshop so resume it in the languages it was last taken in, if they are
+    // still valid, otherwise start it in the default languages. when the workshop isn't available
+    // in the default languages, ask which of the closest languages it is available in to use
+    // instead, and when there are no defaults clear out the local status spoken and programming
+    // languages so we set them from the valid selections associated with the selected workshop
+    let alternatives = {
+        let mut status = status.write().await;
+        let context = status
+            .workshop_context(&workshop_key)
+            .cloned()
+            .unwrap_or_default();
+        let programming_languages = context
+            .spoken_language
+            .and_then(|spoken| all_languages.get(&spoken));
+        let defaults = status
+            .default_spoken_language()
+            .zip(status.default_programming_language());
+        let (spoken, programming, alternatives) = match (
+            programming_languages,
+            context.programming_language,
+            defaults,
+        ) {
+            (Some(languages), Some(programming), _) if languages.contains(&programming) => {
+                (context.spoken_language, Some(programming), None)
+            }
+            (_, _, Some((spoken, programming))) => {
+                let alternatives = workshop_data.get_languages(spoken, programming);
+                if alternatives.is_empty() {
+                    (Some(spoken), Some(programming), None)
+                } else {
+                    (None, None, Some(((spoken, programming), alternatives)))
+                }
+            }
+            _ => (None, None, None),
+        };
+        status.set_spoken_language(spoken, false);
+        status.set_programming_language(programming, false);
+        alternatives
+    };
+
+    let Some(((spoken, programming), alternatives)) = alternatives else {
+        to_ui
+            .send(
+                (
+                    None,
+                    tui::Event::SetWorkshop(Some(workshop_key), all_languages),
+                )
+                    .into(),
+            )
+            .await?;
+        return Ok(());
+    };
+
+    debug!("Workshop {workshop_key} isn't available in {spoken}+{programming}");
+    let choose = |spoken: Option<spoken::Code>, programming: Option<programming::Code>| {
+        let set_workshop = evt!(
+            None,
+            tui::Event::SetWorkshop(Some(workshop_key.clone()), all_languages.clone())
+        );
+        flow::use_languages(spoken, programming, set_workshop).build()
+    };
+    let mut choices = alternatives
+        .into_iter()
+        .map(|(spoken, programming)| {
+            (
+                format!("{}+{}", spoken.get_name_in_native(), programming.get_name()),
+                choose(Some(spoken), Some(programming)),
+            )
+        })
+        .collect::<Vec<_>>();
+    choices.push(("Pick other languages".to_string(), choose(None, None)));
+    let title = format!(
+        "This workshop isn't available in {}+{}; choose:",
+        spoken.get_name_in_native(),
+        programming.get_name()
+    );
+    to_ui
+        .send(
+            (
+                None,
+                tui::Event::Dialog(Dialog::choice(title, choices, None)),
+            )
+                .into(),
+        )
+        .await?;
+    Ok(())
+}
+
 #[async_trait::async_trait]
 impl Screen for Workshops<'_> {
     async fn handle_event(
         &mut self,
         event: screens::Event,
         to_ui: Sender<screens::Event>,
-        status: Arc<Mutex<Status>>,
+        status: StatusHandle,
     ) -> Result<(), Error> {
         match event {
             screens::Event::Input(input_event) => {
@@ -813,6 +1511,35 @@ impl Screen for Workshops<'_> {
         }
     }
 
+    fn actions(&self) -> Vec<screens::Action> {
+        vec![
+            screens::Action::new("Open the highlighted workshop", KeyCode::Enter),
+            screens::Action::new("Open the workshop homepage", KeyCode::Char('w')),
+            screens::Action::new("Show the workshop license", KeyCode::Char('l')),
+            screens::Action::new("Filter the workshops by language", KeyCode::Char('f')),
+            screens::Action::new("Filter the workshops by difficulty", KeyCode::Char('v')),
+            screens::Action::new("Order the workshops by difficulty", KeyCode::Char('o')),
+            screens::Action::ctrl("Search the content of the workshops", KeyCode::Char('f')),
+            screens::Action::new("Pin or unpin the workshop", KeyCode::Char('m')),
+            screens::Action::new("Mark or unmark the workshop", KeyCode::Char(' ')),
+            screens::Action::new("Pre-pull the lesson environments", KeyCode::Char('e')),
+            screens::Action::new("Rescan and revalidate the workshops", KeyCode::Char('r')),
+            screens::Action::new("Browse the workshop catalog", KeyCode::Char('c')),
+            screens::Action::new("Update the workshop", KeyCode::Char('u')),
+            screens::Action::new("Check the integrity of the workshop", KeyCode::Char('i')),
+            screens::Action::new("Reset the workshop progress", KeyCode::Char('p')),
+            screens::Action::new("Reset all progress", KeyCode::Char('P')),
+            screens::Action::new("Uninstall the workshop", KeyCode::Char('X')),
+            screens::Action::new("Show the disk usage", KeyCode::Char('d')),
+            screens::Action::new("Go to the start page", KeyCode::Char('h')),
+            screens::Action::new("Focus the next view", KeyCode::Tab),
+        ]
+    }
+
+    fn is_animating(&self) -> bool {
+        self.loading.is_some()
+    }
+
     fn render_screen(&mut self, area: Rect, buf: &mut Buffer) -> Result<(), Error> {
         // this splits the screen into a top area and a one-line bottom area
         let [workshops_area, status_area] =
@@ -7,11 +7,20 @@ pub use scrolltext::ScrollText;
 pub mod scrolllog;
 pub use scrolllog::ScrollLog;
 
+pub mod framestats;
+pub use framestats::FrameStats;
+
+pub mod toast;
+pub use toast::Toast;
+
+pub mod dialog;
+pub use dialog::{Answer, Dialog, Response};
+
 pub mod statusbar;
 pub use statusbar::{StatusBar, StatusMode};
 
 pub mod lessonbox;
 pub use lessonbox::{
-    parse_markdown, CodeBlock, Content, ContentBlock, Heading, Hint, LessonBox, LessonBoxState,
-    ListItem, ParagraphBlock,
+    parse_markdown, quiz_problems, CodeBlock, Content, ContentBlock, Heading, Hint, LessonBox,
+    LessonBoxState, ListItem, ParagraphBlock, Quiz, TextPosition, Transcript,
 };
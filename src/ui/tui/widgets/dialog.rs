@@ -0,0 +1,561 @@
+use crate::{error::Problem, ui::tui::Evt};
+use crossterm::event::KeyCode;
+use ratatui::{
+    buffer::Buffer,
+    layout::{Alignment, Constraint, Flex, Layout, Rect},
+    style::{Color, Modifier, Style},
+    symbols::border::Set,
+    text::{Line, Span},
+    widgets::{
+        block::Position, Block, Borders, Clear, List, ListState, Padding, Paragraph,
+        StatefulWidget, Widget, Wrap,
+    },
+};
+use std::{fmt, sync::Arc};
+
+const TOP_DIALOG_BORDER: Set = Set {
+    top_left: "┌",
+    top_right: "┐",
+    bottom_left: "│",
+    bottom_right: "│",
+    vertical_left: "│",
+    vertical_right: "│",
+    horizontal_top: "─",
+    horizontal_bottom: " ",
+};
+
+const STATUS_BORDER: Set = Set {
+    top_left: " ",
+    top_right: " ",
+    bottom_left: "└",
+    bottom_right: "┘",
+    vertical_left: " ",
+    vertical_right: " ",
+    horizontal_top: " ",
+    horizontal_bottom: "─",
+};
+
+/// Makes the event to send from the text typed into a prompt
+#[derive(Clone)]
+pub struct Answer(Arc<dyn Fn(String) -> Evt + Send + Sync>);
+
+impl Answer {
+    /// Create an answer from a function making the event to send from the typed text
+    pub fn new(f: impl Fn(String) -> Evt + Send + Sync + 'static) -> Self {
+        Self(Arc::new(f))
+    }
+}
+
+impl fmt::Debug for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Answer")
+    }
+}
+
+/// The most entries of a palette shown at once
+const PALETTE_LINES: u16 = 12;
+
+/// The width of the text in the dialog, inside the borders and the padding
+const TEXT_WIDTH: usize = 52;
+
+/// A choice of a palette
+#[derive(Clone, Debug)]
+struct PaletteChoice {
+    /// the name to match the typed text against
+    name: String,
+    /// the event to send when it is picked
+    event: Option<Evt>,
+    /// where the choice was given, matches that score the same keep this order
+    order: usize,
+    /// how well the typed text matches, lower is better, none if it doesn't match
+    score: Option<usize>,
+}
+
+/// What the dialog asks for
+#[derive(Clone, Debug)]
+enum Kind {
+    /// pick one of the choices, each with the event to send when it is picked
+    Choice(Vec<(String, Option<Evt>)>, ListState),
+    /// pick one of the choices matching the typed text, kept with the best matches first, and the
+    /// index of the selected one
+    Palette(Vec<PaletteChoice>, usize),
+    /// type the expected text to confirm
+    Confirm(String, Option<Evt>),
+    /// type an answer
+    Prompt(Answer),
+    /// what went wrong and what to do about it, with the event to send to view the log
+    Problem(Box<Problem>, Option<Evt>),
+}
+
+/// What a key did to the dialog
+#[derive(Debug)]
+pub enum Response {
+    /// the dialog is still open
+    Pending,
+    /// the dialog closed and the event, if any, is to be sent
+    Closed(Option<Evt>),
+}
+
+/// A modal dialog drawn over the current screen. It gets every key until it closes.
+#[derive(Clone, Debug)]
+pub struct Dialog {
+    /// the title
+    title: String,
+    /// what the dialog asks for
+    kind: Kind,
+    /// the text typed so far
+    input: String,
+    /// event to send if they cancel
+    cancel: Option<Evt>,
+}
+
+impl Dialog {
+    /// A yes or no question, cancelling it answers no
+    pub fn yes_no<S: AsRef<str>>(title: S, yes: Option<Evt>, no: Option<Evt>) -> Self {
+        Self::choice(
+            title,
+            vec![("Yes".to_string(), yes), ("No".to_string(), no.clone())],
+            no,
+        )
+    }
+
+    /// Pick one of the choices, each with the event to send when it is picked
+    pub fn choice<S: AsRef<str>>(
+        title: S,
+        choices: Vec<(String, Option<Evt>)>,
+        cancel: Option<Evt>,
+    ) -> Self {
+        let mut list_state = ListState::default();
+        list_state.select(Some(0));
+        Self {
+            title: title.as_ref().to_string(),
+            kind: Kind::Choice(choices, list_state),
+            input: String::new(),
+            cancel,
+        }
+    }
+
+    /// Show what went wrong and what to do about it, pressing `l` sends the event to view the log
+    pub fn problem(problem: &Problem, view_log: Option<Evt>) -> Self {
+        Self {
+            title: problem.category.to_string(),
+            kind: Kind::Problem(Box::new(problem.clone()), view_log),
+            input: String::new(),
+            cancel: None,
+        }
+    }
+
+    /// Pick one of the choices by typing part of its name, the letters only need to appear in
+    /// order
+    pub fn palette<S: AsRef<str>>(title: S, choices: Vec<(String, Option<Evt>)>) -> Self {
+        let choices = choices
+            .into_iter()
+            .enumerate()
+            .map(|(order, (name, event))| PaletteChoice {
+                name,
+                event,
+                order,
+                score: Some(0),
+            })
+            .collect();
+        Self {
+            title: title.as_ref().to_string(),
+            kind: Kind::Palette(choices, 0),
+            input: String::new(),
+            cancel: None,
+        }
+    }
+
+    /// Guard a destructive action by making the user type the expected text to confirm
+    pub fn confirm<S: AsRef<str>>(
+        title: S,
+        expected: S,
+        yes: Option<Evt>,
+        cancel: Option<Evt>,
+    ) -> Self {
+        Self {
+            title: title.as_ref().to_string(),
+            kind: Kind::Confirm(expected.as_ref().to_string(), yes),
+            input: String::new(),
+            cancel,
+        }
+    }
+
+    /// Ask for a line of text, starting with the given text
+    pub fn prompt<S: AsRef<str>>(
+        title: S,
+        initial: S,
+        answer: Answer,
+        cancel: Option<Evt>,
+    ) -> Self {
+        Self {
+            title: title.as_ref().to_string(),
+            kind: Kind::Prompt(answer),
+            input: initial.as_ref().to_string(),
+            cancel,
+        }
+    }
+
+    /// Does the dialog take text input, so that every key goes to it
+    pub fn takes_text_input(&self) -> bool {
+        !matches!(self.kind, Kind::Choice(..) | Kind::Problem(..))
+    }
+
+    /// Handle a key
+    pub fn handle_key(&mut self, key: KeyCode) -> Response {
+        if key == KeyCode::Esc {
+            return Response::Closed(self.cancel.take());
+        }
+        match &mut self.kind {
+            Kind::Choice(choices, list_state) => match key {
+                KeyCode::Char('j') | KeyCode::Down => list_state.select_next(),
+                KeyCode::Char('k') | KeyCode::Up => list_state.select_previous(),
+                KeyCode::Enter => {
+                    let last = choices.len().saturating_sub(1);
+                    let selected = list_state.selected().unwrap_or_default().min(last);
+                    let event = choices
+                        .get_mut(selected)
+                        .and_then(|(_, event)| event.take());
+                    return Response::Closed(event);
+                }
+                _ => {}
+            },
+            Kind::Palette(choices, selected) => match key {
+                KeyCode::Down => {
+                    let matching = choices.iter().filter(|c| c.score.is_some()).count();
+                    *selected = (*selected + 1).min(matching.saturating_sub(1));
+                }
+                KeyCode::Up => *selected = selected.saturating_sub(1),
+                KeyCode::Enter => {
+                    let event = choices
+                        .get_mut(*selected)
+                        .filter(|choice| choice.score.is_some())
+                        .and_then(|choice| choice.event.take());
+                    return Response::Closed(event);
+                }
+                _ => {
+                    edit(&mut self.input, key);
+                    for choice in choices.iter_mut() {
+                        choice.score = fuzzy_score(&self.input, &choice.name);
+                    }
+                    choices
+                        .sort_by_key(|choice| (choice.score.is_none(), choice.score, choice.order));
+                    *selected = 0;
+                }
+            },
+            Kind::Confirm(expected, yes) => match key {
+                KeyCode::Enter if &self.input == expected => return Response::Closed(yes.take()),
+                _ => edit(&mut self.input, key),
+            },
+            Kind::Prompt(answer) => match key {
+                KeyCode::Enter => {
+                    return Response::Closed(Some((answer.0)(std::mem::take(&mut self.input))))
+                }
+                _ => edit(&mut self.input, key),
+            },
+            Kind::Problem(_, view_log) => match key {
+                KeyCode::Char('l') | KeyCode::Char('L') => {
+                    return Response::Closed(view_log.take())
+                }
+                KeyCode::Enter => return Response::Closed(None),
+                _ => {}
+            },
+        }
+        Response::Pending
+    }
+
+    // the number of lines of the dialog's text
+    fn height(&self) -> u16 {
+        let lines = match &self.kind {
+            Kind::Choice(choices, _) => choices.len() as u16,
+            // the typed text, a blank line, and the matching choices
+            Kind::Palette(choices, _) => (choices.len() as u16).min(PALETTE_LINES) + 2,
+            Kind::Confirm(..) => 3,
+            Kind::Prompt(_) => 1,
+            // the wrapped message, a blank line, and the wrapped remediation
+            Kind::Problem(problem, _) => {
+                let wrapped = |text: &str| text.chars().count().div_ceil(TEXT_WIDTH).max(1) as u16;
+                let remediation = problem.remediation.as_deref();
+                wrapped(&problem.message) + remediation.map_or(0, |r| wrapped(r) + 1)
+            }
+        };
+        // two lines of padding, the title, and the status bar
+        lines + 4
+    }
+
+    // render the text of the dialog
+    fn render_body(&mut self, area: Rect, buf: &mut Buffer) {
+        let title = Line::from(vec![
+            Span::styled("─", Style::default().fg(Color::DarkGray)),
+            Span::styled(
+                format!("/ {} /", self.title),
+                Style::default().fg(Color::White),
+            ),
+        ]);
+        let block = Block::default()
+            .title(title)
+            .title_style(Style::default().fg(Color::White))
+            .padding(Padding::uniform(1))
+            .style(Style::default().fg(Color::DarkGray))
+            .borders(Borders::LEFT | Borders::RIGHT | Borders::TOP)
+            .border_set(TOP_DIALOG_BORDER);
+
+        let text = match &mut self.kind {
+            Kind::Choice(choices, list_state) => {
+                let list = List::new(choices.iter().map(|(choice, _)| choice.as_str()))
+                    .block(block)
+                    .highlight_style(
+                        Style::default()
+                            .fg(Color::Black)
+                            .bg(Color::White)
+                            .add_modifier(Modifier::BOLD),
+                    )
+                    .style(Style::default().fg(Color::White))
+                    .highlight_symbol("> ");
+                StatefulWidget::render(list, area, buf, list_state);
+                return;
+            }
+            Kind::Palette(choices, selected) => {
+                let inner = block.inner(area);
+                Widget::render(block, area, buf);
+                let [input_area, _, list_area] = Layout::vertical([
+                    Constraint::Length(1),
+                    Constraint::Length(1),
+                    Constraint::Min(1),
+                ])
+                .areas(inner);
+                Line::from(Span::styled(
+                    format!("> {}_", self.input),
+                    Style::default().fg(Color::White),
+                ))
+                .render(input_area, buf);
+                let matching = choices
+                    .iter()
+                    .take_while(|choice| choice.score.is_some())
+                    .map(|choice| choice.name.as_str())
+                    .collect::<Vec<_>>();
+                if matching.is_empty() {
+                    Line::from(Span::styled(
+                        "Nothing matches",
+                        Style::default().fg(Color::DarkGray),
+                    ))
+                    .render(list_area, buf);
+                    return;
+                }
+                let list = List::new(matching)
+                    .highlight_style(
+                        Style::default()
+                            .fg(Color::Black)
+                            .bg(Color::White)
+                            .add_modifier(Modifier::BOLD),
+                    )
+                    .style(Style::default().fg(Color::White))
+                    .highlight_symbol("> ");
+                let mut list_state = ListState::default().with_selected(Some(*selected));
+                StatefulWidget::render(list, list_area, buf, &mut list_state);
+                return;
+            }
+            Kind::Confirm(expected, _) => {
+                let color = if &self.input == expected {
+                    Color::Green
+                } else {
+                    Color::White
+                };
+                vec![
+                    Line::from(vec![
+                        Span::styled("Type ", Style::default().fg(Color::White)),
+                        Span::styled(expected.clone(), Style::default().fg(Color::Yellow)),
+                        Span::styled(" to confirm:", Style::default().fg(Color::White)),
+                    ]),
+                    Line::from(""),
+                    Line::from(Span::styled(
+                        format!("> {}_", self.input),
+                        Style::default().fg(color),
+                    )),
+                ]
+            }
+            Kind::Prompt(_) => vec![Line::from(Span::styled(
+                format!("> {}_", self.input),
+                Style::default().fg(Color::White),
+            ))],
+            Kind::Problem(problem, _) => {
+                let mut lines = vec![Line::from(Span::styled(
+                    problem.message.clone(),
+                    Style::default().fg(Color::White),
+                ))];
+                if let Some(remediation) = &problem.remediation {
+                    lines.push(Line::from(""));
+                    lines.push(Line::from(Span::styled(
+                        remediation.clone(),
+                        Style::default().fg(Color::Yellow),
+                    )));
+                }
+                lines
+            }
+        };
+        Paragraph::new(text)
+            .wrap(Wrap { trim: false })
+            .block(block)
+            .render(area, buf);
+    }
+
+    // render the status bar at the bottom
+    fn render_status(&self, area: Rect, buf: &mut Buffer) {
+        let keys = match self.kind {
+            Kind::Choice(..) => "/ j,k scroll / ↵ select / esc cancel /",
+            Kind::Palette(..) => "/ type to filter / ↑,↓ scroll / ↵ select / esc cancel /",
+            Kind::Confirm(..) => "/ ↵ confirm / esc cancel /",
+            Kind::Prompt(_) => "/ ↵ submit / esc cancel /",
+            Kind::Problem(_, Some(_)) => "/ l view log / ↵ close /",
+            Kind::Problem(_, None) => "/ ↵ close /",
+        };
+        let line = Line::from(vec![
+            Span::styled("─", Style::default().fg(Color::DarkGray)),
+            Span::styled(keys, Style::default().fg(Color::White)),
+        ]);
+        let block = Block::default()
+            .title(line)
+            .title_style(Style::default().fg(Color::White))
+            .title_position(Position::Bottom)
+            .title_alignment(Alignment::Left)
+            .style(Style::default().fg(Color::DarkGray))
+            .borders(Borders::LEFT | Borders::BOTTOM | Borders::RIGHT)
+            .border_set(STATUS_BORDER)
+            .padding(Padding::horizontal(1));
+
+        Widget::render(block, area, buf);
+    }
+}
+
+// edit the typed text with the key
+fn edit(input: &mut String, key: KeyCode) {
+    match key {
+        KeyCode::Char(c) => input.push(c),
+        KeyCode::Backspace => {
+            input.pop();
+        }
+        _ => {}
+    }
+}
+
+// score how well the query matches the text, ignoring case, lower is better. Every character of
+// the query has to appear in the text in order, and the score counts the characters skipped
+// between them, so that a match at the start of the text or of a word comes first.
+fn fuzzy_score(query: &str, text: &str) -> Option<usize> {
+    let text = text.to_lowercase().chars().collect::<Vec<_>>();
+    let mut score = 0;
+    let mut position = 0;
+    for c in query.to_lowercase().chars().filter(|c| !c.is_whitespace()) {
+        let found = text[position..].iter().position(|t| *t == c)?;
+        score += found;
+        position += found + 1;
+    }
+    Some(score)
+}
+
+impl Widget for &mut Dialog {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let [centered] = Layout::horizontal([Constraint::Max(56)])
+            .flex(Flex::Center)
+            .areas(area);
+        let [centered] = Layout::vertical([Constraint::Length(self.height())])
+            .flex(Flex::Center)
+            .areas(centered);
+
+        // clear area around the popup
+        Widget::render(Clear, centered, buf);
+
+        let [body_area, status_area] =
+            Layout::vertical([Constraint::Percentage(100), Constraint::Min(1)])
+                .flex(Flex::End)
+                .areas(centered);
+
+        self.render_body(body_area, buf);
+        self.render_status(status_area, buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{evt, ui::tui::Event};
+
+    fn is_quit(response: Response) -> bool {
+        matches!(response, Response::Closed(Some((None, event))) if matches!(*event, Event::Quit))
+    }
+
+    #[test]
+    fn test_dialog_responses() {
+        let mut dialog = Dialog::yes_no("Quit?", Some(evt!(None, Event::Quit)), None);
+        assert!(!dialog.takes_text_input());
+        assert!(matches!(
+            dialog.handle_key(KeyCode::Down),
+            Response::Pending
+        ));
+        assert!(matches!(
+            dialog.handle_key(KeyCode::Enter),
+            Response::Closed(None)
+        ));
+
+        let mut dialog = Dialog::confirm("Reset?", "reset", Some(evt!(None, Event::Quit)), None);
+        assert!(dialog.takes_text_input());
+        assert!(matches!(
+            dialog.handle_key(KeyCode::Enter),
+            Response::Pending
+        ));
+        for c in "reset".chars() {
+            dialog.handle_key(KeyCode::Char(c));
+        }
+        assert!(is_quit(dialog.handle_key(KeyCode::Enter)));
+
+        let answer = Answer::new(|text| evt!(None, Event::Toast(text)));
+        let mut dialog = Dialog::prompt("Name?", "Ad", answer, Some(evt!(None, Event::Quit)));
+        dialog.handle_key(KeyCode::Char('a'));
+        assert!(matches!(
+            dialog.handle_key(KeyCode::Enter),
+            Response::Closed(Some((None, event))) if matches!(&*event, Event::Toast(text) if text == "Ada")
+        ));
+        let mut dialog = Dialog::prompt(
+            "Name?",
+            "",
+            Answer::new(|text| evt!(None, Event::Toast(text))),
+            Some(evt!(None, Event::Quit)),
+        );
+        assert!(is_quit(dialog.handle_key(KeyCode::Esc)));
+
+        let problem = crate::Error::Command("Command 'check' timed out".to_string()).problem();
+        let mut dialog = Dialog::problem(&problem, Some(evt!(None, Event::Quit)));
+        assert!(!dialog.takes_text_input());
+        assert!(matches!(
+            dialog.handle_key(KeyCode::Down),
+            Response::Pending
+        ));
+        assert!(is_quit(dialog.handle_key(KeyCode::Char('l'))));
+    }
+
+    #[test]
+    fn test_palette() {
+        assert_eq!(fuzzy_score("", "Check solution"), Some(0));
+        assert_eq!(fuzzy_score("chk", "Check solution"), Some(2));
+        assert_eq!(fuzzy_score("sol", "Check solution"), Some(6));
+        assert_eq!(fuzzy_score("xyz", "Check solution"), None);
+
+        let mut dialog = Dialog::palette(
+            "Commands",
+            vec![
+                ("Toggle the log".to_string(), None),
+                ("Quit".to_string(), Some(evt!(None, Event::Quit))),
+            ],
+        );
+        assert!(dialog.takes_text_input());
+        dialog.handle_key(KeyCode::Char('q'));
+        assert!(is_quit(dialog.handle_key(KeyCode::Enter)));
+
+        let mut dialog = Dialog::palette("Commands", vec![("Quit".to_string(), None)]);
+        dialog.handle_key(KeyCode::Char('z'));
+        assert!(matches!(
+            dialog.handle_key(KeyCode::Enter),
+            Response::Closed(None)
+        ));
+    }
+}
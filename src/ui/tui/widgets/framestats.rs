@@ -0,0 +1,62 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Style},
+    text::Line,
+    widgets::{Block, Borders, Clear, Paragraph, Widget},
+};
+use std::time::Duration;
+
+/// A debug overlay in the top right corner showing how much work the last frame took
+#[derive(Clone, Debug, Default)]
+pub struct FrameStats {
+    /// how long drawing the last frame took
+    pub draw_time: Duration,
+    /// the number of events handled since the frame before it
+    pub events: usize,
+    /// the number of events still waiting in the queue
+    pub queued: usize,
+    /// the number of workshops loaded into the repository
+    pub workshops: usize,
+    /// the number of lines in the log buffer
+    pub log_lines: usize,
+    /// the bytes of distinct log lines in the log buffer
+    pub log_bytes: usize,
+}
+
+impl Widget for &FrameStats {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let lines = vec![
+            Line::from(format!(
+                "draw   {:>7.2} ms",
+                self.draw_time.as_secs_f64() * 1000.0
+            )),
+            Line::from(format!("events {:>10}", self.events)),
+            Line::from(format!("queued {:>10}", self.queued)),
+            Line::from(format!("loaded {:>10}", self.workshops)),
+            Line::from(format!("log    {:>10}", self.log_lines)),
+            Line::from(format!("log kB {:>10}", self.log_bytes / 1024)),
+        ];
+
+        // two columns and rows for the border
+        let width = 19.min(area.width);
+        let height = (lines.len() as u16 + 2).min(area.height);
+        let overlay = Rect {
+            x: area.right().saturating_sub(width),
+            y: area.y,
+            width,
+            height,
+        };
+
+        Widget::render(Clear, overlay, buf);
+        Paragraph::new(lines)
+            .block(
+                Block::default()
+                    .title("/ Stats /")
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(Color::DarkGray)),
+            )
+            .style(Style::default().fg(Color::White))
+            .render(overlay, buf);
+    }
+}
@@ -1,4 +1,6 @@
-use crate::ui::tui::widgets::scrolltext::Scroll;
+use crate::{
+    fs::cache, languages::spoken::Direction, models::glossary, ui::tui::widgets::scrolltext::Scroll,
+};
 use pulldown_cmark::{Event, Parser, Tag, TagEnd};
 use ratatui::{
     buffer::Buffer,
@@ -9,6 +11,58 @@ use ratatui::{
         Block, Paragraph, Scrollbar, ScrollbarOrientation, ScrollbarState, StatefulWidget, Widget,
     },
 };
+use serde::{Deserialize, Serialize};
+use std::{
+    convert::Infallible,
+    time::{Duration, Instant},
+};
+use tracing::warn;
+use unicode_width::UnicodeWidthStr;
+
+/// Markdown documents this long or longer have their parsed content blocks cached on disk
+const CACHED_MARKDOWN_LEN: usize = 16 * 1024;
+
+/// Marks the ends of a glossary term in the text being wrapped, it takes no room and the line may
+/// break before it
+const TERM_MARK: char = '\u{200c}';
+
+// replace the `[[term]]` links in the text with the terms between marks, with the spaces in the
+// terms kept from breaking the line
+fn mark_terms(text: &str) -> String {
+    glossary::split_links(text)
+        .into_iter()
+        .map(|(is_term, text)| {
+            if is_term {
+                format!("{TERM_MARK}{}{TERM_MARK}", text.replace(' ', "\u{a0}"))
+            } else {
+                text.to_string()
+            }
+        })
+        .collect()
+}
+
+// split the wrapped line into spans with the terms between marks highlighted, a term that was
+// broken at a hyphen carries on to the next line
+fn term_spans(line: &str, style: Style, in_term: &mut bool) -> Vec<Span<'static>> {
+    let term_style = style
+        .fg(Color::LightCyan)
+        .add_modifier(Modifier::UNDERLINED);
+    let mut spans = Vec::new();
+    for (i, part) in line.split(TERM_MARK).enumerate() {
+        if i > 0 {
+            *in_term = !*in_term;
+        }
+        if part.is_empty() {
+            continue;
+        }
+        if *in_term {
+            spans.push(Span::styled(part.replace('\u{a0}', " "), term_style));
+        } else {
+            spans.push(Span::styled(part.to_string(), style));
+        }
+    }
+    spans
+}
 
 /// Trait for content blocks that can be rendered to styled text lines
 pub trait ContentBlock {
@@ -20,10 +74,25 @@ pub trait ContentBlock {
     /// # Returns
     /// A vector of ratatui Line objects with proper styling
     fn render(&self, width: u16) -> Vec<Line<'static>>;
+
+    /// Render the content block for right-to-left text. The lines are aligned to the right edge
+    /// of the render area by default.
+    fn render_rtl(&self, width: u16) -> Vec<Line<'static>> {
+        self.render(width)
+            .into_iter()
+            .map(|line| line.alignment(Alignment::Right))
+            .collect()
+    }
+
+    /// The number of lines the content block renders to. Blocks that are expensive to render
+    /// count their lines without rendering them.
+    fn line_count(&self, width: u16) -> usize {
+        self.render(width).len()
+    }
 }
 
 /// A heading content block (H1, H2, H3, etc.)
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct Heading {
     pub level: u8,
     pub text: String,
@@ -44,23 +113,25 @@ impl ContentBlock for Heading {
 }
 
 /// A paragraph content block
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct ParagraphBlock {
     pub text: String,
 }
 
 impl ContentBlock for ParagraphBlock {
     fn render(&self, width: u16) -> Vec<Line<'static>> {
-        let wrapped_lines = textwrap::wrap(&self.text, width as usize);
+        let mut in_term = false;
+        let text = mark_terms(&self.text);
+        let wrapped_lines = textwrap::wrap(&text, width as usize);
         wrapped_lines
             .into_iter()
-            .map(|line| Line::from(line.to_string()))
+            .map(|line| Line::from(term_spans(&line, Style::default(), &mut in_term)))
             .collect()
     }
 }
 
 /// A list item content block
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct ListItem {
     pub text: String,
     pub indent_level: u8,
@@ -73,8 +144,10 @@ impl ContentBlock for ListItem {
         let bullet_prefix = format!("{indent}• ");
         let continuation_indent = format!("{indent}  "); // Same base indent + 2 spaces for bullet alignment
 
-        let available_width = width.saturating_sub(bullet_prefix.len() as u16);
-        let wrapped_lines = textwrap::wrap(&self.text, available_width.max(10) as usize);
+        let available_width = width.saturating_sub(bullet_prefix.width() as u16);
+        let text = mark_terms(&self.text);
+        let wrapped_lines = textwrap::wrap(&text, available_width.max(10) as usize);
+        let mut in_term = false;
 
         wrapped_lines
             .into_iter()
@@ -85,27 +158,57 @@ impl ContentBlock for ListItem {
                 } else {
                     &continuation_indent
                 };
-                Line::from(Span::styled(format!("{prefix}{line}"), style))
+                Line::from(term_spans(&format!("{prefix}{line}"), style, &mut in_term))
+            })
+            .collect()
+    }
+
+    fn render_rtl(&self, width: u16) -> Vec<Line<'static>> {
+        let style = Style::default().fg(Color::LightYellow);
+        let indent = "  ".repeat(self.indent_level as usize);
+
+        // the bullet and the indentation are mirrored onto the right side
+        let bullet_suffix = format!(" •{indent}");
+        let continuation_indent = format!("  {indent}");
+
+        let available_width = width.saturating_sub(bullet_suffix.width() as u16);
+        let text = mark_terms(&self.text);
+        let wrapped_lines = textwrap::wrap(&text, available_width.max(10) as usize);
+        let mut in_term = false;
+
+        wrapped_lines
+            .into_iter()
+            .enumerate()
+            .map(|(i, line)| {
+                let suffix = if i == 0 {
+                    &bullet_suffix
+                } else {
+                    &continuation_indent
+                };
+                Line::from(term_spans(&format!("{line}{suffix}"), style, &mut in_term))
+                    .alignment(Alignment::Right)
             })
             .collect()
     }
 }
 
 /// A code block content block
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct CodeBlock {
     pub language: Option<String>,
     pub code: String,
 }
 
 /// Enum representing different types of content blocks
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub enum Content {
     Heading(Heading),
     Paragraph(ParagraphBlock),
     ListItem(ListItem),
     CodeBlock(CodeBlock),
     Hint(Hint),
+    Quiz(Quiz),
+    Transcript(Transcript),
 }
 
 impl ContentBlock for Content {
@@ -116,12 +219,38 @@ impl ContentBlock for Content {
             Content::ListItem(l) => l.render(width),
             Content::CodeBlock(c) => c.render(width),
             Content::Hint(h) => h.render(width),
+            Content::Quiz(q) => q.render(width),
+            Content::Transcript(t) => t.render(width),
+        }
+    }
+
+    fn render_rtl(&self, width: u16) -> Vec<Line<'static>> {
+        match self {
+            Content::Heading(h) => h.render_rtl(width),
+            Content::Paragraph(p) => p.render_rtl(width),
+            Content::ListItem(l) => l.render_rtl(width),
+            Content::CodeBlock(c) => c.render_rtl(width),
+            Content::Hint(h) => h.render_rtl(width),
+            Content::Quiz(q) => q.render_rtl(width),
+            Content::Transcript(t) => t.render_rtl(width),
+        }
+    }
+
+    fn line_count(&self, width: u16) -> usize {
+        match self {
+            Content::Heading(h) => h.line_count(width),
+            Content::Paragraph(p) => p.line_count(width),
+            Content::ListItem(l) => l.line_count(width),
+            Content::CodeBlock(c) => c.line_count(width),
+            Content::Hint(h) => h.line_count(width),
+            Content::Quiz(q) => q.line_count(width),
+            Content::Transcript(t) => t.line_count(width),
         }
     }
 }
 
 /// A hint content block that can be collapsed or expanded
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct Hint {
     pub title: String,
     pub content: Vec<Content>,
@@ -156,6 +285,15 @@ impl ContentBlock for CodeBlock {
 
         lines
     }
+    fn render_rtl(&self, width: u16) -> Vec<Line<'static>> {
+        // code is always written left-to-right
+        self.render(width)
+    }
+
+    fn line_count(&self, _width: u16) -> usize {
+        // the code lines between the top and bottom borders
+        self.code.lines().count() + 2
+    }
 }
 
 impl CodeBlock {
@@ -292,13 +430,10 @@ impl CodeBlock {
         let mut pos = 0;
 
         for word in words {
-            // Add any whitespace before the word
-            while pos < line.len() && line.chars().nth(pos).unwrap().is_whitespace() {
-                spans.push(Span::styled(
-                    line.chars().nth(pos).unwrap().to_string(),
-                    default_style,
-                ));
-                pos += 1;
+            // Add any whitespace before the word, pos is a byte offset into the line
+            while let Some(ch) = line[pos..].chars().next().filter(|ch| ch.is_whitespace()) {
+                spans.push(Span::styled(ch.to_string(), default_style));
+                pos += ch.len_utf8();
             }
 
             let style = if keywords.contains(&word.trim_end_matches(['(', ':', ')', '"'])) {
@@ -368,9 +503,48 @@ impl ContentBlock for Hint {
             ))]
         }
     }
+
+    fn render_rtl(&self, width: u16) -> Vec<Line<'static>> {
+        self.render_mirrored(width)
+    }
+
+    fn line_count(&self, width: u16) -> usize {
+        if self.expanded {
+            // the title and the content blocks, each after a blank line
+            1 + self
+                .content
+                .iter()
+                .map(|content| 1 + content.line_count(width))
+                .sum::<usize>()
+        } else {
+            1
+        }
+    }
 }
 
 impl Hint {
+    /// Render the hint with the title marker mirrored onto the right side and the content
+    /// rendered right-to-left
+    fn render_mirrored(&self, width: u16) -> Vec<Line<'static>> {
+        let title_style = Style::default()
+            .fg(Color::Cyan)
+            .add_modifier(Modifier::BOLD);
+        let marker = if self.expanded { "▼" } else { "◀" };
+        let mut lines = vec![Line::from(Span::styled(
+            format!("{}: Hint {marker}", self.title),
+            title_style,
+        ))
+        .alignment(Alignment::Right)];
+
+        if self.expanded {
+            for content in &self.content {
+                lines.push(Line::from(""));
+                lines.extend(content.render_rtl(width));
+            }
+        }
+        lines
+    }
+
     /// Toggle the expanded state of the hint
     pub fn toggle(&mut self) {
         self.expanded = !self.expanded;
@@ -386,6 +560,282 @@ impl Hint {
     }
 }
 
+/// A multiple-choice question from a fenced `quiz` block, answered with the number keys
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Quiz {
+    /// the question
+    pub question: String,
+    /// the choices, numbered from 1 to at most 9
+    pub choices: Vec<String>,
+    /// the number of the right choice
+    pub answer: usize,
+    /// shown once the question is answered
+    #[serde(default)]
+    pub explanation: Option<String>,
+    /// the number of the choice the student picked
+    #[serde(default)]
+    pub picked: Option<usize>,
+}
+
+impl Quiz {
+    /// Parse the YAML of a `quiz` block, the answer must be the number of one of the choices
+    pub fn parse(yaml: &str) -> Result<Self, String> {
+        let mut quiz: Quiz = serde_yaml::from_str(yaml).map_err(|e| e.to_string())?;
+        if quiz.choices.is_empty() || quiz.choices.len() > 9 {
+            return Err("a quiz has from 1 to 9 choices".to_string());
+        }
+        if !(1..=quiz.choices.len()).contains(&quiz.answer) {
+            return Err(format!(
+                "the answer {} isn't the number of a choice",
+                quiz.answer
+            ));
+        }
+        quiz.picked = None;
+        Ok(quiz)
+    }
+
+    /// Pick the choice with the number, returns whether it is the right one or None when there
+    /// is no such choice
+    pub fn pick(&mut self, choice: usize) -> Option<bool> {
+        if !(1..=self.choices.len()).contains(&choice) {
+            return None;
+        }
+        self.picked = Some(choice);
+        Some(choice == self.answer)
+    }
+
+    /// Is the quiz answered right?
+    pub fn is_correct(&self) -> bool {
+        self.picked == Some(self.answer)
+    }
+}
+
+impl ContentBlock for Quiz {
+    fn render(&self, width: u16) -> Vec<Line<'static>> {
+        let question_style = Style::default()
+            .fg(Color::LightMagenta)
+            .add_modifier(Modifier::BOLD);
+        let mut lines = textwrap::wrap(&format!("? {}", self.question), width.max(10) as usize)
+            .into_iter()
+            .map(|line| Line::from(Span::styled(line.to_string(), question_style)))
+            .collect::<Vec<_>>();
+
+        for (i, choice) in self.choices.iter().enumerate() {
+            let number = i + 1;
+            let (marker, style) = match self.picked {
+                Some(_) if number == self.answer => ("✓", Style::default().fg(Color::Green)),
+                Some(picked) if number == picked => ("✗", Style::default().fg(Color::Red)),
+                _ => (" ", Style::default()),
+            };
+            let prefix = format!("{marker} {number}) ");
+            let indent = " ".repeat(prefix.width());
+            let available_width = width.saturating_sub(prefix.width() as u16);
+            for (j, line) in textwrap::wrap(choice, available_width.max(10) as usize)
+                .into_iter()
+                .enumerate()
+            {
+                let prefix = if j == 0 { &prefix } else { &indent };
+                lines.push(Line::from(Span::styled(format!("{prefix}{line}"), style)));
+            }
+        }
+
+        if self.picked.is_some() {
+            let result = if self.is_correct() {
+                Span::styled("Correct!", Style::default().fg(Color::Green))
+            } else {
+                Span::styled(
+                    format!("Not quite, the answer is {}.", self.answer),
+                    Style::default().fg(Color::Red),
+                )
+            };
+            lines.push(Line::from(result));
+            if let Some(explanation) = &self.explanation {
+                lines.extend(
+                    textwrap::wrap(explanation, width.max(10) as usize)
+                        .into_iter()
+                        .map(|line| Line::from(line.to_string())),
+                );
+            }
+        } else {
+            lines.push(Line::from(Span::styled(
+                format!("Press 1-{} to answer", self.choices.len()),
+                Style::default().fg(Color::DarkGray),
+            )));
+        }
+        lines
+    }
+}
+
+/// A recorded terminal session from a fenced `transcript` block, played back line by line. Each
+/// line may start with the time in seconds it was printed at, like `[1.5] Listening`, and the
+/// lines without a time are printed together with the line before them.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Transcript {
+    /// the lines with the time since the start of the session they are printed at
+    pub lines: Vec<(Duration, String)>,
+    /// the number of lines printed so far, all of them until it is played
+    #[serde(skip)]
+    shown: Option<usize>,
+    /// the time into the session the playback is at, not counting the time since it resumed
+    #[serde(skip)]
+    clock: Duration,
+    /// when the playback resumed, while it is playing
+    #[serde(skip)]
+    resumed: Option<Instant>,
+}
+
+impl Transcript {
+    /// Parse the text of a `transcript` block
+    pub fn parse(text: &str) -> Self {
+        let mut time = Duration::ZERO;
+        let lines = text
+            .lines()
+            .map(|line| {
+                let timed = line.strip_prefix('[').and_then(|rest| {
+                    let (seconds, rest) = rest.split_once(']')?;
+                    let seconds = seconds.trim().parse::<f64>().ok()?;
+                    let seconds = Duration::try_from_secs_f64(seconds).ok()?;
+                    Some((seconds, rest.strip_prefix(' ').unwrap_or(rest)))
+                });
+                match timed {
+                    Some((seconds, rest)) => {
+                        // the times never go back
+                        time = time.max(seconds);
+                        (time, rest.to_string())
+                    }
+                    None => (time, line.to_string()),
+                }
+            })
+            .collect();
+        Self {
+            lines,
+            shown: None,
+            clock: Duration::ZERO,
+            resumed: None,
+        }
+    }
+
+    /// The number of lines printed so far
+    pub fn shown(&self) -> usize {
+        self.shown.unwrap_or(self.lines.len())
+    }
+
+    /// Is the transcript playing?
+    pub fn is_playing(&self) -> bool {
+        self.resumed.is_some()
+    }
+
+    /// Play the transcript, from the start if it has finished, or pause it while it is playing
+    pub fn play_pause(&mut self) {
+        if let Some(resumed) = self.resumed.take() {
+            self.clock += resumed.elapsed();
+            return;
+        }
+        if self.shown() >= self.lines.len() {
+            self.shown = Some(0);
+            self.clock = Duration::ZERO;
+        }
+        self.resumed = Some(Instant::now());
+    }
+
+    /// Pause the transcript and print the next line, from the start if it has finished
+    pub fn step(&mut self) {
+        self.resumed = None;
+        let shown = match self.shown() {
+            shown if shown >= self.lines.len() => 1,
+            shown => shown + 1,
+        };
+        self.shown = Some(shown.min(self.lines.len()));
+        if let Some((time, _)) = shown.checked_sub(1).and_then(|i| self.lines.get(i)) {
+            self.clock = *time;
+        }
+    }
+
+    /// Print the lines that are due while it is playing, returns whether more lines are printed
+    pub fn advance(&mut self) -> bool {
+        let Some(resumed) = self.resumed else {
+            return false;
+        };
+        let clock = self.clock + resumed.elapsed();
+        let due = self.lines.partition_point(|(time, _)| *time <= clock);
+        if due >= self.lines.len() {
+            // the playback stops at the end
+            self.resumed = None;
+            self.clock = clock;
+        }
+        let changed = due != self.shown();
+        self.shown = Some(due);
+        changed
+    }
+}
+
+impl ContentBlock for Transcript {
+    fn render(&self, _width: u16) -> Vec<Line<'static>> {
+        let border_style = Style::default().fg(Color::Gray);
+        let text_style = Style::default().bg(Color::Black).fg(Color::White);
+        let shown = self.shown();
+        let state = if self.is_playing() {
+            "▶ playing"
+        } else if shown < self.lines.len() {
+            "❚❚ paused"
+        } else {
+            "■ done"
+        };
+        let mut lines = vec![Line::from(Span::styled(
+            format!("┌─ transcript {state} {shown}/{}", self.lines.len()),
+            border_style,
+        ))];
+
+        // the lines not printed yet keep their place so that the lesson doesn't move
+        for (i, (_, line)) in self.lines.iter().enumerate() {
+            let text = if i < shown { line.as_str() } else { "" };
+            lines.push(Line::from(vec![
+                Span::styled("│ ", border_style),
+                Span::styled(text.to_string(), text_style),
+            ]));
+        }
+
+        lines.push(Line::from(Span::styled("└─", border_style)));
+        lines
+    }
+
+    fn render_rtl(&self, width: u16) -> Vec<Line<'static>> {
+        // terminal output is always written left-to-right
+        self.render(width)
+    }
+
+    fn line_count(&self, _width: u16) -> usize {
+        self.lines.len() + 2
+    }
+}
+
+/// Get the problems with the `quiz` blocks in the markdown, for validating a workshop
+pub fn quiz_problems(markdown: &str) -> Vec<String> {
+    let mut problems = Vec::new();
+    let mut quiz = None;
+    for event in Parser::new(markdown) {
+        match event {
+            Event::Start(Tag::CodeBlock(pulldown_cmark::CodeBlockKind::Fenced(lang)))
+                if lang.as_ref() == "quiz" =>
+            {
+                quiz = Some(String::new());
+            }
+            Event::Text(text) => {
+                if let Some(quiz) = &mut quiz {
+                    quiz.push_str(&text);
+                }
+            }
+            Event::End(TagEnd::CodeBlock) => {
+                if let Some(Err(e)) = quiz.take().map(|yaml| Quiz::parse(&yaml)) {
+                    problems.push(format!("broken quiz: {e}"));
+                }
+            }
+            _ => {}
+        }
+    }
+    problems
+}
+
 /// Parse markdown text into a vector of Content blocks
 pub fn parse_markdown(markdown: &str) -> Vec<Content> {
     let parser = Parser::new(markdown);
@@ -409,46 +859,44 @@ pub fn parse_markdown(markdown: &str) -> Vec<Content> {
                 heading_level = level as u8;
                 current_text.clear();
             }
-            Event::End(TagEnd::Heading(_)) => {
-                if in_heading {
-                    let text = current_text.trim().to_string();
-
-                    // Check if this is a hint heading (H2 starting with "Hint - ")
-                    if heading_level == 2 && text.starts_with("Hint - ") {
-                        // If we were already collecting a hint, finish it first
-                        if collecting_hint && !hint_title.is_empty() {
-                            content_blocks.push(Content::Hint(Hint::new(
-                                hint_title.clone(),
-                                hint_content.clone(),
-                            )));
-                            hint_content.clear();
-                        }
-
-                        // Start collecting new hint
-                        collecting_hint = true;
-                        hint_title = text.strip_prefix("Hint - ").unwrap_or(&text).to_string();
-                    } else {
-                        // Regular heading - if we were collecting a hint, finish it first
-                        if collecting_hint && !hint_title.is_empty() {
-                            content_blocks.push(Content::Hint(Hint::new(
-                                hint_title.clone(),
-                                hint_content.clone(),
-                            )));
-                            hint_content.clear();
-                            collecting_hint = false;
-                        }
+            Event::End(TagEnd::Heading(_)) if in_heading => {
+                let text = current_text.trim().to_string();
+
+                // Check if this is a hint heading (H2 starting with "Hint - ")
+                if heading_level == 2 && text.starts_with("Hint - ") {
+                    // If we were already collecting a hint, finish it first
+                    if collecting_hint && !hint_title.is_empty() {
+                        content_blocks.push(Content::Hint(Hint::new(
+                            hint_title.clone(),
+                            hint_content.clone(),
+                        )));
+                        hint_content.clear();
+                    }
 
-                        // Add the regular heading to main content
-                        let heading = Heading {
-                            level: heading_level,
-                            text,
-                        };
-                        content_blocks.push(Content::Heading(heading));
+                    // Start collecting new hint
+                    collecting_hint = true;
+                    hint_title = text.strip_prefix("Hint - ").unwrap_or(&text).to_string();
+                } else {
+                    // Regular heading - if we were collecting a hint, finish it first
+                    if collecting_hint && !hint_title.is_empty() {
+                        content_blocks.push(Content::Hint(Hint::new(
+                            hint_title.clone(),
+                            hint_content.clone(),
+                        )));
+                        hint_content.clear();
+                        collecting_hint = false;
                     }
 
-                    in_heading = false;
-                    current_text.clear();
+                    // Add the regular heading to main content
+                    let heading = Heading {
+                        level: heading_level,
+                        text,
+                    };
+                    content_blocks.push(Content::Heading(heading));
                 }
+
+                in_heading = false;
+                current_text.clear();
             }
             Event::Start(Tag::Paragraph) => {
                 in_paragraph = true;
@@ -505,15 +953,29 @@ pub fn parse_markdown(markdown: &str) -> Vec<Content> {
             }
             Event::End(TagEnd::CodeBlock) => {
                 if in_code_block {
-                    let code_block = CodeBlock {
-                        language: code_language.clone(),
-                        code: code_content.clone(),
+                    // a quiz that doesn't parse is shown as it was written
+                    let quiz = (code_language.as_deref() == Some("quiz"))
+                        .then(|| {
+                            Quiz::parse(&code_content)
+                                .inspect_err(|e| warn!("Showing the broken quiz as code: {e}"))
+                                .ok()
+                        })
+                        .flatten();
+                    let block = match quiz {
+                        Some(quiz) => Content::Quiz(quiz),
+                        None if code_language.as_deref() == Some("transcript") => {
+                            Content::Transcript(Transcript::parse(&code_content))
+                        }
+                        None => Content::CodeBlock(CodeBlock {
+                            language: code_language.clone(),
+                            code: code_content.clone(),
+                        }),
                     };
 
                     if collecting_hint {
-                        hint_content.push(Content::CodeBlock(code_block));
+                        hint_content.push(block);
                     } else {
-                        content_blocks.push(Content::CodeBlock(code_block));
+                        content_blocks.push(block);
                     }
                 }
                 in_code_block = false;
@@ -554,8 +1016,10 @@ pub fn parse_markdown(markdown: &str) -> Vec<Content> {
 pub struct LessonBoxState {
     /// Cached content blocks from parsed markdown
     content: Vec<Content>,
-    /// Cached rendered lines with metadata
-    cached_lines: Vec<CachedLine>,
+    /// The layout of the content blocks with their rendered lines
+    blocks: Vec<CachedBlock>,
+    /// The width the content blocks are wrapped to
+    width: u16,
     /// Current scroll position
     scroll: Scroll,
     /// Total lines after rendering
@@ -566,9 +1030,22 @@ pub struct LessonBoxState {
     highlighted_line: usize,
     /// Is there a highlighted line?
     is_highlighted_line: bool,
+    /// The direction of the text
+    direction: Direction,
+}
+
+/// A place in the lesson text as a content block and a line in it, so that it stays put when the
+/// text is wrapped to another width
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq, Serialize)]
+pub struct TextPosition {
+    /// the index of the content block
+    pub block: usize,
+    /// the line in the content block
+    pub line: usize,
 }
 
 /// Cached line with metadata for hint tracking
+#[cfg(test)]
 #[derive(Clone, Debug)]
 struct CachedLine {
     /// The rendered line
@@ -579,23 +1056,64 @@ struct CachedLine {
     is_hint_title: bool,
 }
 
+/// A content block's place in the rendered lines. The lines of a block are only rendered once
+/// it scrolls into view and only the toggled block is rendered again when a hint is toggled.
+#[derive(Clone, Debug)]
+struct CachedBlock {
+    /// The index of the block's first line, including the blank line before it
+    start: usize,
+    /// The number of lines, including the blank line before it
+    len: usize,
+    /// Is there a blank line before the block?
+    spaced: bool,
+    /// Index of the hint this block is (if any)
+    hint_index: Option<usize>,
+    /// Index of the quiz this block is (if any)
+    quiz_index: Option<usize>,
+    /// The rendered lines, if the block has been rendered
+    lines: Option<Vec<Line<'static>>>,
+}
+
 impl LessonBoxState {
     /// Create a new state from markdown content
     pub fn from_markdown(markdown: &str) -> Self {
-        let content = parse_markdown(markdown);
+        // parsing is quicker than reading the cache for all but the larger documents
+        let content = if markdown.len() < CACHED_MARKDOWN_LEN {
+            parse_markdown(markdown)
+        } else {
+            let Ok(content) = cache::parse_cached("markdown", markdown, |markdown| {
+                Ok::<_, Infallible>(parse_markdown(markdown))
+            });
+            content
+        };
         let mut state = Self {
             content,
-            cached_lines: Vec::new(),
+            blocks: Vec::new(),
+            width: 0,
             scroll: Scroll::Top,
             total_lines: 0,
             window_lines: 0,
             highlighted_line: 0,
             is_highlighted_line: true,
+            direction: Direction::LeftToRight,
         };
         state.rebuild_cache(80); // Default width
         state
     }
 
+    /// Set the direction of the text, right-to-left text is aligned to the right edge with the
+    /// bullets, hint markers, and scrollbar mirrored
+    pub fn direction(mut self, direction: Direction) -> Self {
+        self.direction = direction;
+        self.rebuild_cache(80);
+        self
+    }
+
+    /// Is the text right-to-left?
+    pub fn is_rtl(&self) -> bool {
+        self.direction == Direction::RightToLeft
+    }
+
     pub fn set_highlighted_line(&mut self, highlighting: bool) {
         self.is_highlighted_line = highlighting;
     }
@@ -604,62 +1122,177 @@ impl LessonBoxState {
         if !self.is_highlighted_line {
             return false;
         }
-        self.highlighted_line == line && self.highlighted_line < self.cached_lines.len()
+        self.highlighted_line == line && self.highlighted_line < self.total_lines
     }
 
-    /// Rebuild the cached lines from content
+    /// Rebuild the layout of the content blocks, the lines are rendered as they are needed. The
+    /// highlighted line and the top of the view stay on the same text.
     fn rebuild_cache(&mut self, width: u16) {
-        self.cached_lines.clear();
+        let highlighted = self.position();
+        let top = self.top_position();
+        self.width = width;
+        self.blocks.clear();
+        let mut start = 0;
         let mut hint_index = 0;
+        let mut quiz_index = 0;
         let mut last_was_list_item = false;
 
         for (content_idx, content_block) in self.content.iter().enumerate() {
             let is_list_item = matches!(content_block, Content::ListItem(_));
 
             // Add empty line before content blocks (except first and between consecutive list items)
-            if content_idx > 0 && !(last_was_list_item && is_list_item) {
-                self.cached_lines.push(CachedLine {
-                    line: Line::from(""),
-                    hint_index: None,
-                    is_hint_title: false,
-                });
-            }
+            let spaced = content_idx > 0 && !(last_was_list_item && is_list_item);
 
-            match content_block {
-                Content::Hint(hint) => {
-                    let lines = hint.render(width);
-                    for (i, line) in lines.into_iter().enumerate() {
-                        self.cached_lines.push(CachedLine {
-                            line,
-                            hint_index: Some(hint_index),
-                            is_hint_title: i == 0, // First line is the title
-                        });
-                    }
-                    hint_index += 1;
-                }
-                _ => {
-                    let lines = content_block.render(width);
-                    for line in lines {
-                        self.cached_lines.push(CachedLine {
-                            line,
-                            hint_index: None,
-                            is_hint_title: false,
-                        });
-                    }
-                }
-            }
+            let block_hint_index = if matches!(content_block, Content::Hint(_)) {
+                hint_index += 1;
+                Some(hint_index - 1)
+            } else {
+                None
+            };
+            let block_quiz_index = if matches!(content_block, Content::Quiz(_)) {
+                quiz_index += 1;
+                Some(quiz_index - 1)
+            } else {
+                None
+            };
+
+            let len = usize::from(spaced) + content_block.line_count(width);
+            self.blocks.push(CachedBlock {
+                start,
+                len,
+                spaced,
+                hint_index: block_hint_index,
+                quiz_index: block_quiz_index,
+                lines: None,
+            });
+            start += len;
 
             last_was_list_item = is_list_item;
         }
 
-        self.total_lines = self.cached_lines.len();
+        self.total_lines = start;
+        if let Some(line) = self.line_at(highlighted) {
+            self.highlighted_line = line;
+        }
+        self.clamp_highlighted_line();
+        if let Some(line) = self.line_at(top) {
+            if let Scroll::MaybeTop(offset) | Scroll::Offset(offset) | Scroll::MaybeBottom(offset) =
+                &mut self.scroll
+            {
+                *offset = line;
+            }
+        }
+    }
+
+    // get the position of the line
+    fn position_of(&self, line: usize) -> TextPosition {
+        self.block_index(line)
+            .map(|index| TextPosition {
+                block: index,
+                line: line - self.blocks[index].start,
+            })
+            .unwrap_or_default()
+    }
+
+    // get the line at the position, if its block exists
+    fn line_at(&self, position: TextPosition) -> Option<usize> {
+        self.blocks
+            .get(position.block)
+            .map(|block| block.start + position.line.min(block.len.saturating_sub(1)))
+    }
+
+    // recalculate where each block starts after the length of a block changed
+    fn update_starts(&mut self) {
+        let mut start = 0;
+        for block in self.blocks.iter_mut() {
+            block.start = start;
+            start += block.len;
+        }
+        self.total_lines = start;
+        self.clamp_highlighted_line();
+    }
 
-        // Ensure highlighted line is within bounds
+    // Ensure highlighted line is within bounds
+    fn clamp_highlighted_line(&mut self) {
         if self.highlighted_line >= self.total_lines {
             self.highlighted_line = self.total_lines.saturating_sub(1);
         }
     }
 
+    // get the index of the block containing the given line
+    fn block_index(&self, line: usize) -> Option<usize> {
+        let index = self
+            .blocks
+            .partition_point(|block| block.start + block.len <= line);
+        (index < self.blocks.len()).then_some(index)
+    }
+
+    // render the lines of the block at the given index unless they already are
+    fn render_block(&mut self, index: usize) {
+        let Some(block) = self.blocks.get(index) else {
+            return;
+        };
+        if block.lines.is_some() {
+            return;
+        }
+
+        let mut lines = Vec::with_capacity(block.len);
+        if block.spaced {
+            lines.push(Line::from(""));
+        }
+        let content_block = &self.content[index];
+        if self.is_rtl() {
+            lines.extend(content_block.render_rtl(self.width));
+        } else {
+            lines.extend(content_block.render(self.width));
+        }
+
+        let len = lines.len();
+        let block = &mut self.blocks[index];
+        block.lines = Some(lines);
+        if block.len != len {
+            block.len = len;
+            self.update_starts();
+        }
+    }
+
+    // get the line at the given index, rendering its block if needed
+    fn line(&mut self, line: usize) -> Option<Line<'static>> {
+        let index = self.block_index(line)?;
+        self.render_block(index);
+        let block = &self.blocks[index];
+        block.lines.as_ref()?.get(line - block.start).cloned()
+    }
+
+    // get the hint the line belongs to and whether it is the hint's title, without rendering
+    fn line_hint(&self, line: usize) -> (Option<usize>, bool) {
+        let Some(block) = self.block_index(line).map(|index| &self.blocks[index]) else {
+            return (None, false);
+        };
+        let offset = line - block.start;
+        if block.spaced && offset == 0 {
+            // the blank line before the block
+            return (None, false);
+        }
+        let is_hint_title = block.hint_index.is_some() && offset == usize::from(block.spaced);
+        (block.hint_index, is_hint_title)
+    }
+
+    /// Render every line, for checking the rendered lesson in the tests
+    #[cfg(test)]
+    fn cached_lines(&mut self) -> Vec<CachedLine> {
+        (0..self.total_lines)
+            .filter_map(|line| {
+                let (hint_index, is_hint_title) = self.line_hint(line);
+                Some(CachedLine {
+                    line: self.line(line)?,
+                    hint_index,
+                    is_hint_title,
+                })
+            })
+            .collect()
+    }
+
     /// Move highlight down
     pub fn highlight_down(&mut self) {
         if self.highlighted_line < self.total_lines.saturating_sub(1) {
@@ -708,17 +1341,13 @@ impl LessonBoxState {
 
     /// Check if the highlighted line is a collapsed hint title
     pub fn is_highlighted_hint(&self) -> Option<usize> {
-        if !self.is_highlighted_line {
+        if !self.is_highlighted_line || self.highlighted_line >= self.total_lines {
             return None;
         }
-        if self.highlighted_line < self.cached_lines.len() {
-            if let Some(cached_line) = self.cached_lines.get(self.highlighted_line) {
-                if cached_line.is_hint_title {
-                    return cached_line.hint_index;
-                }
-            }
+        match self.line_hint(self.highlighted_line) {
+            (hint_index, true) => hint_index,
+            _ => None,
         }
-        None
     }
 
     /// Toggle hint at highlighted line if it's a hint title
@@ -731,21 +1360,128 @@ impl LessonBoxState {
         }
     }
 
-    /// Toggle the hint at the specified index
+    /// Toggle the hint at the specified index, only the toggled hint is rendered again
     pub fn toggle_hint(&mut self, hint_index: usize, width: u16) {
-        let mut content_hint_index = 0;
-        for content_block in &mut self.content {
-            if let Content::Hint(hint) = content_block {
-                if content_hint_index == hint_index {
-                    hint.toggle();
-                    self.rebuild_cache(width);
-                    return;
+        let Some(index) = self
+            .blocks
+            .iter()
+            .position(|block| block.hint_index == Some(hint_index))
+        else {
+            return;
+        };
+        if let Content::Hint(hint) = &mut self.content[index] {
+            hint.toggle();
+        }
+        self.refresh_block(index, width);
+    }
+
+    // lay out the block at the given index again after it changed, it is rendered again when
+    // it is needed
+    fn refresh_block(&mut self, index: usize, width: u16) {
+        if width != self.width {
+            self.rebuild_cache(width);
+            return;
+        }
+        let block = &mut self.blocks[index];
+        block.lines = None;
+        block.len = usize::from(block.spaced) + self.content[index].line_count(width);
+        self.update_starts();
+    }
+
+    // get the index of the block of the quiz with the given index
+    fn quiz_block(&self, quiz_index: usize) -> Option<usize> {
+        self.blocks
+            .iter()
+            .position(|block| block.quiz_index == Some(quiz_index))
+    }
+
+    /// Get the index of the quiz the highlighted line is in, and the quiz
+    pub fn highlighted_quiz(&self) -> Option<(usize, &Quiz)> {
+        if !self.is_highlighted_line {
+            return None;
+        }
+        let index = self.block_index(self.highlighted_line)?;
+        match (&self.content[index], self.blocks[index].quiz_index) {
+            (Content::Quiz(quiz), Some(quiz_index)) => Some((quiz_index, quiz)),
+            _ => None,
+        }
+    }
+
+    // get the transcript the highlighted line is in
+    fn highlighted_transcript_mut(&mut self) -> Option<(usize, &mut Transcript)> {
+        if !self.is_highlighted_line {
+            return None;
+        }
+        let index = self.block_index(self.highlighted_line)?;
+        match &mut self.content[index] {
+            Content::Transcript(transcript) => Some((index, transcript)),
+            _ => None,
+        }
+    }
+
+    /// Get the transcript the highlighted line is in
+    pub fn highlighted_transcript(&self) -> Option<&Transcript> {
+        if !self.is_highlighted_line {
+            return None;
+        }
+        match &self.content[self.block_index(self.highlighted_line)?] {
+            Content::Transcript(transcript) => Some(transcript),
+            _ => None,
+        }
+    }
+
+    /// Play or pause the highlighted transcript, returns false if no transcript is highlighted
+    pub fn play_pause_highlighted_transcript(&mut self) -> bool {
+        let Some((index, transcript)) = self.highlighted_transcript_mut() else {
+            return false;
+        };
+        transcript.play_pause();
+        self.blocks[index].lines = None;
+        true
+    }
+
+    /// Print the next line of the highlighted transcript, returns false if no transcript is
+    /// highlighted
+    pub fn step_highlighted_transcript(&mut self) -> bool {
+        let Some((index, transcript)) = self.highlighted_transcript_mut() else {
+            return false;
+        };
+        transcript.step();
+        self.blocks[index].lines = None;
+        true
+    }
+
+    /// Is a transcript playing?
+    pub fn is_playing(&self) -> bool {
+        self.content
+            .iter()
+            .any(|content| matches!(content, Content::Transcript(t) if t.is_playing()))
+    }
+
+    // print the lines of the playing transcripts that are due, the transcripts keep their
+    // length so only their lines are rendered again
+    fn advance_playback(&mut self) {
+        for (content, block) in self.content.iter_mut().zip(self.blocks.iter_mut()) {
+            if let Content::Transcript(transcript) = content {
+                if transcript.advance() {
+                    block.lines = None;
                 }
-                content_hint_index += 1;
             }
         }
     }
 
+    /// Pick the choice with the number in the quiz with the given index, returns whether it is
+    /// the right one or None when there is no such quiz or choice
+    pub fn answer_quiz(&mut self, quiz_index: usize, choice: usize, width: u16) -> Option<bool> {
+        let index = self.quiz_block(quiz_index)?;
+        let Content::Quiz(quiz) = &mut self.content[index] else {
+            return None;
+        };
+        let correct = quiz.pick(choice)?;
+        self.refresh_block(index, width);
+        Some(correct)
+    }
+
     /// Scroll methods similar to ScrollText
     pub fn scroll_top(&mut self) {
         self.scroll = Scroll::Top;
@@ -795,6 +1531,126 @@ impl LessonBoxState {
     pub fn get_highlighted_line(&self) -> usize {
         self.highlighted_line
     }
+
+    /// Get the position of the highlighted line
+    pub fn position(&self) -> TextPosition {
+        self.position_of(self.highlighted_line)
+    }
+
+    /// Get the headings of the lesson with the position of each one, for an outline
+    pub fn outline(&self) -> Vec<(TextPosition, &Heading)> {
+        self.content
+            .iter()
+            .zip(&self.blocks)
+            .enumerate()
+            .filter_map(|(index, (content, block))| match content {
+                Content::Heading(heading) => Some((
+                    TextPosition {
+                        block: index,
+                        line: usize::from(block.spaced),
+                    },
+                    heading,
+                )),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Get the position of the line at the top of the view
+    pub fn top_position(&self) -> TextPosition {
+        let offset = match self.scroll {
+            Scroll::Top => 0,
+            Scroll::MaybeTop(offset) | Scroll::Offset(offset) | Scroll::MaybeBottom(offset) => {
+                offset
+            }
+            Scroll::Bottom => self.total_lines.saturating_sub(self.window_lines),
+        };
+        self.position_of(offset)
+    }
+
+    /// Highlight the line at the position and scroll it to the top of the view
+    pub fn go_to(&mut self, position: TextPosition) {
+        if let Some(line) = self.line_at(position) {
+            self.highlighted_line = line;
+            self.clamp_highlighted_line();
+            self.scroll_to(position);
+        }
+    }
+
+    /// Scroll the line at the position to the top of the view, or as close as it gets
+    pub fn scroll_to(&mut self, position: TextPosition) {
+        if let Some(line) = self.line_at(position) {
+            self.scroll = Scroll::MaybeBottom(line);
+        }
+    }
+
+    /// Highlight the first line after the highlighted one containing the text, ignoring case and
+    /// wrapping around to the top. Returns false when no line contains it.
+    pub fn find_next(&mut self, text: &str) -> bool {
+        let text = text.to_lowercase();
+        let (start, total) = (self.highlighted_line, self.total_lines);
+        if text.is_empty() || total == 0 {
+            return false;
+        }
+        let found = (1..=total)
+            .map(|offset| (start + offset) % total)
+            .find(|&line| {
+                self.line(line)
+                    .is_some_and(|line| line.to_string().to_lowercase().contains(&text))
+            });
+        match found {
+            Some(line) => {
+                let position = self.position_of(line);
+                self.go_to(position);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Get the glossary terms linked from the highlighted line
+    pub fn highlighted_terms(&mut self) -> Vec<String> {
+        let Some(index) = self.block_index(self.highlighted_line) else {
+            return Vec::new();
+        };
+        let Some(line) = self
+            .line(self.highlighted_line)
+            .map(|line| line.to_string())
+        else {
+            return Vec::new();
+        };
+
+        // the text of the block, and of the content of a hint that is showing it
+        let mut texts = vec![&self.content[index]];
+        if let Content::Hint(hint) = &self.content[index] {
+            if hint.expanded {
+                texts.extend(&hint.content);
+            }
+        }
+        let mut terms = Vec::<String>::new();
+        for content in texts {
+            let text = match content {
+                Content::Paragraph(paragraph) => &paragraph.text,
+                Content::ListItem(item) => &item.text,
+                _ => continue,
+            };
+            for term in glossary::links(text) {
+                if line.contains(term) && !terms.iter().any(|known| known == term) {
+                    terms.push(term.to_string());
+                }
+            }
+        }
+        terms
+    }
+
+    /// Get the text of the highlighted line, or of the first line after it with any text
+    pub fn highlighted_text(&mut self) -> String {
+        (self.highlighted_line..self.total_lines)
+            .filter_map(|line| self.line(line))
+            .map(|line| line.to_string().trim().to_string())
+            .find(|text| !text.is_empty())
+            .unwrap_or_default()
+    }
 }
 
 /// A lesson box widget that displays markdown content with collapsible hints
@@ -836,11 +1692,14 @@ impl StatefulWidget for LessonBox<'_> {
             area.width
         };
 
-        // Rebuild cache if width changed or cache is empty
-        if state.cached_lines.is_empty() {
+        // Rebuild cache if it is empty
+        if state.blocks.is_empty() {
             state.rebuild_cache(width);
         }
 
+        // print the lines of the playing transcripts that are due
+        state.advance_playback();
+
         // Update window size
         state.window_lines = if let Some(block) = &self.block {
             block.inner(area).height as usize
@@ -885,61 +1744,58 @@ impl StatefulWidget for LessonBox<'_> {
             area.width
         };
 
-        // Render lines with highlighting
-        let items: Vec<Line> = state
-            .cached_lines
-            .iter()
-            .enumerate()
-            .skip(start_line)
-            .take(end_line - start_line)
-            .map(|(line_idx, cached_line)| {
-                let is_highlighted = state.is_highlighted(line_idx);
-                let is_hint_title = cached_line.is_hint_title;
-
-                if is_highlighted {
-                    // Create a full-width highlighted line
-                    let mut highlighted_line = cached_line.line.clone();
-
-                    if is_hint_title {
-                        // Highlighted hint title: black text on white background
-                        for span in &mut highlighted_line.spans {
-                            span.style = Style::default().fg(Color::Black).bg(Color::White);
-                        }
-                    } else {
-                        // Regular highlighted line: dark gray background
-                        for span in &mut highlighted_line.spans {
-                            span.style = span.style.bg(Color::DarkGray);
-                        }
+        // Render the visible lines with highlighting, only the blocks in view get rendered
+        let mut items: Vec<Line> = Vec::with_capacity(end_line - start_line);
+        for line_idx in start_line..end_line {
+            let Some(line) = state.line(line_idx) else {
+                break;
+            };
+            let is_highlighted = state.is_highlighted(line_idx);
+            let (_, is_hint_title) = state.line_hint(line_idx);
+
+            let line = if is_highlighted {
+                // Create a full-width highlighted line
+                let mut highlighted_line = line;
+
+                if is_hint_title {
+                    // Highlighted hint title: black text on white background
+                    for span in &mut highlighted_line.spans {
+                        span.style = Style::default().fg(Color::Black).bg(Color::White);
+                    }
+                } else {
+                    // Regular highlighted line: dark gray background
+                    for span in &mut highlighted_line.spans {
+                        span.style = span.style.bg(Color::DarkGray);
                     }
+                }
 
-                    // Calculate remaining width to fill the entire line
-                    let current_width: usize = highlighted_line
-                        .spans
-                        .iter()
-                        .map(|span| span.content.chars().count())
-                        .sum();
+                // Calculate remaining width to fill the entire line, counting the columns
+                // taken by wide characters rather than the number of characters
+                let current_width = highlighted_line.width();
 
-                    let remaining_width = content_width.saturating_sub(current_width as u16);
+                let remaining_width = content_width.saturating_sub(current_width as u16);
 
-                    if remaining_width > 0 {
-                        let fill_style = if is_hint_title {
-                            Style::default().fg(Color::Black).bg(Color::White)
-                        } else {
-                            Style::default().bg(Color::DarkGray)
-                        };
+                if remaining_width > 0 {
+                    let fill_style = if is_hint_title {
+                        Style::default().fg(Color::Black).bg(Color::White)
+                    } else {
+                        Style::default().bg(Color::DarkGray)
+                    };
 
-                        highlighted_line.spans.push(Span::styled(
-                            " ".repeat(remaining_width as usize),
-                            fill_style,
-                        ));
+                    let fill = Span::styled(" ".repeat(remaining_width as usize), fill_style);
+                    if state.is_rtl() {
+                        highlighted_line.spans.insert(0, fill);
+                    } else {
+                        highlighted_line.spans.push(fill);
                     }
-
-                    highlighted_line
-                } else {
-                    cached_line.line.clone()
                 }
-            })
-            .collect();
+
+                highlighted_line
+            } else {
+                line
+            };
+            items.push(line);
+        }
 
         let mut scrollbar_area = area;
 
@@ -962,7 +1818,12 @@ impl StatefulWidget for LessonBox<'_> {
                 ScrollbarState::new(state.total_lines.saturating_sub(state.window_lines))
                     .position(scroll_offset)
                     .viewport_content_length(state.window_lines);
-            let scrollbar = Scrollbar::new(ScrollbarOrientation::VerticalRight)
+            let orientation = if state.is_rtl() {
+                ScrollbarOrientation::VerticalLeft
+            } else {
+                ScrollbarOrientation::VerticalRight
+            };
+            let scrollbar = Scrollbar::new(orientation)
                 .begin_symbol(Some("↑"))
                 .track_symbol(Some("│"))
                 .thumb_symbol("█")
@@ -1011,6 +1872,59 @@ mod tests {
         assert!(lines[0].spans[0].content.contains("• Test list item"));
     }
 
+    #[test]
+    fn test_wide_characters_fit_width() {
+        let paragraph = ParagraphBlock {
+            text: "こんにちは、世界！ピアツーピアのネットワークを作りましょう。".to_string(),
+        };
+        for line in paragraph.render(20) {
+            assert!(line.width() <= 20);
+        }
+
+        let list_item = ListItem {
+            text: "ピアを見つけてメッセージを送信します".to_string(),
+            indent_level: 1,
+        };
+        for line in list_item.render(20) {
+            assert!(line.width() <= 20);
+        }
+    }
+
+    #[test]
+    fn test_python_highlighting_keeps_non_ascii_text() {
+        let code = CodeBlock {
+            language: Some("python".to_string()),
+            code: "  print(\"こんにちは、世界！\")  # 挨拶".to_string(),
+        };
+        let lines = code.render(80);
+        let text = lines[1]
+            .spans
+            .iter()
+            .map(|span| span.content.as_ref())
+            .collect::<String>();
+        assert_eq!(text, "│   print(\"こんにちは、世界！\")  # 挨拶");
+    }
+
+    #[test]
+    fn test_right_to_left_rendering() {
+        let mut state = LessonBoxState::from_markdown(
+            "مرحبا بالعالم\n\n- عنصر القائمة\n\n```rust\nfn main() {}\n```",
+        )
+        .direction(Direction::RightToLeft);
+        let lines = state
+            .cached_lines()
+            .iter()
+            .map(|cached| cached.line.clone())
+            .collect::<Vec<_>>();
+
+        assert_eq!(lines[0].alignment, Some(Alignment::Right));
+        assert_eq!(lines[2].alignment, Some(Alignment::Right));
+        assert_eq!(lines[2].spans[0].content, "عنصر القائمة •");
+
+        // code blocks stay left-to-right
+        assert!(lines[4..].iter().all(|line| line.alignment.is_none()));
+    }
+
     #[test]
     fn test_list_item_wrapping() {
         let list_item = ListItem {
@@ -1291,15 +2205,15 @@ This is a paragraph.
 
 This is hint content.
 "#;
-        let state = LessonBoxState::from_markdown(markdown);
+        let mut state = LessonBoxState::from_markdown(markdown);
 
         // Should have content and cached lines
         assert_eq!(state.content.len(), 3); // heading, paragraph, hint
-        assert!(state.cached_lines.len() > 0);
+        assert!(!state.cached_lines().is_empty());
 
         // Should have one hint
         let hint_count = state
-            .cached_lines
+            .cached_lines()
             .iter()
             .filter(|line| line.hint_index.is_some())
             .count();
@@ -1307,7 +2221,7 @@ This is hint content.
 
         // Should have one hint title line
         let hint_title_count = state
-            .cached_lines
+            .cached_lines()
             .iter()
             .filter(|line| line.is_hint_title)
             .count();
@@ -1321,19 +2235,44 @@ This is hint content.
 This is hint content.
 "#;
         let mut state = LessonBoxState::from_markdown(markdown);
-        let initial_lines = state.cached_lines.len();
+        let initial_lines = state.cached_lines().len();
 
         // Toggle hint (expand)
         state.toggle_hint(0, 80);
-        let expanded_lines = state.cached_lines.len();
+        let expanded_lines = state.cached_lines().len();
         assert!(expanded_lines > initial_lines);
 
         // Toggle hint (collapse)
         state.toggle_hint(0, 80);
-        let collapsed_lines = state.cached_lines.len();
+        let collapsed_lines = state.cached_lines().len();
         assert_eq!(collapsed_lines, initial_lines);
     }
 
+    #[test]
+    fn test_lesson_box_renders_lazily() {
+        let markdown = (0..50)
+            .map(|i| {
+                format!("## Hint - Step {i}\n\nParagraph {i}.\n\n```rust\nfn step() {{}}\n```\n")
+            })
+            .collect::<String>();
+        let mut state = LessonBoxState::from_markdown(&markdown);
+        assert!(state.blocks.iter().all(|block| block.lines.is_none()));
+
+        // only the blocks in view are rendered
+        let area = Rect::new(0, 0, 80, 5);
+        let mut buf = Buffer::empty(area);
+        StatefulWidget::render(LessonBox::new(), area, &mut buf, &mut state);
+        let rendered = state.blocks.iter().filter(|b| b.lines.is_some()).count();
+        assert!(rendered > 0 && rendered < 5);
+
+        // toggling a hint keeps the other blocks and the counted lines match the rendered ones
+        state.toggle_hint(1, 80);
+        assert!(state.blocks[0].lines.is_some());
+        let counted = state.get_lines();
+        assert_eq!(state.cached_lines().len(), counted);
+        assert_eq!(state.get_lines(), counted);
+    }
+
     #[test]
     fn test_lesson_box_scrolling() {
         let mut state = LessonBoxState::from_markdown("# Test\n\nContent");
@@ -1395,13 +2334,153 @@ Hint content.
         assert!(state.is_highlighted_hint().is_some());
 
         // Test that we can toggle the hint
-        let initial_lines = state.cached_lines.len();
+        let initial_lines = state.cached_lines().len();
         let toggle_success = state.toggle_highlighted_hint(80);
         assert!(toggle_success);
-        let after_lines = state.cached_lines.len();
+        let after_lines = state.cached_lines().len();
         assert_ne!(initial_lines, after_lines);
     }
 
+    #[test]
+    fn test_position_survives_rewrapping() {
+        let markdown =
+            "# Title\n\nA paragraph that is long enough to wrap onto more lines when the \
+                        lesson is narrow.\n\nThe bookmarked paragraph.\n";
+        let mut state = LessonBoxState::from_markdown(markdown);
+        let position = TextPosition { block: 2, line: 1 };
+        state.go_to(position);
+        assert_eq!(state.position(), position);
+        assert_eq!(state.highlighted_text(), "The bookmarked paragraph.");
+
+        // the jump follows the text when the width changes before it is drawn
+        state.rebuild_cache(20);
+        assert_eq!(state.position(), position);
+        assert_eq!(state.top_position(), position);
+        assert_eq!(state.highlighted_text(), "The bookmarked");
+    }
+
+    #[test]
+    fn test_find_next() {
+        let mut state = LessonBoxState::from_markdown(
+            "# Title\n\nFirst paragraph.\n\nThe peer dials.\n\nAnother peer listens.",
+        );
+        assert!(state.find_next("PEER"));
+        assert_eq!(state.highlighted_text(), "The peer dials.");
+        assert!(state.find_next("peer"));
+        assert_eq!(state.highlighted_text(), "Another peer listens.");
+        assert!(state.find_next("peer"));
+        assert_eq!(state.highlighted_text(), "The peer dials.");
+        assert!(!state.find_next("missing"));
+    }
+
+    #[test]
+    fn test_quiz() {
+        let markdown = "# Title\n\n```quiz\nquestion: What does a multiaddr describe?\nchoices:\n  - A public key\n  - How to reach a peer\nanswer: 2\nexplanation: It layers the protocols.\n```\n";
+        let mut state = LessonBoxState::from_markdown(markdown);
+        assert!(matches!(state.content[1], Content::Quiz(_)));
+        assert!(state.highlighted_quiz().is_none());
+        state.go_to(TextPosition { block: 1, line: 1 });
+        let (quiz, _) = state.highlighted_quiz().unwrap();
+        assert_eq!(quiz, 0);
+
+        // answering shows the result and the explanation
+        let lines = state.cached_lines().len();
+        assert_eq!(state.answer_quiz(0, 1, 80), Some(false));
+        assert_eq!(state.answer_quiz(0, 3, 80), None);
+        assert_eq!(state.answer_quiz(0, 2, 80), Some(true));
+        assert_eq!(state.cached_lines().len(), lines + 1);
+        assert!(state.find_next("layers the protocols"));
+
+        // a broken quiz is shown as code and reported
+        let broken = "```quiz\nquestion: Which?\nchoices: [a, b]\nanswer: 3\n```\n";
+        assert!(matches!(parse_markdown(broken)[0], Content::CodeBlock(_)));
+        assert_eq!(quiz_problems(broken).len(), 1);
+        assert!(quiz_problems(markdown).is_empty());
+    }
+
+    #[test]
+    fn test_transcript() {
+        let markdown = "```transcript\n[0] $ cargo run\n[1.5] Listening on /ip4/127.0.0.1\n  and /ip6/::1\n[0.5] Dialed\n[oops] not a time\n```\n";
+        let mut state = LessonBoxState::from_markdown(markdown);
+        let Content::Transcript(transcript) = &state.content[0] else {
+            panic!("expected a transcript");
+        };
+        let times = transcript
+            .lines
+            .iter()
+            .map(|(time, _)| time.as_secs_f64())
+            .collect::<Vec<_>>();
+        assert_eq!(times, vec![0.0, 1.5, 1.5, 1.5, 1.5]);
+        assert_eq!(transcript.lines[4].1, "[oops] not a time");
+
+        // every line is printed until it is played
+        let lines = state.cached_lines().len();
+        assert_eq!(lines, 7);
+        assert_eq!(state.highlighted_transcript().unwrap().shown(), 5);
+
+        // stepping starts over and prints one line at a time, keeping the length
+        state.go_to(TextPosition { block: 0, line: 2 });
+        assert!(state.step_highlighted_transcript());
+        assert_eq!(state.highlighted_transcript().unwrap().shown(), 1);
+        assert_eq!(state.cached_lines()[2].line.to_string().trim(), "│");
+        assert!(state.step_highlighted_transcript());
+        assert_eq!(state.highlighted_transcript().unwrap().shown(), 2);
+        assert_eq!(state.cached_lines().len(), lines);
+
+        assert!(state.play_pause_highlighted_transcript());
+        assert!(state.is_playing());
+        assert!(state.play_pause_highlighted_transcript());
+        assert!(!state.is_playing());
+    }
+
+    #[test]
+    fn test_glossary_terms() {
+        let markdown = "Dial the [[multiaddr]] of the peer.\n\n* Try [[NAT hole punching]] first\n";
+        let mut state = LessonBoxState::from_markdown(markdown);
+        let lines = state.cached_lines();
+        assert_eq!(lines[0].line.to_string(), "Dial the multiaddr of the peer.");
+        assert_eq!(lines[0].line.spans[1].content, "multiaddr");
+        assert!(lines[0].line.spans[1]
+            .style
+            .add_modifier
+            .contains(Modifier::UNDERLINED));
+        assert_eq!(state.highlighted_terms(), vec!["multiaddr"]);
+
+        // a term isn't broken across lines
+        let item = ListItem {
+            text: "Try [[NAT hole punching]] first".to_string(),
+            indent_level: 0,
+        };
+        let lines = item.render(20);
+        assert!(lines
+            .iter()
+            .any(|line| line.to_string().contains("NAT hole punching")));
+        state.highlight_down();
+        state.highlight_down();
+        assert_eq!(state.highlighted_terms(), vec!["NAT hole punching"]);
+    }
+
+    #[test]
+    fn test_outline() {
+        let markdown =
+            "# Title\n\nIntro.\n\n## Setup\n\nInstall it.\n\n## Hint - Stuck?\n\nTry again.\n";
+        let mut state = LessonBoxState::from_markdown(markdown);
+        let outline = state
+            .outline()
+            .into_iter()
+            .map(|(position, heading)| (position, heading.level, heading.text.clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            outline,
+            vec![
+                (TextPosition { block: 0, line: 0 }, 1, "Title".to_string()),
+                (TextPosition { block: 2, line: 1 }, 2, "Setup".to_string()),
+            ]
+        );
+        state.go_to(outline[1].0);
+        assert_eq!(state.highlighted_text(), "Setup");
+    }
+
     #[test]
     fn test_list_item_spacing() {
         let markdown = r#"# Test
@@ -1412,14 +2491,14 @@ Hint content.
 
 Next paragraph.
 "#;
-        let state = LessonBoxState::from_markdown(markdown);
+        let mut state = LessonBoxState::from_markdown(markdown);
 
         // Should have content with proper spacing
-        assert!(state.cached_lines.len() > 5);
+        assert!(state.cached_lines().len() > 5);
 
         // Find the lines and check spacing
         let line_contents: Vec<String> = state
-            .cached_lines
+            .cached_lines()
             .iter()
             .map(|line| {
                 line.line
@@ -1461,7 +2540,7 @@ Second paragraph in hint.
 
         // Should have blank lines between content blocks within the hint
         let line_contents: Vec<String> = state
-            .cached_lines
+            .cached_lines()
             .iter()
             .map(|line| {
                 line.line
@@ -1556,7 +2635,7 @@ It should be in the main document.
 
         // Should have multiple content blocks and hints
         assert!(state.content.len() > 3);
-        assert!(state.cached_lines.len() > 5);
+        assert!(state.cached_lines().len() > 5);
 
         // Test scrolling
         state.window_lines = 20;
@@ -1564,7 +2643,7 @@ It should be in the main document.
         state.scroll_down();
 
         // Test highlighting and hint toggling
-        let initial_lines = state.cached_lines.len();
+        let initial_lines = state.cached_lines().len();
 
         // Move highlight to find a hint
         for _ in 0..20 {
@@ -1578,7 +2657,7 @@ It should be in the main document.
         if state.is_highlighted_hint().is_some() {
             let success = state.toggle_highlighted_hint(80);
             assert!(success);
-            let after_toggle_lines = state.cached_lines.len();
+            let after_toggle_lines = state.cached_lines().len();
             // Lines should change when hint is toggled
             assert_ne!(initial_lines, after_toggle_lines);
         }
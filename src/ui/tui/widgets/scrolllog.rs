@@ -7,8 +7,34 @@ use ratatui::{
         Block, Paragraph, Scrollbar, ScrollbarOrientation, ScrollbarState, StatefulWidget, Widget,
     },
 };
-use std::{collections::VecDeque, fmt};
+use std::{
+    collections::{HashMap, VecDeque},
+    fmt,
+    sync::{Arc, OnceLock},
+};
 use textwrap;
+use unicode_width::UnicodeWidthStr;
+
+// maps the log line prefix to the associated emoji
+static EMOJIS: OnceLock<HashMap<&'static str, &'static str>> = OnceLock::new();
+
+fn emoji() -> &'static HashMap<&'static str, &'static str> {
+    EMOJIS.get_or_init(|| {
+        let mut map = HashMap::new();
+        map.insert("* ", "⭐");
+        map.insert("v ", "✅");
+        map.insert("x ", "❌");
+        map.insert("r ", "🚀");
+        map.insert("y ", "🎉");
+        map.insert("n ", "😢");
+        map.insert("! ", "❗");
+        map.insert("^ ", "⚠️ ");
+        map.insert("i ", "ℹ️ ");
+        map.insert("> ", "↪️ ");
+        map.insert("< ", "↩️ ");
+        map
+    })
+}
 
 #[derive(Clone, Debug, Default)]
 pub enum Scroll {
@@ -117,14 +143,10 @@ impl<'a> ScrollLog<'a> {
             _ => {}
         }
     }
-}
-
-impl StatefulWidget for &mut ScrollLog<'_> {
-    type State = VecDeque<(Option<String>, String)>;
 
-    fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
-        // ScrollLog now renders in the full area - StatusBar will be rendered separately
-        let log_messages = state;
+    /// Render the given log lines. The first two characters of each line are the prefix that
+    /// picks its emoji.
+    pub fn render_lines(&mut self, area: Rect, buf: &mut Buffer, log_lines: &VecDeque<Arc<str>>) {
         // get the available width after considering block
         let inner_area = if let Some(block) = &self.block {
             block.inner(area)
@@ -139,14 +161,21 @@ impl StatefulWidget for &mut ScrollLog<'_> {
         // collect all log entries and wrap the messages
         let mut all_lines = Vec::new();
 
-        for (emoji, message) in log_messages.iter() {
+        for line in log_lines.iter() {
+            // lines too short to have a prefix are skipped
+            let (Some(prefix), Some(message)) = (line.get(0..2), line.get(2..)) else {
+                continue;
+            };
+            let emoji = emoji().get(prefix);
             let wrap_options = textwrap::Options::new(right_column_width).break_words(true);
             let wrapped_lines = textwrap::wrap(message, &wrap_options);
 
             // first line includes the emoji
             if let Some(first_line) = wrapped_lines.first() {
                 if let Some(emoji_str) = emoji {
-                    all_lines.push(format!("{emoji_str:<2}{first_line}"));
+                    // pad by display width since emoji take up two columns
+                    let padding = (left_column_width as usize).saturating_sub(emoji_str.width());
+                    all_lines.push(format!("{emoji_str}{}{first_line}", " ".repeat(padding)));
                 } else {
                     all_lines.push(format!("{:<3}{}", "", first_line));
                 }
@@ -259,3 +288,11 @@ impl StatefulWidget for &mut ScrollLog<'_> {
         }
     }
 }
+
+impl StatefulWidget for &mut ScrollLog<'_> {
+    type State = VecDeque<Arc<str>>;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        self.render_lines(area, buf, state);
+    }
+}
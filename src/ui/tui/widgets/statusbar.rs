@@ -1,3 +1,4 @@
+use crate::ui::tui::glyphs;
 use ratatui::{
     buffer::Buffer,
     layout::{Alignment, Rect},
@@ -26,6 +27,8 @@ pub struct StatusBar<'a> {
     progress: u8,
     /// Start time for throbber animation
     start_time: Option<Instant>,
+    /// Show a still throbber instead of animating it
+    reduced_motion: bool,
     /// the block to render with
     block: Block<'a>,
 }
@@ -60,6 +63,16 @@ impl<'a> StatusBar<'a> {
         self.start_time = Some(Instant::now());
     }
 
+    /// Show a still throbber instead of animating it
+    pub fn set_reduced_motion(&mut self, reduced_motion: bool) {
+        self.reduced_motion = reduced_motion;
+    }
+
+    /// Does the throbber need to be redrawn as time passes
+    pub fn is_animating(&self) -> bool {
+        self.start_time.is_some() && !self.reduced_motion
+    }
+
     /// Set the block to render with
     pub fn set_block(&mut self, block: Block<'a>) {
         self.block = block;
@@ -84,25 +97,11 @@ impl<'a> StatusBar<'a> {
 
     /// Get current throbber character based on elapsed time
     fn get_throbber_char(&self) -> char {
-        if let Some(start_time) = self.start_time {
-            let elapsed = start_time.elapsed();
-            let frame = (elapsed.as_millis() / 100) % 10; // 100ms per frame, 10 frames
-            match frame {
-                0 => '⠋',
-                1 => '⠙',
-                2 => '⠹',
-                3 => '⠸',
-                4 => '⠼',
-                5 => '⠴',
-                6 => '⠦',
-                7 => '⠧',
-                8 => '⠇',
-                9 => '⠏',
-                _ => '⠋',
-            }
-        } else {
-            '⠋'
-        }
+        let elapsed = self
+            .start_time
+            .map(|start_time| start_time.elapsed())
+            .unwrap_or_default();
+        glyphs::spinner(elapsed, self.reduced_motion)
     }
 }
 
@@ -0,0 +1,41 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Style},
+    text::Line,
+    widgets::{Block, Borders, Clear, Paragraph, Widget},
+};
+use unicode_width::UnicodeWidthStr;
+
+/// A short notification in the bottom right corner, above the status bar
+#[derive(Clone, Debug, Default)]
+pub struct Toast {
+    /// the notification text
+    pub text: String,
+    /// identifies the notification so that hiding an old one doesn't hide a newer one
+    pub id: u64,
+}
+
+impl Widget for &Toast {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        // two columns of border and two of padding, two rows of border and one for the status bar
+        let width = (self.text.width() as u16 + 4).min(area.width);
+        let height = 3.min(area.height.saturating_sub(1));
+        let toast = Rect {
+            x: area.right().saturating_sub(width),
+            y: area.bottom().saturating_sub(height + 1),
+            width,
+            height,
+        };
+
+        Widget::render(Clear, toast, buf);
+        Paragraph::new(Line::from(format!(" {}", self.text)))
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(Color::Yellow)),
+            )
+            .style(Style::default().fg(Color::White))
+            .render(toast, buf);
+    }
+}